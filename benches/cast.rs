@@ -0,0 +1,131 @@
+//! Benchmarks of the cast kernels. The `*_scalar` benchmarks are the
+//! baseline: the one-float-per-iteration `_ss` loops the packed kernels in
+//! `kernels/cast.c` replaced, and the scalar Rust kernels for the new casts.
+
+#![feature(test)]
+
+extern crate densearray;
+extern crate test;
+
+use densearray::isa::{self, IsaLevel};
+use densearray::kernels::*;
+use densearray::kernels::rust;
+
+use std::arch::x86_64::*;
+use std::mem::{size_of};
+use test::{Bencher, black_box};
+
+const BENCH_DIM: usize = 64 * 1024 + 7;
+
+fn inputs_f32(lower: f32, upper: f32) -> Vec<f32> {
+  (0 .. BENCH_DIM).map(|i| lower + (upper - lower) * ((i * 7919) % 1009) as f32 / 1008.0).collect()
+}
+
+unsafe fn round_clamp_f32_to_u8_scalar(dim: usize, src: *const f32, dst: *mut u8) {
+  let lower = _mm_set_ss(0.0);
+  let upper = _mm_set_ss(255.0);
+  for idx in 0 .. dim {
+    let y = _mm_min_ss(_mm_max_ss(lower, _mm_load_ss(src.offset(idx as isize))), upper);
+    *dst.offset(idx as isize) = _mm_cvtss_si32(y) as u8;
+  }
+}
+
+unsafe fn clamp_f32_scalar(dim: usize, src: *const f32, dst: *mut f32, lowerf: f32, upperf: f32) {
+  let lower = _mm_set_ss(lowerf);
+  let upper = _mm_set_ss(upperf);
+  for idx in 0 .. dim {
+    let y = _mm_min_ss(_mm_max_ss(lower, _mm_load_ss(src.offset(idx as isize))), upper);
+    _mm_store_ss(dst.offset(idx as isize), y);
+  }
+}
+
+fn bench_convert<S: Copy, U: Copy + Default>(b: &mut Bencher, x: &[S], kernel: unsafe fn(usize, *const S, *mut U)) {
+  let mut y = vec![U::default(); x.len()];
+  b.bytes = (x.len() * size_of::<S>()) as u64;
+  b.iter(|| {
+    unsafe { kernel(x.len(), x.as_ptr(), y.as_mut_ptr()) };
+    black_box(&mut y);
+  });
+}
+
+macro_rules! bench_convert {
+  ($name:ident, $src:ty, $dst:ty, $x:expr, $kernel:path) => {
+    #[bench]
+    fn $name(b: &mut Bencher) {
+      unsafe fn kernel(dim: usize, src: *const $src, dst: *mut $dst) {
+        $kernel(dim, src, dst)
+      }
+      let x: Vec<$src> = $x;
+      bench_convert(b, &x, kernel);
+    }
+  };
+}
+
+macro_rules! bench_convert_isa {
+  ($name:ident, $level:expr, $kernel:path) => {
+    #[bench]
+    fn $name(b: &mut Bencher) {
+      if isa::detected_isa_level() < $level {
+        return;
+      }
+      unsafe fn kernel(dim: usize, src: *const f32, dst: *mut u8) {
+        $kernel(dim, src, dst)
+      }
+      bench_convert(b, &inputs_f32(-20.0, 280.0), kernel);
+    }
+  };
+}
+
+bench_convert!(bench_round_clamp_f32_to_u8_scalar, f32, u8, inputs_f32(-20.0, 280.0), round_clamp_f32_to_u8_scalar);
+bench_convert!(bench_round_clamp_f32_to_u8_sse2, f32, u8, inputs_f32(-20.0, 280.0), densearray_kernel_round_clamp_1d_f32_to_u8_sse2);
+bench_convert_isa!(bench_round_clamp_f32_to_u8_sse4, IsaLevel::Sse41, densearray_kernel_round_clamp_1d_f32_to_u8_sse4);
+bench_convert_isa!(bench_round_clamp_f32_to_u8_avx2, IsaLevel::Avx2, densearray_kernel_round_clamp_1d_f32_to_u8_avx2);
+bench_convert_isa!(bench_round_clamp_f32_to_u8_avx512, IsaLevel::Avx512, densearray_kernel_round_clamp_1d_f32_to_u8_avx512);
+bench_convert!(bench_round_clamp_f32_to_u8, f32, u8, inputs_f32(-20.0, 280.0), densearray_kernel_round_clamp_1d_f32_to_u8);
+
+bench_convert!(bench_round_clamp_f32_to_i8_scalar, f32, i8, inputs_f32(-150.0, 150.0), rust::densearray_kernel_round_clamp_1d_f32_to_i8);
+bench_convert!(bench_round_clamp_f32_to_i8, f32, i8, inputs_f32(-150.0, 150.0), densearray_kernel_round_clamp_1d_f32_to_i8);
+bench_convert!(bench_round_clamp_f32_to_u16_scalar, f32, u16, inputs_f32(-100.0, 70000.0), rust::densearray_kernel_round_clamp_1d_f32_to_u16);
+bench_convert!(bench_round_clamp_f32_to_u16, f32, u16, inputs_f32(-100.0, 70000.0), densearray_kernel_round_clamp_1d_f32_to_u16);
+bench_convert!(bench_round_clamp_f32_to_i32_scalar, f32, i32, inputs_f32(-1.0e6, 1.0e6), rust::densearray_kernel_round_clamp_1d_f32_to_i32);
+bench_convert!(bench_round_clamp_f32_to_i32, f32, i32, inputs_f32(-1.0e6, 1.0e6), densearray_kernel_round_clamp_1d_f32_to_i32);
+
+bench_convert!(bench_cast_u8_to_f32_scalar, u8, f32, (0 .. BENCH_DIM).map(|i| i as u8).collect(), rust::densearray_kernel_cast_1d_u8_to_f32);
+bench_convert!(bench_cast_u8_to_f32, u8, f32, (0 .. BENCH_DIM).map(|i| i as u8).collect(), densearray_kernel_cast_1d_u8_to_f32);
+bench_convert!(bench_cast_u16_to_f32_scalar, u16, f32, (0 .. BENCH_DIM).map(|i| i as u16).collect(), rust::densearray_kernel_cast_1d_u16_to_f32);
+bench_convert!(bench_cast_u16_to_f32, u16, f32, (0 .. BENCH_DIM).map(|i| i as u16).collect(), densearray_kernel_cast_1d_u16_to_f32);
+bench_convert!(bench_cast_f32_to_f64_scalar, f32, f64, inputs_f32(-1.0, 1.0), rust::densearray_kernel_cast_1d_f32_to_f64);
+bench_convert!(bench_cast_f32_to_f64, f32, f64, inputs_f32(-1.0, 1.0), densearray_kernel_cast_1d_f32_to_f64);
+bench_convert!(bench_cast_f64_to_f32_scalar, f64, f32, (0 .. BENCH_DIM).map(|i| i as f64 / 3.0).collect(), rust::densearray_kernel_cast_1d_f64_to_f32);
+bench_convert!(bench_cast_f64_to_f32, f64, f32, (0 .. BENCH_DIM).map(|i| i as f64 / 3.0).collect(), densearray_kernel_cast_1d_f64_to_f32);
+
+fn bench_clamp(b: &mut Bencher, kernel: unsafe fn(usize, *const f32, *mut f32, f32, f32)) {
+  let x = inputs_f32(-10.0, 10.0);
+  let mut y = vec![0.0f32; x.len()];
+  b.bytes = x.len() as u64 * 4;
+  b.iter(|| {
+    unsafe { kernel(x.len(), x.as_ptr(), y.as_mut_ptr(), -1.0, 1.0) };
+    black_box(&mut y);
+  });
+}
+
+#[bench]
+fn bench_clamp_f32_scalar(b: &mut Bencher) {
+  bench_clamp(b, clamp_f32_scalar);
+}
+
+#[bench]
+fn bench_clamp_f32_sse2(b: &mut Bencher) {
+  unsafe fn kernel(dim: usize, src: *const f32, dst: *mut f32, lowerf: f32, upperf: f32) {
+    densearray_kernel_clamp_1d_f32_sse2(dim, src, dst, lowerf, upperf)
+  }
+  bench_clamp(b, kernel);
+}
+
+#[bench]
+fn bench_clamp_f32(b: &mut Bencher) {
+  unsafe fn kernel(dim: usize, src: *const f32, dst: *mut f32, lowerf: f32, upperf: f32) {
+    densearray_kernel_clamp_1d_f32(dim, src, dst, lowerf, upperf)
+  }
+  bench_clamp(b, kernel);
+}
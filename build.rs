@@ -6,8 +6,36 @@ use walkdir::{WalkDir};
 use std::env;
 use std::path::{PathBuf};
 
+// The `vector.c` and `reduce_axis.c` kernels are compiled once per ISA level
+// and selected at runtime by `kernels/dispatch.c`. Contraction into FMAs is
+// disabled so that every level computes bitwise identical results.
+const ISA_LEVELS: &'static [(&'static str, &'static [&'static str])] = &[
+  ("sse2",    &[]),
+  ("sse41",   &["-msse4.1"]),
+  ("avx2",    &["-mavx2"]),
+  ("avx512",  &["-mavx512f"]),
+];
+
+fn gcc_config(cc: &str) -> gcc::Config {
+  let mut config = gcc::Config::new();
+  config
+    .compiler(cc)
+    .opt_level(2)
+    .pic(true)
+    .flag("-std=gnu99")
+    .flag("-mfpmath=sse")
+    .flag("-ffp-contract=off")
+    .flag("-fno-strict-aliasing")
+    .flag("-Ikernels");
+  config
+}
+
 fn main() {
   println!("cargo:rerun-if-changed=build.rs");
+  if cfg!(feature = "rust_kernels") {
+    // The kernels are provided by `src/kernels/rust.rs` instead.
+    return;
+  }
   let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
   let out_dir = env::var("OUT_DIR").unwrap();
   let mut kernels_src_dir = PathBuf::from(manifest_dir);
@@ -18,38 +46,43 @@ fn main() {
   }
 
   let cc = env::var("CC").unwrap_or("gcc".to_owned());
-  gcc::Config::new()
-    .compiler(&cc)
-    .opt_level(2)
-    .pic(true)
-    .flag("-std=gnu99")
-    .flag("-mfpmath=sse")
-    .flag("-march=native")
-    .flag("-mno-avx")
-    .flag("-mno-avx2")
-    .flag("-fno-strict-aliasing")
-    .flag("-Ikernels")
+  gcc_config(&cc)
     .file("kernels/cast.c")
+    .file("kernels/dispatch.c")
     .file("kernels/reduce.c")
-    .file("kernels/vector.c")
     .compile("libdensearray_kernels.a");
+  for &(isa, isa_flags) in ISA_LEVELS.iter() {
+    let mut config = gcc_config(&cc);
+    for &flag in isa_flags.iter() {
+      config.flag(flag);
+    }
+    config
+      .flag(&format!("-DDENSEARRAY_ISA={}", isa))
+      .file("kernels/reduce_axis.c")
+      .file("kernels/vector.c")
+      .compile(&format!("libdensearray_kernels_{}.a", isa));
+  }
 
   if cfg!(not(feature = "knl")) {
-    gcc::Config::new()
-      .compiler(&cc)
-      .opt_level(2)
-      .pic(true)
-      .flag("-std=gnu99")
-      .flag("-mfpmath=sse")
-      .flag("-march=native")
-      .flag("-mno-avx")
-      .flag("-mno-avx2")
-      .flag("-fno-strict-aliasing")
+    gcc_config(&cc)
       .flag("-fopenmp")
-      .flag("-Ikernels")
       .flag("-DDENSEARRAY_OMP")
-      .file("kernels/vector.c")
+      .file("kernels/dispatch.c")
+      .file("kernels/threads.c")
       .compile("libdensearray_omp_kernels.a");
+    for &(isa, isa_flags) in ISA_LEVELS.iter() {
+      let mut config = gcc_config(&cc);
+      for &flag in isa_flags.iter() {
+        config.flag(flag);
+      }
+      config
+        .flag("-fopenmp")
+        .flag("-DDENSEARRAY_OMP")
+        .flag(&format!("-DDENSEARRAY_ISA={}", isa))
+        .file("kernels/reduce_axis.c")
+        .file("kernels/vector.c")
+        .compile(&format!("libdensearray_omp_kernels_{}.a", isa));
+    }
   } else {
     /*if cfg!(feature = "mkl_parallel") {
       // XXX: For debugging to check that both features are enabled.
@@ -67,6 +100,8 @@ fn main() {
       .flag("-xMIC-AVX512")
       .flag("-Ikernels")
       .flag("-DDENSEARRAY_OMP")
+      .file("kernels/reduce_axis.c")
+      .file("kernels/threads.c")
       .file("kernels/vector.c")
       .compile("libdensearray_omp_kernels.a");
   }
@@ -1,16 +1,95 @@
-#include <xmmintrin.h>
-#include <smmintrin.h>
+#include "lib.h"
+#include <immintrin.h>
 
 #include <math.h>
 #include <stdint.h>
 #include <stdlib.h>
 
+// All kernels clamp as `min(max(lower, x), upper)` with the packed or scalar
+// SSE/AVX min and max, which return their second operand when either is NaN,
+// so a NaN input clamps to `upper`. The rounding kernels, except for the
+// legacy `_sse2` round-half-up one, round to nearest even; the conversions
+// rely on the default MXCSR rounding mode. The packed loops and their scalar
+// tails produce identical results.
+
+static inline __m128 densearray_clamp_ss(__m128 x, __m128 lower, __m128 upper) {
+  return _mm_min_ss(_mm_max_ss(lower, x), upper);
+}
+
+static inline __m128 densearray_clamp_ps(__m128 x, __m128 lower, __m128 upper) {
+  return _mm_min_ps(_mm_max_ps(lower, x), upper);
+}
+
+static inline int32_t densearray_round_clamp_ss(float x, float lowerf, float upperf) {
+  return _mm_cvtss_si32(densearray_clamp_ss(_mm_set_ss(x), _mm_set_ss(lowerf), _mm_set_ss(upperf)));
+}
+
 void densearray_kernel_cast_1d_u8_to_f32(
     size_t dim,
     const uint8_t *src,
     float *dst)
 {
-  for (size_t idx = 0; idx < dim; idx++) {
+  __m128i zero = _mm_setzero_si128();
+  size_t idx = 0;
+  for (; idx + 16 <= dim; idx += 16) {
+    __m128i x = _mm_loadu_si128((const __m128i *)(src + idx));
+    __m128i x_lo = _mm_unpacklo_epi8(x, zero);
+    __m128i x_hi = _mm_unpackhi_epi8(x, zero);
+    _mm_storeu_ps(dst + idx,      _mm_cvtepi32_ps(_mm_unpacklo_epi16(x_lo, zero)));
+    _mm_storeu_ps(dst + idx + 4,  _mm_cvtepi32_ps(_mm_unpackhi_epi16(x_lo, zero)));
+    _mm_storeu_ps(dst + idx + 8,  _mm_cvtepi32_ps(_mm_unpacklo_epi16(x_hi, zero)));
+    _mm_storeu_ps(dst + idx + 12, _mm_cvtepi32_ps(_mm_unpackhi_epi16(x_hi, zero)));
+  }
+  for (; idx < dim; idx++) {
+    dst[idx] = (float)(src[idx]);
+  }
+}
+
+void densearray_kernel_cast_1d_u16_to_f32(
+    size_t dim,
+    const uint16_t *src,
+    float *dst)
+{
+  __m128i zero = _mm_setzero_si128();
+  size_t idx = 0;
+  for (; idx + 8 <= dim; idx += 8) {
+    __m128i x = _mm_loadu_si128((const __m128i *)(src + idx));
+    _mm_storeu_ps(dst + idx,     _mm_cvtepi32_ps(_mm_unpacklo_epi16(x, zero)));
+    _mm_storeu_ps(dst + idx + 4, _mm_cvtepi32_ps(_mm_unpackhi_epi16(x, zero)));
+  }
+  for (; idx < dim; idx++) {
+    dst[idx] = (float)(src[idx]);
+  }
+}
+
+void densearray_kernel_cast_1d_f32_to_f64(
+    size_t dim,
+    const float *src,
+    double *dst)
+{
+  size_t idx = 0;
+  for (; idx + 4 <= dim; idx += 4) {
+    __m128 x = _mm_loadu_ps(src + idx);
+    _mm_storeu_pd(dst + idx,     _mm_cvtps_pd(x));
+    _mm_storeu_pd(dst + idx + 2, _mm_cvtps_pd(_mm_movehl_ps(x, x)));
+  }
+  for (; idx < dim; idx++) {
+    dst[idx] = (double)(src[idx]);
+  }
+}
+
+void densearray_kernel_cast_1d_f64_to_f32(
+    size_t dim,
+    const double *src,
+    float *dst)
+{
+  size_t idx = 0;
+  for (; idx + 4 <= dim; idx += 4) {
+    __m128 lo = _mm_cvtpd_ps(_mm_loadu_pd(src + idx));
+    __m128 hi = _mm_cvtpd_ps(_mm_loadu_pd(src + idx + 2));
+    _mm_storeu_ps(dst + idx, _mm_movelh_ps(lo, hi));
+  }
+  for (; idx < dim; idx++) {
     dst[idx] = (float)(src[idx]);
   }
 }
@@ -20,37 +99,185 @@ void densearray_kernel_round_clamp_1d_f32_to_u8_sse2(
     const float *src,
     uint8_t *dst)
 {
-  __m128 lower = _mm_set_ss(0.0f);
-  __m128 upper = _mm_set_ss(255.0f);
-  __m128 one_half = _mm_set_ss(0.5f);
-  __m128 one = _mm_set_ss(1.0f);
-  for (size_t idx = 0; idx < dim; idx++) {
-    __m128 x = _mm_load_ss(&src[idx]);
-    __m128 y = _mm_min_ss(_mm_max_ss(lower, x), upper);
-    __m128 u = _mm_add_ps(y, one_half);
-    __m128 v = _mm_cvtepi32_ps(_mm_cvttps_epi32(u));
-    // XXX: Can ignore round-to-zero issues since `v` is always in the range
-    // [0.0f, 255.0f].
-    /*__m128 w = _mm_sub_ps(v, _mm_and_ps(_mm_cmplt_ps(u, v), one));*/
-    dst[idx] = (uint8_t)(_mm_cvtss_f32(v));
+  // XXX: This kernel rounds half up, as `trunc(y + 0.5)`; this is exact since
+  // `y` is always in the range [0.0f, 255.0f].
+  __m128 lower = _mm_set1_ps(0.0f);
+  __m128 upper = _mm_set1_ps(255.0f);
+  __m128 one_half = _mm_set1_ps(0.5f);
+  size_t idx = 0;
+  for (; idx + 16 <= dim; idx += 16) {
+    __m128i v0 = _mm_cvttps_epi32(_mm_add_ps(densearray_clamp_ps(_mm_loadu_ps(src + idx),      lower, upper), one_half));
+    __m128i v1 = _mm_cvttps_epi32(_mm_add_ps(densearray_clamp_ps(_mm_loadu_ps(src + idx + 4),  lower, upper), one_half));
+    __m128i v2 = _mm_cvttps_epi32(_mm_add_ps(densearray_clamp_ps(_mm_loadu_ps(src + idx + 8),  lower, upper), one_half));
+    __m128i v3 = _mm_cvttps_epi32(_mm_add_ps(densearray_clamp_ps(_mm_loadu_ps(src + idx + 12), lower, upper), one_half));
+    __m128i w = _mm_packus_epi16(_mm_packs_epi32(v0, v1), _mm_packs_epi32(v2, v3));
+    _mm_storeu_si128((__m128i *)(dst + idx), w);
+  }
+  for (; idx < dim; idx++) {
+    __m128 y = densearray_clamp_ss(_mm_load_ss(&src[idx]), lower, upper);
+    dst[idx] = (uint8_t)(_mm_cvttss_si32(_mm_add_ss(y, one_half)));
   }
 }
 
+__attribute__((target("sse4.1")))
 void densearray_kernel_round_clamp_1d_f32_to_u8_sse4(
     size_t dim,
     const float *src,
     uint8_t *dst)
 {
-  __m128 lower = _mm_set_ss(0.0f);
-  __m128 upper = _mm_set_ss(255.0f);
-  for (size_t idx = 0; idx < dim; idx++) {
-    __m128 x = _mm_load_ss(&src[idx]);
-    __m128 y = _mm_min_ss(_mm_max_ss(lower, x), upper);
+  __m128 lower = _mm_set1_ps(0.0f);
+  __m128 upper = _mm_set1_ps(255.0f);
+  size_t idx = 0;
+  for (; idx + 16 <= dim; idx += 16) {
+    __m128i v0 = _mm_cvtps_epi32(_mm_round_ps(densearray_clamp_ps(_mm_loadu_ps(src + idx),      lower, upper), _MM_FROUND_NINT));
+    __m128i v1 = _mm_cvtps_epi32(_mm_round_ps(densearray_clamp_ps(_mm_loadu_ps(src + idx + 4),  lower, upper), _MM_FROUND_NINT));
+    __m128i v2 = _mm_cvtps_epi32(_mm_round_ps(densearray_clamp_ps(_mm_loadu_ps(src + idx + 8),  lower, upper), _MM_FROUND_NINT));
+    __m128i v3 = _mm_cvtps_epi32(_mm_round_ps(densearray_clamp_ps(_mm_loadu_ps(src + idx + 12), lower, upper), _MM_FROUND_NINT));
+    __m128i w = _mm_packus_epi16(_mm_packs_epi32(v0, v1), _mm_packs_epi32(v2, v3));
+    _mm_storeu_si128((__m128i *)(dst + idx), w);
+  }
+  for (; idx < dim; idx++) {
+    __m128 y = densearray_clamp_ss(_mm_load_ss(&src[idx]), lower, upper);
     __m128 u = _mm_round_ps(y, _MM_FROUND_NINT);
     dst[idx] = (uint8_t)(_mm_cvtss_f32(u));
   }
 }
 
+__attribute__((target("avx2")))
+void densearray_kernel_round_clamp_1d_f32_to_u8_avx2(
+    size_t dim,
+    const float *src,
+    uint8_t *dst)
+{
+  __m256 lower = _mm256_set1_ps(0.0f);
+  __m256 upper = _mm256_set1_ps(255.0f);
+  size_t idx = 0;
+  for (; idx + 16 <= dim; idx += 16) {
+    __m256i v0 = _mm256_cvtps_epi32(_mm256_min_ps(_mm256_max_ps(lower, _mm256_loadu_ps(src + idx)),     upper));
+    __m256i v1 = _mm256_cvtps_epi32(_mm256_min_ps(_mm256_max_ps(lower, _mm256_loadu_ps(src + idx + 8)), upper));
+    // `_mm256_packs_epi32` packs within 128-bit lanes; restore the order
+    // before the final pack to bytes.
+    __m256i w = _mm256_permute4x64_epi64(_mm256_packs_epi32(v0, v1), 0xd8);
+    __m128i u = _mm_packus_epi16(_mm256_castsi256_si128(w), _mm256_extracti128_si256(w, 1));
+    _mm_storeu_si128((__m128i *)(dst + idx), u);
+  }
+  for (; idx < dim; idx++) {
+    dst[idx] = (uint8_t)(densearray_round_clamp_ss(src[idx], 0.0f, 255.0f));
+  }
+}
+
+__attribute__((target("avx512f")))
+void densearray_kernel_round_clamp_1d_f32_to_u8_avx512(
+    size_t dim,
+    const float *src,
+    uint8_t *dst)
+{
+  __m512 lower = _mm512_set1_ps(0.0f);
+  __m512 upper = _mm512_set1_ps(255.0f);
+  size_t idx = 0;
+  for (; idx + 16 <= dim; idx += 16) {
+    __m512 y = _mm512_min_ps(_mm512_max_ps(lower, _mm512_loadu_ps(src + idx)), upper);
+    __m512i v = _mm512_cvt_roundps_epi32(y, _MM_FROUND_TO_NEAREST_INT | _MM_FROUND_NO_EXC);
+    _mm_storeu_si128((__m128i *)(dst + idx), _mm512_cvtepi32_epi8(v));
+  }
+  for (; idx < dim; idx++) {
+    dst[idx] = (uint8_t)(densearray_round_clamp_ss(src[idx], 0.0f, 255.0f));
+  }
+}
+
+void densearray_kernel_round_clamp_1d_f32_to_u8(
+    size_t dim,
+    const float *src,
+    uint8_t *dst)
+{
+  switch (densearray_isa_level()) {
+    case DENSEARRAY_ISA_AVX512:
+      densearray_kernel_round_clamp_1d_f32_to_u8_avx512(dim, src, dst);
+      return;
+    case DENSEARRAY_ISA_AVX2:
+      densearray_kernel_round_clamp_1d_f32_to_u8_avx2(dim, src, dst);
+      return;
+    case DENSEARRAY_ISA_SSE41:
+      densearray_kernel_round_clamp_1d_f32_to_u8_sse4(dim, src, dst);
+      return;
+  }
+  __m128 lower = _mm_set1_ps(0.0f);
+  __m128 upper = _mm_set1_ps(255.0f);
+  size_t idx = 0;
+  for (; idx + 16 <= dim; idx += 16) {
+    __m128i v0 = _mm_cvtps_epi32(densearray_clamp_ps(_mm_loadu_ps(src + idx),      lower, upper));
+    __m128i v1 = _mm_cvtps_epi32(densearray_clamp_ps(_mm_loadu_ps(src + idx + 4),  lower, upper));
+    __m128i v2 = _mm_cvtps_epi32(densearray_clamp_ps(_mm_loadu_ps(src + idx + 8),  lower, upper));
+    __m128i v3 = _mm_cvtps_epi32(densearray_clamp_ps(_mm_loadu_ps(src + idx + 12), lower, upper));
+    __m128i w = _mm_packus_epi16(_mm_packs_epi32(v0, v1), _mm_packs_epi32(v2, v3));
+    _mm_storeu_si128((__m128i *)(dst + idx), w);
+  }
+  for (; idx < dim; idx++) {
+    dst[idx] = (uint8_t)(densearray_round_clamp_ss(src[idx], 0.0f, 255.0f));
+  }
+}
+
+void densearray_kernel_round_clamp_1d_f32_to_i8(
+    size_t dim,
+    const float *src,
+    int8_t *dst)
+{
+  __m128 lower = _mm_set1_ps(-128.0f);
+  __m128 upper = _mm_set1_ps(127.0f);
+  size_t idx = 0;
+  for (; idx + 16 <= dim; idx += 16) {
+    __m128i v0 = _mm_cvtps_epi32(densearray_clamp_ps(_mm_loadu_ps(src + idx),      lower, upper));
+    __m128i v1 = _mm_cvtps_epi32(densearray_clamp_ps(_mm_loadu_ps(src + idx + 4),  lower, upper));
+    __m128i v2 = _mm_cvtps_epi32(densearray_clamp_ps(_mm_loadu_ps(src + idx + 8),  lower, upper));
+    __m128i v3 = _mm_cvtps_epi32(densearray_clamp_ps(_mm_loadu_ps(src + idx + 12), lower, upper));
+    __m128i w = _mm_packs_epi16(_mm_packs_epi32(v0, v1), _mm_packs_epi32(v2, v3));
+    _mm_storeu_si128((__m128i *)(dst + idx), w);
+  }
+  for (; idx < dim; idx++) {
+    dst[idx] = (int8_t)(densearray_round_clamp_ss(src[idx], -128.0f, 127.0f));
+  }
+}
+
+void densearray_kernel_round_clamp_1d_f32_to_u16(
+    size_t dim,
+    const float *src,
+    uint16_t *dst)
+{
+  // SSE2 has no unsigned 32-to-16 bit pack, so pack with a bias of 2^15.
+  __m128 lower = _mm_set1_ps(0.0f);
+  __m128 upper = _mm_set1_ps(65535.0f);
+  __m128i bias32 = _mm_set1_epi32(32768);
+  __m128i bias16 = _mm_set1_epi16((short)(0x8000));
+  size_t idx = 0;
+  for (; idx + 8 <= dim; idx += 8) {
+    __m128i v0 = _mm_sub_epi32(_mm_cvtps_epi32(densearray_clamp_ps(_mm_loadu_ps(src + idx),     lower, upper)), bias32);
+    __m128i v1 = _mm_sub_epi32(_mm_cvtps_epi32(densearray_clamp_ps(_mm_loadu_ps(src + idx + 4), lower, upper)), bias32);
+    __m128i w = _mm_xor_si128(_mm_packs_epi32(v0, v1), bias16);
+    _mm_storeu_si128((__m128i *)(dst + idx), w);
+  }
+  for (; idx < dim; idx++) {
+    dst[idx] = (uint16_t)(densearray_round_clamp_ss(src[idx], 0.0f, 65535.0f));
+  }
+}
+
+void densearray_kernel_round_clamp_1d_f32_to_i32(
+    size_t dim,
+    const float *src,
+    int32_t *dst)
+{
+  // The upper bound is the largest float below 2^31.
+  __m128 lower = _mm_set1_ps(-2147483648.0f);
+  __m128 upper = _mm_set1_ps(2147483520.0f);
+  size_t idx = 0;
+  for (; idx + 4 <= dim; idx += 4) {
+    __m128i v = _mm_cvtps_epi32(densearray_clamp_ps(_mm_loadu_ps(src + idx), lower, upper));
+    _mm_storeu_si128((__m128i *)(dst + idx), v);
+  }
+  for (; idx < dim; idx++) {
+    dst[idx] = densearray_round_clamp_ss(src[idx], -2147483648.0f, 2147483520.0f);
+  }
+}
+
 void densearray_kernel_clamp_1d_f32_sse2(
     size_t dim,
     const float *src,
@@ -58,11 +285,69 @@ void densearray_kernel_clamp_1d_f32_sse2(
     float lowerf,
     float upperf)
 {
-  __m128 lower = _mm_set_ss(lowerf);
-  __m128 upper = _mm_set_ss(upperf);
-  for (size_t idx = 0; idx < dim; idx++) {
-    __m128 x = _mm_load_ss(&src[idx]);
-    __m128 y = _mm_min_ss(_mm_max_ss(lower, x), upper);
-    _mm_store_ss(&dst[idx], y);
+  __m128 lower = _mm_set1_ps(lowerf);
+  __m128 upper = _mm_set1_ps(upperf);
+  size_t idx = 0;
+  for (; idx + 4 <= dim; idx += 4) {
+    _mm_storeu_ps(dst + idx, densearray_clamp_ps(_mm_loadu_ps(src + idx), lower, upper));
+  }
+  for (; idx < dim; idx++) {
+    _mm_store_ss(&dst[idx], densearray_clamp_ss(_mm_load_ss(&src[idx]), lower, upper));
+  }
+}
+
+__attribute__((target("avx2")))
+void densearray_kernel_clamp_1d_f32_avx2(
+    size_t dim,
+    const float *src,
+    float *dst,
+    float lowerf,
+    float upperf)
+{
+  __m256 lower = _mm256_set1_ps(lowerf);
+  __m256 upper = _mm256_set1_ps(upperf);
+  size_t idx = 0;
+  for (; idx + 8 <= dim; idx += 8) {
+    _mm256_storeu_ps(dst + idx, _mm256_min_ps(_mm256_max_ps(lower, _mm256_loadu_ps(src + idx)), upper));
+  }
+  for (; idx < dim; idx++) {
+    _mm_store_ss(&dst[idx], densearray_clamp_ss(_mm_load_ss(&src[idx]), _mm_set_ss(lowerf), _mm_set_ss(upperf)));
+  }
+}
+
+__attribute__((target("avx512f")))
+void densearray_kernel_clamp_1d_f32_avx512(
+    size_t dim,
+    const float *src,
+    float *dst,
+    float lowerf,
+    float upperf)
+{
+  __m512 lower = _mm512_set1_ps(lowerf);
+  __m512 upper = _mm512_set1_ps(upperf);
+  size_t idx = 0;
+  for (; idx + 16 <= dim; idx += 16) {
+    _mm512_storeu_ps(dst + idx, _mm512_min_ps(_mm512_max_ps(lower, _mm512_loadu_ps(src + idx)), upper));
+  }
+  for (; idx < dim; idx++) {
+    _mm_store_ss(&dst[idx], densearray_clamp_ss(_mm_load_ss(&src[idx]), _mm_set_ss(lowerf), _mm_set_ss(upperf)));
+  }
+}
+
+void densearray_kernel_clamp_1d_f32(
+    size_t dim,
+    const float *src,
+    float *dst,
+    float lowerf,
+    float upperf)
+{
+  switch (densearray_isa_level()) {
+    case DENSEARRAY_ISA_AVX512:
+      densearray_kernel_clamp_1d_f32_avx512(dim, src, dst, lowerf, upperf);
+      return;
+    case DENSEARRAY_ISA_AVX2:
+      densearray_kernel_clamp_1d_f32_avx2(dim, src, dst, lowerf, upperf);
+      return;
   }
+  densearray_kernel_clamp_1d_f32_sse2(dim, src, dst, lowerf, upperf);
 }
@@ -0,0 +1,129 @@
+#include "lib.h"
+#include <stdint.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Runtime selection between the per-ISA builds of `vector.c` and
+// `reduce_axis.c`. The level is detected once from CPUID; it can be lowered
+// with the `DENSEARRAY_ISA` environment variable (`sse2`, `sse41`, `avx2`, or
+// `avx512`) or with `densearray_force_isa_level`, but never raised above what
+// the CPU supports. A forced level of -1 restores the detected one.
+//
+// This file is compiled once for the serial and once for the OpenMP kernels,
+// so the level state is only defined in the serial build.
+
+#ifndef DENSEARRAY_OMP
+
+static int densearray_isa_detected = -1;
+static int densearray_isa_forced = -1;
+
+static int densearray_parse_isa_level(const char *s) {
+  if (s == NULL) {
+    return -1;
+  } else if (strcmp(s, "sse2") == 0) {
+    return DENSEARRAY_ISA_SSE2;
+  } else if (strcmp(s, "sse41") == 0) {
+    return DENSEARRAY_ISA_SSE41;
+  } else if (strcmp(s, "avx2") == 0) {
+    return DENSEARRAY_ISA_AVX2;
+  } else if (strcmp(s, "avx512") == 0) {
+    return DENSEARRAY_ISA_AVX512;
+  }
+  return -1;
+}
+
+int densearray_detected_isa_level(void) {
+  int level = __atomic_load_n(&densearray_isa_detected, __ATOMIC_RELAXED);
+  if (level < 0) {
+    __builtin_cpu_init();
+    if (__builtin_cpu_supports("avx512f")) {
+      level = DENSEARRAY_ISA_AVX512;
+    } else if (__builtin_cpu_supports("avx2")) {
+      level = DENSEARRAY_ISA_AVX2;
+    } else if (__builtin_cpu_supports("sse4.1")) {
+      level = DENSEARRAY_ISA_SSE41;
+    } else {
+      level = DENSEARRAY_ISA_SSE2;
+    }
+    __atomic_store_n(&densearray_isa_detected, level, __ATOMIC_RELAXED);
+    int env_level = densearray_parse_isa_level(getenv("DENSEARRAY_ISA"));
+    if (env_level >= 0) {
+      int expected = -1;
+      __atomic_compare_exchange_n(&densearray_isa_forced, &expected, env_level, 0, __ATOMIC_RELAXED, __ATOMIC_RELAXED);
+    }
+  }
+  return level;
+}
+
+int densearray_isa_level(void) {
+  int detected = densearray_detected_isa_level();
+  int forced = __atomic_load_n(&densearray_isa_forced, __ATOMIC_RELAXED);
+  if (forced >= 0 && forced < detected) {
+    return forced;
+  }
+  return detected;
+}
+
+void densearray_force_isa_level(int level) {
+  densearray_detected_isa_level();
+  __atomic_store_n(&densearray_isa_forced, level, __ATOMIC_RELAXED);
+}
+
+#endif
+
+#define DENSEARRAY_DISPATCH(name, params, args) \
+void DENSEARRAY_SYMBOL(name ## _sse2) params; \
+void DENSEARRAY_SYMBOL(name ## _sse41) params; \
+void DENSEARRAY_SYMBOL(name ## _avx2) params; \
+void DENSEARRAY_SYMBOL(name ## _avx512) params; \
+void DENSEARRAY_SYMBOL(name) params \
+{ \
+  switch (densearray_isa_level()) { \
+    case DENSEARRAY_ISA_AVX512: \
+      DENSEARRAY_SYMBOL(name ## _avx512) args; \
+      break; \
+    case DENSEARRAY_ISA_AVX2: \
+      DENSEARRAY_SYMBOL(name ## _avx2) args; \
+      break; \
+    case DENSEARRAY_ISA_SSE41: \
+      DENSEARRAY_SYMBOL(name ## _sse41) args; \
+      break; \
+    default: \
+      DENSEARRAY_SYMBOL(name ## _sse2) args; \
+  } \
+}
+
+#define DENSEARRAY_DISPATCH_VECTOR(T, suffix) \
+DENSEARRAY_DISPATCH(set_scalar_ ## suffix, (T *dst, size_t dim, T c), (dst, dim, c)) \
+DENSEARRAY_DISPATCH(copy_ ## suffix, (T *dst, size_t dim, const T *src), (dst, dim, src)) \
+DENSEARRAY_DISPATCH(cast_u8_to_ ## suffix, (T *dst, size_t dim, const uint8_t *src), (dst, dim, src)) \
+DENSEARRAY_DISPATCH(square_ ## suffix, (T *dst, size_t dim), (dst, dim)) \
+DENSEARRAY_DISPATCH(cube_ ## suffix, (T *dst, size_t dim), (dst, dim)) \
+DENSEARRAY_DISPATCH(sqrt_ ## suffix, (T *dst, size_t dim), (dst, dim)) \
+DENSEARRAY_DISPATCH(reciprocal_ ## suffix, (T *dst, size_t dim), (dst, dim)) \
+DENSEARRAY_DISPATCH(add_scalar_ ## suffix, (T *dst, size_t dim, T c), (dst, dim, c)) \
+DENSEARRAY_DISPATCH(scale_ ## suffix, (T *dst, size_t dim, T c), (dst, dim, c)) \
+DENSEARRAY_DISPATCH(div_scalar_ ## suffix, (T *dst, size_t dim, T c), (dst, dim, c)) \
+DENSEARRAY_DISPATCH(elem_mult_ ## suffix, (T *dst, size_t dim, const T *xs), (dst, dim, xs)) \
+DENSEARRAY_DISPATCH(elem_div_ ## suffix, (T *dst, size_t dim, const T *xs), (dst, dim, xs)) \
+DENSEARRAY_DISPATCH(elem_ldiv_ ## suffix, (T *dst, size_t dim, const T *xs), (dst, dim, xs)) \
+DENSEARRAY_DISPATCH(vector_add_ ## suffix, (T *dst, size_t dim, const T *xs, T c), (dst, dim, xs, c)) \
+DENSEARRAY_DISPATCH(vector_average_ ## suffix, (T *dst, size_t dim, const T *xs, T c), (dst, dim, xs, c)) \
+DENSEARRAY_DISPATCH(chunk_sum_ ## suffix, (const T *x, size_t dim, size_t incx, size_t chunk_sz, T *partials), (x, dim, incx, chunk_sz, partials)) \
+DENSEARRAY_DISPATCH(chunk_abs_sum_ ## suffix, (const T *x, size_t dim, size_t incx, size_t chunk_sz, T *partials), (x, dim, incx, chunk_sz, partials)) \
+DENSEARRAY_DISPATCH(chunk_square_sum_ ## suffix, (const T *x, size_t dim, size_t incx, size_t chunk_sz, T *partials), (x, dim, incx, chunk_sz, partials)) \
+DENSEARRAY_DISPATCH(chunk_inner_prod_ ## suffix, (const T *x, size_t dim, size_t incx, const T *y, size_t incy, size_t chunk_sz, T *partials), (x, dim, incx, y, incy, chunk_sz, partials))
+
+#define DENSEARRAY_DISPATCH_REDUCE_AXIS(T, suffix) \
+DENSEARRAY_DISPATCH(reduce_axis_sum_ ## suffix, (size_t inner_dim, size_t red_dim, size_t outer_dim, const T *x, size_t x_inc, size_t x_red_inc, size_t x_outer_inc, T *y, size_t y_inc, size_t y_outer_inc), (inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc)) \
+DENSEARRAY_DISPATCH(reduce_axis_mean_ ## suffix, (size_t inner_dim, size_t red_dim, size_t outer_dim, const T *x, size_t x_inc, size_t x_red_inc, size_t x_outer_inc, T *y, size_t y_inc, size_t y_outer_inc), (inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc)) \
+DENSEARRAY_DISPATCH(reduce_axis_max_ ## suffix, (size_t inner_dim, size_t red_dim, size_t outer_dim, const T *x, size_t x_inc, size_t x_red_inc, size_t x_outer_inc, T *y, size_t y_inc, size_t y_outer_inc), (inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc)) \
+DENSEARRAY_DISPATCH(reduce_axis_min_ ## suffix, (size_t inner_dim, size_t red_dim, size_t outer_dim, const T *x, size_t x_inc, size_t x_red_inc, size_t x_outer_inc, T *y, size_t y_inc, size_t y_outer_inc), (inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc)) \
+DENSEARRAY_DISPATCH(reduce_axis_argmax_ ## suffix, (size_t inner_dim, size_t red_dim, size_t outer_dim, const T *x, size_t x_inc, size_t x_red_inc, size_t x_outer_inc, uint32_t *y, size_t y_inc, size_t y_outer_inc), (inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc)) \
+DENSEARRAY_DISPATCH(reduce_axis_argmin_ ## suffix, (size_t inner_dim, size_t red_dim, size_t outer_dim, const T *x, size_t x_inc, size_t x_red_inc, size_t x_outer_inc, uint32_t *y, size_t y_inc, size_t y_outer_inc), (inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc))
+
+DENSEARRAY_DISPATCH(set_scalar_i32, (int32_t *dst, size_t dim, int32_t c), (dst, dim, c))
+DENSEARRAY_DISPATCH_VECTOR(float, f32)
+DENSEARRAY_DISPATCH_VECTOR(double, f64)
+DENSEARRAY_DISPATCH_REDUCE_AXIS(float, f32)
+DENSEARRAY_DISPATCH_REDUCE_AXIS(double, f64)
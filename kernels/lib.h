@@ -1,10 +1,37 @@
 #ifndef __DENSEARRAY_KERNELS_LIB_H__
 #define __DENSEARRAY_KERNELS_LIB_H__
 
+#define DENSEARRAY_PASTE_(a, b) a ## b
+#define DENSEARRAY_PASTE(a, b) DENSEARRAY_PASTE_(a, b)
+
 #ifndef DENSEARRAY_OMP
-#define DENSEARRAY_SYMBOL(name) densearray_ ## name
+#define DENSEARRAY_PREFIX densearray_
+#else
+#define DENSEARRAY_PREFIX densearray_omp_
+#endif
+
+// Kernels that are compiled once per ISA level (see `build.rs`) get the level
+// appended to their symbol, e.g. `densearray_square_f32_avx2`; the unsuffixed
+// symbol is then defined by the runtime dispatcher in `dispatch.c`.
+#ifndef DENSEARRAY_ISA
+#define DENSEARRAY_SYMBOL(name) DENSEARRAY_PASTE(DENSEARRAY_PREFIX, name)
 #else
-#define DENSEARRAY_SYMBOL(name) densearray_omp_ ## name
+#define DENSEARRAY_SYMBOL(name) DENSEARRAY_PASTE(DENSEARRAY_PREFIX, DENSEARRAY_PASTE(name, DENSEARRAY_PASTE(_, DENSEARRAY_ISA)))
+#endif
+
+#define DENSEARRAY_ISA_SSE2     0
+#define DENSEARRAY_ISA_SSE41    1
+#define DENSEARRAY_ISA_AVX2     2
+#define DENSEARRAY_ISA_AVX512   3
+
+int densearray_detected_isa_level(void);
+int densearray_isa_level(void);
+void densearray_force_isa_level(int level);
+
+#ifdef DENSEARRAY_OMP
+void densearray_omp_set_num_threads(int num_threads);
+int densearray_omp_get_num_threads_setting(void);
+int densearray_omp_num_threads(void);
 #endif
 
 #endif
@@ -0,0 +1,158 @@
+#include "lib.h"
+#include <stdint.h>
+#include <stdlib.h>
+
+// The axis reduction kernels operate on a canonical 3-axis layout:
+// `x` has shape (inner_dim, red_dim, outer_dim) and is reduced along its
+// middle axis into `y` with shape (inner_dim, outer_dim). All reductions
+// assume `red_dim >= 1`.
+
+#define DENSEARRAY_AXIS_BLOCK_SZ 64
+
+#define DENSEARRAY_AXIS_REDUCE_KERNEL(name, T, ACCUMULATE, FINALIZE) \
+void DENSEARRAY_SYMBOL(name)( \
+    size_t inner_dim, \
+    size_t red_dim, \
+    size_t outer_dim, \
+    const T *x, \
+    size_t x_inc, \
+    size_t x_red_inc, \
+    size_t x_outer_inc, \
+    T *y, \
+    size_t y_inc, \
+    size_t y_outer_inc) \
+{ \
+  T inv_n = (T)(1) / (T)(red_dim); \
+  (void)(inv_n); \
+  if (x_red_inc == 1) { \
+    _Pragma("omp parallel for collapse(2)") \
+    for (size_t k = 0; k < outer_dim; k++) { \
+      for (size_t i = 0; i < inner_dim; i++) { \
+        const T *xp = x + i * x_inc + k * x_outer_inc; \
+        T acc = xp[0]; \
+        for (size_t r = 1; r < red_dim; r++) { \
+          T x_r = xp[r]; \
+          ACCUMULATE; \
+        } \
+        FINALIZE; \
+        y[i * y_inc + k * y_outer_inc] = acc; \
+      } \
+    } \
+  } else if (x_inc == 1 && y_inc == 1) { \
+    size_t num_blks = (inner_dim + DENSEARRAY_AXIS_BLOCK_SZ - 1) / DENSEARRAY_AXIS_BLOCK_SZ; \
+    _Pragma("omp parallel for collapse(2)") \
+    for (size_t k = 0; k < outer_dim; k++) { \
+      for (size_t blk = 0; blk < num_blks; blk++) { \
+        size_t lo = blk * DENSEARRAY_AXIS_BLOCK_SZ; \
+        size_t hi = lo + DENSEARRAY_AXIS_BLOCK_SZ; \
+        if (hi > inner_dim) { \
+          hi = inner_dim; \
+        } \
+        const T *xp = x + k * x_outer_inc; \
+        T *yp = y + k * y_outer_inc; \
+        for (size_t i = lo; i < hi; i++) { \
+          yp[i] = xp[i]; \
+        } \
+        for (size_t r = 1; r < red_dim; r++) { \
+          const T *xr = xp + r * x_red_inc; \
+          for (size_t i = lo; i < hi; i++) { \
+            T acc = yp[i]; \
+            T x_r = xr[i]; \
+            ACCUMULATE; \
+            yp[i] = acc; \
+          } \
+        } \
+        for (size_t i = lo; i < hi; i++) { \
+          T acc = yp[i]; \
+          FINALIZE; \
+          yp[i] = acc; \
+        } \
+      } \
+    } \
+  } else { \
+    _Pragma("omp parallel for collapse(2)") \
+    for (size_t k = 0; k < outer_dim; k++) { \
+      for (size_t i = 0; i < inner_dim; i++) { \
+        const T *xp = x + i * x_inc + k * x_outer_inc; \
+        T acc = xp[0]; \
+        for (size_t r = 1; r < red_dim; r++) { \
+          T x_r = xp[r * x_red_inc]; \
+          ACCUMULATE; \
+        } \
+        FINALIZE; \
+        y[i * y_inc + k * y_outer_inc] = acc; \
+      } \
+    } \
+  } \
+}
+
+#define DENSEARRAY_AXIS_ARG_KERNEL(name, T, CMP) \
+void DENSEARRAY_SYMBOL(name)( \
+    size_t inner_dim, \
+    size_t red_dim, \
+    size_t outer_dim, \
+    const T *x, \
+    size_t x_inc, \
+    size_t x_red_inc, \
+    size_t x_outer_inc, \
+    uint32_t *y, \
+    size_t y_inc, \
+    size_t y_outer_inc) \
+{ \
+  _Pragma("omp parallel for collapse(2)") \
+  for (size_t k = 0; k < outer_dim; k++) { \
+    for (size_t i = 0; i < inner_dim; i++) { \
+      const T *xp = x + i * x_inc + k * x_outer_inc; \
+      T best = xp[0]; \
+      uint32_t best_r = 0; \
+      for (size_t r = 1; r < red_dim; r++) { \
+        T x_r = xp[r * x_red_inc]; \
+        if (x_r CMP best) { \
+          best = x_r; \
+          best_r = (uint32_t)(r); \
+        } \
+      } \
+      y[i * y_inc + k * y_outer_inc] = best_r; \
+    } \
+  } \
+}
+
+DENSEARRAY_AXIS_REDUCE_KERNEL(reduce_axis_sum_f32, float,
+    acc += x_r,
+    (void)(acc))
+
+DENSEARRAY_AXIS_REDUCE_KERNEL(reduce_axis_mean_f32, float,
+    acc += x_r,
+    acc *= inv_n)
+
+DENSEARRAY_AXIS_REDUCE_KERNEL(reduce_axis_max_f32, float,
+    acc = x_r > acc ? x_r : acc,
+    (void)(acc))
+
+DENSEARRAY_AXIS_REDUCE_KERNEL(reduce_axis_min_f32, float,
+    acc = x_r < acc ? x_r : acc,
+    (void)(acc))
+
+DENSEARRAY_AXIS_ARG_KERNEL(reduce_axis_argmax_f32, float, >)
+
+DENSEARRAY_AXIS_ARG_KERNEL(reduce_axis_argmin_f32, float, <)
+
+DENSEARRAY_AXIS_REDUCE_KERNEL(reduce_axis_sum_f64, double,
+    acc += x_r,
+    (void)(acc))
+
+DENSEARRAY_AXIS_REDUCE_KERNEL(reduce_axis_mean_f64, double,
+    acc += x_r,
+    acc *= inv_n)
+
+DENSEARRAY_AXIS_REDUCE_KERNEL(reduce_axis_max_f64, double,
+    acc = x_r > acc ? x_r : acc,
+    (void)(acc))
+
+DENSEARRAY_AXIS_REDUCE_KERNEL(reduce_axis_min_f64, double,
+    acc = x_r < acc ? x_r : acc,
+    (void)(acc))
+
+DENSEARRAY_AXIS_ARG_KERNEL(reduce_axis_argmax_f64, double, >)
+
+DENSEARRAY_AXIS_ARG_KERNEL(reduce_axis_argmin_f64, double, <)
@@ -0,0 +1,22 @@
+#include "lib.h"
+#include <omp.h>
+
+// Thread count for the OpenMP kernels. `omp_set_num_threads` only affects
+// parallel regions started from the calling thread, so instead every
+// `parallel for` in the kernels asks for `densearray_omp_num_threads()`
+// threads. A setting of 0 defers to the OpenMP runtime default.
+
+static int densearray_omp_threads = 0;
+
+void densearray_omp_set_num_threads(int num_threads) {
+  __atomic_store_n(&densearray_omp_threads, num_threads > 0 ? num_threads : 0, __ATOMIC_RELAXED);
+}
+
+int densearray_omp_get_num_threads_setting(void) {
+  return __atomic_load_n(&densearray_omp_threads, __ATOMIC_RELAXED);
+}
+
+int densearray_omp_num_threads(void) {
+  int num_threads = __atomic_load_n(&densearray_omp_threads, __ATOMIC_RELAXED);
+  return num_threads > 0 ? num_threads : omp_get_max_threads();
+}
@@ -8,7 +8,7 @@ void DENSEARRAY_SYMBOL(set_scalar_f32)(
     size_t dim,
     float c)
 {
-  #pragma omp parallel for
+  #pragma omp parallel for num_threads(densearray_omp_num_threads())
   for (size_t idx = 0; idx < dim; idx++) {
     dst[idx] = c;
   }
@@ -19,7 +19,7 @@ void DENSEARRAY_SYMBOL(set_scalar_i32)(
     size_t dim,
     int32_t c)
 {
-  #pragma omp parallel for
+  #pragma omp parallel for num_threads(densearray_omp_num_threads())
   for (size_t idx = 0; idx < dim; idx++) {
     dst[idx] = c;
   }
@@ -30,7 +30,7 @@ void DENSEARRAY_SYMBOL(copy_f32)(
     size_t dim,
     const float *src)
 {
-  #pragma omp parallel for
+  #pragma omp parallel for num_threads(densearray_omp_num_threads())
   for (size_t idx = 0; idx < dim; idx++) {
     dst[idx] = src[idx];
   }
@@ -41,7 +41,7 @@ void DENSEARRAY_SYMBOL(cast_u8_to_f32)(
     size_t dim,
     const uint8_t *src)
 {
-  #pragma omp parallel for
+  #pragma omp parallel for num_threads(densearray_omp_num_threads())
   for (size_t idx = 0; idx < dim; idx++) {
     dst[idx] = (float)(src[idx]);
   }
@@ -51,7 +51,7 @@ void DENSEARRAY_SYMBOL(square_f32)(
     float *dst,
     size_t dim)
 {
-  #pragma omp parallel for
+  #pragma omp parallel for num_threads(densearray_omp_num_threads())
   for (size_t idx = 0; idx < dim; idx++) {
     float y = dst[idx];
     dst[idx] = y * y;
@@ -62,7 +62,7 @@ void DENSEARRAY_SYMBOL(cube_f32)(
     float *dst,
     size_t dim)
 {
-  #pragma omp parallel for
+  #pragma omp parallel for num_threads(densearray_omp_num_threads())
   for (size_t idx = 0; idx < dim; idx++) {
     float y = dst[idx];
     dst[idx] = y * y * y;
@@ -73,7 +73,7 @@ void DENSEARRAY_SYMBOL(sqrt_f32)(
     float *dst,
     size_t dim)
 {
-  #pragma omp parallel for
+  #pragma omp parallel for num_threads(densearray_omp_num_threads())
   for (size_t idx = 0; idx < dim; idx++) {
     float y = dst[idx];
     dst[idx] = sqrtf(y);
@@ -84,7 +84,7 @@ void DENSEARRAY_SYMBOL(reciprocal_f32)(
     float *dst,
     size_t dim)
 {
-  #pragma omp parallel for
+  #pragma omp parallel for num_threads(densearray_omp_num_threads())
   for (size_t idx = 0; idx < dim; idx++) {
     float y = dst[idx];
     dst[idx] = 1.0f / y;
@@ -96,7 +96,7 @@ void DENSEARRAY_SYMBOL(add_scalar_f32)(
     size_t dim,
     float c)
 {
-  #pragma omp parallel for
+  #pragma omp parallel for num_threads(densearray_omp_num_threads())
   for (size_t idx = 0; idx < dim; idx++) {
     dst[idx] += c;
   }
@@ -107,7 +107,7 @@ void DENSEARRAY_SYMBOL(scale_f32)(
     size_t dim,
     float c)
 {
-  #pragma omp parallel for
+  #pragma omp parallel for num_threads(densearray_omp_num_threads())
   for (size_t idx = 0; idx < dim; idx++) {
     dst[idx] *= c;
   }
@@ -118,7 +118,7 @@ void DENSEARRAY_SYMBOL(div_scalar_f32)(
     size_t dim,
     float c)
 {
-  #pragma omp parallel for
+  #pragma omp parallel for num_threads(densearray_omp_num_threads())
   for (size_t idx = 0; idx < dim; idx++) {
     dst[idx] /= c;
   }
@@ -129,7 +129,7 @@ void DENSEARRAY_SYMBOL(elem_mult_f32)(
     size_t dim,
     const float *xs)
 {
-  #pragma omp parallel for
+  #pragma omp parallel for num_threads(densearray_omp_num_threads())
   for (size_t idx = 0; idx < dim; idx++) {
     dst[idx] *= xs[idx];
   }
@@ -140,7 +140,7 @@ void DENSEARRAY_SYMBOL(elem_div_f32)(
     size_t dim,
     const float *xs)
 {
-  #pragma omp parallel for
+  #pragma omp parallel for num_threads(densearray_omp_num_threads())
   for (size_t idx = 0; idx < dim; idx++) {
     dst[idx] /= xs[idx];
   }
@@ -151,7 +151,7 @@ void DENSEARRAY_SYMBOL(elem_ldiv_f32)(
     size_t dim,
     const float *xs)
 {
-  #pragma omp parallel for
+  #pragma omp parallel for num_threads(densearray_omp_num_threads())
   for (size_t idx = 0; idx < dim; idx++) {
     dst[idx] = xs[idx] / dst[idx];
   }
@@ -163,7 +163,7 @@ void DENSEARRAY_SYMBOL(vector_add_f32)(
     const float *xs,
     float c)
 {
-  #pragma omp parallel for
+  #pragma omp parallel for num_threads(densearray_omp_num_threads())
   for (size_t idx = 0; idx < dim; idx++) {
     dst[idx] += c * xs[idx];
   }
@@ -175,8 +175,346 @@ void DENSEARRAY_SYMBOL(vector_average_f32)(
     const float *xs,
     float c)
 {
-  #pragma omp parallel for
+  #pragma omp parallel for num_threads(densearray_omp_num_threads())
   for (size_t idx = 0; idx < dim; idx++) {
     dst[idx] += c * (xs[idx] - dst[idx]);
   }
 }
+
+// The `chunk_*` reductions split `x` into fixed size chunks and write one
+// partial result per chunk; the chunking does not depend on the number of
+// threads, so the partials (and any fixed-order combination of them) are
+// reproducible.
+
+void DENSEARRAY_SYMBOL(chunk_sum_f32)(
+    const float *x,
+    size_t dim,
+    size_t incx,
+    size_t chunk_sz,
+    float *partials)
+{
+  size_t num_chunks = (dim + chunk_sz - 1) / chunk_sz;
+  #pragma omp parallel for num_threads(densearray_omp_num_threads())
+  for (size_t c = 0; c < num_chunks; c++) {
+    size_t lo = c * chunk_sz;
+    size_t hi = lo + chunk_sz < dim ? lo + chunk_sz : dim;
+    float s = 0.0f;
+    for (size_t idx = lo; idx < hi; idx++) {
+      s += x[idx * incx];
+    }
+    partials[c] = s;
+  }
+}
+
+void DENSEARRAY_SYMBOL(chunk_abs_sum_f32)(
+    const float *x,
+    size_t dim,
+    size_t incx,
+    size_t chunk_sz,
+    float *partials)
+{
+  size_t num_chunks = (dim + chunk_sz - 1) / chunk_sz;
+  #pragma omp parallel for num_threads(densearray_omp_num_threads())
+  for (size_t c = 0; c < num_chunks; c++) {
+    size_t lo = c * chunk_sz;
+    size_t hi = lo + chunk_sz < dim ? lo + chunk_sz : dim;
+    float s = 0.0f;
+    for (size_t idx = lo; idx < hi; idx++) {
+      s += fabsf(x[idx * incx]);
+    }
+    partials[c] = s;
+  }
+}
+
+void DENSEARRAY_SYMBOL(chunk_square_sum_f32)(
+    const float *x,
+    size_t dim,
+    size_t incx,
+    size_t chunk_sz,
+    float *partials)
+{
+  size_t num_chunks = (dim + chunk_sz - 1) / chunk_sz;
+  #pragma omp parallel for num_threads(densearray_omp_num_threads())
+  for (size_t c = 0; c < num_chunks; c++) {
+    size_t lo = c * chunk_sz;
+    size_t hi = lo + chunk_sz < dim ? lo + chunk_sz : dim;
+    float s = 0.0f;
+    for (size_t idx = lo; idx < hi; idx++) {
+      float x_i = x[idx * incx];
+      s += x_i * x_i;
+    }
+    partials[c] = s;
+  }
+}
+
+void DENSEARRAY_SYMBOL(chunk_inner_prod_f32)(
+    const float *x,
+    size_t dim,
+    size_t incx,
+    const float *y,
+    size_t incy,
+    size_t chunk_sz,
+    float *partials)
+{
+  size_t num_chunks = (dim + chunk_sz - 1) / chunk_sz;
+  #pragma omp parallel for num_threads(densearray_omp_num_threads())
+  for (size_t c = 0; c < num_chunks; c++) {
+    size_t lo = c * chunk_sz;
+    size_t hi = lo + chunk_sz < dim ? lo + chunk_sz : dim;
+    float s = 0.0f;
+    for (size_t idx = lo; idx < hi; idx++) {
+      s += x[idx * incx] * y[idx * incy];
+    }
+    partials[c] = s;
+  }
+}
+
+void DENSEARRAY_SYMBOL(set_scalar_f64)(
+    double *dst,
+    size_t dim,
+    double c)
+{
+  #pragma omp parallel for num_threads(densearray_omp_num_threads())
+  for (size_t idx = 0; idx < dim; idx++) {
+    dst[idx] = c;
+  }
+}
+
+void DENSEARRAY_SYMBOL(copy_f64)(
+    double *dst,
+    size_t dim,
+    const double *src)
+{
+  #pragma omp parallel for num_threads(densearray_omp_num_threads())
+  for (size_t idx = 0; idx < dim; idx++) {
+    dst[idx] = src[idx];
+  }
+}
+
+void DENSEARRAY_SYMBOL(cast_u8_to_f64)(
+    double *dst,
+    size_t dim,
+    const uint8_t *src)
+{
+  #pragma omp parallel for num_threads(densearray_omp_num_threads())
+  for (size_t idx = 0; idx < dim; idx++) {
+    dst[idx] = (double)(src[idx]);
+  }
+}
+
+void DENSEARRAY_SYMBOL(square_f64)(
+    double *dst,
+    size_t dim)
+{
+  #pragma omp parallel for num_threads(densearray_omp_num_threads())
+  for (size_t idx = 0; idx < dim; idx++) {
+    double y = dst[idx];
+    dst[idx] = y * y;
+  }
+}
+
+void DENSEARRAY_SYMBOL(cube_f64)(
+    double *dst,
+    size_t dim)
+{
+  #pragma omp parallel for num_threads(densearray_omp_num_threads())
+  for (size_t idx = 0; idx < dim; idx++) {
+    double y = dst[idx];
+    dst[idx] = y * y * y;
+  }
+}
+
+void DENSEARRAY_SYMBOL(sqrt_f64)(
+    double *dst,
+    size_t dim)
+{
+  #pragma omp parallel for num_threads(densearray_omp_num_threads())
+  for (size_t idx = 0; idx < dim; idx++) {
+    double y = dst[idx];
+    dst[idx] = sqrt(y);
+  }
+}
+
+void DENSEARRAY_SYMBOL(reciprocal_f64)(
+    double *dst,
+    size_t dim)
+{
+  #pragma omp parallel for num_threads(densearray_omp_num_threads())
+  for (size_t idx = 0; idx < dim; idx++) {
+    double y = dst[idx];
+    dst[idx] = 1.0 / y;
+  }
+}
+
+void DENSEARRAY_SYMBOL(add_scalar_f64)(
+    double *dst,
+    size_t dim,
+    double c)
+{
+  #pragma omp parallel for num_threads(densearray_omp_num_threads())
+  for (size_t idx = 0; idx < dim; idx++) {
+    dst[idx] += c;
+  }
+}
+
+void DENSEARRAY_SYMBOL(scale_f64)(
+    double *dst,
+    size_t dim,
+    double c)
+{
+  #pragma omp parallel for num_threads(densearray_omp_num_threads())
+  for (size_t idx = 0; idx < dim; idx++) {
+    dst[idx] *= c;
+  }
+}
+
+void DENSEARRAY_SYMBOL(div_scalar_f64)(
+    double *dst,
+    size_t dim,
+    double c)
+{
+  #pragma omp parallel for num_threads(densearray_omp_num_threads())
+  for (size_t idx = 0; idx < dim; idx++) {
+    dst[idx] /= c;
+  }
+}
+
+void DENSEARRAY_SYMBOL(elem_mult_f64)(
+    double *dst,
+    size_t dim,
+    const double *xs)
+{
+  #pragma omp parallel for num_threads(densearray_omp_num_threads())
+  for (size_t idx = 0; idx < dim; idx++) {
+    dst[idx] *= xs[idx];
+  }
+}
+
+void DENSEARRAY_SYMBOL(elem_div_f64)(
+    double *dst,
+    size_t dim,
+    const double *xs)
+{
+  #pragma omp parallel for num_threads(densearray_omp_num_threads())
+  for (size_t idx = 0; idx < dim; idx++) {
+    dst[idx] /= xs[idx];
+  }
+}
+
+void DENSEARRAY_SYMBOL(elem_ldiv_f64)(
+    double *dst,
+    size_t dim,
+    const double *xs)
+{
+  #pragma omp parallel for num_threads(densearray_omp_num_threads())
+  for (size_t idx = 0; idx < dim; idx++) {
+    dst[idx] = xs[idx] / dst[idx];
+  }
+}
+
+void DENSEARRAY_SYMBOL(vector_add_f64)(
+    double *dst,
+    size_t dim,
+    const double *xs,
+    double c)
+{
+  #pragma omp parallel for num_threads(densearray_omp_num_threads())
+  for (size_t idx = 0; idx < dim; idx++) {
+    dst[idx] += c * xs[idx];
+  }
+}
+
+void DENSEARRAY_SYMBOL(vector_average_f64)(
+    double *dst,
+    size_t dim,
+    const double *xs,
+    double c)
+{
+  #pragma omp parallel for num_threads(densearray_omp_num_threads())
+  for (size_t idx = 0; idx < dim; idx++) {
+    dst[idx] += c * (xs[idx] - dst[idx]);
+  }
+}
+
+void DENSEARRAY_SYMBOL(chunk_sum_f64)(
+    const double *x,
+    size_t dim,
+    size_t incx,
+    size_t chunk_sz,
+    double *partials)
+{
+  size_t num_chunks = (dim + chunk_sz - 1) / chunk_sz;
+  #pragma omp parallel for num_threads(densearray_omp_num_threads())
+  for (size_t c = 0; c < num_chunks; c++) {
+    size_t lo = c * chunk_sz;
+    size_t hi = lo + chunk_sz < dim ? lo + chunk_sz : dim;
+    double s = 0.0;
+    for (size_t idx = lo; idx < hi; idx++) {
+      s += x[idx * incx];
+    }
+    partials[c] = s;
+  }
+}
+
+void DENSEARRAY_SYMBOL(chunk_abs_sum_f64)(
+    const double *x,
+    size_t dim,
+    size_t incx,
+    size_t chunk_sz,
+    double *partials)
+{
+  size_t num_chunks = (dim + chunk_sz - 1) / chunk_sz;
+  #pragma omp parallel for num_threads(densearray_omp_num_threads())
+  for (size_t c = 0; c < num_chunks; c++) {
+    size_t lo = c * chunk_sz;
+    size_t hi = lo + chunk_sz < dim ? lo + chunk_sz : dim;
+    double s = 0.0;
+    for (size_t idx = lo; idx < hi; idx++) {
+      s += fabs(x[idx * incx]);
+    }
+    partials[c] = s;
+  }
+}
+
+void DENSEARRAY_SYMBOL(chunk_square_sum_f64)(
+    const double *x,
+    size_t dim,
+    size_t incx,
+    size_t chunk_sz,
+    double *partials)
+{
+  size_t num_chunks = (dim + chunk_sz - 1) / chunk_sz;
+  #pragma omp parallel for num_threads(densearray_omp_num_threads())
+  for (size_t c = 0; c < num_chunks; c++) {
+    size_t lo = c * chunk_sz;
+    size_t hi = lo + chunk_sz < dim ? lo + chunk_sz : dim;
+    double s = 0.0;
+    for (size_t idx = lo; idx < hi; idx++) {
+      double x_i = x[idx * incx];
+      s += x_i * x_i;
+    }
+    partials[c] = s;
+  }
+}
+
+void DENSEARRAY_SYMBOL(chunk_inner_prod_f64)(
+    const double *x,
+    size_t dim,
+    size_t incx,
+    const double *y,
+    size_t incy,
+    size_t chunk_sz,
+    double *partials)
+{
+  size_t num_chunks = (dim + chunk_sz - 1) / chunk_sz;
+  #pragma omp parallel for num_threads(densearray_omp_num_threads())
+  for (size_t c = 0; c < num_chunks; c++) {
+    size_t lo = c * chunk_sz;
+    size_t hi = lo + chunk_sz < dim ? lo + chunk_sz : dim;
+    double s = 0.0;
+    for (size_t idx = lo; idx < hi; idx++) {
+      s += x[idx * incx] * y[idx * incy];
+    }
+    partials[c] = s;
+  }
+}
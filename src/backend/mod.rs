@@ -0,0 +1,211 @@
+use super::{SyncConstPtr, SyncMutPtr};
+use linalg::{EigenRange, SvdMode, Transpose, Uplo};
+use parallel::{parallel_for_items};
+
+#[cfg(any(feature = "openblas", feature = "mkl"))]
+use cblas_ffi::*;
+#[cfg(any(feature = "openblas", feature = "mkl"))]
+use libc::{c_int};
+
+use std::cmp::{max};
+
+pub use self::reference::{Reference, ReferenceScalar};
+#[cfg(feature = "mkl")]
+pub use self::mkl::{Mkl};
+#[cfg(feature = "openblas")]
+pub use self::openblas::{OpenblasSequential, OpenblasParallel};
+
+#[cfg(feature = "mkl")]
+pub mod mkl;
+#[cfg(feature = "openblas")]
+pub mod openblas;
+pub mod reference;
+
+/// The backend used by the sequential `BlasScalar` routines: OpenBLAS when the
+/// `openblas` feature is enabled, otherwise MKL, otherwise the pure-Rust
+/// reference backend.
+#[cfg(feature = "openblas")]
+pub type SequentialBackend = OpenblasSequential;
+#[cfg(all(not(feature = "openblas"), feature = "mkl"))]
+pub type SequentialBackend = Mkl;
+#[cfg(all(not(feature = "openblas"), not(feature = "mkl")))]
+pub type SequentialBackend = Reference;
+
+/// The backend used by the `parallel_*` `BlasScalar` routines: MKL when the
+/// `mkl_parallel` feature is enabled, otherwise the threaded OpenBLAS,
+/// otherwise the same backend as the sequential routines.
+#[cfg(feature = "mkl_parallel")]
+pub type ParallelBackend = Mkl;
+#[cfg(all(not(feature = "mkl_parallel"), feature = "openblas"))]
+pub type ParallelBackend = OpenblasParallel;
+#[cfg(all(not(feature = "mkl_parallel"), not(feature = "openblas"), feature = "mkl"))]
+pub type ParallelBackend = Mkl;
+#[cfg(all(not(feature = "mkl_parallel"), not(feature = "openblas"), not(feature = "mkl")))]
+pub type ParallelBackend = Reference;
+
+/// Column-major BLAS and LAPACK routines over the scalar type `T`.
+///
+/// The LAPACK-style routines return the `info` status: zero on success, a
+/// negative value for an invalid argument, and a positive value for a
+/// numerical failure (e.g. a non positive definite matrix in `potrf`).
+pub trait Backend<T> {
+  unsafe fn gemm(a_trans: Transpose, b_trans: Transpose, m: usize, n: usize, k: usize, alpha: T, a: *const T, lda: usize, b: *const T, ldb: usize, beta: T, c: *mut T, ldc: usize);
+  /// The stride-batched `gemm`: `C_l = alpha * op(A_l) * op(B_l) + beta * C_l`
+  /// for `l` in `0 .. batch_sz`, where `A_l`, `B_l` and `C_l` start
+  /// `l * stride_a`, `l * stride_b` and `l * stride_c` elements into `a`, `b`
+  /// and `c`. Backends without a batched routine split the batch across the
+  /// `parallel` pool, with single-threaded `gemm` calls.
+  unsafe fn gemm_batch(a_trans: Transpose, b_trans: Transpose, m: usize, n: usize, k: usize, alpha: T, a: *const T, lda: usize, stride_a: usize, b: *const T, ldb: usize, stride_b: usize, beta: T, c: *mut T, ldc: usize, stride_c: usize, batch_sz: usize);
+  unsafe fn gemv(a_trans: Transpose, m: usize, n: usize, alpha: T, a: *const T, lda: usize, x: *const T, incx: usize, beta: T, y: *mut T, incy: usize);
+  unsafe fn dot(n: usize, alpha: T, x: *const T, incx: usize, y: *const T, incy: usize) -> T;
+  unsafe fn nrm2(n: usize, x: *const T, incx: usize) -> T;
+  unsafe fn axpy(n: usize, alpha: T, x: *const T, incx: usize, y: *mut T, incy: usize);
+  unsafe fn scal(n: usize, alpha: T, x: *mut T, incx: usize);
+  /// Solves `op(A) * x = b` in place of `x`, where `A` is `n x n` triangular
+  /// with a non-unit diagonal; only the `uplo` triangle of `A` is read.
+  unsafe fn trsv(uplo: Uplo, a_trans: Transpose, n: usize, a: *const T, lda: usize, x: *mut T, incx: usize);
+  /// Solves `op(A) * X = alpha * B` in place of the `m x n` matrix `B`, where
+  /// `A` is `m x m` triangular as in `trsv`.
+  unsafe fn trsm(uplo: Uplo, a_trans: Transpose, m: usize, n: usize, alpha: T, a: *const T, lda: usize, b: *mut T, ldb: usize);
+  /// The rank-1 update `A += alpha * x * y^T` of the `m x n` matrix `A`.
+  unsafe fn ger(m: usize, n: usize, alpha: T, x: *const T, incx: usize, y: *const T, incy: usize, a: *mut T, lda: usize);
+  /// The symmetric rank-1 update `A += alpha * x * x^T` of the `uplo`
+  /// triangle of the `n x n` matrix `A`.
+  unsafe fn syr(uplo: Uplo, n: usize, alpha: T, x: *const T, incx: usize, a: *mut T, lda: usize);
+  /// The symmetric rank-k update `C = alpha * op(A) * op(A)^T + beta * C` of
+  /// the `uplo` triangle of the `n x n` matrix `C`, where `op(A)` is `n x k`.
+  unsafe fn syrk(uplo: Uplo, a_trans: Transpose, n: usize, k: usize, alpha: T, a: *const T, lda: usize, beta: T, c: *mut T, ldc: usize);
+
+  unsafe fn potrf(uplo: Uplo, n: usize, a: *mut T, lda: usize) -> i32;
+  unsafe fn potrs(uplo: Uplo, n: usize, nrhs: usize, a: *const T, lda: usize, b: *mut T, ldb: usize) -> i32;
+  /// LU factorization with partial pivoting of the `m x n` matrix `A`, with
+  /// the 1-based row interchanges stored in `ipiv` (length `min(m, n)`). A
+  /// positive status `k` means that `U(k, k)` is exactly zero.
+  unsafe fn getrf(m: usize, n: usize, a: *mut T, lda: usize, ipiv: *mut i32) -> i32;
+  unsafe fn getrs(a_trans: Transpose, n: usize, nrhs: usize, a: *const T, lda: usize, ipiv: *const i32, b: *mut T, ldb: usize) -> i32;
+  unsafe fn getri(n: usize, a: *mut T, lda: usize, ipiv: *const i32) -> i32;
+  /// QR factorization of the `m x n` matrix `A`, with `R` on and above the
+  /// diagonal, and the Householder reflectors below it and in `tau` (length
+  /// `min(m, n)`).
+  unsafe fn geqrf(m: usize, n: usize, a: *mut T, lda: usize, tau: *mut T) -> i32;
+  /// Overwrites the reflectors from `geqrf` with the first `n` columns of
+  /// `Q`, where `k` reflectors define `Q` and `m >= n >= k`.
+  unsafe fn orgqr(m: usize, n: usize, k: usize, a: *mut T, lda: usize, tau: *const T) -> i32;
+  /// Solves the least squares problem `min ||A * X - B||` for a full rank
+  /// `A` with `m >= n` via its QR factorization, overwriting the first `n`
+  /// rows of the `m x nrhs` matrix `B` with `X`. A positive status `k` means
+  /// that `R(k, k)` is exactly zero.
+  unsafe fn gels(m: usize, n: usize, nrhs: usize, a: *mut T, lda: usize, b: *mut T, ldb: usize) -> i32;
+  /// Singular value decomposition `A = U * S * V^T` of the `m x n` matrix
+  /// `A`, which is destroyed. The `min(m, n)` singular values go to `s` in
+  /// descending order, and `u` and `vt` are written as selected by `mode`
+  /// (full: `m x m` and `n x n`, thin: `m x k` and `k x n`). A positive
+  /// status means that the iteration did not converge.
+  unsafe fn gesdd(mode: SvdMode, m: usize, n: usize, a: *mut T, lda: usize, s: *mut T, u: *mut T, ldu: usize, vt: *mut T, ldvt: usize) -> i32;
+  /// Eigenvalues, and with `vectors` also eigenvectors, of the symmetric
+  /// `n x n` matrix `A`, of which only the `uplo` triangle is read and which
+  /// is destroyed. The `m` eigenvalues selected by `range` go to `w` in
+  /// ascending order and their orthonormal eigenvectors to the columns of
+  /// `z`, which needs room for `n` columns unless `range` selects indices.
+  unsafe fn syevr(vectors: bool, range: EigenRange<T>, uplo: Uplo, n: usize, a: *mut T, lda: usize, abs_tol: T, m: &mut usize, w: *mut T, z: *mut T, ldz: usize) -> i32;
+  /// Computes the eigenvalues selected by `range` of the symmetric
+  /// tridiagonal matrix with diagonal `d` (length `n`) and off-diagonal `e`
+  /// (length `n - 1`) into `w` in ascending order, storing the number of
+  /// eigenvalues found in `m`. As in LAPACK, `iblock` receives the block of
+  /// each eigenvalue, and `nsplit` and `isplit` the number of blocks and
+  /// their ends; `w`, `iblock` and `isplit` all have length `n`.
+  unsafe fn stebz(range: EigenRange<T>, n: usize, abs_tol: T, d: *const T, e: *const T, m: &mut usize, nsplit: &mut usize, w: *mut T, iblock: *mut i32, isplit: *mut i32) -> i32;
+  /// Eigenvectors of the symmetric tridiagonal matrix as in `stebz` for the
+  /// `m` eigenvalues in `w`, by inverse iteration, into the columns of `z`.
+  /// `iblock` and `isplit` are as returned by `stebz`, and the eigenvalues
+  /// are grouped by block and ascending within each. A positive status is
+  /// the number of eigenvectors that did not converge.
+  unsafe fn stein(n: usize, d: *const T, e: *const T, m: usize, w: *const T, iblock: *const i32, isplit: *const i32, z: *mut T, ldz: usize) -> i32;
+}
+
+/// `Backend::gemm_batch` as a loop of `B::gemm` calls over the `parallel`
+/// pool.
+unsafe fn gemm_batch_loop<T, B>(a_trans: Transpose, b_trans: Transpose, m: usize, n: usize, k: usize, alpha: T, a: *const T, lda: usize, stride_a: usize, b: *const T, ldb: usize, stride_b: usize, beta: T, c: *mut T, ldc: usize, stride_c: usize, batch_sz: usize)
+where T: Copy + Send + Sync, B: Backend<T> {
+  let a = SyncConstPtr(a);
+  let b = SyncConstPtr(b);
+  let c = SyncMutPtr(c);
+  parallel_for_items(batch_sz, m * n * max(k, 1), |range| {
+    for l in range {
+      B::gemm(
+          a_trans,
+          b_trans,
+          m, n, k,
+          alpha,
+          a.0.offset((l * stride_a) as isize), lda,
+          b.0.offset((l * stride_b) as isize), ldb,
+          beta,
+          c.0.offset((l * stride_c) as isize), ldc,
+      );
+    }
+  });
+}
+
+/// Thread count control for backends with their own thread pool. The
+/// setting is process-wide; backends without threads report one thread.
+pub trait BackendThreads {
+  fn set_num_threads(num_threads: usize);
+  fn num_threads() -> usize;
+}
+
+#[cfg(any(feature = "openblas", feature = "mkl"))]
+fn cblas_trans(trans: Transpose) -> CblasTranspose {
+  match trans {
+    Transpose::N => CblasTranspose::NoTrans,
+    Transpose::T => CblasTranspose::Trans,
+  }
+}
+
+#[cfg(any(feature = "openblas", feature = "mkl"))]
+const CBLAS_LEFT: c_int = 141;
+#[cfg(any(feature = "openblas", feature = "mkl"))]
+const CBLAS_NON_UNIT: c_int = 131;
+
+#[cfg(any(feature = "openblas", feature = "mkl"))]
+fn cblas_uplo(uplo: Uplo) -> c_int {
+  match uplo {
+    Uplo::Upper => 121,
+    Uplo::Lower => 122,
+  }
+}
+
+/// The LAPACK `range`, `vl`, `vu`, `il` and `iu` arguments, with 1-based
+/// inclusive indices.
+#[cfg(any(feature = "openblas", feature = "mkl"))]
+fn lapack_range<T>(range: EigenRange<T>) -> (i8, T, T, c_int, c_int) where T: Copy + Default {
+  match range {
+    EigenRange::All => ('A' as i8, T::default(), T::default(), 0, 0),
+    EigenRange::Values(lo, hi) => ('V' as i8, lo, hi, 0, 0),
+    EigenRange::Indices(lo, hi) => ('I' as i8, T::default(), T::default(), (lo + 1) as c_int, hi as c_int),
+  }
+}
+
+#[cfg(any(feature = "openblas", feature = "mkl"))]
+fn lapack_svd_job(mode: SvdMode) -> i8 {
+  match mode {
+    SvdMode::Full       => 'A' as i8,
+    SvdMode::Thin       => 'S' as i8,
+    SvdMode::ValuesOnly => 'N' as i8,
+  }
+}
+
+#[cfg(any(feature = "openblas", feature = "mkl"))]
+fn lapack_trans(trans: Transpose) -> i8 {
+  match trans {
+    Transpose::N => 'N' as i8,
+    Transpose::T => 'T' as i8,
+  }
+}
+
+#[cfg(any(feature = "openblas", feature = "mkl"))]
+fn lapack_uplo(uplo: Uplo) -> i8 {
+  match uplo {
+    Uplo::Lower => 'L' as i8,
+    Uplo::Upper => 'U' as i8,
+  }
+}
@@ -0,0 +1,1193 @@
+use backend::{Backend, BackendThreads, CBLAS_LEFT, CBLAS_NON_UNIT, cblas_trans, cblas_uplo, gemm_batch_loop, lapack_range, lapack_svd_job, lapack_trans, lapack_uplo};
+use linalg::{EigenRange, SvdMode, Transpose, Uplo};
+
+use cblas_ffi::*;
+use libc::{c_char, c_int};
+use openblas_ffi::*;
+
+use std::cmp::{max};
+
+/// Single-threaded OpenBLAS.
+#[derive(Clone, Copy, Debug)]
+pub struct OpenblasSequential;
+
+/// Multithreaded OpenBLAS. LAPACK routines go through the sequential library.
+#[derive(Clone, Copy, Debug)]
+pub struct OpenblasParallel;
+
+extern "C" {
+  fn openblas_parallel_openblas_set_num_threads(num_threads: c_int);
+  fn openblas_parallel_openblas_get_num_threads() -> c_int;
+
+  // The side, uplo and diag arguments take the plain CBLAS enum values.
+  fn openblas_sequential_cblas_strsv(order: CblasOrder, uplo: c_int, trans: CblasTranspose, diag: c_int, n: c_int, a: *const f32, lda: c_int, x: *mut f32, incx: c_int);
+  fn openblas_sequential_cblas_strsm(order: CblasOrder, side: c_int, uplo: c_int, trans: CblasTranspose, diag: c_int, m: c_int, n: c_int, alpha: f32, a: *const f32, lda: c_int, b: *mut f32, ldb: c_int);
+  fn openblas_sequential_cblas_dtrsv(order: CblasOrder, uplo: c_int, trans: CblasTranspose, diag: c_int, n: c_int, a: *const f64, lda: c_int, x: *mut f64, incx: c_int);
+  fn openblas_sequential_cblas_dtrsm(order: CblasOrder, side: c_int, uplo: c_int, trans: CblasTranspose, diag: c_int, m: c_int, n: c_int, alpha: f64, a: *const f64, lda: c_int, b: *mut f64, ldb: c_int);
+  fn openblas_sequential_cblas_sger(order: CblasOrder, m: c_int, n: c_int, alpha: f32, x: *const f32, incx: c_int, y: *const f32, incy: c_int, a: *mut f32, lda: c_int);
+  fn openblas_sequential_cblas_ssyr(order: CblasOrder, uplo: c_int, n: c_int, alpha: f32, x: *const f32, incx: c_int, a: *mut f32, lda: c_int);
+  fn openblas_sequential_cblas_ssyrk(order: CblasOrder, uplo: c_int, trans: CblasTranspose, n: c_int, k: c_int, alpha: f32, a: *const f32, lda: c_int, beta: f32, c: *mut f32, ldc: c_int);
+  fn openblas_sequential_cblas_dger(order: CblasOrder, m: c_int, n: c_int, alpha: f64, x: *const f64, incx: c_int, y: *const f64, incy: c_int, a: *mut f64, lda: c_int);
+  fn openblas_sequential_cblas_dsyr(order: CblasOrder, uplo: c_int, n: c_int, alpha: f64, x: *const f64, incx: c_int, a: *mut f64, lda: c_int);
+  fn openblas_sequential_cblas_dsyrk(order: CblasOrder, uplo: c_int, trans: CblasTranspose, n: c_int, k: c_int, alpha: f64, a: *const f64, lda: c_int, beta: f64, c: *mut f64, ldc: c_int);
+  fn openblas_parallel_cblas_strsv(order: CblasOrder, uplo: c_int, trans: CblasTranspose, diag: c_int, n: c_int, a: *const f32, lda: c_int, x: *mut f32, incx: c_int);
+  fn openblas_parallel_cblas_strsm(order: CblasOrder, side: c_int, uplo: c_int, trans: CblasTranspose, diag: c_int, m: c_int, n: c_int, alpha: f32, a: *const f32, lda: c_int, b: *mut f32, ldb: c_int);
+  fn openblas_parallel_cblas_dtrsv(order: CblasOrder, uplo: c_int, trans: CblasTranspose, diag: c_int, n: c_int, a: *const f64, lda: c_int, x: *mut f64, incx: c_int);
+  fn openblas_parallel_cblas_dtrsm(order: CblasOrder, side: c_int, uplo: c_int, trans: CblasTranspose, diag: c_int, m: c_int, n: c_int, alpha: f64, a: *const f64, lda: c_int, b: *mut f64, ldb: c_int);
+  fn openblas_parallel_cblas_sger(order: CblasOrder, m: c_int, n: c_int, alpha: f32, x: *const f32, incx: c_int, y: *const f32, incy: c_int, a: *mut f32, lda: c_int);
+  fn openblas_parallel_cblas_ssyr(order: CblasOrder, uplo: c_int, n: c_int, alpha: f32, x: *const f32, incx: c_int, a: *mut f32, lda: c_int);
+  fn openblas_parallel_cblas_ssyrk(order: CblasOrder, uplo: c_int, trans: CblasTranspose, n: c_int, k: c_int, alpha: f32, a: *const f32, lda: c_int, beta: f32, c: *mut f32, ldc: c_int);
+  fn openblas_parallel_cblas_dger(order: CblasOrder, m: c_int, n: c_int, alpha: f64, x: *const f64, incx: c_int, y: *const f64, incy: c_int, a: *mut f64, lda: c_int);
+  fn openblas_parallel_cblas_dsyr(order: CblasOrder, uplo: c_int, n: c_int, alpha: f64, x: *const f64, incx: c_int, a: *mut f64, lda: c_int);
+  fn openblas_parallel_cblas_dsyrk(order: CblasOrder, uplo: c_int, trans: CblasTranspose, n: c_int, k: c_int, alpha: f64, a: *const f64, lda: c_int, beta: f64, c: *mut f64, ldc: c_int);
+
+  fn openblas_sequential_LAPACKE_sgetrf(matrix_layout: c_int, m: c_int, n: c_int, a: *mut f32, lda: c_int, ipiv: *mut c_int) -> c_int;
+  fn openblas_sequential_LAPACKE_sgetrs(matrix_layout: c_int, trans: c_char, n: c_int, nrhs: c_int, a: *const f32, lda: c_int, ipiv: *const c_int, b: *mut f32, ldb: c_int) -> c_int;
+  fn openblas_sequential_LAPACKE_sgetri(matrix_layout: c_int, n: c_int, a: *mut f32, lda: c_int, ipiv: *const c_int) -> c_int;
+  fn openblas_sequential_LAPACKE_dgetrf(matrix_layout: c_int, m: c_int, n: c_int, a: *mut f64, lda: c_int, ipiv: *mut c_int) -> c_int;
+  fn openblas_sequential_LAPACKE_dgetrs(matrix_layout: c_int, trans: c_char, n: c_int, nrhs: c_int, a: *const f64, lda: c_int, ipiv: *const c_int, b: *mut f64, ldb: c_int) -> c_int;
+  fn openblas_sequential_LAPACKE_dgetri(matrix_layout: c_int, n: c_int, a: *mut f64, lda: c_int, ipiv: *const c_int) -> c_int;
+  fn openblas_sequential_LAPACKE_sgeqrf(matrix_layout: c_int, m: c_int, n: c_int, a: *mut f32, lda: c_int, tau: *mut f32) -> c_int;
+  fn openblas_sequential_LAPACKE_sorgqr(matrix_layout: c_int, m: c_int, n: c_int, k: c_int, a: *mut f32, lda: c_int, tau: *const f32) -> c_int;
+  fn openblas_sequential_LAPACKE_sgels(matrix_layout: c_int, trans: c_char, m: c_int, n: c_int, nrhs: c_int, a: *mut f32, lda: c_int, b: *mut f32, ldb: c_int) -> c_int;
+  fn openblas_sequential_LAPACKE_dgeqrf(matrix_layout: c_int, m: c_int, n: c_int, a: *mut f64, lda: c_int, tau: *mut f64) -> c_int;
+  fn openblas_sequential_LAPACKE_dorgqr(matrix_layout: c_int, m: c_int, n: c_int, k: c_int, a: *mut f64, lda: c_int, tau: *const f64) -> c_int;
+  fn openblas_sequential_LAPACKE_dgels(matrix_layout: c_int, trans: c_char, m: c_int, n: c_int, nrhs: c_int, a: *mut f64, lda: c_int, b: *mut f64, ldb: c_int) -> c_int;
+  fn openblas_sequential_LAPACKE_sgesdd(matrix_layout: c_int, jobz: c_char, m: c_int, n: c_int, a: *mut f32, lda: c_int, s: *mut f32, u: *mut f32, ldu: c_int, vt: *mut f32, ldvt: c_int) -> c_int;
+  fn openblas_sequential_LAPACKE_dgesdd(matrix_layout: c_int, jobz: c_char, m: c_int, n: c_int, a: *mut f64, lda: c_int, s: *mut f64, u: *mut f64, ldu: c_int, vt: *mut f64, ldvt: c_int) -> c_int;
+  fn openblas_sequential_LAPACKE_ssyevr(matrix_layout: c_int, jobz: c_char, range: c_char, uplo: c_char, n: c_int, a: *mut f32, lda: c_int, vl: f32, vu: f32, il: c_int, iu: c_int, abstol: f32, m: *mut c_int, w: *mut f32, z: *mut f32, ldz: c_int, isuppz: *mut c_int) -> c_int;
+  fn openblas_sequential_LAPACKE_dsyevr(matrix_layout: c_int, jobz: c_char, range: c_char, uplo: c_char, n: c_int, a: *mut f64, lda: c_int, vl: f64, vu: f64, il: c_int, iu: c_int, abstol: f64, m: *mut c_int, w: *mut f64, z: *mut f64, ldz: c_int, isuppz: *mut c_int) -> c_int;
+  fn openblas_sequential_LAPACKE_sstein(matrix_layout: c_int, n: c_int, d: *const f32, e: *const f32, m: c_int, w: *const f32, iblock: *const c_int, isplit: *const c_int, z: *mut f32, ldz: c_int, ifailv: *mut c_int) -> c_int;
+  fn openblas_sequential_LAPACKE_dstein(matrix_layout: c_int, n: c_int, d: *const f64, e: *const f64, m: c_int, w: *const f64, iblock: *const c_int, isplit: *const c_int, z: *mut f64, ldz: c_int, ifailv: *mut c_int) -> c_int;
+}
+
+impl BackendThreads for OpenblasSequential {
+  fn set_num_threads(_num_threads: usize) {
+  }
+
+  fn num_threads() -> usize {
+    1
+  }
+}
+
+impl BackendThreads for OpenblasParallel {
+  fn set_num_threads(num_threads: usize) {
+    unsafe { openblas_parallel_openblas_set_num_threads(num_threads as _) };
+  }
+
+  fn num_threads() -> usize {
+    unsafe { openblas_parallel_openblas_get_num_threads() as usize }
+  }
+}
+
+impl Backend<f32> for OpenblasSequential {
+  unsafe fn gemm(a_trans: Transpose, b_trans: Transpose, m: usize, n: usize, k: usize, alpha: f32, a: *const f32, lda: usize, b: *const f32, ldb: usize, beta: f32, c: *mut f32, ldc: usize) {
+    openblas_sequential_cblas_sgemm(
+        CblasOrder::ColMajor,
+        cblas_trans(a_trans),
+        cblas_trans(b_trans),
+        m as _, n as _, k as _,
+        alpha,
+        a, lda as _,
+        b, ldb as _,
+        beta,
+        c, ldc as _,
+    )
+  }
+
+  unsafe fn gemm_batch(a_trans: Transpose, b_trans: Transpose, m: usize, n: usize, k: usize, alpha: f32, a: *const f32, lda: usize, stride_a: usize, b: *const f32, ldb: usize, stride_b: usize, beta: f32, c: *mut f32, ldc: usize, stride_c: usize, batch_sz: usize) {
+    gemm_batch_loop::<f32, OpenblasSequential>(a_trans, b_trans, m, n, k, alpha, a, lda, stride_a, b, ldb, stride_b, beta, c, ldc, stride_c, batch_sz)
+  }
+
+  unsafe fn gemv(a_trans: Transpose, m: usize, n: usize, alpha: f32, a: *const f32, lda: usize, x: *const f32, incx: usize, beta: f32, y: *mut f32, incy: usize) {
+    openblas_sequential_cblas_sgemv(
+        CblasOrder::ColMajor,
+        cblas_trans(a_trans),
+        m as _, n as _,
+        alpha,
+        a, lda as _,
+        x, incx as _,
+        beta,
+        y, incy as _,
+    )
+  }
+
+  unsafe fn dot(n: usize, alpha: f32, x: *const f32, incx: usize, y: *const f32, incy: usize) -> f32 {
+    openblas_sequential_cblas_sdot(
+        n as _,
+        alpha,
+        x, incx as _,
+        y, incy as _,
+    )
+  }
+
+  unsafe fn nrm2(n: usize, x: *const f32, incx: usize) -> f32 {
+    openblas_sequential_cblas_snrm2(
+        n as _,
+        x, incx as _,
+    )
+  }
+
+  unsafe fn axpy(n: usize, alpha: f32, x: *const f32, incx: usize, y: *mut f32, incy: usize) {
+    openblas_sequential_cblas_saxpy(
+        n as _,
+        alpha,
+        x, incx as _,
+        y, incy as _,
+    )
+  }
+
+  unsafe fn scal(n: usize, alpha: f32, x: *mut f32, incx: usize) {
+    openblas_sequential_cblas_sscal(
+        n as _,
+        alpha,
+        x, incx as _,
+    )
+  }
+
+  unsafe fn trsv(uplo: Uplo, a_trans: Transpose, n: usize, a: *const f32, lda: usize, x: *mut f32, incx: usize) {
+    openblas_sequential_cblas_strsv(
+        CblasOrder::ColMajor,
+        cblas_uplo(uplo),
+        cblas_trans(a_trans),
+        CBLAS_NON_UNIT,
+        n as _,
+        a, lda as _,
+        x, incx as _,
+    )
+  }
+
+  unsafe fn trsm(uplo: Uplo, a_trans: Transpose, m: usize, n: usize, alpha: f32, a: *const f32, lda: usize, b: *mut f32, ldb: usize) {
+    openblas_sequential_cblas_strsm(
+        CblasOrder::ColMajor,
+        CBLAS_LEFT,
+        cblas_uplo(uplo),
+        cblas_trans(a_trans),
+        CBLAS_NON_UNIT,
+        m as _, n as _,
+        alpha,
+        a, lda as _,
+        b, ldb as _,
+    )
+  }
+
+  unsafe fn ger(m: usize, n: usize, alpha: f32, x: *const f32, incx: usize, y: *const f32, incy: usize, a: *mut f32, lda: usize) {
+    openblas_sequential_cblas_sger(
+        CblasOrder::ColMajor,
+        m as _, n as _,
+        alpha,
+        x, incx as _,
+        y, incy as _,
+        a, lda as _,
+    )
+  }
+
+  unsafe fn syr(uplo: Uplo, n: usize, alpha: f32, x: *const f32, incx: usize, a: *mut f32, lda: usize) {
+    openblas_sequential_cblas_ssyr(
+        CblasOrder::ColMajor,
+        cblas_uplo(uplo),
+        n as _,
+        alpha,
+        x, incx as _,
+        a, lda as _,
+    )
+  }
+
+  unsafe fn syrk(uplo: Uplo, a_trans: Transpose, n: usize, k: usize, alpha: f32, a: *const f32, lda: usize, beta: f32, c: *mut f32, ldc: usize) {
+    openblas_sequential_cblas_ssyrk(
+        CblasOrder::ColMajor,
+        cblas_uplo(uplo),
+        cblas_trans(a_trans),
+        n as _, k as _,
+        alpha,
+        a, lda as _,
+        beta,
+        c, ldc as _,
+    )
+  }
+
+  unsafe fn potrf(uplo: Uplo, n: usize, a: *mut f32, lda: usize) -> i32 {
+    openblas_sequential_LAPACKE_spotrf(
+        CblasOrder::ColMajor as i32,
+        lapack_uplo(uplo),
+        n as _,
+        a, lda as _,
+    ) as i32
+  }
+
+  unsafe fn potrs(uplo: Uplo, n: usize, nrhs: usize, a: *const f32, lda: usize, b: *mut f32, ldb: usize) -> i32 {
+    openblas_sequential_LAPACKE_spotrs(
+        CblasOrder::ColMajor as i32,
+        lapack_uplo(uplo),
+        n as _, nrhs as _,
+        a, lda as _,
+        b, ldb as _,
+    ) as i32
+  }
+
+  unsafe fn getrf(m: usize, n: usize, a: *mut f32, lda: usize, ipiv: *mut i32) -> i32 {
+    openblas_sequential_LAPACKE_sgetrf(
+        CblasOrder::ColMajor as i32,
+        m as _, n as _,
+        a, lda as _,
+        ipiv,
+    ) as i32
+  }
+
+  unsafe fn getrs(a_trans: Transpose, n: usize, nrhs: usize, a: *const f32, lda: usize, ipiv: *const i32, b: *mut f32, ldb: usize) -> i32 {
+    openblas_sequential_LAPACKE_sgetrs(
+        CblasOrder::ColMajor as i32,
+        lapack_trans(a_trans),
+        n as _, nrhs as _,
+        a, lda as _,
+        ipiv,
+        b, ldb as _,
+    ) as i32
+  }
+
+  unsafe fn getri(n: usize, a: *mut f32, lda: usize, ipiv: *const i32) -> i32 {
+    openblas_sequential_LAPACKE_sgetri(
+        CblasOrder::ColMajor as i32,
+        n as _,
+        a, lda as _,
+        ipiv,
+    ) as i32
+  }
+
+  unsafe fn geqrf(m: usize, n: usize, a: *mut f32, lda: usize, tau: *mut f32) -> i32 {
+    openblas_sequential_LAPACKE_sgeqrf(
+        CblasOrder::ColMajor as i32,
+        m as _, n as _,
+        a, lda as _,
+        tau,
+    ) as i32
+  }
+
+  unsafe fn orgqr(m: usize, n: usize, k: usize, a: *mut f32, lda: usize, tau: *const f32) -> i32 {
+    openblas_sequential_LAPACKE_sorgqr(
+        CblasOrder::ColMajor as i32,
+        m as _, n as _, k as _,
+        a, lda as _,
+        tau,
+    ) as i32
+  }
+
+  unsafe fn gels(m: usize, n: usize, nrhs: usize, a: *mut f32, lda: usize, b: *mut f32, ldb: usize) -> i32 {
+    openblas_sequential_LAPACKE_sgels(
+        CblasOrder::ColMajor as i32,
+        'N' as i8,
+        m as _, n as _, nrhs as _,
+        a, lda as _,
+        b, ldb as _,
+    ) as i32
+  }
+
+  unsafe fn gesdd(mode: SvdMode, m: usize, n: usize, a: *mut f32, lda: usize, s: *mut f32, u: *mut f32, ldu: usize, vt: *mut f32, ldvt: usize) -> i32 {
+    openblas_sequential_LAPACKE_sgesdd(
+        CblasOrder::ColMajor as i32,
+        lapack_svd_job(mode),
+        m as _, n as _,
+        a, lda as _,
+        s,
+        u, ldu as _,
+        vt, ldvt as _,
+    ) as i32
+  }
+
+  unsafe fn syevr(vectors: bool, range: EigenRange<f32>, uplo: Uplo, n: usize, a: *mut f32, lda: usize, abs_tol: f32, m: &mut usize, w: *mut f32, z: *mut f32, ldz: usize) -> i32 {
+    let (range, vl, vu, il, iu) = lapack_range(range);
+    let mut m_found: c_int = 0;
+    let mut isuppz: Vec<c_int> = vec![0; 2 * max(1, n)];
+    let status = openblas_sequential_LAPACKE_ssyevr(
+        CblasOrder::ColMajor as i32,
+        if vectors { 'V' as i8 } else { 'N' as i8 },
+        range,
+        lapack_uplo(uplo),
+        n as _,
+        a, lda as _,
+        vl, vu,
+        il, iu,
+        abs_tol,
+        &mut m_found as *mut _,
+        w,
+        z, ldz as _,
+        isuppz.as_mut_ptr(),
+    ) as i32;
+    *m = m_found as usize;
+    status
+  }
+
+  unsafe fn stebz(range: EigenRange<f32>, n: usize, abs_tol: f32, d: *const f32, e: *const f32, m: &mut usize, nsplit: &mut usize, w: *mut f32, iblock: *mut i32, isplit: *mut i32) -> i32 {
+    let (range, vl, vu, il, iu) = lapack_range(range);
+    let mut m_found: c_int = 0;
+    let mut nsplit_found: c_int = 0;
+    let status = openblas_sequential_LAPACKE_sstebz(
+        range,
+        'E' as i8,
+        n as _,
+        vl, vu,
+        il, iu,
+        abs_tol,
+        d,
+        e,
+        &mut m_found as *mut _,
+        &mut nsplit_found as *mut _,
+        w,
+        iblock,
+        isplit,
+    ) as i32;
+    *m = m_found as usize;
+    *nsplit = nsplit_found as usize;
+    status
+  }
+
+  unsafe fn stein(n: usize, d: *const f32, e: *const f32, m: usize, w: *const f32, iblock: *const i32, isplit: *const i32, z: *mut f32, ldz: usize) -> i32 {
+    let mut ifail: Vec<c_int> = vec![0; max(1, m)];
+    openblas_sequential_LAPACKE_sstein(
+        CblasOrder::ColMajor as i32,
+        n as _,
+        d,
+        e,
+        m as _,
+        w,
+        iblock,
+        isplit,
+        z, ldz as _,
+        ifail.as_mut_ptr(),
+    ) as i32
+  }
+}
+
+impl Backend<f64> for OpenblasSequential {
+  unsafe fn gemm(a_trans: Transpose, b_trans: Transpose, m: usize, n: usize, k: usize, alpha: f64, a: *const f64, lda: usize, b: *const f64, ldb: usize, beta: f64, c: *mut f64, ldc: usize) {
+    openblas_sequential_cblas_dgemm(
+        CblasOrder::ColMajor,
+        cblas_trans(a_trans),
+        cblas_trans(b_trans),
+        m as _, n as _, k as _,
+        alpha,
+        a, lda as _,
+        b, ldb as _,
+        beta,
+        c, ldc as _,
+    )
+  }
+
+  unsafe fn gemm_batch(a_trans: Transpose, b_trans: Transpose, m: usize, n: usize, k: usize, alpha: f64, a: *const f64, lda: usize, stride_a: usize, b: *const f64, ldb: usize, stride_b: usize, beta: f64, c: *mut f64, ldc: usize, stride_c: usize, batch_sz: usize) {
+    gemm_batch_loop::<f64, OpenblasSequential>(a_trans, b_trans, m, n, k, alpha, a, lda, stride_a, b, ldb, stride_b, beta, c, ldc, stride_c, batch_sz)
+  }
+
+  unsafe fn gemv(a_trans: Transpose, m: usize, n: usize, alpha: f64, a: *const f64, lda: usize, x: *const f64, incx: usize, beta: f64, y: *mut f64, incy: usize) {
+    openblas_sequential_cblas_dgemv(
+        CblasOrder::ColMajor,
+        cblas_trans(a_trans),
+        m as _, n as _,
+        alpha,
+        a, lda as _,
+        x, incx as _,
+        beta,
+        y, incy as _,
+    )
+  }
+
+  unsafe fn dot(n: usize, alpha: f64, x: *const f64, incx: usize, y: *const f64, incy: usize) -> f64 {
+    openblas_sequential_cblas_ddot(
+        n as _,
+        alpha,
+        x, incx as _,
+        y, incy as _,
+    )
+  }
+
+  unsafe fn nrm2(n: usize, x: *const f64, incx: usize) -> f64 {
+    openblas_sequential_cblas_dnrm2(
+        n as _,
+        x, incx as _,
+    )
+  }
+
+  unsafe fn axpy(n: usize, alpha: f64, x: *const f64, incx: usize, y: *mut f64, incy: usize) {
+    openblas_sequential_cblas_daxpy(
+        n as _,
+        alpha,
+        x, incx as _,
+        y, incy as _,
+    )
+  }
+
+  unsafe fn scal(n: usize, alpha: f64, x: *mut f64, incx: usize) {
+    openblas_sequential_cblas_dscal(
+        n as _,
+        alpha,
+        x, incx as _,
+    )
+  }
+
+  unsafe fn trsv(uplo: Uplo, a_trans: Transpose, n: usize, a: *const f64, lda: usize, x: *mut f64, incx: usize) {
+    openblas_sequential_cblas_dtrsv(
+        CblasOrder::ColMajor,
+        cblas_uplo(uplo),
+        cblas_trans(a_trans),
+        CBLAS_NON_UNIT,
+        n as _,
+        a, lda as _,
+        x, incx as _,
+    )
+  }
+
+  unsafe fn trsm(uplo: Uplo, a_trans: Transpose, m: usize, n: usize, alpha: f64, a: *const f64, lda: usize, b: *mut f64, ldb: usize) {
+    openblas_sequential_cblas_dtrsm(
+        CblasOrder::ColMajor,
+        CBLAS_LEFT,
+        cblas_uplo(uplo),
+        cblas_trans(a_trans),
+        CBLAS_NON_UNIT,
+        m as _, n as _,
+        alpha,
+        a, lda as _,
+        b, ldb as _,
+    )
+  }
+
+  unsafe fn ger(m: usize, n: usize, alpha: f64, x: *const f64, incx: usize, y: *const f64, incy: usize, a: *mut f64, lda: usize) {
+    openblas_sequential_cblas_dger(
+        CblasOrder::ColMajor,
+        m as _, n as _,
+        alpha,
+        x, incx as _,
+        y, incy as _,
+        a, lda as _,
+    )
+  }
+
+  unsafe fn syr(uplo: Uplo, n: usize, alpha: f64, x: *const f64, incx: usize, a: *mut f64, lda: usize) {
+    openblas_sequential_cblas_dsyr(
+        CblasOrder::ColMajor,
+        cblas_uplo(uplo),
+        n as _,
+        alpha,
+        x, incx as _,
+        a, lda as _,
+    )
+  }
+
+  unsafe fn syrk(uplo: Uplo, a_trans: Transpose, n: usize, k: usize, alpha: f64, a: *const f64, lda: usize, beta: f64, c: *mut f64, ldc: usize) {
+    openblas_sequential_cblas_dsyrk(
+        CblasOrder::ColMajor,
+        cblas_uplo(uplo),
+        cblas_trans(a_trans),
+        n as _, k as _,
+        alpha,
+        a, lda as _,
+        beta,
+        c, ldc as _,
+    )
+  }
+
+  unsafe fn potrf(uplo: Uplo, n: usize, a: *mut f64, lda: usize) -> i32 {
+    openblas_sequential_LAPACKE_dpotrf(
+        CblasOrder::ColMajor as i32,
+        lapack_uplo(uplo),
+        n as _,
+        a, lda as _,
+    ) as i32
+  }
+
+  unsafe fn potrs(uplo: Uplo, n: usize, nrhs: usize, a: *const f64, lda: usize, b: *mut f64, ldb: usize) -> i32 {
+    openblas_sequential_LAPACKE_dpotrs(
+        CblasOrder::ColMajor as i32,
+        lapack_uplo(uplo),
+        n as _, nrhs as _,
+        a, lda as _,
+        b, ldb as _,
+    ) as i32
+  }
+
+  unsafe fn getrf(m: usize, n: usize, a: *mut f64, lda: usize, ipiv: *mut i32) -> i32 {
+    openblas_sequential_LAPACKE_dgetrf(
+        CblasOrder::ColMajor as i32,
+        m as _, n as _,
+        a, lda as _,
+        ipiv,
+    ) as i32
+  }
+
+  unsafe fn getrs(a_trans: Transpose, n: usize, nrhs: usize, a: *const f64, lda: usize, ipiv: *const i32, b: *mut f64, ldb: usize) -> i32 {
+    openblas_sequential_LAPACKE_dgetrs(
+        CblasOrder::ColMajor as i32,
+        lapack_trans(a_trans),
+        n as _, nrhs as _,
+        a, lda as _,
+        ipiv,
+        b, ldb as _,
+    ) as i32
+  }
+
+  unsafe fn getri(n: usize, a: *mut f64, lda: usize, ipiv: *const i32) -> i32 {
+    openblas_sequential_LAPACKE_dgetri(
+        CblasOrder::ColMajor as i32,
+        n as _,
+        a, lda as _,
+        ipiv,
+    ) as i32
+  }
+
+  unsafe fn geqrf(m: usize, n: usize, a: *mut f64, lda: usize, tau: *mut f64) -> i32 {
+    openblas_sequential_LAPACKE_dgeqrf(
+        CblasOrder::ColMajor as i32,
+        m as _, n as _,
+        a, lda as _,
+        tau,
+    ) as i32
+  }
+
+  unsafe fn orgqr(m: usize, n: usize, k: usize, a: *mut f64, lda: usize, tau: *const f64) -> i32 {
+    openblas_sequential_LAPACKE_dorgqr(
+        CblasOrder::ColMajor as i32,
+        m as _, n as _, k as _,
+        a, lda as _,
+        tau,
+    ) as i32
+  }
+
+  unsafe fn gels(m: usize, n: usize, nrhs: usize, a: *mut f64, lda: usize, b: *mut f64, ldb: usize) -> i32 {
+    openblas_sequential_LAPACKE_dgels(
+        CblasOrder::ColMajor as i32,
+        'N' as i8,
+        m as _, n as _, nrhs as _,
+        a, lda as _,
+        b, ldb as _,
+    ) as i32
+  }
+
+  unsafe fn gesdd(mode: SvdMode, m: usize, n: usize, a: *mut f64, lda: usize, s: *mut f64, u: *mut f64, ldu: usize, vt: *mut f64, ldvt: usize) -> i32 {
+    openblas_sequential_LAPACKE_dgesdd(
+        CblasOrder::ColMajor as i32,
+        lapack_svd_job(mode),
+        m as _, n as _,
+        a, lda as _,
+        s,
+        u, ldu as _,
+        vt, ldvt as _,
+    ) as i32
+  }
+
+  unsafe fn syevr(vectors: bool, range: EigenRange<f64>, uplo: Uplo, n: usize, a: *mut f64, lda: usize, abs_tol: f64, m: &mut usize, w: *mut f64, z: *mut f64, ldz: usize) -> i32 {
+    let (range, vl, vu, il, iu) = lapack_range(range);
+    let mut m_found: c_int = 0;
+    let mut isuppz: Vec<c_int> = vec![0; 2 * max(1, n)];
+    let status = openblas_sequential_LAPACKE_dsyevr(
+        CblasOrder::ColMajor as i32,
+        if vectors { 'V' as i8 } else { 'N' as i8 },
+        range,
+        lapack_uplo(uplo),
+        n as _,
+        a, lda as _,
+        vl, vu,
+        il, iu,
+        abs_tol,
+        &mut m_found as *mut _,
+        w,
+        z, ldz as _,
+        isuppz.as_mut_ptr(),
+    ) as i32;
+    *m = m_found as usize;
+    status
+  }
+
+  unsafe fn stebz(range: EigenRange<f64>, n: usize, abs_tol: f64, d: *const f64, e: *const f64, m: &mut usize, nsplit: &mut usize, w: *mut f64, iblock: *mut i32, isplit: *mut i32) -> i32 {
+    let (range, vl, vu, il, iu) = lapack_range(range);
+    let mut m_found: c_int = 0;
+    let mut nsplit_found: c_int = 0;
+    let status = openblas_sequential_LAPACKE_dstebz(
+        range,
+        'E' as i8,
+        n as _,
+        vl, vu,
+        il, iu,
+        abs_tol,
+        d,
+        e,
+        &mut m_found as *mut _,
+        &mut nsplit_found as *mut _,
+        w,
+        iblock,
+        isplit,
+    ) as i32;
+    *m = m_found as usize;
+    *nsplit = nsplit_found as usize;
+    status
+  }
+
+  unsafe fn stein(n: usize, d: *const f64, e: *const f64, m: usize, w: *const f64, iblock: *const i32, isplit: *const i32, z: *mut f64, ldz: usize) -> i32 {
+    let mut ifail: Vec<c_int> = vec![0; max(1, m)];
+    openblas_sequential_LAPACKE_dstein(
+        CblasOrder::ColMajor as i32,
+        n as _,
+        d,
+        e,
+        m as _,
+        w,
+        iblock,
+        isplit,
+        z, ldz as _,
+        ifail.as_mut_ptr(),
+    ) as i32
+  }
+}
+
+impl Backend<f32> for OpenblasParallel {
+  unsafe fn gemm(a_trans: Transpose, b_trans: Transpose, m: usize, n: usize, k: usize, alpha: f32, a: *const f32, lda: usize, b: *const f32, ldb: usize, beta: f32, c: *mut f32, ldc: usize) {
+    openblas_parallel_cblas_sgemm(
+        CblasOrder::ColMajor,
+        cblas_trans(a_trans),
+        cblas_trans(b_trans),
+        m as _, n as _, k as _,
+        alpha,
+        a, lda as _,
+        b, ldb as _,
+        beta,
+        c, ldc as _,
+    )
+  }
+
+  unsafe fn gemm_batch(a_trans: Transpose, b_trans: Transpose, m: usize, n: usize, k: usize, alpha: f32, a: *const f32, lda: usize, stride_a: usize, b: *const f32, ldb: usize, stride_b: usize, beta: f32, c: *mut f32, ldc: usize, stride_c: usize, batch_sz: usize) {
+    gemm_batch_loop::<f32, OpenblasSequential>(a_trans, b_trans, m, n, k, alpha, a, lda, stride_a, b, ldb, stride_b, beta, c, ldc, stride_c, batch_sz)
+  }
+
+  unsafe fn gemv(a_trans: Transpose, m: usize, n: usize, alpha: f32, a: *const f32, lda: usize, x: *const f32, incx: usize, beta: f32, y: *mut f32, incy: usize) {
+    openblas_parallel_cblas_sgemv(
+        CblasOrder::ColMajor,
+        cblas_trans(a_trans),
+        m as _, n as _,
+        alpha,
+        a, lda as _,
+        x, incx as _,
+        beta,
+        y, incy as _,
+    )
+  }
+
+  unsafe fn dot(n: usize, alpha: f32, x: *const f32, incx: usize, y: *const f32, incy: usize) -> f32 {
+    openblas_parallel_cblas_sdot(
+        n as _,
+        alpha,
+        x, incx as _,
+        y, incy as _,
+    )
+  }
+
+  unsafe fn nrm2(n: usize, x: *const f32, incx: usize) -> f32 {
+    openblas_parallel_cblas_snrm2(
+        n as _,
+        x, incx as _,
+    )
+  }
+
+  unsafe fn axpy(n: usize, alpha: f32, x: *const f32, incx: usize, y: *mut f32, incy: usize) {
+    openblas_parallel_cblas_saxpy(
+        n as _,
+        alpha,
+        x, incx as _,
+        y, incy as _,
+    )
+  }
+
+  unsafe fn scal(n: usize, alpha: f32, x: *mut f32, incx: usize) {
+    openblas_parallel_cblas_sscal(
+        n as _,
+        alpha,
+        x, incx as _,
+    )
+  }
+
+  unsafe fn trsv(uplo: Uplo, a_trans: Transpose, n: usize, a: *const f32, lda: usize, x: *mut f32, incx: usize) {
+    openblas_parallel_cblas_strsv(
+        CblasOrder::ColMajor,
+        cblas_uplo(uplo),
+        cblas_trans(a_trans),
+        CBLAS_NON_UNIT,
+        n as _,
+        a, lda as _,
+        x, incx as _,
+    )
+  }
+
+  unsafe fn trsm(uplo: Uplo, a_trans: Transpose, m: usize, n: usize, alpha: f32, a: *const f32, lda: usize, b: *mut f32, ldb: usize) {
+    openblas_parallel_cblas_strsm(
+        CblasOrder::ColMajor,
+        CBLAS_LEFT,
+        cblas_uplo(uplo),
+        cblas_trans(a_trans),
+        CBLAS_NON_UNIT,
+        m as _, n as _,
+        alpha,
+        a, lda as _,
+        b, ldb as _,
+    )
+  }
+
+  unsafe fn ger(m: usize, n: usize, alpha: f32, x: *const f32, incx: usize, y: *const f32, incy: usize, a: *mut f32, lda: usize) {
+    openblas_parallel_cblas_sger(
+        CblasOrder::ColMajor,
+        m as _, n as _,
+        alpha,
+        x, incx as _,
+        y, incy as _,
+        a, lda as _,
+    )
+  }
+
+  unsafe fn syr(uplo: Uplo, n: usize, alpha: f32, x: *const f32, incx: usize, a: *mut f32, lda: usize) {
+    openblas_parallel_cblas_ssyr(
+        CblasOrder::ColMajor,
+        cblas_uplo(uplo),
+        n as _,
+        alpha,
+        x, incx as _,
+        a, lda as _,
+    )
+  }
+
+  unsafe fn syrk(uplo: Uplo, a_trans: Transpose, n: usize, k: usize, alpha: f32, a: *const f32, lda: usize, beta: f32, c: *mut f32, ldc: usize) {
+    openblas_parallel_cblas_ssyrk(
+        CblasOrder::ColMajor,
+        cblas_uplo(uplo),
+        cblas_trans(a_trans),
+        n as _, k as _,
+        alpha,
+        a, lda as _,
+        beta,
+        c, ldc as _,
+    )
+  }
+
+  unsafe fn potrf(uplo: Uplo, n: usize, a: *mut f32, lda: usize) -> i32 {
+    openblas_sequential_LAPACKE_spotrf(
+        CblasOrder::ColMajor as i32,
+        lapack_uplo(uplo),
+        n as _,
+        a, lda as _,
+    ) as i32
+  }
+
+  unsafe fn potrs(uplo: Uplo, n: usize, nrhs: usize, a: *const f32, lda: usize, b: *mut f32, ldb: usize) -> i32 {
+    openblas_sequential_LAPACKE_spotrs(
+        CblasOrder::ColMajor as i32,
+        lapack_uplo(uplo),
+        n as _, nrhs as _,
+        a, lda as _,
+        b, ldb as _,
+    ) as i32
+  }
+
+  unsafe fn getrf(m: usize, n: usize, a: *mut f32, lda: usize, ipiv: *mut i32) -> i32 {
+    openblas_sequential_LAPACKE_sgetrf(
+        CblasOrder::ColMajor as i32,
+        m as _, n as _,
+        a, lda as _,
+        ipiv,
+    ) as i32
+  }
+
+  unsafe fn getrs(a_trans: Transpose, n: usize, nrhs: usize, a: *const f32, lda: usize, ipiv: *const i32, b: *mut f32, ldb: usize) -> i32 {
+    openblas_sequential_LAPACKE_sgetrs(
+        CblasOrder::ColMajor as i32,
+        lapack_trans(a_trans),
+        n as _, nrhs as _,
+        a, lda as _,
+        ipiv,
+        b, ldb as _,
+    ) as i32
+  }
+
+  unsafe fn getri(n: usize, a: *mut f32, lda: usize, ipiv: *const i32) -> i32 {
+    openblas_sequential_LAPACKE_sgetri(
+        CblasOrder::ColMajor as i32,
+        n as _,
+        a, lda as _,
+        ipiv,
+    ) as i32
+  }
+
+  unsafe fn geqrf(m: usize, n: usize, a: *mut f32, lda: usize, tau: *mut f32) -> i32 {
+    openblas_sequential_LAPACKE_sgeqrf(
+        CblasOrder::ColMajor as i32,
+        m as _, n as _,
+        a, lda as _,
+        tau,
+    ) as i32
+  }
+
+  unsafe fn orgqr(m: usize, n: usize, k: usize, a: *mut f32, lda: usize, tau: *const f32) -> i32 {
+    openblas_sequential_LAPACKE_sorgqr(
+        CblasOrder::ColMajor as i32,
+        m as _, n as _, k as _,
+        a, lda as _,
+        tau,
+    ) as i32
+  }
+
+  unsafe fn gels(m: usize, n: usize, nrhs: usize, a: *mut f32, lda: usize, b: *mut f32, ldb: usize) -> i32 {
+    openblas_sequential_LAPACKE_sgels(
+        CblasOrder::ColMajor as i32,
+        'N' as i8,
+        m as _, n as _, nrhs as _,
+        a, lda as _,
+        b, ldb as _,
+    ) as i32
+  }
+
+  unsafe fn gesdd(mode: SvdMode, m: usize, n: usize, a: *mut f32, lda: usize, s: *mut f32, u: *mut f32, ldu: usize, vt: *mut f32, ldvt: usize) -> i32 {
+    openblas_sequential_LAPACKE_sgesdd(
+        CblasOrder::ColMajor as i32,
+        lapack_svd_job(mode),
+        m as _, n as _,
+        a, lda as _,
+        s,
+        u, ldu as _,
+        vt, ldvt as _,
+    ) as i32
+  }
+
+  unsafe fn syevr(vectors: bool, range: EigenRange<f32>, uplo: Uplo, n: usize, a: *mut f32, lda: usize, abs_tol: f32, m: &mut usize, w: *mut f32, z: *mut f32, ldz: usize) -> i32 {
+    let (range, vl, vu, il, iu) = lapack_range(range);
+    let mut m_found: c_int = 0;
+    let mut isuppz: Vec<c_int> = vec![0; 2 * max(1, n)];
+    let status = openblas_sequential_LAPACKE_ssyevr(
+        CblasOrder::ColMajor as i32,
+        if vectors { 'V' as i8 } else { 'N' as i8 },
+        range,
+        lapack_uplo(uplo),
+        n as _,
+        a, lda as _,
+        vl, vu,
+        il, iu,
+        abs_tol,
+        &mut m_found as *mut _,
+        w,
+        z, ldz as _,
+        isuppz.as_mut_ptr(),
+    ) as i32;
+    *m = m_found as usize;
+    status
+  }
+
+  unsafe fn stebz(range: EigenRange<f32>, n: usize, abs_tol: f32, d: *const f32, e: *const f32, m: &mut usize, nsplit: &mut usize, w: *mut f32, iblock: *mut i32, isplit: *mut i32) -> i32 {
+    let (range, vl, vu, il, iu) = lapack_range(range);
+    let mut m_found: c_int = 0;
+    let mut nsplit_found: c_int = 0;
+    let status = openblas_sequential_LAPACKE_sstebz(
+        range,
+        'E' as i8,
+        n as _,
+        vl, vu,
+        il, iu,
+        abs_tol,
+        d,
+        e,
+        &mut m_found as *mut _,
+        &mut nsplit_found as *mut _,
+        w,
+        iblock,
+        isplit,
+    ) as i32;
+    *m = m_found as usize;
+    *nsplit = nsplit_found as usize;
+    status
+  }
+
+  unsafe fn stein(n: usize, d: *const f32, e: *const f32, m: usize, w: *const f32, iblock: *const i32, isplit: *const i32, z: *mut f32, ldz: usize) -> i32 {
+    let mut ifail: Vec<c_int> = vec![0; max(1, m)];
+    openblas_sequential_LAPACKE_sstein(
+        CblasOrder::ColMajor as i32,
+        n as _,
+        d,
+        e,
+        m as _,
+        w,
+        iblock,
+        isplit,
+        z, ldz as _,
+        ifail.as_mut_ptr(),
+    ) as i32
+  }
+}
+
+impl Backend<f64> for OpenblasParallel {
+  unsafe fn gemm(a_trans: Transpose, b_trans: Transpose, m: usize, n: usize, k: usize, alpha: f64, a: *const f64, lda: usize, b: *const f64, ldb: usize, beta: f64, c: *mut f64, ldc: usize) {
+    openblas_parallel_cblas_dgemm(
+        CblasOrder::ColMajor,
+        cblas_trans(a_trans),
+        cblas_trans(b_trans),
+        m as _, n as _, k as _,
+        alpha,
+        a, lda as _,
+        b, ldb as _,
+        beta,
+        c, ldc as _,
+    )
+  }
+
+  unsafe fn gemm_batch(a_trans: Transpose, b_trans: Transpose, m: usize, n: usize, k: usize, alpha: f64, a: *const f64, lda: usize, stride_a: usize, b: *const f64, ldb: usize, stride_b: usize, beta: f64, c: *mut f64, ldc: usize, stride_c: usize, batch_sz: usize) {
+    gemm_batch_loop::<f64, OpenblasSequential>(a_trans, b_trans, m, n, k, alpha, a, lda, stride_a, b, ldb, stride_b, beta, c, ldc, stride_c, batch_sz)
+  }
+
+  unsafe fn gemv(a_trans: Transpose, m: usize, n: usize, alpha: f64, a: *const f64, lda: usize, x: *const f64, incx: usize, beta: f64, y: *mut f64, incy: usize) {
+    openblas_parallel_cblas_dgemv(
+        CblasOrder::ColMajor,
+        cblas_trans(a_trans),
+        m as _, n as _,
+        alpha,
+        a, lda as _,
+        x, incx as _,
+        beta,
+        y, incy as _,
+    )
+  }
+
+  unsafe fn dot(n: usize, alpha: f64, x: *const f64, incx: usize, y: *const f64, incy: usize) -> f64 {
+    openblas_parallel_cblas_ddot(
+        n as _,
+        alpha,
+        x, incx as _,
+        y, incy as _,
+    )
+  }
+
+  unsafe fn nrm2(n: usize, x: *const f64, incx: usize) -> f64 {
+    openblas_parallel_cblas_dnrm2(
+        n as _,
+        x, incx as _,
+    )
+  }
+
+  unsafe fn axpy(n: usize, alpha: f64, x: *const f64, incx: usize, y: *mut f64, incy: usize) {
+    openblas_parallel_cblas_daxpy(
+        n as _,
+        alpha,
+        x, incx as _,
+        y, incy as _,
+    )
+  }
+
+  unsafe fn scal(n: usize, alpha: f64, x: *mut f64, incx: usize) {
+    openblas_parallel_cblas_dscal(
+        n as _,
+        alpha,
+        x, incx as _,
+    )
+  }
+
+  unsafe fn trsv(uplo: Uplo, a_trans: Transpose, n: usize, a: *const f64, lda: usize, x: *mut f64, incx: usize) {
+    openblas_parallel_cblas_dtrsv(
+        CblasOrder::ColMajor,
+        cblas_uplo(uplo),
+        cblas_trans(a_trans),
+        CBLAS_NON_UNIT,
+        n as _,
+        a, lda as _,
+        x, incx as _,
+    )
+  }
+
+  unsafe fn trsm(uplo: Uplo, a_trans: Transpose, m: usize, n: usize, alpha: f64, a: *const f64, lda: usize, b: *mut f64, ldb: usize) {
+    openblas_parallel_cblas_dtrsm(
+        CblasOrder::ColMajor,
+        CBLAS_LEFT,
+        cblas_uplo(uplo),
+        cblas_trans(a_trans),
+        CBLAS_NON_UNIT,
+        m as _, n as _,
+        alpha,
+        a, lda as _,
+        b, ldb as _,
+    )
+  }
+
+  unsafe fn ger(m: usize, n: usize, alpha: f64, x: *const f64, incx: usize, y: *const f64, incy: usize, a: *mut f64, lda: usize) {
+    openblas_parallel_cblas_dger(
+        CblasOrder::ColMajor,
+        m as _, n as _,
+        alpha,
+        x, incx as _,
+        y, incy as _,
+        a, lda as _,
+    )
+  }
+
+  unsafe fn syr(uplo: Uplo, n: usize, alpha: f64, x: *const f64, incx: usize, a: *mut f64, lda: usize) {
+    openblas_parallel_cblas_dsyr(
+        CblasOrder::ColMajor,
+        cblas_uplo(uplo),
+        n as _,
+        alpha,
+        x, incx as _,
+        a, lda as _,
+    )
+  }
+
+  unsafe fn syrk(uplo: Uplo, a_trans: Transpose, n: usize, k: usize, alpha: f64, a: *const f64, lda: usize, beta: f64, c: *mut f64, ldc: usize) {
+    openblas_parallel_cblas_dsyrk(
+        CblasOrder::ColMajor,
+        cblas_uplo(uplo),
+        cblas_trans(a_trans),
+        n as _, k as _,
+        alpha,
+        a, lda as _,
+        beta,
+        c, ldc as _,
+    )
+  }
+
+  unsafe fn potrf(uplo: Uplo, n: usize, a: *mut f64, lda: usize) -> i32 {
+    openblas_sequential_LAPACKE_dpotrf(
+        CblasOrder::ColMajor as i32,
+        lapack_uplo(uplo),
+        n as _,
+        a, lda as _,
+    ) as i32
+  }
+
+  unsafe fn potrs(uplo: Uplo, n: usize, nrhs: usize, a: *const f64, lda: usize, b: *mut f64, ldb: usize) -> i32 {
+    openblas_sequential_LAPACKE_dpotrs(
+        CblasOrder::ColMajor as i32,
+        lapack_uplo(uplo),
+        n as _, nrhs as _,
+        a, lda as _,
+        b, ldb as _,
+    ) as i32
+  }
+
+  unsafe fn getrf(m: usize, n: usize, a: *mut f64, lda: usize, ipiv: *mut i32) -> i32 {
+    openblas_sequential_LAPACKE_dgetrf(
+        CblasOrder::ColMajor as i32,
+        m as _, n as _,
+        a, lda as _,
+        ipiv,
+    ) as i32
+  }
+
+  unsafe fn getrs(a_trans: Transpose, n: usize, nrhs: usize, a: *const f64, lda: usize, ipiv: *const i32, b: *mut f64, ldb: usize) -> i32 {
+    openblas_sequential_LAPACKE_dgetrs(
+        CblasOrder::ColMajor as i32,
+        lapack_trans(a_trans),
+        n as _, nrhs as _,
+        a, lda as _,
+        ipiv,
+        b, ldb as _,
+    ) as i32
+  }
+
+  unsafe fn getri(n: usize, a: *mut f64, lda: usize, ipiv: *const i32) -> i32 {
+    openblas_sequential_LAPACKE_dgetri(
+        CblasOrder::ColMajor as i32,
+        n as _,
+        a, lda as _,
+        ipiv,
+    ) as i32
+  }
+
+  unsafe fn geqrf(m: usize, n: usize, a: *mut f64, lda: usize, tau: *mut f64) -> i32 {
+    openblas_sequential_LAPACKE_dgeqrf(
+        CblasOrder::ColMajor as i32,
+        m as _, n as _,
+        a, lda as _,
+        tau,
+    ) as i32
+  }
+
+  unsafe fn orgqr(m: usize, n: usize, k: usize, a: *mut f64, lda: usize, tau: *const f64) -> i32 {
+    openblas_sequential_LAPACKE_dorgqr(
+        CblasOrder::ColMajor as i32,
+        m as _, n as _, k as _,
+        a, lda as _,
+        tau,
+    ) as i32
+  }
+
+  unsafe fn gels(m: usize, n: usize, nrhs: usize, a: *mut f64, lda: usize, b: *mut f64, ldb: usize) -> i32 {
+    openblas_sequential_LAPACKE_dgels(
+        CblasOrder::ColMajor as i32,
+        'N' as i8,
+        m as _, n as _, nrhs as _,
+        a, lda as _,
+        b, ldb as _,
+    ) as i32
+  }
+
+  unsafe fn gesdd(mode: SvdMode, m: usize, n: usize, a: *mut f64, lda: usize, s: *mut f64, u: *mut f64, ldu: usize, vt: *mut f64, ldvt: usize) -> i32 {
+    openblas_sequential_LAPACKE_dgesdd(
+        CblasOrder::ColMajor as i32,
+        lapack_svd_job(mode),
+        m as _, n as _,
+        a, lda as _,
+        s,
+        u, ldu as _,
+        vt, ldvt as _,
+    ) as i32
+  }
+
+  unsafe fn syevr(vectors: bool, range: EigenRange<f64>, uplo: Uplo, n: usize, a: *mut f64, lda: usize, abs_tol: f64, m: &mut usize, w: *mut f64, z: *mut f64, ldz: usize) -> i32 {
+    let (range, vl, vu, il, iu) = lapack_range(range);
+    let mut m_found: c_int = 0;
+    let mut isuppz: Vec<c_int> = vec![0; 2 * max(1, n)];
+    let status = openblas_sequential_LAPACKE_dsyevr(
+        CblasOrder::ColMajor as i32,
+        if vectors { 'V' as i8 } else { 'N' as i8 },
+        range,
+        lapack_uplo(uplo),
+        n as _,
+        a, lda as _,
+        vl, vu,
+        il, iu,
+        abs_tol,
+        &mut m_found as *mut _,
+        w,
+        z, ldz as _,
+        isuppz.as_mut_ptr(),
+    ) as i32;
+    *m = m_found as usize;
+    status
+  }
+
+  unsafe fn stebz(range: EigenRange<f64>, n: usize, abs_tol: f64, d: *const f64, e: *const f64, m: &mut usize, nsplit: &mut usize, w: *mut f64, iblock: *mut i32, isplit: *mut i32) -> i32 {
+    let (range, vl, vu, il, iu) = lapack_range(range);
+    let mut m_found: c_int = 0;
+    let mut nsplit_found: c_int = 0;
+    let status = openblas_sequential_LAPACKE_dstebz(
+        range,
+        'E' as i8,
+        n as _,
+        vl, vu,
+        il, iu,
+        abs_tol,
+        d,
+        e,
+        &mut m_found as *mut _,
+        &mut nsplit_found as *mut _,
+        w,
+        iblock,
+        isplit,
+    ) as i32;
+    *m = m_found as usize;
+    *nsplit = nsplit_found as usize;
+    status
+  }
+
+  unsafe fn stein(n: usize, d: *const f64, e: *const f64, m: usize, w: *const f64, iblock: *const i32, isplit: *const i32, z: *mut f64, ldz: usize) -> i32 {
+    let mut ifail: Vec<c_int> = vec![0; max(1, m)];
+    openblas_sequential_LAPACKE_dstein(
+        CblasOrder::ColMajor as i32,
+        n as _,
+        d,
+        e,
+        m as _,
+        w,
+        iblock,
+        isplit,
+        z, ldz as _,
+        ifail.as_mut_ptr(),
+    ) as i32
+  }
+}
@@ -0,0 +1,1194 @@
+use backend::{Backend, BackendThreads, gemm_batch_loop};
+use linalg::{EigenRange, SvdMode, Transpose, Uplo};
+
+use std::cmp::{Ordering, min};
+use std::ops::{Add, Sub, Mul, Div, Neg};
+use std::{f32, f64};
+
+const REFERENCE_BLOCK_SZ: usize = 64;
+const REFERENCE_BISECT_MAX_ITERS: usize = 256;
+const REFERENCE_JACOBI_MAX_SWEEPS: usize = 64;
+const REFERENCE_INVERSE_ITERS: usize = 5;
+
+/// Dependency-free implementations of the backend routines. These are meant
+/// for correctness on machines without a system BLAS, not for speed.
+#[derive(Clone, Copy, Debug)]
+pub struct Reference;
+
+pub trait ReferenceScalar: Copy + Send + Sync + PartialOrd + Add<Output=Self> + Sub<Output=Self> + Mul<Output=Self> + Div<Output=Self> + Neg<Output=Self> {
+  fn zero() -> Self;
+  fn one() -> Self;
+  fn abs(self) -> Self;
+  fn sqrt(self) -> Self;
+  fn epsilon() -> Self;
+  fn min_positive() -> Self;
+  fn from_f64(x: f64) -> Self;
+  fn to_f64(self) -> f64;
+}
+
+impl ReferenceScalar for f32 {
+  fn zero() -> f32 { 0.0 }
+  fn one() -> f32 { 1.0 }
+  fn abs(self) -> f32 { self.abs() }
+  fn sqrt(self) -> f32 { self.sqrt() }
+  fn epsilon() -> f32 { f32::EPSILON }
+  fn min_positive() -> f32 { f32::MIN_POSITIVE }
+  fn from_f64(x: f64) -> f32 { x as f32 }
+  fn to_f64(self) -> f64 { self as f64 }
+}
+
+impl ReferenceScalar for f64 {
+  fn zero() -> f64 { 0.0 }
+  fn one() -> f64 { 1.0 }
+  fn abs(self) -> f64 { self.abs() }
+  fn sqrt(self) -> f64 { self.sqrt() }
+  fn epsilon() -> f64 { f64::EPSILON }
+  fn min_positive() -> f64 { f64::MIN_POSITIVE }
+  fn from_f64(x: f64) -> f64 { x }
+  fn to_f64(self) -> f64 { self }
+}
+
+#[inline]
+unsafe fn ld<T: Copy>(p: *const T, idx: usize) -> T {
+  *p.offset(idx as isize)
+}
+
+#[inline]
+unsafe fn st<T: Copy>(p: *mut T, idx: usize, x: T) {
+  *p.offset(idx as isize) = x;
+}
+
+fn max<T: ReferenceScalar>(x: T, y: T) -> T {
+  if x >= y { x } else { y }
+}
+
+unsafe fn scale_matrix<T: ReferenceScalar>(m: usize, n: usize, beta: T, c: *mut T, ldc: usize) {
+  if beta == T::one() {
+    return;
+  }
+  for j in 0 .. n {
+    for i in 0 .. m {
+      let idx = i + j * ldc;
+      if beta == T::zero() {
+        st(c, idx, T::zero());
+      } else {
+        st(c, idx, beta * ld(c, idx));
+      }
+    }
+  }
+}
+
+unsafe fn scale_vector<T: ReferenceScalar>(n: usize, beta: T, y: *mut T, incy: usize) {
+  if beta == T::one() {
+    return;
+  }
+  for i in 0 .. n {
+    let idx = i * incy;
+    if beta == T::zero() {
+      st(y, idx, T::zero());
+    } else {
+      st(y, idx, beta * ld(y, idx));
+    }
+  }
+}
+
+unsafe fn swap_rows<T: Copy>(n: usize, a: *mut T, lda: usize, i1: usize, i2: usize) {
+  for j in 0 .. n {
+    let t = ld(a, i1 + j * lda);
+    st(a, i1 + j * lda, ld(a, i2 + j * lda));
+    st(a, i2 + j * lda, t);
+  }
+}
+
+/// Applies the reflector `H = I - tau * v * v^T` from the left to the
+/// `m x n` matrix `C`.
+unsafe fn apply_reflector<T: ReferenceScalar>(m: usize, n: usize, v: *const T, tau: T, c: *mut T, ldc: usize) {
+  if tau == T::zero() {
+    return;
+  }
+  for j in 0 .. n {
+    let c_j = c.offset((j * ldc) as isize);
+    let mut w = T::zero();
+    for i in 0 .. m {
+      w = w + ld(v, i) * ld(c_j, i);
+    }
+    w = tau * w;
+    for i in 0 .. m {
+      st(c_j, i, ld(c_j, i) - w * ld(v, i));
+    }
+  }
+}
+
+/// Solves `op(A) * x = alpha * b` in place of `x` by forward or back
+/// substitution.
+unsafe fn tri_solve<T: ReferenceScalar>(uplo: Uplo, a_trans: Transpose, n: usize, alpha: T, a: *const T, lda: usize, x: *mut T, incx: usize) {
+  // `op(A)` is lower triangular when `A` is lower and not transposed, or
+  // upper and transposed.
+  let op_a = |i: usize, k: usize| match a_trans {
+    Transpose::N => ld(a, i + k * lda),
+    Transpose::T => ld(a, k + i * lda),
+  };
+  let lower = match (uplo, a_trans) {
+    (Uplo::Lower, Transpose::N) | (Uplo::Upper, Transpose::T) => true,
+    _ => false,
+  };
+  scale_vector(n, alpha, x, incx);
+  if lower {
+    for i in 0 .. n {
+      let mut t = ld(x, i * incx);
+      for k in 0 .. i {
+        t = t - op_a(i, k) * ld(x, k * incx);
+      }
+      st(x, i * incx, t / op_a(i, i));
+    }
+  } else {
+    for i in (0 .. n).rev() {
+      let mut t = ld(x, i * incx);
+      for k in i + 1 .. n {
+        t = t - op_a(i, k) * ld(x, k * incx);
+      }
+      st(x, i * incx, t / op_a(i, i));
+    }
+  }
+}
+
+/// One-sided Jacobi: rotates the columns of the packed `m x n` matrix `W`
+/// until they are mutually orthogonal, and applies the same rotations to the
+/// columns of the packed `n x n` matrix `V`. Returns false if the sweeps run
+/// out first.
+fn jacobi_orthogonalize<T: ReferenceScalar>(m: usize, n: usize, w: &mut [T], v: &mut [T]) -> bool {
+  let tol = T::from_f64(m as f64) * T::epsilon();
+  let two = T::from_f64(2.0);
+  for _ in 0 .. REFERENCE_JACOBI_MAX_SWEEPS {
+    let mut rotated = false;
+    for p in 0 .. n {
+      for q in p + 1 .. n {
+        let mut alpha = T::zero();
+        let mut beta = T::zero();
+        let mut gamma = T::zero();
+        for i in 0 .. m {
+          let w_ip = w[i + p * m];
+          let w_iq = w[i + q * m];
+          alpha = alpha + w_ip * w_ip;
+          beta = beta + w_iq * w_iq;
+          gamma = gamma + w_ip * w_iq;
+        }
+        if !(gamma.abs() > tol * alpha.sqrt() * beta.sqrt()) {
+          continue;
+        }
+        rotated = true;
+        // The smaller root `t = tan(theta)` of `t^2 + 2 * zeta * t - 1 = 0`
+        // zeroes the inner product of the rotated columns.
+        let zeta = (beta - alpha) / (two * gamma);
+        let t_abs = T::one() / (zeta.abs() + (T::one() + zeta * zeta).sqrt());
+        let t = if zeta >= T::zero() { t_abs } else { -t_abs };
+        let c = T::one() / (T::one() + t * t).sqrt();
+        let s = c * t;
+        for i in 0 .. m {
+          let w_ip = w[i + p * m];
+          let w_iq = w[i + q * m];
+          w[i + p * m] = c * w_ip - s * w_iq;
+          w[i + q * m] = s * w_ip + c * w_iq;
+        }
+        for i in 0 .. n {
+          let v_ip = v[i + p * n];
+          let v_iq = v[i + q * n];
+          v[i + p * n] = c * v_ip - s * v_iq;
+          v[i + q * n] = s * v_ip + c * v_iq;
+        }
+      }
+    }
+    if !rotated {
+      return true;
+    }
+  }
+  false
+}
+
+/// Fills the columns of the packed `m x n` matrix `Q` which are not `filled`
+/// so that all of its columns are orthonormal, using for each the unit vector
+/// with the largest component orthogonal to the columns so far.
+fn complete_orthonormal<T: ReferenceScalar>(m: usize, n: usize, q: &mut [T], filled: &mut [bool]) {
+  for j in 0 .. n {
+    if filled[j] {
+      continue;
+    }
+    let mut best = vec![T::zero(); m];
+    let mut best_norm = T::zero();
+    for e in 0 .. m {
+      let mut x = vec![T::zero(); m];
+      x[e] = T::one();
+      for _ in 0 .. 2 {
+        for c in 0 .. n {
+          if !filled[c] {
+            continue;
+          }
+          let mut d = T::zero();
+          for i in 0 .. m {
+            d = d + q[i + c * m] * x[i];
+          }
+          for i in 0 .. m {
+            x[i] = x[i] - d * q[i + c * m];
+          }
+        }
+      }
+      let mut norm = T::zero();
+      for i in 0 .. m {
+        norm = norm + x[i] * x[i];
+      }
+      let norm = norm.sqrt();
+      if norm > best_norm {
+        best = x;
+        best_norm = norm;
+      }
+    }
+    for i in 0 .. m {
+      q[i + j * m] = best[i] / best_norm;
+    }
+    filled[j] = true;
+  }
+}
+
+/// Diagonalizes the packed symmetric `n x n` matrix `A` by cyclic two-sided
+/// Jacobi rotations `A <- J^T * A * J`, accumulating them into `V`. Returns
+/// false if the off-diagonal part has not become negligible after the
+/// maximum number of sweeps.
+fn jacobi_diagonalize<T: ReferenceScalar>(n: usize, a: &mut [T], v: &mut [T]) -> bool {
+  let two = T::from_f64(2.0);
+  let mut norm2 = T::zero();
+  for idx in 0 .. n * n {
+    norm2 = norm2 + a[idx] * a[idx];
+  }
+  let tol2 = T::epsilon() * T::epsilon() * norm2;
+  for _ in 0 .. REFERENCE_JACOBI_MAX_SWEEPS {
+    let mut off2 = T::zero();
+    for q in 0 .. n {
+      for p in 0 .. q {
+        off2 = off2 + two * a[p + q * n] * a[p + q * n];
+      }
+    }
+    if off2 <= tol2 {
+      return true;
+    }
+    for q in 1 .. n {
+      for p in 0 .. q {
+        let a_pq = a[p + q * n];
+        if a_pq == T::zero() {
+          continue;
+        }
+        // The smaller root `t = tan(theta)` of `t^2 + 2 * zeta * t - 1 = 0`
+        // zeroes `A(p, q)`.
+        let zeta = (a[q + q * n] - a[p + p * n]) / (two * a_pq);
+        let t_abs = T::one() / (zeta.abs() + (T::one() + zeta * zeta).sqrt());
+        let t = if zeta >= T::zero() { t_abs } else { -t_abs };
+        let c = T::one() / (T::one() + t * t).sqrt();
+        let s = c * t;
+        for k in 0 .. n {
+          let a_kp = a[k + p * n];
+          let a_kq = a[k + q * n];
+          a[k + p * n] = c * a_kp - s * a_kq;
+          a[k + q * n] = s * a_kp + c * a_kq;
+        }
+        for k in 0 .. n {
+          let a_pk = a[p + k * n];
+          let a_qk = a[q + k * n];
+          a[p + k * n] = c * a_pk - s * a_qk;
+          a[q + k * n] = s * a_pk + c * a_qk;
+        }
+        for k in 0 .. n {
+          let v_kp = v[k + p * n];
+          let v_kq = v[k + q * n];
+          v[k + p * n] = c * v_kp - s * v_kq;
+          v[k + q * n] = s * v_kp + c * v_kq;
+        }
+      }
+    }
+  }
+  false
+}
+
+/// Solves `(T - shift * I) * x = b` in place of `x` for the symmetric
+/// tridiagonal `T` by Gaussian elimination with partial pivoting, with pivots
+/// smaller than `pivmin` in magnitude replaced by `pivmin`.
+unsafe fn shifted_tridiag_solve<T: ReferenceScalar>(n: usize, d: *const T, e: *const T, shift: T, pivmin: T, x: &mut [T]) {
+  // The factor `U` has the diagonal `u0` and two superdiagonals `u1` and
+  // `u2`; `l` holds the multipliers and `swapped` the row interchanges.
+  let mut u0: Vec<T> = (0 .. n).map(|i| ld(d, i) - shift).collect();
+  let mut u1: Vec<T> = (0 .. n).map(|i| if i + 1 < n { ld(e, i) } else { T::zero() }).collect();
+  let mut u2 = vec![T::zero(); n];
+  let mut l = vec![T::zero(); n];
+  let mut swapped = vec![false; n];
+  for i in 0 .. n - 1 {
+    let e_i = ld(e, i);
+    if u0[i].abs() >= e_i.abs() {
+      if u0[i] != T::zero() {
+        l[i] = e_i / u0[i];
+        u0[i + 1] = u0[i + 1] - l[i] * u1[i];
+      }
+    } else {
+      l[i] = u0[i] / e_i;
+      u0[i] = e_i;
+      let t = u1[i];
+      u1[i] = u0[i + 1];
+      u0[i + 1] = t - l[i] * u0[i + 1];
+      if i + 2 < n {
+        u2[i] = u1[i + 1];
+        u1[i + 1] = -l[i] * u1[i + 1];
+      }
+      swapped[i] = true;
+    }
+  }
+  for i in 0 .. n {
+    if u0[i].abs() < pivmin {
+      u0[i] = if u0[i] < T::zero() { -pivmin } else { pivmin };
+    }
+  }
+  for i in 0 .. n - 1 {
+    if swapped[i] {
+      let t = x[i];
+      x[i] = x[i + 1];
+      x[i + 1] = t - l[i] * x[i];
+    } else {
+      x[i + 1] = x[i + 1] - l[i] * x[i];
+    }
+  }
+  for i in (0 .. n).rev() {
+    let mut r = x[i];
+    if i + 1 < n {
+      r = r - u1[i] * x[i + 1];
+    }
+    if i + 2 < n {
+      r = r - u2[i] * x[i + 2];
+    }
+    x[i] = r / u0[i];
+  }
+}
+
+/// Counts the eigenvalues of the symmetric tridiagonal matrix that are less
+/// than `x` via the Sturm sequence.
+unsafe fn sturm_count<T: ReferenceScalar>(n: usize, d: *const T, e: *const T, pivmin: T, x: T) -> usize {
+  let mut count = 0;
+  let mut q = ld(d, 0) - x;
+  if q.abs() < pivmin {
+    q = -pivmin;
+  }
+  if q < T::zero() {
+    count += 1;
+  }
+  for i in 1 .. n {
+    let e_prev = ld(e, i - 1);
+    q = ld(d, i) - x - e_prev * e_prev / q;
+    if q.abs() < pivmin {
+      q = -pivmin;
+    }
+    if q < T::zero() {
+      count += 1;
+    }
+  }
+  count
+}
+
+impl BackendThreads for Reference {
+  fn set_num_threads(_num_threads: usize) {
+  }
+
+  fn num_threads() -> usize {
+    1
+  }
+}
+
+impl<T> Backend<T> for Reference where T: ReferenceScalar {
+  unsafe fn gemm(a_trans: Transpose, b_trans: Transpose, m: usize, n: usize, k: usize, alpha: T, a: *const T, lda: usize, b: *const T, ldb: usize, beta: T, c: *mut T, ldc: usize) {
+    scale_matrix(m, n, beta, c, ldc);
+    if k == 0 || alpha == T::zero() {
+      return;
+    }
+    let mut j_blk = 0;
+    while j_blk < n {
+      let j_end = min(j_blk + REFERENCE_BLOCK_SZ, n);
+      let mut l_blk = 0;
+      while l_blk < k {
+        let l_end = min(l_blk + REFERENCE_BLOCK_SZ, k);
+        let mut i_blk = 0;
+        while i_blk < m {
+          let i_end = min(i_blk + REFERENCE_BLOCK_SZ, m);
+          for j in j_blk .. j_end {
+            for l in l_blk .. l_end {
+              let b_lj = alpha * match b_trans {
+                Transpose::N => ld(b, l + j * ldb),
+                Transpose::T => ld(b, j + l * ldb),
+              };
+              match a_trans {
+                Transpose::N => {
+                  for i in i_blk .. i_end {
+                    let idx = i + j * ldc;
+                    st(c, idx, ld(c, idx) + ld(a, i + l * lda) * b_lj);
+                  }
+                }
+                Transpose::T => {
+                  for i in i_blk .. i_end {
+                    let idx = i + j * ldc;
+                    st(c, idx, ld(c, idx) + ld(a, l + i * lda) * b_lj);
+                  }
+                }
+              }
+            }
+          }
+          i_blk = i_end;
+        }
+        l_blk = l_end;
+      }
+      j_blk = j_end;
+    }
+  }
+
+  unsafe fn gemm_batch(a_trans: Transpose, b_trans: Transpose, m: usize, n: usize, k: usize, alpha: T, a: *const T, lda: usize, stride_a: usize, b: *const T, ldb: usize, stride_b: usize, beta: T, c: *mut T, ldc: usize, stride_c: usize, batch_sz: usize) {
+    gemm_batch_loop::<T, Reference>(a_trans, b_trans, m, n, k, alpha, a, lda, stride_a, b, ldb, stride_b, beta, c, ldc, stride_c, batch_sz)
+  }
+
+  unsafe fn gemv(a_trans: Transpose, m: usize, n: usize, alpha: T, a: *const T, lda: usize, x: *const T, incx: usize, beta: T, y: *mut T, incy: usize) {
+    match a_trans {
+      Transpose::N => {
+        scale_vector(m, beta, y, incy);
+        for j in 0 .. n {
+          let t = alpha * ld(x, j * incx);
+          for i in 0 .. m {
+            let idx = i * incy;
+            st(y, idx, ld(y, idx) + t * ld(a, i + j * lda));
+          }
+        }
+      }
+      Transpose::T => {
+        scale_vector(n, beta, y, incy);
+        for j in 0 .. n {
+          let mut s = T::zero();
+          for i in 0 .. m {
+            s = s + ld(a, i + j * lda) * ld(x, i * incx);
+          }
+          let idx = j * incy;
+          st(y, idx, ld(y, idx) + alpha * s);
+        }
+      }
+    }
+  }
+
+  unsafe fn dot(n: usize, alpha: T, x: *const T, incx: usize, y: *const T, incy: usize) -> T {
+    let mut s = T::zero();
+    for i in 0 .. n {
+      s = s + ld(x, i * incx) * ld(y, i * incy);
+    }
+    alpha * s
+  }
+
+  unsafe fn nrm2(n: usize, x: *const T, incx: usize) -> T {
+    // Scaled sum of squares, as in the reference BLAS, to avoid overflow.
+    let mut scale = T::zero();
+    let mut ssq = T::one();
+    for i in 0 .. n {
+      let x_i = ld(x, i * incx);
+      if x_i != T::zero() {
+        let abs_x_i = x_i.abs();
+        if scale < abs_x_i {
+          let r = scale / abs_x_i;
+          ssq = T::one() + ssq * r * r;
+          scale = abs_x_i;
+        } else {
+          let r = abs_x_i / scale;
+          ssq = ssq + r * r;
+        }
+      }
+    }
+    scale * ssq.sqrt()
+  }
+
+  unsafe fn axpy(n: usize, alpha: T, x: *const T, incx: usize, y: *mut T, incy: usize) {
+    for i in 0 .. n {
+      let idx = i * incy;
+      st(y, idx, ld(y, idx) + alpha * ld(x, i * incx));
+    }
+  }
+
+  unsafe fn scal(n: usize, alpha: T, x: *mut T, incx: usize) {
+    for i in 0 .. n {
+      let idx = i * incx;
+      st(x, idx, alpha * ld(x, idx));
+    }
+  }
+
+  unsafe fn trsv(uplo: Uplo, a_trans: Transpose, n: usize, a: *const T, lda: usize, x: *mut T, incx: usize) {
+    tri_solve(uplo, a_trans, n, T::one(), a, lda, x, incx);
+  }
+
+  unsafe fn trsm(uplo: Uplo, a_trans: Transpose, m: usize, n: usize, alpha: T, a: *const T, lda: usize, b: *mut T, ldb: usize) {
+    for j in 0 .. n {
+      tri_solve(uplo, a_trans, m, alpha, a, lda, b.offset((j * ldb) as isize), 1);
+    }
+  }
+
+  unsafe fn ger(m: usize, n: usize, alpha: T, x: *const T, incx: usize, y: *const T, incy: usize, a: *mut T, lda: usize) {
+    for j in 0 .. n {
+      let t = alpha * ld(y, j * incy);
+      for i in 0 .. m {
+        let idx = i + j * lda;
+        st(a, idx, ld(a, idx) + t * ld(x, i * incx));
+      }
+    }
+  }
+
+  unsafe fn syr(uplo: Uplo, n: usize, alpha: T, x: *const T, incx: usize, a: *mut T, lda: usize) {
+    for j in 0 .. n {
+      let t = alpha * ld(x, j * incx);
+      let (lo, hi) = match uplo {
+        Uplo::Lower => (j, n),
+        Uplo::Upper => (0, j + 1),
+      };
+      for i in lo .. hi {
+        let idx = i + j * lda;
+        st(a, idx, ld(a, idx) + t * ld(x, i * incx));
+      }
+    }
+  }
+
+  unsafe fn syrk(uplo: Uplo, a_trans: Transpose, n: usize, k: usize, alpha: T, a: *const T, lda: usize, beta: T, c: *mut T, ldc: usize) {
+    for j in 0 .. n {
+      let (lo, hi) = match uplo {
+        Uplo::Lower => (j, n),
+        Uplo::Upper => (0, j + 1),
+      };
+      for i in lo .. hi {
+        let mut s = T::zero();
+        for l in 0 .. k {
+          s = s + match a_trans {
+            Transpose::N => ld(a, i + l * lda) * ld(a, j + l * lda),
+            Transpose::T => ld(a, l + i * lda) * ld(a, l + j * lda),
+          };
+        }
+        let idx = i + j * ldc;
+        // As in BLAS, `C` is not read when `beta` is zero.
+        if beta == T::zero() {
+          st(c, idx, alpha * s);
+        } else {
+          st(c, idx, beta * ld(c, idx) + alpha * s);
+        }
+      }
+    }
+  }
+
+  unsafe fn potrf(uplo: Uplo, n: usize, a: *mut T, lda: usize) -> i32 {
+    if lda < n {
+      return -4;
+    }
+    for j in 0 .. n {
+      let mut s = ld(a, j + j * lda);
+      for k in 0 .. j {
+        let a_jk = match uplo {
+          Uplo::Lower => ld(a, j + k * lda),
+          Uplo::Upper => ld(a, k + j * lda),
+        };
+        s = s - a_jk * a_jk;
+      }
+      if !(s > T::zero()) {
+        return (j + 1) as i32;
+      }
+      let a_jj = s.sqrt();
+      st(a, j + j * lda, a_jj);
+      for i in j + 1 .. n {
+        match uplo {
+          Uplo::Lower => {
+            let mut t = ld(a, i + j * lda);
+            for k in 0 .. j {
+              t = t - ld(a, i + k * lda) * ld(a, j + k * lda);
+            }
+            st(a, i + j * lda, t / a_jj);
+          }
+          Uplo::Upper => {
+            let mut t = ld(a, j + i * lda);
+            for k in 0 .. j {
+              t = t - ld(a, k + i * lda) * ld(a, k + j * lda);
+            }
+            st(a, j + i * lda, t / a_jj);
+          }
+        }
+      }
+    }
+    0
+  }
+
+  unsafe fn potrs(uplo: Uplo, n: usize, nrhs: usize, a: *const T, lda: usize, b: *mut T, ldb: usize) -> i32 {
+    if lda < n {
+      return -5;
+    }
+    if ldb < n {
+      return -7;
+    }
+    // With the lower factor A = L * L^T, and with the upper factor
+    // A = U^T * U; in both cases `tri(i, k)` is the (i, k) entry of the lower
+    // triangular factor.
+    let tri = |i: usize, k: usize| match uplo {
+      Uplo::Lower => ld(a, i + k * lda),
+      Uplo::Upper => ld(a, k + i * lda),
+    };
+    for r in 0 .. nrhs {
+      let b_r = b.offset((r * ldb) as isize);
+      for i in 0 .. n {
+        let mut t = ld(b_r, i);
+        for k in 0 .. i {
+          t = t - tri(i, k) * ld(b_r, k);
+        }
+        st(b_r, i, t / tri(i, i));
+      }
+      for i in (0 .. n).rev() {
+        let mut t = ld(b_r, i);
+        for k in i + 1 .. n {
+          t = t - tri(k, i) * ld(b_r, k);
+        }
+        st(b_r, i, t / tri(i, i));
+      }
+    }
+    0
+  }
+
+  unsafe fn getrf(m: usize, n: usize, a: *mut T, lda: usize, ipiv: *mut i32) -> i32 {
+    if lda < m {
+      return -4;
+    }
+    let mut info = 0;
+    for j in 0 .. min(m, n) {
+      let mut p = j;
+      for i in j + 1 .. m {
+        if ld(a, i + j * lda).abs() > ld(a, p + j * lda).abs() {
+          p = i;
+        }
+      }
+      st(ipiv, j, (p + 1) as i32);
+      let a_pj = ld(a, p + j * lda);
+      if a_pj == T::zero() {
+        // The column is zero below the diagonal, so there is nothing to
+        // eliminate; as in LAPACK, report the first zero pivot and go on.
+        if info == 0 {
+          info = (j + 1) as i32;
+        }
+        continue;
+      }
+      if p != j {
+        swap_rows(n, a, lda, j, p);
+      }
+      for i in j + 1 .. m {
+        st(a, i + j * lda, ld(a, i + j * lda) / a_pj);
+      }
+      for k in j + 1 .. n {
+        let a_jk = ld(a, j + k * lda);
+        if a_jk == T::zero() {
+          continue;
+        }
+        for i in j + 1 .. m {
+          let idx = i + k * lda;
+          st(a, idx, ld(a, idx) - ld(a, i + j * lda) * a_jk);
+        }
+      }
+    }
+    info
+  }
+
+  unsafe fn getrs(a_trans: Transpose, n: usize, nrhs: usize, a: *const T, lda: usize, ipiv: *const i32, b: *mut T, ldb: usize) -> i32 {
+    if lda < n {
+      return -5;
+    }
+    if ldb < n {
+      return -8;
+    }
+    // With A = P * L * U, where L has a unit diagonal below the diagonal of
+    // `a` and U is the upper triangle of `a`.
+    match a_trans {
+      Transpose::N => {
+        for i in 0 .. n {
+          let p = (ld(ipiv, i) - 1) as usize;
+          if p != i {
+            swap_rows(nrhs, b, ldb, i, p);
+          }
+        }
+        for r in 0 .. nrhs {
+          let b_r = b.offset((r * ldb) as isize);
+          for i in 0 .. n {
+            let mut t = ld(b_r, i);
+            for k in 0 .. i {
+              t = t - ld(a, i + k * lda) * ld(b_r, k);
+            }
+            st(b_r, i, t);
+          }
+          tri_solve(Uplo::Upper, Transpose::N, n, T::one(), a, lda, b_r, 1);
+        }
+      }
+      Transpose::T => {
+        for r in 0 .. nrhs {
+          let b_r = b.offset((r * ldb) as isize);
+          tri_solve(Uplo::Upper, Transpose::T, n, T::one(), a, lda, b_r, 1);
+          for i in (0 .. n).rev() {
+            let mut t = ld(b_r, i);
+            for k in i + 1 .. n {
+              t = t - ld(a, k + i * lda) * ld(b_r, k);
+            }
+            st(b_r, i, t);
+          }
+        }
+        for i in (0 .. n).rev() {
+          let p = (ld(ipiv, i) - 1) as usize;
+          if p != i {
+            swap_rows(nrhs, b, ldb, i, p);
+          }
+        }
+      }
+    }
+    0
+  }
+
+  unsafe fn getri(n: usize, a: *mut T, lda: usize, ipiv: *const i32) -> i32 {
+    if lda < n {
+      return -4;
+    }
+    for i in 0 .. n {
+      if ld(a, i + i * lda) == T::zero() {
+        return (i + 1) as i32;
+      }
+    }
+    // Solves A * X = I into a separate buffer, then copies X over the factors.
+    let mut inv = vec![T::zero(); n * n];
+    for i in 0 .. n {
+      inv[i + i * n] = T::one();
+    }
+    <Reference as Backend<T>>::getrs(Transpose::N, n, n, a, lda, ipiv, inv.as_mut_ptr(), n);
+    for j in 0 .. n {
+      for i in 0 .. n {
+        st(a, i + j * lda, inv[i + j * n]);
+      }
+    }
+    0
+  }
+
+  unsafe fn geqrf(m: usize, n: usize, a: *mut T, lda: usize, tau: *mut T) -> i32 {
+    if lda < m {
+      return -4;
+    }
+    for j in 0 .. min(m, n) {
+      // The reflector maps `a[j .. m, j]` to `(beta, 0, ..., 0)`, with
+      // `v = (1, a[j + 1 .. m, j] / (alpha - beta))`.
+      let a_j = a.offset((j + j * lda) as isize);
+      let alpha = ld(a_j, 0);
+      let x_norm = <Reference as Backend<T>>::nrm2(m - j - 1, a_j.offset(1), 1);
+      if x_norm == T::zero() {
+        st(tau, j, T::zero());
+        continue;
+      }
+      let r = (alpha * alpha + x_norm * x_norm).sqrt();
+      let beta = if alpha >= T::zero() { -r } else { r };
+      st(tau, j, (beta - alpha) / beta);
+      let s = T::one() / (alpha - beta);
+      for i in 1 .. m - j {
+        st(a_j, i, s * ld(a_j, i));
+      }
+      st(a_j, 0, T::one());
+      apply_reflector(m - j, n - j - 1, a_j, ld(tau, j), a_j.offset(lda as isize), lda);
+      st(a_j, 0, beta);
+    }
+    0
+  }
+
+  unsafe fn orgqr(m: usize, n: usize, k: usize, a: *mut T, lda: usize, tau: *const T) -> i32 {
+    if n > m {
+      return -2;
+    }
+    if k > n {
+      return -3;
+    }
+    if lda < m {
+      return -5;
+    }
+    // Accumulates `Q = H(0) * ... * H(k - 1)` applied to the first `n`
+    // columns of the identity, from the last reflector backwards.
+    for j in k .. n {
+      for i in 0 .. m {
+        st(a, i + j * lda, T::zero());
+      }
+      st(a, j + j * lda, T::one());
+    }
+    for j in (0 .. k).rev() {
+      let a_j = a.offset((j + j * lda) as isize);
+      let tau_j = ld(tau, j);
+      st(a_j, 0, T::one());
+      apply_reflector(m - j, n - j - 1, a_j, tau_j, a_j.offset(lda as isize), lda);
+      for i in 1 .. m - j {
+        st(a_j, i, -tau_j * ld(a_j, i));
+      }
+      st(a_j, 0, T::one() - tau_j);
+      for i in 0 .. j {
+        st(a, i + j * lda, T::zero());
+      }
+    }
+    0
+  }
+
+  unsafe fn gels(m: usize, n: usize, nrhs: usize, a: *mut T, lda: usize, b: *mut T, ldb: usize) -> i32 {
+    if lda < m {
+      return -6;
+    }
+    if ldb < m || ldb < n {
+      return -8;
+    }
+    if m < n {
+      // The minimum norm solution: with `A^T = Q * R`, solves
+      // `R^T * Y = B`, then `X = Q * [Y; 0]`.
+      let mut at = vec![T::zero(); n * m];
+      for j in 0 .. n {
+        for i in 0 .. m {
+          at[j + i * n] = ld(a, i + j * lda);
+        }
+      }
+      let at = at.as_mut_ptr();
+      let mut tau = vec![T::zero(); m];
+      <Reference as Backend<T>>::geqrf(n, m, at, n, tau.as_mut_ptr());
+      for i in 0 .. m {
+        if ld(at, i + i * n) == T::zero() {
+          return (i + 1) as i32;
+        }
+      }
+      <Reference as Backend<T>>::trsm(Uplo::Upper, Transpose::T, m, nrhs, T::one(), at, n, b, ldb);
+      for k in 0 .. nrhs {
+        for i in m .. n {
+          st(b, i + k * ldb, T::zero());
+        }
+      }
+      for i in (0 .. m).rev() {
+        let at_i = at.offset((i + i * n) as isize);
+        st(at_i, 0, T::one());
+        apply_reflector(n - i, nrhs, at_i, tau[i], b.offset(i as isize), ldb);
+      }
+      return 0;
+    }
+    let mut tau = vec![T::zero(); n];
+    <Reference as Backend<T>>::geqrf(m, n, a, lda, tau.as_mut_ptr());
+    for j in 0 .. n {
+      if ld(a, j + j * lda) == T::zero() {
+        return (j + 1) as i32;
+      }
+    }
+    // Applies `Q^T` to `B`, then solves with `R`.
+    for j in 0 .. n {
+      let a_j = a.offset((j + j * lda) as isize);
+      let beta = ld(a_j, 0);
+      st(a_j, 0, T::one());
+      apply_reflector(m - j, nrhs, a_j, tau[j], b.offset(j as isize), ldb);
+      st(a_j, 0, beta);
+    }
+    <Reference as Backend<T>>::trsm(Uplo::Upper, Transpose::N, n, nrhs, T::one(), a, lda, b, ldb);
+    0
+  }
+
+  unsafe fn gesdd(mode: SvdMode, m: usize, n: usize, a: *mut T, lda: usize, s: *mut T, u: *mut T, ldu: usize, vt: *mut T, ldvt: usize) -> i32 {
+    if lda < m {
+      return -5;
+    }
+    // One-sided Jacobi on `W = A` if `A` is tall and on `W = A^T` if it is
+    // wide, so that `W` is `p x k` with `p >= k`. Then `W * V` has orthogonal
+    // columns, whose norms are the singular values.
+    let k = min(m, n);
+    let tall = m >= n;
+    let p = if tall { m } else { n };
+    let mut w = vec![T::zero(); p * k];
+    for j in 0 .. n {
+      for i in 0 .. m {
+        let a_ij = ld(a, i + j * lda);
+        if tall {
+          w[i + j * p] = a_ij;
+        } else {
+          w[j + i * p] = a_ij;
+        }
+      }
+    }
+    let mut v = vec![T::zero(); k * k];
+    for i in 0 .. k {
+      v[i + i * k] = T::one();
+    }
+    if !jacobi_orthogonalize(p, k, &mut w, &mut v) {
+      return 1;
+    }
+    let norms: Vec<T> = (0 .. k).map(|j| <Reference as Backend<T>>::nrm2(p, w[j * p .. ].as_ptr(), 1)).collect();
+    let mut order: Vec<usize> = (0 .. k).collect();
+    order.sort_by(|&i, &j| norms[j].partial_cmp(&norms[i]).unwrap_or(Ordering::Equal));
+    for (idx, &j) in order.iter().enumerate() {
+      st(s, idx, norms[j]);
+    }
+    if mode == SvdMode::ValuesOnly {
+      return 0;
+    }
+    // The left singular vectors of `W`, completed to an orthonormal basis
+    // where the singular values vanish.
+    let q_cols = match mode {
+      SvdMode::Full => p,
+      _ => k,
+    };
+    let mut q = vec![T::zero(); p * q_cols];
+    let mut filled = vec![false; q_cols];
+    for (idx, &j) in order.iter().enumerate() {
+      if norms[j] > T::min_positive() {
+        for i in 0 .. p {
+          q[i + idx * p] = w[i + j * p] / norms[j];
+        }
+        filled[idx] = true;
+      }
+    }
+    complete_orthonormal(p, q_cols, &mut q, &mut filled);
+    // With `W = Q * S * V^T`, either `A = Q * S * V^T` or `A = V * S * Q^T`.
+    if tall {
+      for j in 0 .. q_cols {
+        for i in 0 .. m {
+          st(u, i + j * ldu, q[i + j * p]);
+        }
+      }
+      for (idx, &j) in order.iter().enumerate() {
+        for i in 0 .. n {
+          st(vt, idx + i * ldvt, v[i + j * k]);
+        }
+      }
+    } else {
+      for (idx, &j) in order.iter().enumerate() {
+        for i in 0 .. m {
+          st(u, i + idx * ldu, v[i + j * k]);
+        }
+      }
+      for j in 0 .. q_cols {
+        for i in 0 .. n {
+          st(vt, j + i * ldvt, q[i + j * p]);
+        }
+      }
+    }
+    0
+  }
+
+  unsafe fn syevr(vectors: bool, range: EigenRange<T>, uplo: Uplo, n: usize, a: *mut T, lda: usize, _abs_tol: T, m: &mut usize, w: *mut T, z: *mut T, ldz: usize) -> i32 {
+    *m = 0;
+    let mut d = vec![T::zero(); n * n];
+    for j in 0 .. n {
+      for i in 0 .. n {
+        let in_tri = match uplo {
+          Uplo::Lower => i >= j,
+          Uplo::Upper => i <= j,
+        };
+        if in_tri {
+          d[i + j * n] = ld(a, i + j * lda);
+          d[j + i * n] = ld(a, i + j * lda);
+        }
+      }
+    }
+    let mut v = vec![T::zero(); n * n];
+    for i in 0 .. n {
+      v[i + i * n] = T::one();
+    }
+    if !jacobi_diagonalize(n, &mut d, &mut v) {
+      return 1;
+    }
+    let mut order: Vec<usize> = (0 .. n).collect();
+    order.sort_by(|&i, &j| d[i + i * n].partial_cmp(&d[j + j * n]).unwrap_or(Ordering::Equal));
+    let (lo, hi) = match range {
+      EigenRange::All => (0, n),
+      EigenRange::Values(vl, vu) => {
+        let lo = order.iter().take_while(|&&j| d[j + j * n] <= vl).count();
+        let hi = order.iter().take_while(|&&j| d[j + j * n] <= vu).count();
+        (lo, hi.max(lo))
+      }
+      EigenRange::Indices(lo, hi) => {
+        if hi > n {
+          return -10;
+        } else if lo > hi {
+          return -9;
+        }
+        (lo, hi)
+      }
+    };
+    for (idx, &j) in order[lo .. hi].iter().enumerate() {
+      st(w, idx, d[j + j * n]);
+      if vectors {
+        for i in 0 .. n {
+          st(z, i + idx * ldz, v[i + j * n]);
+        }
+      }
+    }
+    *m = hi - lo;
+    0
+  }
+
+  unsafe fn stebz(range: EigenRange<T>, n: usize, abs_tol: T, d: *const T, e: *const T, m: &mut usize, nsplit: &mut usize, w: *mut T, iblock: *mut i32, isplit: *mut i32) -> i32 {
+    *m = 0;
+    *nsplit = 0;
+    match range {
+      EigenRange::Values(vl, vu) if vl >= vu => return -5,
+      EigenRange::Indices(_, hi) if hi > n => return -7,
+      EigenRange::Indices(lo, hi) if lo > hi => return -6,
+      _ => {}
+    }
+    if n == 0 {
+      return 0;
+    }
+    let mut max_e2 = T::one();
+    for i in 0 .. n - 1 {
+      let e_i = ld(e, i);
+      max_e2 = max(max_e2, e_i * e_i);
+    }
+    let pivmin = T::min_positive() * max_e2;
+    let mut lo_bound = ld(d, 0);
+    let mut hi_bound = ld(d, 0);
+    for i in 0 .. n {
+      let mut r = T::zero();
+      if i > 0 {
+        r = r + ld(e, i - 1).abs();
+      }
+      if i + 1 < n {
+        r = r + ld(e, i).abs();
+      }
+      let d_i = ld(d, i);
+      if d_i - r < lo_bound {
+        lo_bound = d_i - r;
+      }
+      if d_i + r > hi_bound {
+        hi_bound = d_i + r;
+      }
+    }
+    let two = T::from_f64(2.0);
+    let half = T::from_f64(0.5);
+    let bound_norm = max(lo_bound.abs(), hi_bound.abs());
+    let slack = two * T::epsilon() * bound_norm * T::from_f64(n as f64) + two * pivmin;
+    lo_bound = lo_bound - slack;
+    hi_bound = hi_bound + slack;
+    // The matrix splits into blocks where an off-diagonal entry is
+    // negligible, as in LAPACK. The eigenvalues of each block are found
+    // separately and then merged in ascending order.
+    let mut ends = vec![];
+    for i in 0 .. n - 1 {
+      let e_i = ld(e, i);
+      if e_i * e_i <= (ld(d, i) * ld(d, i + 1)).abs() * T::epsilon() * T::epsilon() + T::min_positive() {
+        ends.push(i + 1);
+      }
+    }
+    ends.push(n);
+    let bisect = |len: usize, d: *const T, e: *const T, k: usize| {
+      let mut lo = lo_bound;
+      let mut hi = hi_bound;
+      for _ in 0 .. REFERENCE_BISECT_MAX_ITERS {
+        let tol = max(abs_tol, two * T::epsilon() * max(lo.abs(), hi.abs())) + pivmin;
+        if hi - lo <= tol {
+          break;
+        }
+        let mid = half * (lo + hi);
+        if sturm_count(len, d, e, pivmin, mid) > k {
+          hi = mid;
+        } else {
+          lo = mid;
+        }
+      }
+      half * (lo + hi)
+    };
+    let mut found: Vec<(T, usize)> = vec![];
+    let mut start = 0;
+    for (b, &end) in ends.iter().enumerate() {
+      let len = end - start;
+      let d_b = d.offset(start as isize);
+      let e_b = e.offset(start as isize);
+      let (k_lo, k_hi) = match range {
+        EigenRange::Values(vl, vu) => (sturm_count(len, d_b, e_b, pivmin, vl), sturm_count(len, d_b, e_b, pivmin, vu)),
+        _ => (0, len),
+      };
+      for k in k_lo .. k_hi {
+        found.push((bisect(len, d_b, e_b, k), b));
+      }
+      st(isplit, b, end as i32);
+      start = end;
+    }
+    // A stable sort, so equal eigenvalues stay in block order.
+    found.sort_by(|x, y| x.0.partial_cmp(&y.0).unwrap_or(Ordering::Equal));
+    let (k_lo, k_hi) = match range {
+      EigenRange::Indices(lo, hi) => (lo, hi),
+      _ => (0, found.len()),
+    };
+    for (k, &(w_k, b)) in found[k_lo .. k_hi].iter().enumerate() {
+      st(w, k, w_k);
+      st(iblock, k, b as i32 + 1);
+    }
+    *nsplit = ends.len();
+    *m = k_hi - k_lo;
+    0
+  }
+
+  unsafe fn stein(n: usize, d: *const T, e: *const T, m: usize, w: *const T, iblock: *const i32, isplit: *const i32, z: *mut T, ldz: usize) -> i32 {
+    if n == 0 {
+      return 0;
+    }
+    let mut t_norm = T::zero();
+    for i in 0 .. n {
+      let mut r = ld(d, i).abs();
+      if i > 0 {
+        r = r + ld(e, i - 1).abs();
+      }
+      if i + 1 < n {
+        r = r + ld(e, i).abs();
+      }
+      t_norm = max(t_norm, r);
+    }
+    let pivmin = max(T::epsilon() * t_norm, T::min_positive());
+    // Eigenvectors whose eigenvalues are this close are reorthogonalized
+    // against each other, as in LAPACK.
+    let ortho_tol = T::from_f64(1.0e-3) * t_norm;
+    let mut num_failed = 0;
+    for k in 0 .. m {
+      // Each eigenvector is computed from the block of its eigenvalue and is
+      // zero outside of it.
+      let w_k = ld(w, k);
+      let block = ld(iblock, k);
+      let start = if block > 1 { ld(isplit, block as usize - 2) as usize } else { 0 };
+      let len = ld(isplit, block as usize - 1) as usize - start;
+      let d_b = d.offset(start as isize);
+      let e_b = e.offset(start as isize);
+      let mut x: Vec<T> = (0 .. len).map(|i| T::from_f64((((start + i) * 7919 + k * 104729 + 1) % 1009) as f64 / 1009.0 + 0.5)).collect();
+      let mut norm = T::zero();
+      for _ in 0 .. REFERENCE_INVERSE_ITERS {
+        shifted_tridiag_solve(len, d_b, e_b, w_k, pivmin, &mut x);
+        for c in (0 .. k).rev() {
+          if ld(iblock, c) != block || (w_k - ld(w, c)).abs() > ortho_tol {
+            break;
+          }
+          let z_c = z.offset((start + c * ldz) as isize);
+          let mut dot = T::zero();
+          for i in 0 .. len {
+            dot = dot + ld(z_c, i) * x[i];
+          }
+          for i in 0 .. len {
+            x[i] = x[i] - dot * ld(z_c, i);
+          }
+        }
+        norm = <Reference as Backend<T>>::nrm2(len, x.as_ptr(), 1);
+        if norm == T::zero() {
+          break;
+        }
+        for i in 0 .. len {
+          x[i] = x[i] / norm;
+        }
+      }
+      if norm == T::zero() {
+        num_failed += 1;
+      }
+      // The largest component is made positive, as in LAPACK.
+      let mut i_max = 0;
+      for i in 1 .. len {
+        if x[i].abs() > x[i_max].abs() {
+          i_max = i;
+        }
+      }
+      let sign = if x[i_max] < T::zero() { -T::one() } else { T::one() };
+      for i in 0 .. n {
+        st(z, i + k * ldz, T::zero());
+      }
+      for i in 0 .. len {
+        st(z, start + i + k * ldz, sign * x[i]);
+      }
+    }
+    num_failed
+  }
+}
@@ -0,0 +1,166 @@
+use super::{ZeroBits};
+use backend::{Backend, ReferenceScalar, SequentialBackend, ParallelBackend};
+use linalg::{EigenRange, SvdMode, Transpose, Uplo};
+
+/// Column-major BLAS and LAPACK routines over a real scalar type. The plain
+/// routines go through `backend::SequentialBackend` and the `parallel_*`
+/// variants through `backend::ParallelBackend`; see `backend` for how the
+/// cargo features select them. The scalar arithmetic of `ReferenceScalar` is
+/// available to the generic solvers built on these routines. It is
+/// implemented for every scalar type that both backends support.
+pub trait BlasScalar: ZeroBits + ReferenceScalar {
+  unsafe fn gemm(a_trans: Transpose, b_trans: Transpose, m: usize, n: usize, k: usize, alpha: Self, a: *const Self, lda: usize, b: *const Self, ldb: usize, beta: Self, c: *mut Self, ldc: usize);
+  unsafe fn gemm_batch(a_trans: Transpose, b_trans: Transpose, m: usize, n: usize, k: usize, alpha: Self, a: *const Self, lda: usize, stride_a: usize, b: *const Self, ldb: usize, stride_b: usize, beta: Self, c: *mut Self, ldc: usize, stride_c: usize, batch_sz: usize);
+  unsafe fn gemv(a_trans: Transpose, m: usize, n: usize, alpha: Self, a: *const Self, lda: usize, x: *const Self, incx: usize, beta: Self, y: *mut Self, incy: usize);
+  unsafe fn dot(n: usize, alpha: Self, x: *const Self, incx: usize, y: *const Self, incy: usize) -> Self;
+  unsafe fn nrm2(n: usize, x: *const Self, incx: usize) -> Self;
+  unsafe fn axpy(n: usize, alpha: Self, x: *const Self, incx: usize, y: *mut Self, incy: usize);
+  unsafe fn scal(n: usize, alpha: Self, x: *mut Self, incx: usize);
+  unsafe fn trsv(uplo: Uplo, a_trans: Transpose, n: usize, a: *const Self, lda: usize, x: *mut Self, incx: usize);
+  unsafe fn trsm(uplo: Uplo, a_trans: Transpose, m: usize, n: usize, alpha: Self, a: *const Self, lda: usize, b: *mut Self, ldb: usize);
+  unsafe fn ger(m: usize, n: usize, alpha: Self, x: *const Self, incx: usize, y: *const Self, incy: usize, a: *mut Self, lda: usize);
+  unsafe fn syr(uplo: Uplo, n: usize, alpha: Self, x: *const Self, incx: usize, a: *mut Self, lda: usize);
+  unsafe fn syrk(uplo: Uplo, a_trans: Transpose, n: usize, k: usize, alpha: Self, a: *const Self, lda: usize, beta: Self, c: *mut Self, ldc: usize);
+
+  unsafe fn parallel_gemm(a_trans: Transpose, b_trans: Transpose, m: usize, n: usize, k: usize, alpha: Self, a: *const Self, lda: usize, b: *const Self, ldb: usize, beta: Self, c: *mut Self, ldc: usize);
+  unsafe fn parallel_gemv(a_trans: Transpose, m: usize, n: usize, alpha: Self, a: *const Self, lda: usize, x: *const Self, incx: usize, beta: Self, y: *mut Self, incy: usize);
+  unsafe fn parallel_dot(n: usize, alpha: Self, x: *const Self, incx: usize, y: *const Self, incy: usize) -> Self;
+  unsafe fn parallel_nrm2(n: usize, x: *const Self, incx: usize) -> Self;
+  unsafe fn parallel_axpy(n: usize, alpha: Self, x: *const Self, incx: usize, y: *mut Self, incy: usize);
+  unsafe fn parallel_scal(n: usize, alpha: Self, x: *mut Self, incx: usize);
+
+  unsafe fn potrf(uplo: Uplo, n: usize, a: *mut Self, lda: usize) -> i32;
+  unsafe fn potrs(uplo: Uplo, n: usize, nrhs: usize, a: *const Self, lda: usize, b: *mut Self, ldb: usize) -> i32;
+  unsafe fn getrf(m: usize, n: usize, a: *mut Self, lda: usize, ipiv: *mut i32) -> i32;
+  unsafe fn getrs(a_trans: Transpose, n: usize, nrhs: usize, a: *const Self, lda: usize, ipiv: *const i32, b: *mut Self, ldb: usize) -> i32;
+  unsafe fn getri(n: usize, a: *mut Self, lda: usize, ipiv: *const i32) -> i32;
+  unsafe fn geqrf(m: usize, n: usize, a: *mut Self, lda: usize, tau: *mut Self) -> i32;
+  unsafe fn orgqr(m: usize, n: usize, k: usize, a: *mut Self, lda: usize, tau: *const Self) -> i32;
+  unsafe fn gels(m: usize, n: usize, nrhs: usize, a: *mut Self, lda: usize, b: *mut Self, ldb: usize) -> i32;
+  unsafe fn gesdd(mode: SvdMode, m: usize, n: usize, a: *mut Self, lda: usize, s: *mut Self, u: *mut Self, ldu: usize, vt: *mut Self, ldvt: usize) -> i32;
+  unsafe fn syevr(vectors: bool, range: EigenRange<Self>, uplo: Uplo, n: usize, a: *mut Self, lda: usize, abs_tol: Self, m: &mut usize, w: *mut Self, z: *mut Self, ldz: usize) -> i32;
+  unsafe fn stebz(range: EigenRange<Self>, n: usize, abs_tol: Self, d: *const Self, e: *const Self, m: &mut usize, nsplit: &mut usize, w: *mut Self, iblock: *mut i32, isplit: *mut i32) -> i32;
+  unsafe fn stein(n: usize, d: *const Self, e: *const Self, m: usize, w: *const Self, iblock: *const i32, isplit: *const i32, z: *mut Self, ldz: usize) -> i32;
+}
+
+impl<T> BlasScalar for T where T: ZeroBits + ReferenceScalar, SequentialBackend: Backend<T>, ParallelBackend: Backend<T> {
+  unsafe fn gemm(a_trans: Transpose, b_trans: Transpose, m: usize, n: usize, k: usize, alpha: T, a: *const T, lda: usize, b: *const T, ldb: usize, beta: T, c: *mut T, ldc: usize) {
+    <SequentialBackend as Backend<T>>::gemm(a_trans, b_trans, m, n, k, alpha, a, lda, b, ldb, beta, c, ldc)
+  }
+
+  unsafe fn gemm_batch(a_trans: Transpose, b_trans: Transpose, m: usize, n: usize, k: usize, alpha: T, a: *const T, lda: usize, stride_a: usize, b: *const T, ldb: usize, stride_b: usize, beta: T, c: *mut T, ldc: usize, stride_c: usize, batch_sz: usize) {
+    <SequentialBackend as Backend<T>>::gemm_batch(a_trans, b_trans, m, n, k, alpha, a, lda, stride_a, b, ldb, stride_b, beta, c, ldc, stride_c, batch_sz)
+  }
+
+  unsafe fn gemv(a_trans: Transpose, m: usize, n: usize, alpha: T, a: *const T, lda: usize, x: *const T, incx: usize, beta: T, y: *mut T, incy: usize) {
+    <SequentialBackend as Backend<T>>::gemv(a_trans, m, n, alpha, a, lda, x, incx, beta, y, incy)
+  }
+
+  unsafe fn dot(n: usize, alpha: T, x: *const T, incx: usize, y: *const T, incy: usize) -> T {
+    <SequentialBackend as Backend<T>>::dot(n, alpha, x, incx, y, incy)
+  }
+
+  unsafe fn nrm2(n: usize, x: *const T, incx: usize) -> T {
+    <SequentialBackend as Backend<T>>::nrm2(n, x, incx)
+  }
+
+  unsafe fn axpy(n: usize, alpha: T, x: *const T, incx: usize, y: *mut T, incy: usize) {
+    <SequentialBackend as Backend<T>>::axpy(n, alpha, x, incx, y, incy)
+  }
+
+  unsafe fn scal(n: usize, alpha: T, x: *mut T, incx: usize) {
+    <SequentialBackend as Backend<T>>::scal(n, alpha, x, incx)
+  }
+
+  unsafe fn trsv(uplo: Uplo, a_trans: Transpose, n: usize, a: *const T, lda: usize, x: *mut T, incx: usize) {
+    <SequentialBackend as Backend<T>>::trsv(uplo, a_trans, n, a, lda, x, incx)
+  }
+
+  unsafe fn trsm(uplo: Uplo, a_trans: Transpose, m: usize, n: usize, alpha: T, a: *const T, lda: usize, b: *mut T, ldb: usize) {
+    <SequentialBackend as Backend<T>>::trsm(uplo, a_trans, m, n, alpha, a, lda, b, ldb)
+  }
+
+  unsafe fn ger(m: usize, n: usize, alpha: T, x: *const T, incx: usize, y: *const T, incy: usize, a: *mut T, lda: usize) {
+    <SequentialBackend as Backend<T>>::ger(m, n, alpha, x, incx, y, incy, a, lda)
+  }
+
+  unsafe fn syr(uplo: Uplo, n: usize, alpha: T, x: *const T, incx: usize, a: *mut T, lda: usize) {
+    <SequentialBackend as Backend<T>>::syr(uplo, n, alpha, x, incx, a, lda)
+  }
+
+  unsafe fn syrk(uplo: Uplo, a_trans: Transpose, n: usize, k: usize, alpha: T, a: *const T, lda: usize, beta: T, c: *mut T, ldc: usize) {
+    <SequentialBackend as Backend<T>>::syrk(uplo, a_trans, n, k, alpha, a, lda, beta, c, ldc)
+  }
+
+  unsafe fn parallel_gemm(a_trans: Transpose, b_trans: Transpose, m: usize, n: usize, k: usize, alpha: T, a: *const T, lda: usize, b: *const T, ldb: usize, beta: T, c: *mut T, ldc: usize) {
+    <ParallelBackend as Backend<T>>::gemm(a_trans, b_trans, m, n, k, alpha, a, lda, b, ldb, beta, c, ldc)
+  }
+
+  unsafe fn parallel_gemv(a_trans: Transpose, m: usize, n: usize, alpha: T, a: *const T, lda: usize, x: *const T, incx: usize, beta: T, y: *mut T, incy: usize) {
+    <ParallelBackend as Backend<T>>::gemv(a_trans, m, n, alpha, a, lda, x, incx, beta, y, incy)
+  }
+
+  unsafe fn parallel_dot(n: usize, alpha: T, x: *const T, incx: usize, y: *const T, incy: usize) -> T {
+    <ParallelBackend as Backend<T>>::dot(n, alpha, x, incx, y, incy)
+  }
+
+  unsafe fn parallel_nrm2(n: usize, x: *const T, incx: usize) -> T {
+    <ParallelBackend as Backend<T>>::nrm2(n, x, incx)
+  }
+
+  unsafe fn parallel_axpy(n: usize, alpha: T, x: *const T, incx: usize, y: *mut T, incy: usize) {
+    <ParallelBackend as Backend<T>>::axpy(n, alpha, x, incx, y, incy)
+  }
+
+  unsafe fn parallel_scal(n: usize, alpha: T, x: *mut T, incx: usize) {
+    <ParallelBackend as Backend<T>>::scal(n, alpha, x, incx)
+  }
+
+  unsafe fn potrf(uplo: Uplo, n: usize, a: *mut T, lda: usize) -> i32 {
+    <SequentialBackend as Backend<T>>::potrf(uplo, n, a, lda)
+  }
+
+  unsafe fn potrs(uplo: Uplo, n: usize, nrhs: usize, a: *const T, lda: usize, b: *mut T, ldb: usize) -> i32 {
+    <SequentialBackend as Backend<T>>::potrs(uplo, n, nrhs, a, lda, b, ldb)
+  }
+
+  unsafe fn getrf(m: usize, n: usize, a: *mut T, lda: usize, ipiv: *mut i32) -> i32 {
+    <SequentialBackend as Backend<T>>::getrf(m, n, a, lda, ipiv)
+  }
+
+  unsafe fn getrs(a_trans: Transpose, n: usize, nrhs: usize, a: *const T, lda: usize, ipiv: *const i32, b: *mut T, ldb: usize) -> i32 {
+    <SequentialBackend as Backend<T>>::getrs(a_trans, n, nrhs, a, lda, ipiv, b, ldb)
+  }
+
+  unsafe fn getri(n: usize, a: *mut T, lda: usize, ipiv: *const i32) -> i32 {
+    <SequentialBackend as Backend<T>>::getri(n, a, lda, ipiv)
+  }
+
+  unsafe fn geqrf(m: usize, n: usize, a: *mut T, lda: usize, tau: *mut T) -> i32 {
+    <SequentialBackend as Backend<T>>::geqrf(m, n, a, lda, tau)
+  }
+
+  unsafe fn orgqr(m: usize, n: usize, k: usize, a: *mut T, lda: usize, tau: *const T) -> i32 {
+    <SequentialBackend as Backend<T>>::orgqr(m, n, k, a, lda, tau)
+  }
+
+  unsafe fn gels(m: usize, n: usize, nrhs: usize, a: *mut T, lda: usize, b: *mut T, ldb: usize) -> i32 {
+    <SequentialBackend as Backend<T>>::gels(m, n, nrhs, a, lda, b, ldb)
+  }
+
+  unsafe fn gesdd(mode: SvdMode, m: usize, n: usize, a: *mut T, lda: usize, s: *mut T, u: *mut T, ldu: usize, vt: *mut T, ldvt: usize) -> i32 {
+    <SequentialBackend as Backend<T>>::gesdd(mode, m, n, a, lda, s, u, ldu, vt, ldvt)
+  }
+
+  unsafe fn syevr(vectors: bool, range: EigenRange<T>, uplo: Uplo, n: usize, a: *mut T, lda: usize, abs_tol: T, m: &mut usize, w: *mut T, z: *mut T, ldz: usize) -> i32 {
+    <SequentialBackend as Backend<T>>::syevr(vectors, range, uplo, n, a, lda, abs_tol, m, w, z, ldz)
+  }
+
+  unsafe fn stebz(range: EigenRange<T>, n: usize, abs_tol: T, d: *const T, e: *const T, m: &mut usize, nsplit: &mut usize, w: *mut T, iblock: *mut i32, isplit: *mut i32) -> i32 {
+    <SequentialBackend as Backend<T>>::stebz(range, n, abs_tol, d, e, m, nsplit, w, iblock, isplit)
+  }
+
+  unsafe fn stein(n: usize, d: *const T, e: *const T, m: usize, w: *const T, iblock: *const i32, isplit: *const i32, z: *mut T, ldz: usize) -> i32 {
+    <SequentialBackend as Backend<T>>::stein(n, d, e, m, w, iblock, isplit, z, ldz)
+  }
+}
@@ -0,0 +1,53 @@
+use kernels::*;
+
+/// Instruction set levels that the C kernels are compiled for. The kernels
+/// dispatch at runtime to the highest level supported by the CPU.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum IsaLevel {
+  Sse2,
+  Sse41,
+  Avx2,
+  Avx512,
+}
+
+impl IsaLevel {
+  fn from_raw(level: i32) -> IsaLevel {
+    match level {
+      0 => IsaLevel::Sse2,
+      1 => IsaLevel::Sse41,
+      2 => IsaLevel::Avx2,
+      3 => IsaLevel::Avx512,
+      _ => panic!("invalid ISA level: {}", level),
+    }
+  }
+
+  fn to_raw(self) -> i32 {
+    match self {
+      IsaLevel::Sse2    => 0,
+      IsaLevel::Sse41   => 1,
+      IsaLevel::Avx2    => 2,
+      IsaLevel::Avx512  => 3,
+    }
+  }
+}
+
+/// The highest ISA level supported by the CPU.
+pub fn detected_isa_level() -> IsaLevel {
+  IsaLevel::from_raw(unsafe { densearray_detected_isa_level() } as i32)
+}
+
+/// The ISA level that the kernels currently dispatch to.
+pub fn isa_level() -> IsaLevel {
+  IsaLevel::from_raw(unsafe { densearray_isa_level() } as i32)
+}
+
+/// Restricts the kernels to at most `level`, or restores automatic selection
+/// with `None`. Levels above `detected_isa_level()` are clamped to it. This
+/// overrides the `DENSEARRAY_ISA` environment variable.
+pub fn force_isa_level(level: Option<IsaLevel>) {
+  let raw = match level {
+    None => -1,
+    Some(level) => level.to_raw(),
+  };
+  unsafe { densearray_force_isa_level(raw as _) };
+}
@@ -0,0 +1,567 @@
+//! Iterative methods for large symmetric operators which are only available
+//! through matrix-vector products.
+//!
+//! The operator is a `LinearOperator`: a dense `Array2dView`, or a closure
+//! wrapped in a `FnOperator`. The methods keep their vectors in packed
+//! buffers and go through the sequential `BlasScalar` routines.
+//!
+//! `lanczos` computes extreme eigenpairs; `conjugate_gradient`,
+//! `preconditioned_conjugate_gradient` and `minres` solve linear systems.
+
+use super::{Array1dView, Array1dViewMut, Array2d, Array2dView, Reshape, ReshapeMut};
+use blas::{BlasScalar};
+use linalg::{EigenRange, LinalgError, SymmetricTridiagonalEigenWorkspace, Transpose, solve_symmetric_tridiagonal_eigenvalues, solve_symmetric_tridiagonal_eigenvectors};
+
+use std::cmp::{min};
+use std::marker::{PhantomData};
+
+/// A linear operator `A` on vectors of dimension `dim()`.
+pub trait LinearOperator<T> where T: Copy {
+  fn dim(&self) -> usize;
+  /// Writes `A * x` to `y`.
+  fn apply(&mut self, x: Array1dView<T>, y: Array1dViewMut<T>);
+}
+
+impl<'a, T> LinearOperator<T> for Array2dView<'a, T> where T: 'a + BlasScalar {
+  fn dim(&self) -> usize {
+    let (m, n) = Array2dView::dim(self);
+    assert_eq!(m, n);
+    n
+  }
+
+  fn apply(&mut self, x: Array1dView<T>, y: Array1dViewMut<T>) {
+    // Moving `y` lets its borrow be shortened to this call.
+    let mut y = y;
+    y.matrix_vector_prod(T::one(), *self, Transpose::N, x, T::zero());
+  }
+}
+
+/// A `LinearOperator` from a closure which writes `A * x` to `y`.
+pub struct FnOperator<T, F> {
+  dim:  usize,
+  f:    F,
+  _m:   PhantomData<fn(T)>,
+}
+
+impl<T, F> FnOperator<T, F> where T: Copy, F: FnMut(Array1dView<T>, Array1dViewMut<T>) {
+  pub fn new(dim: usize, f: F) -> FnOperator<T, F> {
+    FnOperator{
+      dim:  dim,
+      f:    f,
+      _m:   PhantomData,
+    }
+  }
+}
+
+impl<T, F> LinearOperator<T> for FnOperator<T, F> where T: Copy, F: FnMut(Array1dView<T>, Array1dViewMut<T>) {
+  fn dim(&self) -> usize {
+    self.dim
+  }
+
+  fn apply(&mut self, x: Array1dView<T>, y: Array1dViewMut<T>) {
+    (self.f)(x, y);
+  }
+}
+
+fn dot<T>(x: &[T], y: &[T]) -> T where T: BlasScalar {
+  assert_eq!(x.len(), y.len());
+  unsafe { T::dot(
+      x.len(),
+      T::one(),
+      x.as_ptr(), 1,
+      y.as_ptr(), 1,
+  ) }
+}
+
+fn nrm2<T>(x: &[T]) -> T where T: BlasScalar {
+  unsafe { T::nrm2(
+      x.len(),
+      x.as_ptr(), 1,
+  ) }
+}
+
+fn axpy<T>(alpha: T, x: &[T], y: &mut [T]) where T: BlasScalar {
+  assert_eq!(x.len(), y.len());
+  unsafe { T::axpy(
+      x.len(),
+      alpha,
+      x.as_ptr(), 1,
+      y.as_mut_ptr(), 1,
+  ) };
+}
+
+fn scal<T>(alpha: T, x: &mut [T]) where T: BlasScalar {
+  unsafe { T::scal(
+      x.len(),
+      alpha,
+      x.as_mut_ptr(), 1,
+  ) };
+}
+
+/// Writes the packed `n x k` matrix `Q * S` to `y`, where `Q` is `n x j`
+/// and `S` is `j x k`, both packed.
+fn basis_prod<T>(n: usize, j: usize, k: usize, q: &[T], s: &[T], y: &mut [T]) where T: BlasScalar {
+  unsafe { T::gemm(
+      Transpose::N, Transpose::N,
+      n, k, j,
+      T::one(),
+      q.as_ptr(), n,
+      s.as_ptr(), j,
+      T::zero(),
+      y.as_mut_ptr(), n,
+  ) };
+}
+
+/// Which end of the spectrum to compute.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SpectrumEnd {
+  Largest,
+  Smallest,
+}
+
+/// How the Lanczos vectors are kept orthogonal in floating point.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Reorthogonalization {
+  /// Against all previous Lanczos vectors, at every step.
+  Full,
+  /// Against the Ritz vectors which have converged so far (Parlett and
+  /// Scott), which is cheaper while few of them have.
+  Selective,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct LanczosConfig<T> {
+  /// The number `k` of Ritz pairs to compute.
+  pub num_values:   usize,
+  pub which:        SpectrumEnd,
+  /// At most this many steps, and at most the dimension of the operator.
+  pub max_iters:    usize,
+  /// A Ritz pair has converged when its residual norm is at most `tol`
+  /// times the largest Ritz value in magnitude.
+  pub tol:          T,
+  pub reorth:       Reorthogonalization,
+  /// Whether to also compute the Ritz vectors.
+  pub vectors:      bool,
+}
+
+impl<T> LanczosConfig<T> where T: BlasScalar {
+  /// The `k` extreme Ritz values at the `which` end, with full
+  /// reorthogonalization and `tol` the square root of the machine epsilon.
+  pub fn new(num_values: usize, which: SpectrumEnd) -> LanczosConfig<T> {
+    LanczosConfig{
+      num_values:   num_values,
+      which:        which,
+      max_iters:    300,
+      tol:          T::epsilon().sqrt(),
+      reorth:       Reorthogonalization::Full,
+      vectors:      false,
+    }
+  }
+}
+
+/// The Ritz pairs computed by `lanczos`.
+pub struct RitzPairs<T> where T: Copy {
+  /// The Ritz values, starting with the most extreme one.
+  pub values:         Vec<T>,
+  /// The Ritz vectors as the columns of an `n x values.len()` matrix, if
+  /// they were requested.
+  pub vectors:        Option<Array2d<T>>,
+  /// The residual norms `||A * y - theta * y||` of the Ritz pairs, as
+  /// estimated from the tridiagonal matrix.
+  pub residuals:      Vec<T>,
+  /// How many of the Ritz pairs have converged.
+  pub num_converged:  usize,
+  /// The number of Lanczos steps taken.
+  pub iters:          usize,
+}
+
+/// The eigenvalues `theta` and packed eigenvectors `S` of the `j x j`
+/// tridiagonal matrix with diagonal `alpha` and off-diagonal `beta`, where
+/// `j = alpha.len()`.
+fn tridiagonal_ritz<T>(
+    alpha: &[T],
+    beta: &[T],
+    workspace: &mut SymmetricTridiagonalEigenWorkspace,
+    theta: &mut [T],
+    s: &mut [T],
+) -> Result<(), LinalgError>
+where T: BlasScalar
+{
+  let j = alpha.len();
+  let m = solve_symmetric_tridiagonal_eigenvalues(alpha, &beta[ .. j - 1], EigenRange::All, &mut theta[ .. j], workspace, T::zero())?;
+  assert_eq!(j, m);
+  solve_symmetric_tridiagonal_eigenvectors(alpha, &beta[ .. j - 1], &theta[ .. j], workspace, (&mut s[ .. j * j]).reshape_mut((j, j)))
+}
+
+/// Computes the `config.num_values` extreme eigenvalues at the
+/// `config.which` end of the symmetric operator `op` by the Lanczos
+/// iteration started from `x0`, as Ritz values of the projected tridiagonal
+/// matrix. Fewer values are returned if the Krylov space of `x0` turns out
+/// to be invariant with a smaller dimension.
+///
+/// Fails with `InvalidArgument(2)` if `x0` is zero, and with
+/// `InvalidArgument(3)` if `config.num_values` exceeds `config.max_iters` or
+/// the dimension of `op`.
+pub fn lanczos<T, Op>(op: &mut Op, x0: Array1dView<T>, config: &LanczosConfig<T>) -> Result<RitzPairs<T>, LinalgError>
+where T: BlasScalar, Op: LinearOperator<T> + ?Sized
+{
+  let n = op.dim();
+  assert_eq!(n, x0.dim());
+  let max_iters = min(config.max_iters, n);
+  let k = config.num_values;
+  if k > max_iters {
+    return Err(LinalgError::InvalidArgument(3));
+  }
+  // The Lanczos vectors `Q` are stored as packed columns, also for
+  // `Reorthogonalization::Selective`, as they make up the Ritz vectors.
+  let mut q = vec![T::zero(); n * (max_iters + 1)];
+  for i in 0 .. n {
+    q[i] = x0.buf[i * x0.stride];
+  }
+  let x0_norm = nrm2(&q[ .. n]);
+  if x0_norm == T::zero() {
+    return Err(LinalgError::InvalidArgument(2));
+  }
+  scal(T::one() / x0_norm, &mut q[ .. n]);
+  let mut alpha: Vec<T> = Vec::with_capacity(max_iters);
+  let mut beta: Vec<T> = Vec::with_capacity(max_iters);
+  let mut w = vec![T::zero(); n];
+  let mut workspace = SymmetricTridiagonalEigenWorkspace::new(max_iters);
+  let mut theta = vec![T::zero(); max_iters];
+  let mut s = vec![T::zero(); max_iters * max_iters];
+  let mut y = vec![T::zero(); n];
+  let mut iters = 0;
+  let mut bounds = vec![];
+  while iters < max_iters {
+    let j = iters;
+    {
+      let (q_prev, q_next) = q.split_at(j * n);
+      let q_j = &q_next[ .. n];
+      op.apply(q_j.reshape(n), (&mut w[..]).reshape_mut(n));
+      if j > 0 {
+        axpy(-beta[j - 1], &q_prev[(j - 1) * n .. ], &mut w);
+      }
+      let alpha_j = dot(q_j, &w);
+      axpy(-alpha_j, q_j, &mut w);
+      alpha.push(alpha_j);
+      if config.reorth == Reorthogonalization::Full {
+        for i in 0 .. j + 1 {
+          let q_i = &q[i * n .. (i + 1) * n];
+          let c = dot(q_i, &w);
+          axpy(-c, q_i, &mut w);
+        }
+      }
+    }
+    if config.reorth == Reorthogonalization::Selective && iters > 0 {
+      // Purge the components along the Ritz vectors of the previous step
+      // which have converged to about half precision, which are the ones
+      // that reappear. This is done before `beta_j` and the Ritz pairs of
+      // this step are computed, so that they include the purge.
+      let good_tol = T::epsilon().sqrt() * max_abs(&theta[ .. iters]);
+      for i in 0 .. iters {
+        if bounds[i] <= good_tol {
+          basis_prod(n, iters, 1, &q[ .. iters * n], &s[i * iters .. (i + 1) * iters], &mut y);
+          let c = dot(&y, &w);
+          axpy(-c, &y, &mut w);
+        }
+      }
+    }
+    let beta_j = nrm2(&w);
+    beta.push(beta_j);
+    iters += 1;
+    tridiagonal_ritz(&alpha, &beta, &mut workspace, &mut theta, &mut s)?;
+    let t_norm = max_abs(&theta[ .. iters]);
+    // The residual norm of the Ritz pair `(theta_i, Q * s_i)` is
+    // `beta_j * |s_i(j)|`.
+    bounds.clear();
+    for i in 0 .. iters {
+      bounds.push(beta_j * s[iters - 1 + i * iters].abs());
+    }
+    let wanted = min(k, iters);
+    let num_converged = (0 .. wanted)
+      .filter(|&c| bounds[extreme_index(config.which, iters, c)] <= config.tol * t_norm)
+      .count();
+    if beta_j <= T::epsilon() * t_norm || (iters >= k && num_converged == k) {
+      break;
+    }
+    scal(T::one() / beta_j, &mut w);
+    q[iters * n .. (iters + 1) * n].copy_from_slice(&w);
+  }
+  let t_norm = max_abs(&theta[ .. iters]);
+  let order: Vec<usize> = (0 .. min(k, iters)).map(|c| extreme_index(config.which, iters, c)).collect();
+  let values: Vec<T> = order.iter().map(|&i| theta[i]).collect();
+  let residuals: Vec<T> = order.iter().map(|&i| bounds[i]).collect();
+  let num_converged = residuals.iter().filter(|&&r| r <= config.tol * t_norm).count();
+  let vectors = if config.vectors {
+    let mut s_k = vec![T::zero(); iters * order.len()];
+    for (c, &i) in order.iter().enumerate() {
+      s_k[c * iters .. (c + 1) * iters].copy_from_slice(&s[i * iters .. (i + 1) * iters]);
+    }
+    let mut vectors = Array2d::zeros((n, order.len()));
+    basis_prod(n, iters, order.len(), &q[ .. iters * n], &s_k, vectors.as_mut_slice());
+    Some(vectors)
+  } else {
+    None
+  };
+  Ok(RitzPairs{
+    values:         values,
+    vectors:        vectors,
+    residuals:      residuals,
+    num_converged:  num_converged,
+    iters:          iters,
+  })
+}
+
+/// The index of the `c`-th of `j` ascending Ritz values, counted from the
+/// `which` end.
+fn extreme_index(which: SpectrumEnd, j: usize, c: usize) -> usize {
+  match which {
+    SpectrumEnd::Largest => j - 1 - c,
+    SpectrumEnd::Smallest => c,
+  }
+}
+
+fn max_abs<T>(x: &[T]) -> T where T: BlasScalar {
+  x.iter().fold(T::zero(), |m, &x_i| if x_i.abs() > m { x_i.abs() } else { m })
+}
+
+/// Stopping criteria for the linear solvers.
+#[derive(Clone, Copy, Debug)]
+pub struct SolverConfig<T> {
+  /// The solve has converged when `||b - A * x|| <= tol * ||b||`.
+  pub tol:        T,
+  /// At most this many products with the operator, after the initial
+  /// residual.
+  pub max_iters:  usize,
+}
+
+impl<T> SolverConfig<T> where T: Copy {
+  pub fn new(tol: T, max_iters: usize) -> SolverConfig<T> {
+    SolverConfig{
+      tol:        tol,
+      max_iters:  max_iters,
+    }
+  }
+}
+
+/// How a linear solve ended.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SolverReport<T> {
+  pub converged:      bool,
+  pub iters:          usize,
+  /// The final residual norm `||b - A * x||`, as updated by the recurrence
+  /// rather than recomputed.
+  pub residual_norm:  T,
+}
+
+/// The preconditioner `M^-1 = diag(d)^-1` for a positive diagonal `d`,
+/// typically the diagonal of `A` (Jacobi).
+pub struct DiagonalPreconditioner<T> where T: Copy {
+  inv_diag: Vec<T>,
+}
+
+impl<T> DiagonalPreconditioner<T> where T: BlasScalar {
+  pub fn new(diag: Array1dView<T>) -> DiagonalPreconditioner<T> {
+    let inv_diag: Vec<T> = (0 .. diag.dim()).map(|i| {
+      let d_i = diag.buf[i * diag.stride];
+      assert!(d_i > T::zero(), "the preconditioner diagonal must be positive");
+      T::one() / d_i
+    }).collect();
+    DiagonalPreconditioner{
+      inv_diag: inv_diag,
+    }
+  }
+}
+
+impl<T> LinearOperator<T> for DiagonalPreconditioner<T> where T: BlasScalar {
+  fn dim(&self) -> usize {
+    self.inv_diag.len()
+  }
+
+  fn apply(&mut self, x: Array1dView<T>, y: Array1dViewMut<T>) {
+    let n = self.inv_diag.len();
+    assert_eq!(n, x.dim());
+    assert_eq!(n, y.dim());
+    for i in 0 .. n {
+      y.buf[i * y.stride] = self.inv_diag[i] * x.buf[i * x.stride];
+    }
+  }
+}
+
+struct Identity {
+  dim:  usize,
+}
+
+impl<T> LinearOperator<T> for Identity where T: Copy {
+  fn dim(&self) -> usize {
+    self.dim
+  }
+
+  fn apply(&mut self, x: Array1dView<T>, y: Array1dViewMut<T>) {
+    assert_eq!(self.dim, x.dim());
+    assert_eq!(self.dim, y.dim());
+    for i in 0 .. self.dim {
+      y.buf[i * y.stride] = x.buf[i * x.stride];
+    }
+  }
+}
+
+/// The packed residual `b - A * x` for the packed `x`.
+fn residual<T, Op>(op: &mut Op, b: Array1dView<T>, x: &[T]) -> Vec<T> where T: BlasScalar, Op: LinearOperator<T> + ?Sized {
+  let n = x.len();
+  let mut r = vec![T::zero(); n];
+  op.apply(x.reshape(n), (&mut r[..]).reshape_mut(n));
+  for i in 0 .. n {
+    r[i] = b.buf[i * b.stride] - r[i];
+  }
+  r
+}
+
+/// Solves `A * x = b` for a symmetric positive definite operator `A` by the
+/// conjugate gradient method, where `x` holds the initial guess on entry.
+/// Fails with `NotPositiveDefinite(k)` if the `k`-th search direction shows
+/// that `A` is not positive definite.
+pub fn conjugate_gradient<T, Op>(op: &mut Op, b: Array1dView<T>, x: Array1dViewMut<T>, config: &SolverConfig<T>) -> Result<SolverReport<T>, LinalgError>
+where T: BlasScalar, Op: LinearOperator<T> + ?Sized
+{
+  let n = op.dim();
+  preconditioned_conjugate_gradient(op, &mut Identity{dim: n}, b, x, config)
+}
+
+/// Like `conjugate_gradient`, with the symmetric positive definite
+/// preconditioner `precond` applying `M^-1`, e.g. a
+/// `DiagonalPreconditioner`.
+pub fn preconditioned_conjugate_gradient<T, Op, P>(op: &mut Op, precond: &mut P, b: Array1dView<T>, x: Array1dViewMut<T>, config: &SolverConfig<T>) -> Result<SolverReport<T>, LinalgError>
+where T: BlasScalar, Op: LinearOperator<T> + ?Sized, P: LinearOperator<T> + ?Sized
+{
+  let n = op.dim();
+  assert_eq!(n, precond.dim());
+  assert_eq!(n, b.dim());
+  assert_eq!(n, x.dim());
+  let mut xs: Vec<T> = (0 .. n).map(|i| x.buf[i * x.stride]).collect();
+  let b_norm = b.l2_norm();
+  let mut r = residual(op, b, &xs);
+  let mut z = vec![T::zero(); n];
+  precond.apply(r.reshape(n), (&mut z[..]).reshape_mut(n));
+  let mut p = z.clone();
+  let mut q = vec![T::zero(); n];
+  let mut rz = dot(&r, &z);
+  let mut r_norm = nrm2(&r);
+  let mut iters = 0;
+  let mut converged = false;
+  loop {
+    if r_norm <= config.tol * b_norm {
+      converged = true;
+      break;
+    }
+    if iters == config.max_iters {
+      break;
+    }
+    op.apply(p.reshape(n), (&mut q[..]).reshape_mut(n));
+    iters += 1;
+    let pq = dot(&p, &q);
+    if pq <= T::zero() {
+      return Err(LinalgError::NotPositiveDefinite(iters));
+    }
+    let step = rz / pq;
+    axpy(step, &p, &mut xs);
+    axpy(-step, &q, &mut r);
+    r_norm = nrm2(&r);
+    precond.apply(r.reshape(n), (&mut z[..]).reshape_mut(n));
+    let rz_next = dot(&r, &z);
+    scal(rz_next / rz, &mut p);
+    axpy(T::one(), &z, &mut p);
+    rz = rz_next;
+  }
+  for i in 0 .. n {
+    x.buf[i * x.stride] = xs[i];
+  }
+  Ok(SolverReport{
+    converged:      converged,
+    iters:          iters,
+    residual_norm:  r_norm,
+  })
+}
+
+/// Solves `A * x = b` for a symmetric, possibly indefinite, operator `A` by
+/// MINRES (Paige and Saunders), where `x` holds the initial guess on entry.
+/// For a singular but consistent system it converges to a solution.
+pub fn minres<T, Op>(op: &mut Op, b: Array1dView<T>, x: Array1dViewMut<T>, config: &SolverConfig<T>) -> SolverReport<T>
+where T: BlasScalar, Op: LinearOperator<T> + ?Sized
+{
+  let n = op.dim();
+  assert_eq!(n, b.dim());
+  assert_eq!(n, x.dim());
+  let mut xs: Vec<T> = (0 .. n).map(|i| x.buf[i * x.stride]).collect();
+  let b_norm = b.l2_norm();
+  // The Lanczos vectors `v` are `r2 / beta`, with `r1` the previous `r2`.
+  let mut r2 = residual(op, b, &xs);
+  let mut r1 = r2.clone();
+  let mut v = vec![T::zero(); n];
+  let mut y = vec![T::zero(); n];
+  let mut beta = nrm2(&r2);
+  let mut old_beta = T::zero();
+  // The plane rotations of the QR factorization of the tridiagonal matrix,
+  // and the search directions `w`.
+  let (mut cs, mut sn) = (-T::one(), T::zero());
+  let (mut dbar, mut eps_l) = (T::zero(), T::zero());
+  let mut phi_bar = beta;
+  let mut w = vec![T::zero(); n];
+  let mut w1 = vec![T::zero(); n];
+  let mut iters = 0;
+  let mut converged = false;
+  loop {
+    if phi_bar <= config.tol * b_norm {
+      converged = true;
+      break;
+    }
+    if iters == config.max_iters {
+      break;
+    }
+    for i in 0 .. n {
+      v[i] = r2[i] / beta;
+    }
+    op.apply(v.reshape(n), (&mut y[..]).reshape_mut(n));
+    iters += 1;
+    if iters >= 2 {
+      axpy(-beta / old_beta, &r1, &mut y);
+    }
+    let alpha = dot(&v, &y);
+    axpy(-alpha / beta, &r2, &mut y);
+    r1.copy_from_slice(&r2);
+    r2.copy_from_slice(&y);
+    old_beta = beta;
+    beta = nrm2(&r2);
+    let old_eps = eps_l;
+    let delta = cs * dbar + sn * alpha;
+    let gbar = sn * dbar - cs * alpha;
+    eps_l = sn * beta;
+    dbar = -cs * beta;
+    let mut gamma = (gbar * gbar + beta * beta).sqrt();
+    if gamma < T::epsilon() {
+      gamma = T::epsilon();
+    }
+    cs = gbar / gamma;
+    sn = beta / gamma;
+    let phi = cs * phi_bar;
+    phi_bar = sn * phi_bar;
+    for i in 0 .. n {
+      let w_i = (v[i] - old_eps * w1[i] - delta * w[i]) / gamma;
+      w1[i] = w[i];
+      w[i] = w_i;
+    }
+    axpy(phi, &w, &mut xs);
+    if beta == T::zero() {
+      // The Krylov space is invariant, so `x` is the exact solution.
+      phi_bar = T::zero();
+    }
+  }
+  for i in 0 .. n {
+    x.buf[i * x.stride] = xs[i];
+  }
+  SolverReport{
+    converged:      converged,
+    iters:          iters,
+    residual_norm:  phi_bar,
+  }
+}
@@ -0,0 +1,733 @@
+//extern crate libc;
+
+#[cfg(not(feature = "rust_kernels"))]
+use libc::*;
+
+#[cfg(feature = "rust_kernels")]
+pub use self::rust::*;
+
+pub use self::scalar::{KernelScalar};
+
+pub mod rust;
+mod scalar;
+
+#[cfg(all(not(feature = "rust_kernels"), not(feature = "knl")))]
+#[link(name = "gomp")]
+extern "C" {}
+
+#[cfg(all(not(feature = "rust_kernels"), feature = "knl"))]
+#[link(name = "iomp5")]
+extern "C" {}
+
+#[cfg(not(feature = "rust_kernels"))]
+#[link(name = "densearray_kernels_sse2", kind = "static")]
+#[link(name = "densearray_kernels_sse41", kind = "static")]
+#[link(name = "densearray_kernels_avx2", kind = "static")]
+#[link(name = "densearray_kernels_avx512", kind = "static")]
+extern "C" {}
+
+#[cfg(all(not(feature = "rust_kernels"), not(feature = "knl")))]
+#[link(name = "densearray_omp_kernels_sse2", kind = "static")]
+#[link(name = "densearray_omp_kernels_sse41", kind = "static")]
+#[link(name = "densearray_omp_kernels_avx2", kind = "static")]
+#[link(name = "densearray_omp_kernels_avx512", kind = "static")]
+extern "C" {}
+
+#[cfg(not(feature = "rust_kernels"))]
+#[link(name = "densearray_kernels", kind = "static")]
+extern "C" {
+  pub fn densearray_detected_isa_level() -> c_int;
+  pub fn densearray_isa_level() -> c_int;
+  pub fn densearray_force_isa_level(level: c_int);
+
+  pub fn densearray_kernel_cast_1d_u8_to_f32(
+      dim: usize,
+      src: *const u8,
+      dst: *mut f32);
+  pub fn densearray_kernel_cast_1d_u16_to_f32(
+      dim: usize,
+      src: *const u16,
+      dst: *mut f32);
+  pub fn densearray_kernel_cast_1d_f32_to_f64(
+      dim: usize,
+      src: *const f32,
+      dst: *mut f64);
+  pub fn densearray_kernel_cast_1d_f64_to_f32(
+      dim: usize,
+      src: *const f64,
+      dst: *mut f32);
+  pub fn densearray_kernel_round_clamp_1d_f32_to_u8(
+      dim: usize,
+      src: *const f32,
+      dst: *mut u8);
+  pub fn densearray_kernel_round_clamp_1d_f32_to_u8_sse2(
+      dim: usize,
+      src: *const f32,
+      dst: *mut u8);
+  pub fn densearray_kernel_round_clamp_1d_f32_to_u8_sse4(
+      dim: usize,
+      src: *const f32,
+      dst: *mut u8);
+  pub fn densearray_kernel_round_clamp_1d_f32_to_u8_avx2(
+      dim: usize,
+      src: *const f32,
+      dst: *mut u8);
+  pub fn densearray_kernel_round_clamp_1d_f32_to_u8_avx512(
+      dim: usize,
+      src: *const f32,
+      dst: *mut u8);
+  pub fn densearray_kernel_round_clamp_1d_f32_to_i8(
+      dim: usize,
+      src: *const f32,
+      dst: *mut i8);
+  pub fn densearray_kernel_round_clamp_1d_f32_to_u16(
+      dim: usize,
+      src: *const f32,
+      dst: *mut u16);
+  pub fn densearray_kernel_round_clamp_1d_f32_to_i32(
+      dim: usize,
+      src: *const f32,
+      dst: *mut i32);
+  pub fn densearray_kernel_clamp_1d_f32_sse2(
+      dim: usize,
+      src: *const f32,
+      dst: *mut f32,
+      lowerf: f32,
+      upperf: f32);
+  pub fn densearray_kernel_clamp_1d_f32_avx2(
+      dim: usize,
+      src: *const f32,
+      dst: *mut f32,
+      lowerf: f32,
+      upperf: f32);
+  pub fn densearray_kernel_clamp_1d_f32_avx512(
+      dim: usize,
+      src: *const f32,
+      dst: *mut f32,
+      lowerf: f32,
+      upperf: f32);
+  pub fn densearray_kernel_clamp_1d_f32(
+      dim: usize,
+      src: *const f32,
+      dst: *mut f32,
+      lowerf: f32,
+      upperf: f32);
+
+  pub fn densearray_set_scalar_f32(
+      dst: *mut f32,
+      dim: size_t,
+      c: f32);
+  pub fn densearray_set_scalar_i32(
+      dst: *mut i32,
+      dim: size_t,
+      c: i32);
+  pub fn densearray_copy_f32(
+      dst: *mut f32,
+      dim: size_t,
+      src: *const f32);
+  pub fn densearray_cast_u8_to_f32(
+      dst: *mut f32,
+      dim: size_t,
+      src: *const u8);
+  pub fn densearray_square_f32(
+      dst: *mut f32,
+      dim: size_t);
+  pub fn densearray_cube_f32(
+      dst: *mut f32,
+      dim: size_t);
+  pub fn densearray_sqrt_f32(
+      dst: *mut f32,
+      dim: size_t);
+  pub fn densearray_reciprocal_f32(
+      dst: *mut f32,
+      dim: size_t);
+  pub fn densearray_add_scalar_f32(
+      dst: *mut f32,
+      dim: size_t,
+      c: f32);
+  pub fn densearray_scale_f32(
+      dst: *mut f32,
+      dim: size_t,
+      c: f32);
+  pub fn densearray_div_scalar_f32(
+      dst: *mut f32,
+      dim: size_t,
+      c: f32);
+  pub fn densearray_elem_mult_f32(
+      dst: *mut f32,
+      dim: size_t,
+      xs: *const f32);
+  pub fn densearray_elem_div_f32(
+      dst: *mut f32,
+      dim: size_t,
+      xs: *const f32);
+  pub fn densearray_elem_ldiv_f32(
+      dst: *mut f32,
+      dim: size_t,
+      xs: *const f32);
+  pub fn densearray_vector_add_f32(
+      dst: *mut f32,
+      dim: size_t,
+      xs: *const f32,
+      alpha: f32);
+  pub fn densearray_vector_average_f32(
+      dst: *mut f32,
+      dim: size_t,
+      xs: *const f32,
+      alpha: f32);
+  pub fn densearray_chunk_sum_f32(
+      x: *const f32,
+      dim: size_t,
+      incx: size_t,
+      chunk_sz: size_t,
+      partials: *mut f32);
+  pub fn densearray_chunk_abs_sum_f32(
+      x: *const f32,
+      dim: size_t,
+      incx: size_t,
+      chunk_sz: size_t,
+      partials: *mut f32);
+  pub fn densearray_chunk_square_sum_f32(
+      x: *const f32,
+      dim: size_t,
+      incx: size_t,
+      chunk_sz: size_t,
+      partials: *mut f32);
+  pub fn densearray_chunk_inner_prod_f32(
+      x: *const f32,
+      dim: size_t,
+      incx: size_t,
+      y: *const f32,
+      incy: size_t,
+      chunk_sz: size_t,
+      partials: *mut f32);
+  pub fn densearray_reduce_axis_sum_f32(
+      inner_dim: size_t,
+      red_dim: size_t,
+      outer_dim: size_t,
+      x: *const f32,
+      x_inc: size_t,
+      x_red_inc: size_t,
+      x_outer_inc: size_t,
+      y: *mut f32,
+      y_inc: size_t,
+      y_outer_inc: size_t);
+  pub fn densearray_reduce_axis_mean_f32(
+      inner_dim: size_t,
+      red_dim: size_t,
+      outer_dim: size_t,
+      x: *const f32,
+      x_inc: size_t,
+      x_red_inc: size_t,
+      x_outer_inc: size_t,
+      y: *mut f32,
+      y_inc: size_t,
+      y_outer_inc: size_t);
+  pub fn densearray_reduce_axis_max_f32(
+      inner_dim: size_t,
+      red_dim: size_t,
+      outer_dim: size_t,
+      x: *const f32,
+      x_inc: size_t,
+      x_red_inc: size_t,
+      x_outer_inc: size_t,
+      y: *mut f32,
+      y_inc: size_t,
+      y_outer_inc: size_t);
+  pub fn densearray_reduce_axis_min_f32(
+      inner_dim: size_t,
+      red_dim: size_t,
+      outer_dim: size_t,
+      x: *const f32,
+      x_inc: size_t,
+      x_red_inc: size_t,
+      x_outer_inc: size_t,
+      y: *mut f32,
+      y_inc: size_t,
+      y_outer_inc: size_t);
+  pub fn densearray_reduce_axis_argmax_f32(
+      inner_dim: size_t,
+      red_dim: size_t,
+      outer_dim: size_t,
+      x: *const f32,
+      x_inc: size_t,
+      x_red_inc: size_t,
+      x_outer_inc: size_t,
+      y: *mut u32,
+      y_inc: size_t,
+      y_outer_inc: size_t);
+  pub fn densearray_reduce_axis_argmin_f32(
+      inner_dim: size_t,
+      red_dim: size_t,
+      outer_dim: size_t,
+      x: *const f32,
+      x_inc: size_t,
+      x_red_inc: size_t,
+      x_outer_inc: size_t,
+      y: *mut u32,
+      y_inc: size_t,
+      y_outer_inc: size_t);
+  pub fn densearray_set_scalar_f64(
+      dst: *mut f64,
+      dim: size_t,
+      c: f64);
+  pub fn densearray_copy_f64(
+      dst: *mut f64,
+      dim: size_t,
+      src: *const f64);
+  pub fn densearray_cast_u8_to_f64(
+      dst: *mut f64,
+      dim: size_t,
+      src: *const u8);
+  pub fn densearray_square_f64(
+      dst: *mut f64,
+      dim: size_t);
+  pub fn densearray_cube_f64(
+      dst: *mut f64,
+      dim: size_t);
+  pub fn densearray_sqrt_f64(
+      dst: *mut f64,
+      dim: size_t);
+  pub fn densearray_reciprocal_f64(
+      dst: *mut f64,
+      dim: size_t);
+  pub fn densearray_add_scalar_f64(
+      dst: *mut f64,
+      dim: size_t,
+      c: f64);
+  pub fn densearray_scale_f64(
+      dst: *mut f64,
+      dim: size_t,
+      c: f64);
+  pub fn densearray_div_scalar_f64(
+      dst: *mut f64,
+      dim: size_t,
+      c: f64);
+  pub fn densearray_elem_mult_f64(
+      dst: *mut f64,
+      dim: size_t,
+      xs: *const f64);
+  pub fn densearray_elem_div_f64(
+      dst: *mut f64,
+      dim: size_t,
+      xs: *const f64);
+  pub fn densearray_elem_ldiv_f64(
+      dst: *mut f64,
+      dim: size_t,
+      xs: *const f64);
+  pub fn densearray_vector_add_f64(
+      dst: *mut f64,
+      dim: size_t,
+      xs: *const f64,
+      alpha: f64);
+  pub fn densearray_vector_average_f64(
+      dst: *mut f64,
+      dim: size_t,
+      xs: *const f64,
+      alpha: f64);
+  pub fn densearray_chunk_sum_f64(
+      x: *const f64,
+      dim: size_t,
+      incx: size_t,
+      chunk_sz: size_t,
+      partials: *mut f64);
+  pub fn densearray_chunk_abs_sum_f64(
+      x: *const f64,
+      dim: size_t,
+      incx: size_t,
+      chunk_sz: size_t,
+      partials: *mut f64);
+  pub fn densearray_chunk_square_sum_f64(
+      x: *const f64,
+      dim: size_t,
+      incx: size_t,
+      chunk_sz: size_t,
+      partials: *mut f64);
+  pub fn densearray_chunk_inner_prod_f64(
+      x: *const f64,
+      dim: size_t,
+      incx: size_t,
+      y: *const f64,
+      incy: size_t,
+      chunk_sz: size_t,
+      partials: *mut f64);
+  pub fn densearray_reduce_axis_sum_f64(
+      inner_dim: size_t,
+      red_dim: size_t,
+      outer_dim: size_t,
+      x: *const f64,
+      x_inc: size_t,
+      x_red_inc: size_t,
+      x_outer_inc: size_t,
+      y: *mut f64,
+      y_inc: size_t,
+      y_outer_inc: size_t);
+  pub fn densearray_reduce_axis_mean_f64(
+      inner_dim: size_t,
+      red_dim: size_t,
+      outer_dim: size_t,
+      x: *const f64,
+      x_inc: size_t,
+      x_red_inc: size_t,
+      x_outer_inc: size_t,
+      y: *mut f64,
+      y_inc: size_t,
+      y_outer_inc: size_t);
+  pub fn densearray_reduce_axis_max_f64(
+      inner_dim: size_t,
+      red_dim: size_t,
+      outer_dim: size_t,
+      x: *const f64,
+      x_inc: size_t,
+      x_red_inc: size_t,
+      x_outer_inc: size_t,
+      y: *mut f64,
+      y_inc: size_t,
+      y_outer_inc: size_t);
+  pub fn densearray_reduce_axis_min_f64(
+      inner_dim: size_t,
+      red_dim: size_t,
+      outer_dim: size_t,
+      x: *const f64,
+      x_inc: size_t,
+      x_red_inc: size_t,
+      x_outer_inc: size_t,
+      y: *mut f64,
+      y_inc: size_t,
+      y_outer_inc: size_t);
+  pub fn densearray_reduce_axis_argmax_f64(
+      inner_dim: size_t,
+      red_dim: size_t,
+      outer_dim: size_t,
+      x: *const f64,
+      x_inc: size_t,
+      x_red_inc: size_t,
+      x_outer_inc: size_t,
+      y: *mut u32,
+      y_inc: size_t,
+      y_outer_inc: size_t);
+  pub fn densearray_reduce_axis_argmin_f64(
+      inner_dim: size_t,
+      red_dim: size_t,
+      outer_dim: size_t,
+      x: *const f64,
+      x_inc: size_t,
+      x_red_inc: size_t,
+      x_outer_inc: size_t,
+      y: *mut u32,
+      y_inc: size_t,
+      y_outer_inc: size_t);
+}
+
+#[cfg(not(feature = "rust_kernels"))]
+#[link(name = "densearray_omp_kernels", kind = "static")]
+extern "C" {
+  pub fn densearray_omp_set_num_threads(num_threads: c_int);
+  pub fn densearray_omp_get_num_threads_setting() -> c_int;
+  pub fn densearray_omp_num_threads() -> c_int;
+
+  pub fn densearray_omp_set_scalar_f32(
+      dst: *mut f32,
+      dim: size_t,
+      c: f32);
+  pub fn densearray_omp_set_scalar_i32(
+      dst: *mut i32,
+      dim: size_t,
+      c: i32);
+  pub fn densearray_omp_copy_f32(
+      dst: *mut f32,
+      dim: size_t,
+      src: *const f32);
+  pub fn densearray_omp_cast_u8_to_f32(
+      dst: *mut f32,
+      dim: size_t,
+      src: *const u8);
+  pub fn densearray_omp_square_f32(
+      dst: *mut f32,
+      dim: size_t);
+  pub fn densearray_omp_cube_f32(
+      dst: *mut f32,
+      dim: size_t);
+  pub fn densearray_omp_sqrt_f32(
+      dst: *mut f32,
+      dim: size_t);
+  pub fn densearray_omp_reciprocal_f32(
+      dst: *mut f32,
+      dim: size_t);
+  pub fn densearray_omp_add_scalar_f32(
+      dst: *mut f32,
+      dim: size_t,
+      c: f32);
+  pub fn densearray_omp_scale_f32(
+      dst: *mut f32,
+      dim: size_t,
+      c: f32);
+  pub fn densearray_omp_div_scalar_f32(
+      dst: *mut f32,
+      dim: size_t,
+      c: f32);
+  pub fn densearray_omp_elem_mult_f32(
+      dst: *mut f32,
+      dim: size_t,
+      xs: *const f32);
+  pub fn densearray_omp_elem_div_f32(
+      dst: *mut f32,
+      dim: size_t,
+      xs: *const f32);
+  pub fn densearray_omp_elem_ldiv_f32(
+      dst: *mut f32,
+      dim: size_t,
+      xs: *const f32);
+  pub fn densearray_omp_vector_add_f32(
+      dst: *mut f32,
+      dim: size_t,
+      xs: *const f32,
+      alpha: f32);
+  pub fn densearray_omp_vector_average_f32(
+      dst: *mut f32,
+      dim: size_t,
+      xs: *const f32,
+      alpha: f32);
+  pub fn densearray_omp_chunk_sum_f32(
+      x: *const f32,
+      dim: size_t,
+      incx: size_t,
+      chunk_sz: size_t,
+      partials: *mut f32);
+  pub fn densearray_omp_chunk_abs_sum_f32(
+      x: *const f32,
+      dim: size_t,
+      incx: size_t,
+      chunk_sz: size_t,
+      partials: *mut f32);
+  pub fn densearray_omp_chunk_square_sum_f32(
+      x: *const f32,
+      dim: size_t,
+      incx: size_t,
+      chunk_sz: size_t,
+      partials: *mut f32);
+  pub fn densearray_omp_chunk_inner_prod_f32(
+      x: *const f32,
+      dim: size_t,
+      incx: size_t,
+      y: *const f32,
+      incy: size_t,
+      chunk_sz: size_t,
+      partials: *mut f32);
+  pub fn densearray_omp_reduce_axis_sum_f32(
+      inner_dim: size_t,
+      red_dim: size_t,
+      outer_dim: size_t,
+      x: *const f32,
+      x_inc: size_t,
+      x_red_inc: size_t,
+      x_outer_inc: size_t,
+      y: *mut f32,
+      y_inc: size_t,
+      y_outer_inc: size_t);
+  pub fn densearray_omp_reduce_axis_mean_f32(
+      inner_dim: size_t,
+      red_dim: size_t,
+      outer_dim: size_t,
+      x: *const f32,
+      x_inc: size_t,
+      x_red_inc: size_t,
+      x_outer_inc: size_t,
+      y: *mut f32,
+      y_inc: size_t,
+      y_outer_inc: size_t);
+  pub fn densearray_omp_reduce_axis_max_f32(
+      inner_dim: size_t,
+      red_dim: size_t,
+      outer_dim: size_t,
+      x: *const f32,
+      x_inc: size_t,
+      x_red_inc: size_t,
+      x_outer_inc: size_t,
+      y: *mut f32,
+      y_inc: size_t,
+      y_outer_inc: size_t);
+  pub fn densearray_omp_reduce_axis_min_f32(
+      inner_dim: size_t,
+      red_dim: size_t,
+      outer_dim: size_t,
+      x: *const f32,
+      x_inc: size_t,
+      x_red_inc: size_t,
+      x_outer_inc: size_t,
+      y: *mut f32,
+      y_inc: size_t,
+      y_outer_inc: size_t);
+  pub fn densearray_omp_reduce_axis_argmax_f32(
+      inner_dim: size_t,
+      red_dim: size_t,
+      outer_dim: size_t,
+      x: *const f32,
+      x_inc: size_t,
+      x_red_inc: size_t,
+      x_outer_inc: size_t,
+      y: *mut u32,
+      y_inc: size_t,
+      y_outer_inc: size_t);
+  pub fn densearray_omp_reduce_axis_argmin_f32(
+      inner_dim: size_t,
+      red_dim: size_t,
+      outer_dim: size_t,
+      x: *const f32,
+      x_inc: size_t,
+      x_red_inc: size_t,
+      x_outer_inc: size_t,
+      y: *mut u32,
+      y_inc: size_t,
+      y_outer_inc: size_t);
+  pub fn densearray_omp_set_scalar_f64(
+      dst: *mut f64,
+      dim: size_t,
+      c: f64);
+  pub fn densearray_omp_copy_f64(
+      dst: *mut f64,
+      dim: size_t,
+      src: *const f64);
+  pub fn densearray_omp_cast_u8_to_f64(
+      dst: *mut f64,
+      dim: size_t,
+      src: *const u8);
+  pub fn densearray_omp_square_f64(
+      dst: *mut f64,
+      dim: size_t);
+  pub fn densearray_omp_cube_f64(
+      dst: *mut f64,
+      dim: size_t);
+  pub fn densearray_omp_sqrt_f64(
+      dst: *mut f64,
+      dim: size_t);
+  pub fn densearray_omp_reciprocal_f64(
+      dst: *mut f64,
+      dim: size_t);
+  pub fn densearray_omp_add_scalar_f64(
+      dst: *mut f64,
+      dim: size_t,
+      c: f64);
+  pub fn densearray_omp_scale_f64(
+      dst: *mut f64,
+      dim: size_t,
+      c: f64);
+  pub fn densearray_omp_div_scalar_f64(
+      dst: *mut f64,
+      dim: size_t,
+      c: f64);
+  pub fn densearray_omp_elem_mult_f64(
+      dst: *mut f64,
+      dim: size_t,
+      xs: *const f64);
+  pub fn densearray_omp_elem_div_f64(
+      dst: *mut f64,
+      dim: size_t,
+      xs: *const f64);
+  pub fn densearray_omp_elem_ldiv_f64(
+      dst: *mut f64,
+      dim: size_t,
+      xs: *const f64);
+  pub fn densearray_omp_vector_add_f64(
+      dst: *mut f64,
+      dim: size_t,
+      xs: *const f64,
+      alpha: f64);
+  pub fn densearray_omp_vector_average_f64(
+      dst: *mut f64,
+      dim: size_t,
+      xs: *const f64,
+      alpha: f64);
+  pub fn densearray_omp_chunk_sum_f64(
+      x: *const f64,
+      dim: size_t,
+      incx: size_t,
+      chunk_sz: size_t,
+      partials: *mut f64);
+  pub fn densearray_omp_chunk_abs_sum_f64(
+      x: *const f64,
+      dim: size_t,
+      incx: size_t,
+      chunk_sz: size_t,
+      partials: *mut f64);
+  pub fn densearray_omp_chunk_square_sum_f64(
+      x: *const f64,
+      dim: size_t,
+      incx: size_t,
+      chunk_sz: size_t,
+      partials: *mut f64);
+  pub fn densearray_omp_chunk_inner_prod_f64(
+      x: *const f64,
+      dim: size_t,
+      incx: size_t,
+      y: *const f64,
+      incy: size_t,
+      chunk_sz: size_t,
+      partials: *mut f64);
+  pub fn densearray_omp_reduce_axis_sum_f64(
+      inner_dim: size_t,
+      red_dim: size_t,
+      outer_dim: size_t,
+      x: *const f64,
+      x_inc: size_t,
+      x_red_inc: size_t,
+      x_outer_inc: size_t,
+      y: *mut f64,
+      y_inc: size_t,
+      y_outer_inc: size_t);
+  pub fn densearray_omp_reduce_axis_mean_f64(
+      inner_dim: size_t,
+      red_dim: size_t,
+      outer_dim: size_t,
+      x: *const f64,
+      x_inc: size_t,
+      x_red_inc: size_t,
+      x_outer_inc: size_t,
+      y: *mut f64,
+      y_inc: size_t,
+      y_outer_inc: size_t);
+  pub fn densearray_omp_reduce_axis_max_f64(
+      inner_dim: size_t,
+      red_dim: size_t,
+      outer_dim: size_t,
+      x: *const f64,
+      x_inc: size_t,
+      x_red_inc: size_t,
+      x_outer_inc: size_t,
+      y: *mut f64,
+      y_inc: size_t,
+      y_outer_inc: size_t);
+  pub fn densearray_omp_reduce_axis_min_f64(
+      inner_dim: size_t,
+      red_dim: size_t,
+      outer_dim: size_t,
+      x: *const f64,
+      x_inc: size_t,
+      x_red_inc: size_t,
+      x_outer_inc: size_t,
+      y: *mut f64,
+      y_inc: size_t,
+      y_outer_inc: size_t);
+  pub fn densearray_omp_reduce_axis_argmax_f64(
+      inner_dim: size_t,
+      red_dim: size_t,
+      outer_dim: size_t,
+      x: *const f64,
+      x_inc: size_t,
+      x_red_inc: size_t,
+      x_outer_inc: size_t,
+      y: *mut u32,
+      y_inc: size_t,
+      y_outer_inc: size_t);
+  pub fn densearray_omp_reduce_axis_argmin_f64(
+      inner_dim: size_t,
+      red_dim: size_t,
+      outer_dim: size_t,
+      x: *const f64,
+      x_inc: size_t,
+      x_red_inc: size_t,
+      x_outer_inc: size_t,
+      y: *mut u32,
+      y_inc: size_t,
+      y_outer_inc: size_t);
+}
@@ -0,0 +1,1132 @@
+//! Pure-Rust versions of the C kernels, with the same names and signatures.
+//! These are used in place of the C kernels with the `rust_kernels` feature,
+//! and are always compiled so that they can be checked against the C kernels.
+//!
+//! The elementwise kernels use SSE2 on x86_64; each lane performs the same
+//! IEEE operation as the scalar C loop, so the results are bitwise identical.
+//! The reductions are scalar and accumulate in the same order as the C
+//! kernels. The `densearray_omp_*` names are aliases of the serial kernels.
+
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+use std::env;
+use std::ops::{Add, Sub, Mul, Div};
+use std::ptr;
+use std::sync::atomic::{AtomicIsize, Ordering};
+
+const ISA_SSE2:   i32 = 0;
+const ISA_SSE41:  i32 = 1;
+const ISA_AVX2:   i32 = 2;
+const ISA_AVX512: i32 = 3;
+
+static ISA_DETECTED: AtomicIsize = AtomicIsize::new(-1);
+static ISA_FORCED: AtomicIsize = AtomicIsize::new(-1);
+static OMP_THREADS: AtomicIsize = AtomicIsize::new(0);
+
+trait SimdScalar: Copy + PartialOrd + Add<Output=Self> + Sub<Output=Self> + Mul<Output=Self> + Div<Output=Self> {
+  type Vector: Copy;
+  const LANES: usize;
+
+  fn zero() -> Self;
+  fn one() -> Self;
+  fn sqrt(self) -> Self;
+  fn from_u8(x: u8) -> Self;
+  fn abs(self) -> Self;
+
+  unsafe fn load(p: *const Self) -> Self::Vector;
+  unsafe fn store(p: *mut Self, v: Self::Vector);
+  unsafe fn splat(x: Self) -> Self::Vector;
+  unsafe fn vadd(a: Self::Vector, b: Self::Vector) -> Self::Vector;
+  unsafe fn vsub(a: Self::Vector, b: Self::Vector) -> Self::Vector;
+  unsafe fn vmul(a: Self::Vector, b: Self::Vector) -> Self::Vector;
+  unsafe fn vdiv(a: Self::Vector, b: Self::Vector) -> Self::Vector;
+  unsafe fn vsqrt(a: Self::Vector) -> Self::Vector;
+}
+
+#[cfg(target_arch = "x86_64")]
+impl SimdScalar for f32 {
+  type Vector = __m128;
+  const LANES: usize = 4;
+
+  fn zero() -> f32 { 0.0 }
+  fn one() -> f32 { 1.0 }
+  fn sqrt(self) -> f32 { self.sqrt() }
+  fn from_u8(x: u8) -> f32 { x as f32 }
+  fn abs(self) -> f32 { self.abs() }
+
+  #[inline(always)] unsafe fn load(p: *const f32) -> __m128 { _mm_loadu_ps(p) }
+  #[inline(always)] unsafe fn store(p: *mut f32, v: __m128) { _mm_storeu_ps(p, v) }
+  #[inline(always)] unsafe fn splat(x: f32) -> __m128 { _mm_set1_ps(x) }
+  #[inline(always)] unsafe fn vadd(a: __m128, b: __m128) -> __m128 { _mm_add_ps(a, b) }
+  #[inline(always)] unsafe fn vsub(a: __m128, b: __m128) -> __m128 { _mm_sub_ps(a, b) }
+  #[inline(always)] unsafe fn vmul(a: __m128, b: __m128) -> __m128 { _mm_mul_ps(a, b) }
+  #[inline(always)] unsafe fn vdiv(a: __m128, b: __m128) -> __m128 { _mm_div_ps(a, b) }
+  #[inline(always)] unsafe fn vsqrt(a: __m128) -> __m128 { _mm_sqrt_ps(a) }
+}
+
+#[cfg(target_arch = "x86_64")]
+impl SimdScalar for f64 {
+  type Vector = __m128d;
+  const LANES: usize = 2;
+
+  fn zero() -> f64 { 0.0 }
+  fn one() -> f64 { 1.0 }
+  fn sqrt(self) -> f64 { self.sqrt() }
+  fn from_u8(x: u8) -> f64 { x as f64 }
+  fn abs(self) -> f64 { self.abs() }
+
+  #[inline(always)] unsafe fn load(p: *const f64) -> __m128d { _mm_loadu_pd(p) }
+  #[inline(always)] unsafe fn store(p: *mut f64, v: __m128d) { _mm_storeu_pd(p, v) }
+  #[inline(always)] unsafe fn splat(x: f64) -> __m128d { _mm_set1_pd(x) }
+  #[inline(always)] unsafe fn vadd(a: __m128d, b: __m128d) -> __m128d { _mm_add_pd(a, b) }
+  #[inline(always)] unsafe fn vsub(a: __m128d, b: __m128d) -> __m128d { _mm_sub_pd(a, b) }
+  #[inline(always)] unsafe fn vmul(a: __m128d, b: __m128d) -> __m128d { _mm_mul_pd(a, b) }
+  #[inline(always)] unsafe fn vdiv(a: __m128d, b: __m128d) -> __m128d { _mm_div_pd(a, b) }
+  #[inline(always)] unsafe fn vsqrt(a: __m128d) -> __m128d { _mm_sqrt_pd(a) }
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+impl SimdScalar for f32 {
+  type Vector = f32;
+  const LANES: usize = 1;
+
+  fn zero() -> f32 { 0.0 }
+  fn one() -> f32 { 1.0 }
+  fn sqrt(self) -> f32 { self.sqrt() }
+  fn from_u8(x: u8) -> f32 { x as f32 }
+  fn abs(self) -> f32 { self.abs() }
+
+  #[inline(always)] unsafe fn load(p: *const f32) -> f32 { *p }
+  #[inline(always)] unsafe fn store(p: *mut f32, v: f32) { *p = v }
+  #[inline(always)] unsafe fn splat(x: f32) -> f32 { x }
+  #[inline(always)] unsafe fn vadd(a: f32, b: f32) -> f32 { a + b }
+  #[inline(always)] unsafe fn vsub(a: f32, b: f32) -> f32 { a - b }
+  #[inline(always)] unsafe fn vmul(a: f32, b: f32) -> f32 { a * b }
+  #[inline(always)] unsafe fn vdiv(a: f32, b: f32) -> f32 { a / b }
+  #[inline(always)] unsafe fn vsqrt(a: f32) -> f32 { a.sqrt() }
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+impl SimdScalar for f64 {
+  type Vector = f64;
+  const LANES: usize = 1;
+
+  fn zero() -> f64 { 0.0 }
+  fn one() -> f64 { 1.0 }
+  fn sqrt(self) -> f64 { self.sqrt() }
+  fn from_u8(x: u8) -> f64 { x as f64 }
+  fn abs(self) -> f64 { self.abs() }
+
+  #[inline(always)] unsafe fn load(p: *const f64) -> f64 { *p }
+  #[inline(always)] unsafe fn store(p: *mut f64, v: f64) { *p = v }
+  #[inline(always)] unsafe fn splat(x: f64) -> f64 { x }
+  #[inline(always)] unsafe fn vadd(a: f64, b: f64) -> f64 { a + b }
+  #[inline(always)] unsafe fn vsub(a: f64, b: f64) -> f64 { a - b }
+  #[inline(always)] unsafe fn vmul(a: f64, b: f64) -> f64 { a * b }
+  #[inline(always)] unsafe fn vdiv(a: f64, b: f64) -> f64 { a / b }
+  #[inline(always)] unsafe fn vsqrt(a: f64) -> f64 { a.sqrt() }
+}
+
+#[inline(always)]
+unsafe fn map_inplace<T, V, S>(dst: *mut T, dim: usize, vector_op: V, scalar_op: S)
+where T: SimdScalar, V: Fn(T::Vector) -> T::Vector, S: Fn(T) -> T {
+  let mut idx = 0;
+  while idx + T::LANES <= dim {
+    let p = dst.offset(idx as isize);
+    T::store(p, vector_op(T::load(p)));
+    idx += T::LANES;
+  }
+  while idx < dim {
+    let p = dst.offset(idx as isize);
+    *p = scalar_op(*p);
+    idx += 1;
+  }
+}
+
+#[inline(always)]
+unsafe fn zip_inplace<T, V, S>(dst: *mut T, dim: usize, xs: *const T, vector_op: V, scalar_op: S)
+where T: SimdScalar, V: Fn(T::Vector, T::Vector) -> T::Vector, S: Fn(T, T) -> T {
+  let mut idx = 0;
+  while idx + T::LANES <= dim {
+    let p = dst.offset(idx as isize);
+    let q = xs.offset(idx as isize);
+    T::store(p, vector_op(T::load(p), T::load(q)));
+    idx += T::LANES;
+  }
+  while idx < dim {
+    let p = dst.offset(idx as isize);
+    *p = scalar_op(*p, *xs.offset(idx as isize));
+    idx += 1;
+  }
+}
+
+unsafe fn set_scalar<T: Copy>(dst: *mut T, dim: usize, c: T) {
+  for idx in 0 .. dim {
+    *dst.offset(idx as isize) = c;
+  }
+}
+
+unsafe fn copy<T: Copy>(dst: *mut T, dim: usize, src: *const T) {
+  ptr::copy(src, dst, dim);
+}
+
+unsafe fn cast_u8<T: SimdScalar>(dst: *mut T, dim: usize, src: *const u8) {
+  for idx in 0 .. dim {
+    *dst.offset(idx as isize) = T::from_u8(*src.offset(idx as isize));
+  }
+}
+
+unsafe fn square<T: SimdScalar>(dst: *mut T, dim: usize) {
+  map_inplace(dst, dim, |y| T::vmul(y, y), |y| y * y);
+}
+
+unsafe fn cube<T: SimdScalar>(dst: *mut T, dim: usize) {
+  map_inplace(dst, dim, |y| T::vmul(T::vmul(y, y), y), |y| y * y * y);
+}
+
+unsafe fn sqrt<T: SimdScalar>(dst: *mut T, dim: usize) {
+  map_inplace(dst, dim, |y| T::vsqrt(y), |y| y.sqrt());
+}
+
+unsafe fn reciprocal<T: SimdScalar>(dst: *mut T, dim: usize) {
+  let one = T::splat(T::one());
+  map_inplace(dst, dim, |y| T::vdiv(one, y), |y| T::one() / y);
+}
+
+unsafe fn add_scalar<T: SimdScalar>(dst: *mut T, dim: usize, c: T) {
+  let cv = T::splat(c);
+  map_inplace(dst, dim, |y| T::vadd(y, cv), |y| y + c);
+}
+
+unsafe fn scale<T: SimdScalar>(dst: *mut T, dim: usize, c: T) {
+  let cv = T::splat(c);
+  map_inplace(dst, dim, |y| T::vmul(y, cv), |y| y * c);
+}
+
+unsafe fn div_scalar<T: SimdScalar>(dst: *mut T, dim: usize, c: T) {
+  let cv = T::splat(c);
+  map_inplace(dst, dim, |y| T::vdiv(y, cv), |y| y / c);
+}
+
+unsafe fn elem_mult<T: SimdScalar>(dst: *mut T, dim: usize, xs: *const T) {
+  zip_inplace(dst, dim, xs, |y, x| T::vmul(y, x), |y, x| y * x);
+}
+
+unsafe fn elem_div<T: SimdScalar>(dst: *mut T, dim: usize, xs: *const T) {
+  zip_inplace(dst, dim, xs, |y, x| T::vdiv(y, x), |y, x| y / x);
+}
+
+unsafe fn elem_ldiv<T: SimdScalar>(dst: *mut T, dim: usize, xs: *const T) {
+  zip_inplace(dst, dim, xs, |y, x| T::vdiv(x, y), |y, x| x / y);
+}
+
+unsafe fn vector_add<T: SimdScalar>(dst: *mut T, dim: usize, xs: *const T, c: T) {
+  let cv = T::splat(c);
+  zip_inplace(dst, dim, xs, |y, x| T::vadd(y, T::vmul(cv, x)), |y, x| y + c * x);
+}
+
+unsafe fn vector_average<T: SimdScalar>(dst: *mut T, dim: usize, xs: *const T, c: T) {
+  let cv = T::splat(c);
+  zip_inplace(dst, dim, xs, |y, x| T::vadd(y, T::vmul(cv, T::vsub(x, y))), |y, x| y + c * (x - y));
+}
+
+#[inline(always)]
+unsafe fn chunk_reduce<T, F>(dim: usize, chunk_sz: usize, partials: *mut T, term: F)
+where T: SimdScalar, F: Fn(usize) -> T {
+  let num_chunks = (dim + chunk_sz - 1) / chunk_sz;
+  for c in 0 .. num_chunks {
+    let lo = c * chunk_sz;
+    let hi = if lo + chunk_sz < dim { lo + chunk_sz } else { dim };
+    let mut s = T::zero();
+    for idx in lo .. hi {
+      s = s + term(idx);
+    }
+    *partials.offset(c as isize) = s;
+  }
+}
+
+unsafe fn chunk_sum<T: SimdScalar>(x: *const T, dim: usize, incx: usize, chunk_sz: usize, partials: *mut T) {
+  chunk_reduce(dim, chunk_sz, partials, |idx| *x.offset((idx * incx) as isize));
+}
+
+unsafe fn chunk_abs_sum<T: SimdScalar>(x: *const T, dim: usize, incx: usize, chunk_sz: usize, partials: *mut T) {
+  chunk_reduce(dim, chunk_sz, partials, |idx| (*x.offset((idx * incx) as isize)).abs());
+}
+
+unsafe fn chunk_square_sum<T: SimdScalar>(x: *const T, dim: usize, incx: usize, chunk_sz: usize, partials: *mut T) {
+  chunk_reduce(dim, chunk_sz, partials, |idx| {
+    let x_i = *x.offset((idx * incx) as isize);
+    x_i * x_i
+  });
+}
+
+unsafe fn chunk_inner_prod<T: SimdScalar>(x: *const T, dim: usize, incx: usize, y: *const T, incy: usize, chunk_sz: usize, partials: *mut T) {
+  chunk_reduce(dim, chunk_sz, partials, |idx| *x.offset((idx * incx) as isize) * *y.offset((idx * incy) as isize));
+}
+
+// See `kernels/reduce_axis.c` for the canonical (inner, reduced, outer) layout.
+#[inline(always)]
+unsafe fn reduce_axis<T, A, F>(
+    inner_dim: usize, red_dim: usize, outer_dim: usize,
+    x: *const T, x_inc: usize, x_red_inc: usize, x_outer_inc: usize,
+    y: *mut T, y_inc: usize, y_outer_inc: usize,
+    accumulate: A, finalize: F)
+where T: SimdScalar, A: Fn(T, T) -> T, F: Fn(T) -> T {
+  for k in 0 .. outer_dim {
+    for i in 0 .. inner_dim {
+      let xp = x.offset((i * x_inc + k * x_outer_inc) as isize);
+      let mut acc = *xp;
+      for r in 1 .. red_dim {
+        acc = accumulate(acc, *xp.offset((r * x_red_inc) as isize));
+      }
+      *y.offset((i * y_inc + k * y_outer_inc) as isize) = finalize(acc);
+    }
+  }
+}
+
+#[inline(always)]
+unsafe fn reduce_axis_arg<T, C>(
+    inner_dim: usize, red_dim: usize, outer_dim: usize,
+    x: *const T, x_inc: usize, x_red_inc: usize, x_outer_inc: usize,
+    y: *mut u32, y_inc: usize, y_outer_inc: usize,
+    better: C)
+where T: SimdScalar, C: Fn(T, T) -> bool {
+  for k in 0 .. outer_dim {
+    for i in 0 .. inner_dim {
+      let xp = x.offset((i * x_inc + k * x_outer_inc) as isize);
+      let mut best = *xp;
+      let mut best_r = 0;
+      for r in 1 .. red_dim {
+        let x_r = *xp.offset((r * x_red_inc) as isize);
+        if better(x_r, best) {
+          best = x_r;
+          best_r = r as u32;
+        }
+      }
+      *y.offset((i * y_inc + k * y_outer_inc) as isize) = best_r;
+    }
+  }
+}
+
+unsafe fn reduce_axis_sum<T: SimdScalar>(inner_dim: usize, red_dim: usize, outer_dim: usize, x: *const T, x_inc: usize, x_red_inc: usize, x_outer_inc: usize, y: *mut T, y_inc: usize, y_outer_inc: usize) {
+  reduce_axis(inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc, |acc, x_r| acc + x_r, |acc| acc);
+}
+
+unsafe fn reduce_axis_mean<T: SimdScalar>(inner_dim: usize, red_dim: usize, outer_dim: usize, x: *const T, x_inc: usize, x_red_inc: usize, x_outer_inc: usize, y: *mut T, y_inc: usize, y_outer_inc: usize, n: T) {
+  let inv_n = T::one() / n;
+  reduce_axis(inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc, |acc, x_r| acc + x_r, |acc| acc * inv_n);
+}
+
+unsafe fn reduce_axis_max<T: SimdScalar>(inner_dim: usize, red_dim: usize, outer_dim: usize, x: *const T, x_inc: usize, x_red_inc: usize, x_outer_inc: usize, y: *mut T, y_inc: usize, y_outer_inc: usize) {
+  reduce_axis(inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc, |acc, x_r| if x_r > acc { x_r } else { acc }, |acc| acc);
+}
+
+unsafe fn reduce_axis_min<T: SimdScalar>(inner_dim: usize, red_dim: usize, outer_dim: usize, x: *const T, x_inc: usize, x_red_inc: usize, x_outer_inc: usize, y: *mut T, y_inc: usize, y_outer_inc: usize) {
+  reduce_axis(inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc, |acc, x_r| if x_r < acc { x_r } else { acc }, |acc| acc);
+}
+
+// `clamp_ss` follows the operand order of `_mm_min_ss(_mm_max_ss(lower, x), upper)`
+// in `kernels/cast.c`, so a NaN input clamps to `upper`.
+#[inline(always)]
+fn clamp_ss(x: f32, lower: f32, upper: f32) -> f32 {
+  let y = if lower > x { lower } else { x };
+  if y < upper { y } else { upper }
+}
+
+#[inline(always)]
+fn round_half_even(y: f32) -> f32 {
+  let r = y.round();
+  if (r - y).abs() == 0.5 && r % 2.0 != 0.0 {
+    r - y.signum()
+  } else {
+    r
+  }
+}
+
+fn parse_isa_level(s: &str) -> i32 {
+  match s {
+    "sse2"    => ISA_SSE2,
+    "sse41"   => ISA_SSE41,
+    "avx2"    => ISA_AVX2,
+    "avx512"  => ISA_AVX512,
+    _ => -1,
+  }
+}
+
+#[cfg(target_arch = "x86_64")]
+fn cpu_isa_level() -> i32 {
+  if is_x86_feature_detected!("avx512f") {
+    ISA_AVX512
+  } else if is_x86_feature_detected!("avx2") {
+    ISA_AVX2
+  } else if is_x86_feature_detected!("sse4.1") {
+    ISA_SSE41
+  } else {
+    ISA_SSE2
+  }
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+fn cpu_isa_level() -> i32 {
+  ISA_SSE2
+}
+
+pub unsafe extern "C" fn densearray_detected_isa_level() -> i32 {
+  let mut level = ISA_DETECTED.load(Ordering::Relaxed) as i32;
+  if level < 0 {
+    level = cpu_isa_level();
+    ISA_DETECTED.store(level as isize, Ordering::Relaxed);
+    if let Ok(s) = env::var("DENSEARRAY_ISA") {
+      let env_level = parse_isa_level(&s);
+      if env_level >= 0 {
+        let _ = ISA_FORCED.compare_exchange(-1, env_level as isize, Ordering::Relaxed, Ordering::Relaxed);
+      }
+    }
+  }
+  level
+}
+
+pub unsafe extern "C" fn densearray_isa_level() -> i32 {
+  let detected = densearray_detected_isa_level();
+  let forced = ISA_FORCED.load(Ordering::Relaxed) as i32;
+  if forced >= 0 && forced < detected {
+    forced
+  } else {
+    detected
+  }
+}
+
+pub unsafe extern "C" fn densearray_force_isa_level(level: i32) {
+  densearray_detected_isa_level();
+  ISA_FORCED.store(level as isize, Ordering::Relaxed);
+}
+
+// The `densearray_omp_*` aliases run serially, so the OpenMP thread count is
+// only recorded.
+pub unsafe extern "C" fn densearray_omp_set_num_threads(num_threads: i32) {
+  OMP_THREADS.store(if num_threads > 0 { num_threads as isize } else { 0 }, Ordering::Relaxed);
+}
+
+pub unsafe extern "C" fn densearray_omp_get_num_threads_setting() -> i32 {
+  OMP_THREADS.load(Ordering::Relaxed) as i32
+}
+
+pub unsafe extern "C" fn densearray_omp_num_threads() -> i32 {
+  1
+}
+
+pub unsafe extern "C" fn densearray_kernel_cast_1d_u8_to_f32(
+    dim: usize,
+    src: *const u8,
+    dst: *mut f32)
+{
+  cast_u8(dst, dim, src);
+}
+
+pub unsafe extern "C" fn densearray_kernel_cast_1d_u16_to_f32(
+    dim: usize,
+    src: *const u16,
+    dst: *mut f32)
+{
+  for idx in 0 .. dim {
+    *dst.offset(idx as isize) = *src.offset(idx as isize) as f32;
+  }
+}
+
+pub unsafe extern "C" fn densearray_kernel_cast_1d_f32_to_f64(
+    dim: usize,
+    src: *const f32,
+    dst: *mut f64)
+{
+  for idx in 0 .. dim {
+    *dst.offset(idx as isize) = *src.offset(idx as isize) as f64;
+  }
+}
+
+pub unsafe extern "C" fn densearray_kernel_cast_1d_f64_to_f32(
+    dim: usize,
+    src: *const f64,
+    dst: *mut f32)
+{
+  for idx in 0 .. dim {
+    *dst.offset(idx as isize) = *src.offset(idx as isize) as f32;
+  }
+}
+
+pub unsafe extern "C" fn densearray_kernel_round_clamp_1d_f32_to_u8(
+    dim: usize,
+    src: *const f32,
+    dst: *mut u8)
+{
+  densearray_kernel_round_clamp_1d_f32_to_u8_sse4(dim, src, dst);
+}
+
+pub unsafe extern "C" fn densearray_kernel_round_clamp_1d_f32_to_u8_sse2(
+    dim: usize,
+    src: *const f32,
+    dst: *mut u8)
+{
+  for idx in 0 .. dim {
+    let y = clamp_ss(*src.offset(idx as isize), 0.0, 255.0);
+    *dst.offset(idx as isize) = ((y + 0.5) as i32) as u8;
+  }
+}
+
+pub unsafe extern "C" fn densearray_kernel_round_clamp_1d_f32_to_u8_sse4(
+    dim: usize,
+    src: *const f32,
+    dst: *mut u8)
+{
+  for idx in 0 .. dim {
+    let y = clamp_ss(*src.offset(idx as isize), 0.0, 255.0);
+    *dst.offset(idx as isize) = round_half_even(y) as u8;
+  }
+}
+
+pub unsafe extern "C" fn densearray_kernel_round_clamp_1d_f32_to_u8_avx2(
+    dim: usize,
+    src: *const f32,
+    dst: *mut u8)
+{
+  densearray_kernel_round_clamp_1d_f32_to_u8_sse4(dim, src, dst);
+}
+
+pub unsafe extern "C" fn densearray_kernel_round_clamp_1d_f32_to_u8_avx512(
+    dim: usize,
+    src: *const f32,
+    dst: *mut u8)
+{
+  densearray_kernel_round_clamp_1d_f32_to_u8_sse4(dim, src, dst);
+}
+
+pub unsafe extern "C" fn densearray_kernel_round_clamp_1d_f32_to_i8(
+    dim: usize,
+    src: *const f32,
+    dst: *mut i8)
+{
+  for idx in 0 .. dim {
+    *dst.offset(idx as isize) = round_half_even(clamp_ss(*src.offset(idx as isize), -128.0, 127.0)) as i8;
+  }
+}
+
+pub unsafe extern "C" fn densearray_kernel_round_clamp_1d_f32_to_u16(
+    dim: usize,
+    src: *const f32,
+    dst: *mut u16)
+{
+  for idx in 0 .. dim {
+    *dst.offset(idx as isize) = round_half_even(clamp_ss(*src.offset(idx as isize), 0.0, 65535.0)) as u16;
+  }
+}
+
+pub unsafe extern "C" fn densearray_kernel_round_clamp_1d_f32_to_i32(
+    dim: usize,
+    src: *const f32,
+    dst: *mut i32)
+{
+  for idx in 0 .. dim {
+    *dst.offset(idx as isize) = round_half_even(clamp_ss(*src.offset(idx as isize), -2147483648.0, 2147483520.0)) as i32;
+  }
+}
+
+pub unsafe extern "C" fn densearray_kernel_clamp_1d_f32_sse2(
+    dim: usize,
+    src: *const f32,
+    dst: *mut f32,
+    lowerf: f32,
+    upperf: f32)
+{
+  for idx in 0 .. dim {
+    *dst.offset(idx as isize) = clamp_ss(*src.offset(idx as isize), lowerf, upperf);
+  }
+}
+
+pub unsafe extern "C" fn densearray_kernel_clamp_1d_f32_avx2(
+    dim: usize,
+    src: *const f32,
+    dst: *mut f32,
+    lowerf: f32,
+    upperf: f32)
+{
+  densearray_kernel_clamp_1d_f32_sse2(dim, src, dst, lowerf, upperf);
+}
+
+pub unsafe extern "C" fn densearray_kernel_clamp_1d_f32_avx512(
+    dim: usize,
+    src: *const f32,
+    dst: *mut f32,
+    lowerf: f32,
+    upperf: f32)
+{
+  densearray_kernel_clamp_1d_f32_sse2(dim, src, dst, lowerf, upperf);
+}
+
+pub unsafe extern "C" fn densearray_kernel_clamp_1d_f32(
+    dim: usize,
+    src: *const f32,
+    dst: *mut f32,
+    lowerf: f32,
+    upperf: f32)
+{
+  densearray_kernel_clamp_1d_f32_sse2(dim, src, dst, lowerf, upperf);
+}
+
+pub unsafe extern "C" fn densearray_set_scalar_f32(
+    dst: *mut f32,
+    dim: usize,
+    c: f32)
+{
+  set_scalar(dst, dim, c);
+}
+
+pub unsafe extern "C" fn densearray_set_scalar_i32(
+    dst: *mut i32,
+    dim: usize,
+    c: i32)
+{
+  set_scalar(dst, dim, c);
+}
+
+pub unsafe extern "C" fn densearray_copy_f32(
+    dst: *mut f32,
+    dim: usize,
+    src: *const f32)
+{
+  copy(dst, dim, src);
+}
+
+pub unsafe extern "C" fn densearray_cast_u8_to_f32(
+    dst: *mut f32,
+    dim: usize,
+    src: *const u8)
+{
+  cast_u8(dst, dim, src);
+}
+
+pub unsafe extern "C" fn densearray_square_f32(
+    dst: *mut f32,
+    dim: usize)
+{
+  square(dst, dim);
+}
+
+pub unsafe extern "C" fn densearray_cube_f32(
+    dst: *mut f32,
+    dim: usize)
+{
+  cube(dst, dim);
+}
+
+pub unsafe extern "C" fn densearray_sqrt_f32(
+    dst: *mut f32,
+    dim: usize)
+{
+  sqrt(dst, dim);
+}
+
+pub unsafe extern "C" fn densearray_reciprocal_f32(
+    dst: *mut f32,
+    dim: usize)
+{
+  reciprocal(dst, dim);
+}
+
+pub unsafe extern "C" fn densearray_add_scalar_f32(
+    dst: *mut f32,
+    dim: usize,
+    c: f32)
+{
+  add_scalar(dst, dim, c);
+}
+
+pub unsafe extern "C" fn densearray_scale_f32(
+    dst: *mut f32,
+    dim: usize,
+    c: f32)
+{
+  scale(dst, dim, c);
+}
+
+pub unsafe extern "C" fn densearray_div_scalar_f32(
+    dst: *mut f32,
+    dim: usize,
+    c: f32)
+{
+  div_scalar(dst, dim, c);
+}
+
+pub unsafe extern "C" fn densearray_elem_mult_f32(
+    dst: *mut f32,
+    dim: usize,
+    xs: *const f32)
+{
+  elem_mult(dst, dim, xs);
+}
+
+pub unsafe extern "C" fn densearray_elem_div_f32(
+    dst: *mut f32,
+    dim: usize,
+    xs: *const f32)
+{
+  elem_div(dst, dim, xs);
+}
+
+pub unsafe extern "C" fn densearray_elem_ldiv_f32(
+    dst: *mut f32,
+    dim: usize,
+    xs: *const f32)
+{
+  elem_ldiv(dst, dim, xs);
+}
+
+pub unsafe extern "C" fn densearray_vector_add_f32(
+    dst: *mut f32,
+    dim: usize,
+    xs: *const f32,
+    alpha: f32)
+{
+  vector_add(dst, dim, xs, alpha);
+}
+
+pub unsafe extern "C" fn densearray_vector_average_f32(
+    dst: *mut f32,
+    dim: usize,
+    xs: *const f32,
+    alpha: f32)
+{
+  vector_average(dst, dim, xs, alpha);
+}
+
+pub unsafe extern "C" fn densearray_chunk_sum_f32(
+    x: *const f32,
+    dim: usize,
+    incx: usize,
+    chunk_sz: usize,
+    partials: *mut f32)
+{
+  chunk_sum(x, dim, incx, chunk_sz, partials);
+}
+
+pub unsafe extern "C" fn densearray_chunk_abs_sum_f32(
+    x: *const f32,
+    dim: usize,
+    incx: usize,
+    chunk_sz: usize,
+    partials: *mut f32)
+{
+  chunk_abs_sum(x, dim, incx, chunk_sz, partials);
+}
+
+pub unsafe extern "C" fn densearray_chunk_square_sum_f32(
+    x: *const f32,
+    dim: usize,
+    incx: usize,
+    chunk_sz: usize,
+    partials: *mut f32)
+{
+  chunk_square_sum(x, dim, incx, chunk_sz, partials);
+}
+
+pub unsafe extern "C" fn densearray_chunk_inner_prod_f32(
+    x: *const f32,
+    dim: usize,
+    incx: usize,
+    y: *const f32,
+    incy: usize,
+    chunk_sz: usize,
+    partials: *mut f32)
+{
+  chunk_inner_prod(x, dim, incx, y, incy, chunk_sz, partials);
+}
+
+pub unsafe extern "C" fn densearray_reduce_axis_sum_f32(
+    inner_dim: usize,
+    red_dim: usize,
+    outer_dim: usize,
+    x: *const f32,
+    x_inc: usize,
+    x_red_inc: usize,
+    x_outer_inc: usize,
+    y: *mut f32,
+    y_inc: usize,
+    y_outer_inc: usize)
+{
+  reduce_axis_sum(inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc);
+}
+
+pub unsafe extern "C" fn densearray_reduce_axis_mean_f32(
+    inner_dim: usize,
+    red_dim: usize,
+    outer_dim: usize,
+    x: *const f32,
+    x_inc: usize,
+    x_red_inc: usize,
+    x_outer_inc: usize,
+    y: *mut f32,
+    y_inc: usize,
+    y_outer_inc: usize)
+{
+  reduce_axis_mean(inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc, red_dim as f32);
+}
+
+pub unsafe extern "C" fn densearray_reduce_axis_max_f32(
+    inner_dim: usize,
+    red_dim: usize,
+    outer_dim: usize,
+    x: *const f32,
+    x_inc: usize,
+    x_red_inc: usize,
+    x_outer_inc: usize,
+    y: *mut f32,
+    y_inc: usize,
+    y_outer_inc: usize)
+{
+  reduce_axis_max(inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc);
+}
+
+pub unsafe extern "C" fn densearray_reduce_axis_min_f32(
+    inner_dim: usize,
+    red_dim: usize,
+    outer_dim: usize,
+    x: *const f32,
+    x_inc: usize,
+    x_red_inc: usize,
+    x_outer_inc: usize,
+    y: *mut f32,
+    y_inc: usize,
+    y_outer_inc: usize)
+{
+  reduce_axis_min(inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc);
+}
+
+pub unsafe extern "C" fn densearray_reduce_axis_argmax_f32(
+    inner_dim: usize,
+    red_dim: usize,
+    outer_dim: usize,
+    x: *const f32,
+    x_inc: usize,
+    x_red_inc: usize,
+    x_outer_inc: usize,
+    y: *mut u32,
+    y_inc: usize,
+    y_outer_inc: usize)
+{
+  reduce_axis_arg(inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc, |x_r, best| x_r > best);
+}
+
+pub unsafe extern "C" fn densearray_reduce_axis_argmin_f32(
+    inner_dim: usize,
+    red_dim: usize,
+    outer_dim: usize,
+    x: *const f32,
+    x_inc: usize,
+    x_red_inc: usize,
+    x_outer_inc: usize,
+    y: *mut u32,
+    y_inc: usize,
+    y_outer_inc: usize)
+{
+  reduce_axis_arg(inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc, |x_r, best| x_r < best);
+}
+
+pub unsafe extern "C" fn densearray_set_scalar_f64(
+    dst: *mut f64,
+    dim: usize,
+    c: f64)
+{
+  set_scalar(dst, dim, c);
+}
+
+pub unsafe extern "C" fn densearray_copy_f64(
+    dst: *mut f64,
+    dim: usize,
+    src: *const f64)
+{
+  copy(dst, dim, src);
+}
+
+pub unsafe extern "C" fn densearray_cast_u8_to_f64(
+    dst: *mut f64,
+    dim: usize,
+    src: *const u8)
+{
+  cast_u8(dst, dim, src);
+}
+
+pub unsafe extern "C" fn densearray_square_f64(
+    dst: *mut f64,
+    dim: usize)
+{
+  square(dst, dim);
+}
+
+pub unsafe extern "C" fn densearray_cube_f64(
+    dst: *mut f64,
+    dim: usize)
+{
+  cube(dst, dim);
+}
+
+pub unsafe extern "C" fn densearray_sqrt_f64(
+    dst: *mut f64,
+    dim: usize)
+{
+  sqrt(dst, dim);
+}
+
+pub unsafe extern "C" fn densearray_reciprocal_f64(
+    dst: *mut f64,
+    dim: usize)
+{
+  reciprocal(dst, dim);
+}
+
+pub unsafe extern "C" fn densearray_add_scalar_f64(
+    dst: *mut f64,
+    dim: usize,
+    c: f64)
+{
+  add_scalar(dst, dim, c);
+}
+
+pub unsafe extern "C" fn densearray_scale_f64(
+    dst: *mut f64,
+    dim: usize,
+    c: f64)
+{
+  scale(dst, dim, c);
+}
+
+pub unsafe extern "C" fn densearray_div_scalar_f64(
+    dst: *mut f64,
+    dim: usize,
+    c: f64)
+{
+  div_scalar(dst, dim, c);
+}
+
+pub unsafe extern "C" fn densearray_elem_mult_f64(
+    dst: *mut f64,
+    dim: usize,
+    xs: *const f64)
+{
+  elem_mult(dst, dim, xs);
+}
+
+pub unsafe extern "C" fn densearray_elem_div_f64(
+    dst: *mut f64,
+    dim: usize,
+    xs: *const f64)
+{
+  elem_div(dst, dim, xs);
+}
+
+pub unsafe extern "C" fn densearray_elem_ldiv_f64(
+    dst: *mut f64,
+    dim: usize,
+    xs: *const f64)
+{
+  elem_ldiv(dst, dim, xs);
+}
+
+pub unsafe extern "C" fn densearray_vector_add_f64(
+    dst: *mut f64,
+    dim: usize,
+    xs: *const f64,
+    alpha: f64)
+{
+  vector_add(dst, dim, xs, alpha);
+}
+
+pub unsafe extern "C" fn densearray_vector_average_f64(
+    dst: *mut f64,
+    dim: usize,
+    xs: *const f64,
+    alpha: f64)
+{
+  vector_average(dst, dim, xs, alpha);
+}
+
+pub unsafe extern "C" fn densearray_chunk_sum_f64(
+    x: *const f64,
+    dim: usize,
+    incx: usize,
+    chunk_sz: usize,
+    partials: *mut f64)
+{
+  chunk_sum(x, dim, incx, chunk_sz, partials);
+}
+
+pub unsafe extern "C" fn densearray_chunk_abs_sum_f64(
+    x: *const f64,
+    dim: usize,
+    incx: usize,
+    chunk_sz: usize,
+    partials: *mut f64)
+{
+  chunk_abs_sum(x, dim, incx, chunk_sz, partials);
+}
+
+pub unsafe extern "C" fn densearray_chunk_square_sum_f64(
+    x: *const f64,
+    dim: usize,
+    incx: usize,
+    chunk_sz: usize,
+    partials: *mut f64)
+{
+  chunk_square_sum(x, dim, incx, chunk_sz, partials);
+}
+
+pub unsafe extern "C" fn densearray_chunk_inner_prod_f64(
+    x: *const f64,
+    dim: usize,
+    incx: usize,
+    y: *const f64,
+    incy: usize,
+    chunk_sz: usize,
+    partials: *mut f64)
+{
+  chunk_inner_prod(x, dim, incx, y, incy, chunk_sz, partials);
+}
+
+pub unsafe extern "C" fn densearray_reduce_axis_sum_f64(
+    inner_dim: usize,
+    red_dim: usize,
+    outer_dim: usize,
+    x: *const f64,
+    x_inc: usize,
+    x_red_inc: usize,
+    x_outer_inc: usize,
+    y: *mut f64,
+    y_inc: usize,
+    y_outer_inc: usize)
+{
+  reduce_axis_sum(inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc);
+}
+
+pub unsafe extern "C" fn densearray_reduce_axis_mean_f64(
+    inner_dim: usize,
+    red_dim: usize,
+    outer_dim: usize,
+    x: *const f64,
+    x_inc: usize,
+    x_red_inc: usize,
+    x_outer_inc: usize,
+    y: *mut f64,
+    y_inc: usize,
+    y_outer_inc: usize)
+{
+  reduce_axis_mean(inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc, red_dim as f64);
+}
+
+pub unsafe extern "C" fn densearray_reduce_axis_max_f64(
+    inner_dim: usize,
+    red_dim: usize,
+    outer_dim: usize,
+    x: *const f64,
+    x_inc: usize,
+    x_red_inc: usize,
+    x_outer_inc: usize,
+    y: *mut f64,
+    y_inc: usize,
+    y_outer_inc: usize)
+{
+  reduce_axis_max(inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc);
+}
+
+pub unsafe extern "C" fn densearray_reduce_axis_min_f64(
+    inner_dim: usize,
+    red_dim: usize,
+    outer_dim: usize,
+    x: *const f64,
+    x_inc: usize,
+    x_red_inc: usize,
+    x_outer_inc: usize,
+    y: *mut f64,
+    y_inc: usize,
+    y_outer_inc: usize)
+{
+  reduce_axis_min(inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc);
+}
+
+pub unsafe extern "C" fn densearray_reduce_axis_argmax_f64(
+    inner_dim: usize,
+    red_dim: usize,
+    outer_dim: usize,
+    x: *const f64,
+    x_inc: usize,
+    x_red_inc: usize,
+    x_outer_inc: usize,
+    y: *mut u32,
+    y_inc: usize,
+    y_outer_inc: usize)
+{
+  reduce_axis_arg(inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc, |x_r, best| x_r > best);
+}
+
+pub unsafe extern "C" fn densearray_reduce_axis_argmin_f64(
+    inner_dim: usize,
+    red_dim: usize,
+    outer_dim: usize,
+    x: *const f64,
+    x_inc: usize,
+    x_red_inc: usize,
+    x_outer_inc: usize,
+    y: *mut u32,
+    y_inc: usize,
+    y_outer_inc: usize)
+{
+  reduce_axis_arg(inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc, |x_r, best| x_r < best);
+}
+
+pub use self::densearray_set_scalar_f32 as densearray_omp_set_scalar_f32;
+pub use self::densearray_set_scalar_i32 as densearray_omp_set_scalar_i32;
+pub use self::densearray_copy_f32 as densearray_omp_copy_f32;
+pub use self::densearray_cast_u8_to_f32 as densearray_omp_cast_u8_to_f32;
+pub use self::densearray_square_f32 as densearray_omp_square_f32;
+pub use self::densearray_cube_f32 as densearray_omp_cube_f32;
+pub use self::densearray_sqrt_f32 as densearray_omp_sqrt_f32;
+pub use self::densearray_reciprocal_f32 as densearray_omp_reciprocal_f32;
+pub use self::densearray_add_scalar_f32 as densearray_omp_add_scalar_f32;
+pub use self::densearray_scale_f32 as densearray_omp_scale_f32;
+pub use self::densearray_div_scalar_f32 as densearray_omp_div_scalar_f32;
+pub use self::densearray_elem_mult_f32 as densearray_omp_elem_mult_f32;
+pub use self::densearray_elem_div_f32 as densearray_omp_elem_div_f32;
+pub use self::densearray_elem_ldiv_f32 as densearray_omp_elem_ldiv_f32;
+pub use self::densearray_vector_add_f32 as densearray_omp_vector_add_f32;
+pub use self::densearray_vector_average_f32 as densearray_omp_vector_average_f32;
+pub use self::densearray_chunk_sum_f32 as densearray_omp_chunk_sum_f32;
+pub use self::densearray_chunk_abs_sum_f32 as densearray_omp_chunk_abs_sum_f32;
+pub use self::densearray_chunk_square_sum_f32 as densearray_omp_chunk_square_sum_f32;
+pub use self::densearray_chunk_inner_prod_f32 as densearray_omp_chunk_inner_prod_f32;
+pub use self::densearray_reduce_axis_sum_f32 as densearray_omp_reduce_axis_sum_f32;
+pub use self::densearray_reduce_axis_mean_f32 as densearray_omp_reduce_axis_mean_f32;
+pub use self::densearray_reduce_axis_max_f32 as densearray_omp_reduce_axis_max_f32;
+pub use self::densearray_reduce_axis_min_f32 as densearray_omp_reduce_axis_min_f32;
+pub use self::densearray_reduce_axis_argmax_f32 as densearray_omp_reduce_axis_argmax_f32;
+pub use self::densearray_reduce_axis_argmin_f32 as densearray_omp_reduce_axis_argmin_f32;
+pub use self::densearray_set_scalar_f64 as densearray_omp_set_scalar_f64;
+pub use self::densearray_copy_f64 as densearray_omp_copy_f64;
+pub use self::densearray_cast_u8_to_f64 as densearray_omp_cast_u8_to_f64;
+pub use self::densearray_square_f64 as densearray_omp_square_f64;
+pub use self::densearray_cube_f64 as densearray_omp_cube_f64;
+pub use self::densearray_sqrt_f64 as densearray_omp_sqrt_f64;
+pub use self::densearray_reciprocal_f64 as densearray_omp_reciprocal_f64;
+pub use self::densearray_add_scalar_f64 as densearray_omp_add_scalar_f64;
+pub use self::densearray_scale_f64 as densearray_omp_scale_f64;
+pub use self::densearray_div_scalar_f64 as densearray_omp_div_scalar_f64;
+pub use self::densearray_elem_mult_f64 as densearray_omp_elem_mult_f64;
+pub use self::densearray_elem_div_f64 as densearray_omp_elem_div_f64;
+pub use self::densearray_elem_ldiv_f64 as densearray_omp_elem_ldiv_f64;
+pub use self::densearray_vector_add_f64 as densearray_omp_vector_add_f64;
+pub use self::densearray_vector_average_f64 as densearray_omp_vector_average_f64;
+pub use self::densearray_chunk_sum_f64 as densearray_omp_chunk_sum_f64;
+pub use self::densearray_chunk_abs_sum_f64 as densearray_omp_chunk_abs_sum_f64;
+pub use self::densearray_chunk_square_sum_f64 as densearray_omp_chunk_square_sum_f64;
+pub use self::densearray_chunk_inner_prod_f64 as densearray_omp_chunk_inner_prod_f64;
+pub use self::densearray_reduce_axis_sum_f64 as densearray_omp_reduce_axis_sum_f64;
+pub use self::densearray_reduce_axis_mean_f64 as densearray_omp_reduce_axis_mean_f64;
+pub use self::densearray_reduce_axis_max_f64 as densearray_omp_reduce_axis_max_f64;
+pub use self::densearray_reduce_axis_min_f64 as densearray_omp_reduce_axis_min_f64;
+pub use self::densearray_reduce_axis_argmax_f64 as densearray_omp_reduce_axis_argmax_f64;
+pub use self::densearray_reduce_axis_argmin_f64 as densearray_omp_reduce_axis_argmin_f64;
@@ -0,0 +1,168 @@
+use blas::{BlasScalar};
+use super::*;
+
+/// The elementwise, chunked and axis reduction kernels over a real scalar
+/// type. Each method forwards to the C kernel of the same name, or to its
+/// Rust port under the `rust_kernels` feature; the `omp_*` variants are the
+/// OpenMP threaded kernels.
+pub trait KernelScalar: BlasScalar {
+  unsafe fn copy(dst: *mut Self, dim: usize, src: *const Self);
+  unsafe fn cast_u8(dst: *mut Self, dim: usize, src: *const u8);
+  unsafe fn square(dst: *mut Self, dim: usize);
+  unsafe fn cube(dst: *mut Self, dim: usize);
+  unsafe fn sqrt(dst: *mut Self, dim: usize);
+  unsafe fn reciprocal(dst: *mut Self, dim: usize);
+  unsafe fn add_scalar(dst: *mut Self, dim: usize, c: Self);
+  unsafe fn scale(dst: *mut Self, dim: usize, c: Self);
+  unsafe fn div_scalar(dst: *mut Self, dim: usize, c: Self);
+  unsafe fn elem_mult(dst: *mut Self, dim: usize, xs: *const Self);
+  unsafe fn elem_div(dst: *mut Self, dim: usize, xs: *const Self);
+  unsafe fn elem_ldiv(dst: *mut Self, dim: usize, xs: *const Self);
+  unsafe fn vector_add(dst: *mut Self, dim: usize, xs: *const Self, alpha: Self);
+  unsafe fn vector_average(dst: *mut Self, dim: usize, xs: *const Self, alpha: Self);
+  unsafe fn chunk_sum(x: *const Self, dim: usize, incx: usize, chunk_sz: usize, partials: *mut Self);
+  unsafe fn chunk_abs_sum(x: *const Self, dim: usize, incx: usize, chunk_sz: usize, partials: *mut Self);
+  unsafe fn chunk_square_sum(x: *const Self, dim: usize, incx: usize, chunk_sz: usize, partials: *mut Self);
+  unsafe fn chunk_inner_prod(x: *const Self, dim: usize, incx: usize, y: *const Self, incy: usize, chunk_sz: usize, partials: *mut Self);
+  unsafe fn reduce_axis_sum(inner_dim: usize, red_dim: usize, outer_dim: usize, x: *const Self, x_inc: usize, x_red_inc: usize, x_outer_inc: usize, y: *mut Self, y_inc: usize, y_outer_inc: usize);
+  unsafe fn reduce_axis_mean(inner_dim: usize, red_dim: usize, outer_dim: usize, x: *const Self, x_inc: usize, x_red_inc: usize, x_outer_inc: usize, y: *mut Self, y_inc: usize, y_outer_inc: usize);
+  unsafe fn reduce_axis_max(inner_dim: usize, red_dim: usize, outer_dim: usize, x: *const Self, x_inc: usize, x_red_inc: usize, x_outer_inc: usize, y: *mut Self, y_inc: usize, y_outer_inc: usize);
+  unsafe fn reduce_axis_min(inner_dim: usize, red_dim: usize, outer_dim: usize, x: *const Self, x_inc: usize, x_red_inc: usize, x_outer_inc: usize, y: *mut Self, y_inc: usize, y_outer_inc: usize);
+  unsafe fn reduce_axis_argmax(inner_dim: usize, red_dim: usize, outer_dim: usize, x: *const Self, x_inc: usize, x_red_inc: usize, x_outer_inc: usize, y: *mut u32, y_inc: usize, y_outer_inc: usize);
+  unsafe fn reduce_axis_argmin(inner_dim: usize, red_dim: usize, outer_dim: usize, x: *const Self, x_inc: usize, x_red_inc: usize, x_outer_inc: usize, y: *mut u32, y_inc: usize, y_outer_inc: usize);
+
+  unsafe fn omp_copy(dst: *mut Self, dim: usize, src: *const Self);
+  unsafe fn omp_cast_u8(dst: *mut Self, dim: usize, src: *const u8);
+  unsafe fn omp_square(dst: *mut Self, dim: usize);
+  unsafe fn omp_cube(dst: *mut Self, dim: usize);
+  unsafe fn omp_sqrt(dst: *mut Self, dim: usize);
+  unsafe fn omp_reciprocal(dst: *mut Self, dim: usize);
+  unsafe fn omp_add_scalar(dst: *mut Self, dim: usize, c: Self);
+  unsafe fn omp_scale(dst: *mut Self, dim: usize, c: Self);
+  unsafe fn omp_div_scalar(dst: *mut Self, dim: usize, c: Self);
+  unsafe fn omp_elem_mult(dst: *mut Self, dim: usize, xs: *const Self);
+  unsafe fn omp_elem_div(dst: *mut Self, dim: usize, xs: *const Self);
+  unsafe fn omp_elem_ldiv(dst: *mut Self, dim: usize, xs: *const Self);
+  unsafe fn omp_vector_add(dst: *mut Self, dim: usize, xs: *const Self, alpha: Self);
+  unsafe fn omp_vector_average(dst: *mut Self, dim: usize, xs: *const Self, alpha: Self);
+  unsafe fn omp_chunk_sum(x: *const Self, dim: usize, incx: usize, chunk_sz: usize, partials: *mut Self);
+  unsafe fn omp_chunk_abs_sum(x: *const Self, dim: usize, incx: usize, chunk_sz: usize, partials: *mut Self);
+  unsafe fn omp_chunk_square_sum(x: *const Self, dim: usize, incx: usize, chunk_sz: usize, partials: *mut Self);
+  unsafe fn omp_chunk_inner_prod(x: *const Self, dim: usize, incx: usize, y: *const Self, incy: usize, chunk_sz: usize, partials: *mut Self);
+  unsafe fn omp_reduce_axis_sum(inner_dim: usize, red_dim: usize, outer_dim: usize, x: *const Self, x_inc: usize, x_red_inc: usize, x_outer_inc: usize, y: *mut Self, y_inc: usize, y_outer_inc: usize);
+  unsafe fn omp_reduce_axis_mean(inner_dim: usize, red_dim: usize, outer_dim: usize, x: *const Self, x_inc: usize, x_red_inc: usize, x_outer_inc: usize, y: *mut Self, y_inc: usize, y_outer_inc: usize);
+  unsafe fn omp_reduce_axis_max(inner_dim: usize, red_dim: usize, outer_dim: usize, x: *const Self, x_inc: usize, x_red_inc: usize, x_outer_inc: usize, y: *mut Self, y_inc: usize, y_outer_inc: usize);
+  unsafe fn omp_reduce_axis_min(inner_dim: usize, red_dim: usize, outer_dim: usize, x: *const Self, x_inc: usize, x_red_inc: usize, x_outer_inc: usize, y: *mut Self, y_inc: usize, y_outer_inc: usize);
+  unsafe fn omp_reduce_axis_argmax(inner_dim: usize, red_dim: usize, outer_dim: usize, x: *const Self, x_inc: usize, x_red_inc: usize, x_outer_inc: usize, y: *mut u32, y_inc: usize, y_outer_inc: usize);
+  unsafe fn omp_reduce_axis_argmin(inner_dim: usize, red_dim: usize, outer_dim: usize, x: *const Self, x_inc: usize, x_red_inc: usize, x_outer_inc: usize, y: *mut u32, y_inc: usize, y_outer_inc: usize);
+
+  fn exp(self) -> Self;
+}
+
+impl KernelScalar for f32 {
+  unsafe fn copy(dst: *mut f32, dim: usize, src: *const f32) { densearray_copy_f32(dst, dim, src) }
+  unsafe fn cast_u8(dst: *mut f32, dim: usize, src: *const u8) { densearray_cast_u8_to_f32(dst, dim, src) }
+  unsafe fn square(dst: *mut f32, dim: usize) { densearray_square_f32(dst, dim) }
+  unsafe fn cube(dst: *mut f32, dim: usize) { densearray_cube_f32(dst, dim) }
+  unsafe fn sqrt(dst: *mut f32, dim: usize) { densearray_sqrt_f32(dst, dim) }
+  unsafe fn reciprocal(dst: *mut f32, dim: usize) { densearray_reciprocal_f32(dst, dim) }
+  unsafe fn add_scalar(dst: *mut f32, dim: usize, c: f32) { densearray_add_scalar_f32(dst, dim, c) }
+  unsafe fn scale(dst: *mut f32, dim: usize, c: f32) { densearray_scale_f32(dst, dim, c) }
+  unsafe fn div_scalar(dst: *mut f32, dim: usize, c: f32) { densearray_div_scalar_f32(dst, dim, c) }
+  unsafe fn elem_mult(dst: *mut f32, dim: usize, xs: *const f32) { densearray_elem_mult_f32(dst, dim, xs) }
+  unsafe fn elem_div(dst: *mut f32, dim: usize, xs: *const f32) { densearray_elem_div_f32(dst, dim, xs) }
+  unsafe fn elem_ldiv(dst: *mut f32, dim: usize, xs: *const f32) { densearray_elem_ldiv_f32(dst, dim, xs) }
+  unsafe fn vector_add(dst: *mut f32, dim: usize, xs: *const f32, alpha: f32) { densearray_vector_add_f32(dst, dim, xs, alpha) }
+  unsafe fn vector_average(dst: *mut f32, dim: usize, xs: *const f32, alpha: f32) { densearray_vector_average_f32(dst, dim, xs, alpha) }
+  unsafe fn chunk_sum(x: *const f32, dim: usize, incx: usize, chunk_sz: usize, partials: *mut f32) { densearray_chunk_sum_f32(x, dim, incx, chunk_sz, partials) }
+  unsafe fn chunk_abs_sum(x: *const f32, dim: usize, incx: usize, chunk_sz: usize, partials: *mut f32) { densearray_chunk_abs_sum_f32(x, dim, incx, chunk_sz, partials) }
+  unsafe fn chunk_square_sum(x: *const f32, dim: usize, incx: usize, chunk_sz: usize, partials: *mut f32) { densearray_chunk_square_sum_f32(x, dim, incx, chunk_sz, partials) }
+  unsafe fn chunk_inner_prod(x: *const f32, dim: usize, incx: usize, y: *const f32, incy: usize, chunk_sz: usize, partials: *mut f32) { densearray_chunk_inner_prod_f32(x, dim, incx, y, incy, chunk_sz, partials) }
+  unsafe fn reduce_axis_sum(inner_dim: usize, red_dim: usize, outer_dim: usize, x: *const f32, x_inc: usize, x_red_inc: usize, x_outer_inc: usize, y: *mut f32, y_inc: usize, y_outer_inc: usize) { densearray_reduce_axis_sum_f32(inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc) }
+  unsafe fn reduce_axis_mean(inner_dim: usize, red_dim: usize, outer_dim: usize, x: *const f32, x_inc: usize, x_red_inc: usize, x_outer_inc: usize, y: *mut f32, y_inc: usize, y_outer_inc: usize) { densearray_reduce_axis_mean_f32(inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc) }
+  unsafe fn reduce_axis_max(inner_dim: usize, red_dim: usize, outer_dim: usize, x: *const f32, x_inc: usize, x_red_inc: usize, x_outer_inc: usize, y: *mut f32, y_inc: usize, y_outer_inc: usize) { densearray_reduce_axis_max_f32(inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc) }
+  unsafe fn reduce_axis_min(inner_dim: usize, red_dim: usize, outer_dim: usize, x: *const f32, x_inc: usize, x_red_inc: usize, x_outer_inc: usize, y: *mut f32, y_inc: usize, y_outer_inc: usize) { densearray_reduce_axis_min_f32(inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc) }
+  unsafe fn reduce_axis_argmax(inner_dim: usize, red_dim: usize, outer_dim: usize, x: *const f32, x_inc: usize, x_red_inc: usize, x_outer_inc: usize, y: *mut u32, y_inc: usize, y_outer_inc: usize) { densearray_reduce_axis_argmax_f32(inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc) }
+  unsafe fn reduce_axis_argmin(inner_dim: usize, red_dim: usize, outer_dim: usize, x: *const f32, x_inc: usize, x_red_inc: usize, x_outer_inc: usize, y: *mut u32, y_inc: usize, y_outer_inc: usize) { densearray_reduce_axis_argmin_f32(inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc) }
+
+  unsafe fn omp_copy(dst: *mut f32, dim: usize, src: *const f32) { densearray_omp_copy_f32(dst, dim, src) }
+  unsafe fn omp_cast_u8(dst: *mut f32, dim: usize, src: *const u8) { densearray_omp_cast_u8_to_f32(dst, dim, src) }
+  unsafe fn omp_square(dst: *mut f32, dim: usize) { densearray_omp_square_f32(dst, dim) }
+  unsafe fn omp_cube(dst: *mut f32, dim: usize) { densearray_omp_cube_f32(dst, dim) }
+  unsafe fn omp_sqrt(dst: *mut f32, dim: usize) { densearray_omp_sqrt_f32(dst, dim) }
+  unsafe fn omp_reciprocal(dst: *mut f32, dim: usize) { densearray_omp_reciprocal_f32(dst, dim) }
+  unsafe fn omp_add_scalar(dst: *mut f32, dim: usize, c: f32) { densearray_omp_add_scalar_f32(dst, dim, c) }
+  unsafe fn omp_scale(dst: *mut f32, dim: usize, c: f32) { densearray_omp_scale_f32(dst, dim, c) }
+  unsafe fn omp_div_scalar(dst: *mut f32, dim: usize, c: f32) { densearray_omp_div_scalar_f32(dst, dim, c) }
+  unsafe fn omp_elem_mult(dst: *mut f32, dim: usize, xs: *const f32) { densearray_omp_elem_mult_f32(dst, dim, xs) }
+  unsafe fn omp_elem_div(dst: *mut f32, dim: usize, xs: *const f32) { densearray_omp_elem_div_f32(dst, dim, xs) }
+  unsafe fn omp_elem_ldiv(dst: *mut f32, dim: usize, xs: *const f32) { densearray_omp_elem_ldiv_f32(dst, dim, xs) }
+  unsafe fn omp_vector_add(dst: *mut f32, dim: usize, xs: *const f32, alpha: f32) { densearray_omp_vector_add_f32(dst, dim, xs, alpha) }
+  unsafe fn omp_vector_average(dst: *mut f32, dim: usize, xs: *const f32, alpha: f32) { densearray_omp_vector_average_f32(dst, dim, xs, alpha) }
+  unsafe fn omp_chunk_sum(x: *const f32, dim: usize, incx: usize, chunk_sz: usize, partials: *mut f32) { densearray_omp_chunk_sum_f32(x, dim, incx, chunk_sz, partials) }
+  unsafe fn omp_chunk_abs_sum(x: *const f32, dim: usize, incx: usize, chunk_sz: usize, partials: *mut f32) { densearray_omp_chunk_abs_sum_f32(x, dim, incx, chunk_sz, partials) }
+  unsafe fn omp_chunk_square_sum(x: *const f32, dim: usize, incx: usize, chunk_sz: usize, partials: *mut f32) { densearray_omp_chunk_square_sum_f32(x, dim, incx, chunk_sz, partials) }
+  unsafe fn omp_chunk_inner_prod(x: *const f32, dim: usize, incx: usize, y: *const f32, incy: usize, chunk_sz: usize, partials: *mut f32) { densearray_omp_chunk_inner_prod_f32(x, dim, incx, y, incy, chunk_sz, partials) }
+  unsafe fn omp_reduce_axis_sum(inner_dim: usize, red_dim: usize, outer_dim: usize, x: *const f32, x_inc: usize, x_red_inc: usize, x_outer_inc: usize, y: *mut f32, y_inc: usize, y_outer_inc: usize) { densearray_omp_reduce_axis_sum_f32(inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc) }
+  unsafe fn omp_reduce_axis_mean(inner_dim: usize, red_dim: usize, outer_dim: usize, x: *const f32, x_inc: usize, x_red_inc: usize, x_outer_inc: usize, y: *mut f32, y_inc: usize, y_outer_inc: usize) { densearray_omp_reduce_axis_mean_f32(inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc) }
+  unsafe fn omp_reduce_axis_max(inner_dim: usize, red_dim: usize, outer_dim: usize, x: *const f32, x_inc: usize, x_red_inc: usize, x_outer_inc: usize, y: *mut f32, y_inc: usize, y_outer_inc: usize) { densearray_omp_reduce_axis_max_f32(inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc) }
+  unsafe fn omp_reduce_axis_min(inner_dim: usize, red_dim: usize, outer_dim: usize, x: *const f32, x_inc: usize, x_red_inc: usize, x_outer_inc: usize, y: *mut f32, y_inc: usize, y_outer_inc: usize) { densearray_omp_reduce_axis_min_f32(inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc) }
+  unsafe fn omp_reduce_axis_argmax(inner_dim: usize, red_dim: usize, outer_dim: usize, x: *const f32, x_inc: usize, x_red_inc: usize, x_outer_inc: usize, y: *mut u32, y_inc: usize, y_outer_inc: usize) { densearray_omp_reduce_axis_argmax_f32(inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc) }
+  unsafe fn omp_reduce_axis_argmin(inner_dim: usize, red_dim: usize, outer_dim: usize, x: *const f32, x_inc: usize, x_red_inc: usize, x_outer_inc: usize, y: *mut u32, y_inc: usize, y_outer_inc: usize) { densearray_omp_reduce_axis_argmin_f32(inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc) }
+
+  fn exp(self) -> f32 { self.exp() }
+}
+
+impl KernelScalar for f64 {
+  unsafe fn copy(dst: *mut f64, dim: usize, src: *const f64) { densearray_copy_f64(dst, dim, src) }
+  unsafe fn cast_u8(dst: *mut f64, dim: usize, src: *const u8) { densearray_cast_u8_to_f64(dst, dim, src) }
+  unsafe fn square(dst: *mut f64, dim: usize) { densearray_square_f64(dst, dim) }
+  unsafe fn cube(dst: *mut f64, dim: usize) { densearray_cube_f64(dst, dim) }
+  unsafe fn sqrt(dst: *mut f64, dim: usize) { densearray_sqrt_f64(dst, dim) }
+  unsafe fn reciprocal(dst: *mut f64, dim: usize) { densearray_reciprocal_f64(dst, dim) }
+  unsafe fn add_scalar(dst: *mut f64, dim: usize, c: f64) { densearray_add_scalar_f64(dst, dim, c) }
+  unsafe fn scale(dst: *mut f64, dim: usize, c: f64) { densearray_scale_f64(dst, dim, c) }
+  unsafe fn div_scalar(dst: *mut f64, dim: usize, c: f64) { densearray_div_scalar_f64(dst, dim, c) }
+  unsafe fn elem_mult(dst: *mut f64, dim: usize, xs: *const f64) { densearray_elem_mult_f64(dst, dim, xs) }
+  unsafe fn elem_div(dst: *mut f64, dim: usize, xs: *const f64) { densearray_elem_div_f64(dst, dim, xs) }
+  unsafe fn elem_ldiv(dst: *mut f64, dim: usize, xs: *const f64) { densearray_elem_ldiv_f64(dst, dim, xs) }
+  unsafe fn vector_add(dst: *mut f64, dim: usize, xs: *const f64, alpha: f64) { densearray_vector_add_f64(dst, dim, xs, alpha) }
+  unsafe fn vector_average(dst: *mut f64, dim: usize, xs: *const f64, alpha: f64) { densearray_vector_average_f64(dst, dim, xs, alpha) }
+  unsafe fn chunk_sum(x: *const f64, dim: usize, incx: usize, chunk_sz: usize, partials: *mut f64) { densearray_chunk_sum_f64(x, dim, incx, chunk_sz, partials) }
+  unsafe fn chunk_abs_sum(x: *const f64, dim: usize, incx: usize, chunk_sz: usize, partials: *mut f64) { densearray_chunk_abs_sum_f64(x, dim, incx, chunk_sz, partials) }
+  unsafe fn chunk_square_sum(x: *const f64, dim: usize, incx: usize, chunk_sz: usize, partials: *mut f64) { densearray_chunk_square_sum_f64(x, dim, incx, chunk_sz, partials) }
+  unsafe fn chunk_inner_prod(x: *const f64, dim: usize, incx: usize, y: *const f64, incy: usize, chunk_sz: usize, partials: *mut f64) { densearray_chunk_inner_prod_f64(x, dim, incx, y, incy, chunk_sz, partials) }
+  unsafe fn reduce_axis_sum(inner_dim: usize, red_dim: usize, outer_dim: usize, x: *const f64, x_inc: usize, x_red_inc: usize, x_outer_inc: usize, y: *mut f64, y_inc: usize, y_outer_inc: usize) { densearray_reduce_axis_sum_f64(inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc) }
+  unsafe fn reduce_axis_mean(inner_dim: usize, red_dim: usize, outer_dim: usize, x: *const f64, x_inc: usize, x_red_inc: usize, x_outer_inc: usize, y: *mut f64, y_inc: usize, y_outer_inc: usize) { densearray_reduce_axis_mean_f64(inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc) }
+  unsafe fn reduce_axis_max(inner_dim: usize, red_dim: usize, outer_dim: usize, x: *const f64, x_inc: usize, x_red_inc: usize, x_outer_inc: usize, y: *mut f64, y_inc: usize, y_outer_inc: usize) { densearray_reduce_axis_max_f64(inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc) }
+  unsafe fn reduce_axis_min(inner_dim: usize, red_dim: usize, outer_dim: usize, x: *const f64, x_inc: usize, x_red_inc: usize, x_outer_inc: usize, y: *mut f64, y_inc: usize, y_outer_inc: usize) { densearray_reduce_axis_min_f64(inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc) }
+  unsafe fn reduce_axis_argmax(inner_dim: usize, red_dim: usize, outer_dim: usize, x: *const f64, x_inc: usize, x_red_inc: usize, x_outer_inc: usize, y: *mut u32, y_inc: usize, y_outer_inc: usize) { densearray_reduce_axis_argmax_f64(inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc) }
+  unsafe fn reduce_axis_argmin(inner_dim: usize, red_dim: usize, outer_dim: usize, x: *const f64, x_inc: usize, x_red_inc: usize, x_outer_inc: usize, y: *mut u32, y_inc: usize, y_outer_inc: usize) { densearray_reduce_axis_argmin_f64(inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc) }
+
+  unsafe fn omp_copy(dst: *mut f64, dim: usize, src: *const f64) { densearray_omp_copy_f64(dst, dim, src) }
+  unsafe fn omp_cast_u8(dst: *mut f64, dim: usize, src: *const u8) { densearray_omp_cast_u8_to_f64(dst, dim, src) }
+  unsafe fn omp_square(dst: *mut f64, dim: usize) { densearray_omp_square_f64(dst, dim) }
+  unsafe fn omp_cube(dst: *mut f64, dim: usize) { densearray_omp_cube_f64(dst, dim) }
+  unsafe fn omp_sqrt(dst: *mut f64, dim: usize) { densearray_omp_sqrt_f64(dst, dim) }
+  unsafe fn omp_reciprocal(dst: *mut f64, dim: usize) { densearray_omp_reciprocal_f64(dst, dim) }
+  unsafe fn omp_add_scalar(dst: *mut f64, dim: usize, c: f64) { densearray_omp_add_scalar_f64(dst, dim, c) }
+  unsafe fn omp_scale(dst: *mut f64, dim: usize, c: f64) { densearray_omp_scale_f64(dst, dim, c) }
+  unsafe fn omp_div_scalar(dst: *mut f64, dim: usize, c: f64) { densearray_omp_div_scalar_f64(dst, dim, c) }
+  unsafe fn omp_elem_mult(dst: *mut f64, dim: usize, xs: *const f64) { densearray_omp_elem_mult_f64(dst, dim, xs) }
+  unsafe fn omp_elem_div(dst: *mut f64, dim: usize, xs: *const f64) { densearray_omp_elem_div_f64(dst, dim, xs) }
+  unsafe fn omp_elem_ldiv(dst: *mut f64, dim: usize, xs: *const f64) { densearray_omp_elem_ldiv_f64(dst, dim, xs) }
+  unsafe fn omp_vector_add(dst: *mut f64, dim: usize, xs: *const f64, alpha: f64) { densearray_omp_vector_add_f64(dst, dim, xs, alpha) }
+  unsafe fn omp_vector_average(dst: *mut f64, dim: usize, xs: *const f64, alpha: f64) { densearray_omp_vector_average_f64(dst, dim, xs, alpha) }
+  unsafe fn omp_chunk_sum(x: *const f64, dim: usize, incx: usize, chunk_sz: usize, partials: *mut f64) { densearray_omp_chunk_sum_f64(x, dim, incx, chunk_sz, partials) }
+  unsafe fn omp_chunk_abs_sum(x: *const f64, dim: usize, incx: usize, chunk_sz: usize, partials: *mut f64) { densearray_omp_chunk_abs_sum_f64(x, dim, incx, chunk_sz, partials) }
+  unsafe fn omp_chunk_square_sum(x: *const f64, dim: usize, incx: usize, chunk_sz: usize, partials: *mut f64) { densearray_omp_chunk_square_sum_f64(x, dim, incx, chunk_sz, partials) }
+  unsafe fn omp_chunk_inner_prod(x: *const f64, dim: usize, incx: usize, y: *const f64, incy: usize, chunk_sz: usize, partials: *mut f64) { densearray_omp_chunk_inner_prod_f64(x, dim, incx, y, incy, chunk_sz, partials) }
+  unsafe fn omp_reduce_axis_sum(inner_dim: usize, red_dim: usize, outer_dim: usize, x: *const f64, x_inc: usize, x_red_inc: usize, x_outer_inc: usize, y: *mut f64, y_inc: usize, y_outer_inc: usize) { densearray_omp_reduce_axis_sum_f64(inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc) }
+  unsafe fn omp_reduce_axis_mean(inner_dim: usize, red_dim: usize, outer_dim: usize, x: *const f64, x_inc: usize, x_red_inc: usize, x_outer_inc: usize, y: *mut f64, y_inc: usize, y_outer_inc: usize) { densearray_omp_reduce_axis_mean_f64(inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc) }
+  unsafe fn omp_reduce_axis_max(inner_dim: usize, red_dim: usize, outer_dim: usize, x: *const f64, x_inc: usize, x_red_inc: usize, x_outer_inc: usize, y: *mut f64, y_inc: usize, y_outer_inc: usize) { densearray_omp_reduce_axis_max_f64(inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc) }
+  unsafe fn omp_reduce_axis_min(inner_dim: usize, red_dim: usize, outer_dim: usize, x: *const f64, x_inc: usize, x_red_inc: usize, x_outer_inc: usize, y: *mut f64, y_inc: usize, y_outer_inc: usize) { densearray_omp_reduce_axis_min_f64(inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc) }
+  unsafe fn omp_reduce_axis_argmax(inner_dim: usize, red_dim: usize, outer_dim: usize, x: *const f64, x_inc: usize, x_red_inc: usize, x_outer_inc: usize, y: *mut u32, y_inc: usize, y_outer_inc: usize) { densearray_omp_reduce_axis_argmax_f64(inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc) }
+  unsafe fn omp_reduce_axis_argmin(inner_dim: usize, red_dim: usize, outer_dim: usize, x: *const f64, x_inc: usize, x_red_inc: usize, x_outer_inc: usize, y: *mut u32, y_inc: usize, y_outer_inc: usize) { densearray_omp_reduce_axis_argmin_f64(inner_dim, red_dim, outer_dim, x, x_inc, x_red_inc, x_outer_inc, y, y_inc, y_outer_inc) }
+
+  fn exp(self) -> f64 { self.exp() }
+}
@@ -4,14 +4,19 @@
 //extern crate densearray_kernels;
 
 extern crate byteorder;
+#[cfg(any(feature = "openblas", feature = "mkl"))]
 extern crate cblas_ffi;
-#[cfg(feature = "mkl_parallel")]
+#[cfg(feature = "mkl")]
 extern crate mkl_link;
+#[cfg(feature = "openblas")]
 extern crate openblas_ffi;
+#[cfg(feature = "rayon")]
+extern crate rayon;
 extern crate sharedmem;
 
 extern crate libc;
 
+use backend::{ReferenceScalar};
 use kernels::*;
 
 use std::marker::{PhantomData};
@@ -22,10 +27,17 @@ use std::rc::{Rc};
 use std::slice::{from_raw_parts, from_raw_parts_mut};
 use std::sync::{Arc};
 
+pub mod backend;
+pub mod blas;
 pub mod io;
+pub mod isa;
+pub mod iterative;
 pub mod kernels;
 pub mod linalg;
+pub mod parallel;
 pub mod parallel_linalg;
+#[cfg(feature = "rayon")]
+pub mod par_iter;
 pub mod prelude;
 pub mod serial;
 
@@ -74,6 +86,9 @@ pub trait ArrayIndex: Copy {
   fn flat_len(self) -> usize;
   fn offset(self, stride: Self) -> usize;
   fn diff(self, rhs: Self) -> Self;
+  /// The length of the buffer spanned by an array with this dimension and
+  /// the given stride.
+  fn extent(self, stride: Self) -> usize;
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -97,6 +112,10 @@ impl ArrayIndex for usize {
   fn diff(self, rhs: Self) -> Self {
     self - rhs
   }
+
+  fn extent(self, stride: Self) -> usize {
+    if self == 0 { 0 } else { 1 + (self - 1) * stride }
+  }
 }
 
 impl ArrayIndex for (usize, usize) {
@@ -117,6 +136,10 @@ impl ArrayIndex for (usize, usize) {
   fn diff(self, rhs: Self) -> Self {
     (self.0 - rhs.0, self.1 - rhs.1)
   }
+
+  fn extent(self, stride: Self) -> usize {
+    if self.flat_len() == 0 { 0 } else { 1 + (self.0 - 1) * stride.0 + (self.1 - 1) * stride.1 }
+  }
 }
 
 impl ArrayIndex for (usize, usize, usize) {
@@ -137,6 +160,10 @@ impl ArrayIndex for (usize, usize, usize) {
   fn diff(self, rhs: Self) -> Self {
     (self.0 - rhs.0, self.1 - rhs.1, self.2 - rhs.2)
   }
+
+  fn extent(self, stride: Self) -> usize {
+    if self.flat_len() == 0 { 0 } else { 1 + (self.0 - 1) * stride.0 + (self.1 - 1) * stride.1 + (self.2 - 1) * stride.2 }
+  }
 }
 
 impl ArrayIndex for (usize, usize, usize, usize) {
@@ -157,6 +184,251 @@ impl ArrayIndex for (usize, usize, usize, usize) {
   fn diff(self, rhs: Self) -> Self {
     (self.0 - rhs.0, self.1 - rhs.1, self.2 - rhs.2, self.3 - rhs.3)
   }
+
+  fn extent(self, stride: Self) -> usize {
+    if self.flat_len() == 0 { 0 } else { 1 + (self.0 - 1) * stride.0 + (self.1 - 1) * stride.1 + (self.2 - 1) * stride.2 + (self.3 - 1) * stride.3 }
+  }
+}
+
+type AxisReduceKernel<T, U> = unsafe fn (usize, usize, usize, *const T, usize, usize, usize, *mut U, usize, usize);
+
+fn collapse_axes(axes: &mut Vec<(usize, usize, usize)>) {
+  // Each entry is `(dim, x_stride, y_stride)`; merge adjacent entries which
+  // are contiguous w.r.t. each other in both `x` and `y`.
+  let mut d = 0;
+  while d + 1 < axes.len() {
+    let (lo_dim, lo_x_stride, lo_y_stride) = axes[d];
+    let (hi_dim, hi_x_stride, hi_y_stride) = axes[d + 1];
+    if hi_x_stride == lo_dim * lo_x_stride && hi_y_stride == lo_dim * lo_y_stride {
+      axes[d] = (lo_dim * hi_dim, lo_x_stride, lo_y_stride);
+      axes.remove(d + 1);
+    } else {
+      d += 1;
+    }
+  }
+}
+
+/// Reduces `x` along `axis` into `y`. An empty `axis` fills `y` with `empty`,
+/// or panics if there is none, as for the max and min reductions.
+fn axis_reduce<T, U>(x_buf: &[T], x_dim: &[usize], x_stride: &[usize], axis: usize, y_buf: &mut [U], y_dim: &[usize], y_stride: &[usize], empty: Option<U>, kernel: AxisReduceKernel<T, U>) where U: Copy {
+  let ndim = x_dim.len();
+  assert!(axis < ndim);
+  assert_eq!(ndim, x_stride.len());
+  assert_eq!(ndim, y_dim.len() + 1);
+  assert_eq!(ndim, y_stride.len() + 1);
+  let red_dim = x_dim[axis];
+  let red_stride = x_stride[axis];
+  let mut inner = Vec::with_capacity(ndim);
+  let mut outer = Vec::with_capacity(ndim);
+  for d in 0 .. ndim {
+    if d < axis {
+      assert_eq!(x_dim[d], y_dim[d]);
+      inner.push((x_dim[d], x_stride[d], y_stride[d]));
+    } else if d > axis {
+      assert_eq!(x_dim[d], y_dim[d - 1]);
+      outer.push((x_dim[d], x_stride[d], y_stride[d - 1]));
+    }
+  }
+  if red_dim == 0 {
+    let c = empty.expect("axis_reduce: the reduced axis is empty");
+    elem_map_inplace(y_buf, y_dim, y_stride, |n, _, y| {
+      for i in 0 .. n {
+        unsafe { *y.offset(i as isize) = c };
+      }
+    });
+    return;
+  }
+  if y_dim.iter().any(|&d| d == 0) {
+    return;
+  }
+  let x_extent = 1 + (0 .. ndim).map(|d| (x_dim[d] - 1) * x_stride[d]).sum::<usize>();
+  let y_extent = 1 + (0 .. ndim - 1).map(|d| (y_dim[d] - 1) * y_stride[d]).sum::<usize>();
+  assert!(x_extent <= x_buf.len());
+  assert!(y_extent <= y_buf.len());
+  collapse_axes(&mut inner);
+  collapse_axes(&mut outer);
+  // The kernel handles the least inner axis and the least outer axis; any
+  // remaining axes which could not be collapsed are iterated over here.
+  let (inner_dim, x_inc, y_inc) = if inner.is_empty() { (1, 1, 1) } else { inner.remove(0) };
+  let (outer_dim, x_outer_inc, y_outer_inc) = if outer.is_empty() { (1, 0, 0) } else { outer.remove(0) };
+  let mut rest = inner;
+  rest.extend_from_slice(&outer);
+  let mut idx = vec![0; rest.len()];
+  loop {
+    let mut x_offset = 0;
+    let mut y_offset = 0;
+    for (&(_, x_s, y_s), &i) in rest.iter().zip(idx.iter()) {
+      x_offset += i * x_s;
+      y_offset += i * y_s;
+    }
+    unsafe { (kernel)(
+        inner_dim, red_dim, outer_dim,
+        x_buf.as_ptr().offset(x_offset as isize),
+        x_inc, red_stride, x_outer_inc,
+        y_buf.as_mut_ptr().offset(y_offset as isize),
+        y_inc, y_outer_inc,
+    ) };
+    let mut d = 0;
+    loop {
+      if d == rest.len() {
+        return;
+      }
+      idx[d] += 1;
+      if idx[d] < rest[d].0 {
+        break;
+      }
+      idx[d] = 0;
+      d += 1;
+    }
+  }
+}
+
+fn check_extent(buf_len: usize, dim: &[usize], stride: &[usize]) {
+  assert_eq!(dim.len(), stride.len());
+  if dim.iter().any(|&d| d == 0) {
+    return;
+  }
+  let extent = 1 + (0 .. dim.len()).map(|d| (dim[d] - 1) * stride[d]).sum::<usize>();
+  assert!(extent <= buf_len);
+}
+
+/// The 1d lanes of a pair of equally shaped strided arrays `x` and `y`:
+/// lanes of `len` elements with strides `x_inc` and `y_inc`, one for each
+/// index into the remaining `outer` axes.
+struct Lanes {
+  len:    usize,
+  x_inc:  usize,
+  y_inc:  usize,
+  outer:  Vec<(usize, usize, usize)>,
+  count:  usize,
+}
+
+impl Lanes {
+  /// With `unit_inc`, the lanes are only taken along the least axis when it
+  /// is contiguous in both `x` and `y`; otherwise each lane is one element.
+  fn new(dim: &[usize], x_stride: &[usize], y_stride: &[usize], unit_inc: bool) -> Lanes {
+    if dim.iter().any(|&d| d == 0) {
+      return Lanes{len: 0, x_inc: 1, y_inc: 1, outer: vec![], count: 0};
+    }
+    // Unit axes are dropped since their strides are arbitrary and would
+    // otherwise prevent collapsing.
+    let mut axes: Vec<_> = (0 .. dim.len())
+      .filter(|&d| dim[d] != 1)
+      .map(|d| (dim[d], x_stride[d], y_stride[d]))
+      .collect();
+    collapse_axes(&mut axes);
+    let (len, x_inc, y_inc) = if !axes.is_empty() && (!unit_inc || (axes[0].1 == 1 && axes[0].2 == 1)) {
+      axes.remove(0)
+    } else {
+      (1, 1, 1)
+    };
+    let count = axes.iter().map(|&(d, _, _)| d).product();
+    Lanes{len: len, x_inc: x_inc, y_inc: y_inc, outer: axes, count: count}
+  }
+
+  /// The offsets into `x` and `y` of the lane with index `lane`.
+  fn offsets(&self, mut lane: usize) -> (usize, usize) {
+    let mut x_offset = 0;
+    let mut y_offset = 0;
+    for &(d, x_s, y_s) in self.outer.iter() {
+      let i = lane % d;
+      lane /= d;
+      x_offset += i * x_s;
+      y_offset += i * y_s;
+    }
+    (x_offset, y_offset)
+  }
+}
+
+/// Combines the l2 norms of disjoint parts into the l2 norm of the whole,
+/// scaling by the largest part to avoid overflow.
+fn combine_norms<T>(norms: &[T]) -> T where T: ReferenceScalar {
+  let scale = norms.iter().fold(0.0, |m, &x| f64::max(m, x.to_f64()));
+  if scale == 0.0 || scale.is_infinite() {
+    return T::from_f64(scale);
+  }
+  let sum_sq: f64 = norms.iter().map(|&x| {
+    let r = x.to_f64() / scale;
+    r * r
+  }).sum();
+  T::from_f64(scale * sum_sq.sqrt())
+}
+
+/// A raw pointer which may be shared with the `parallel` pool threads; each
+/// thread must only access disjoint elements through it.
+struct SyncMutPtr<T>(*mut T);
+
+unsafe impl<T> Send for SyncMutPtr<T> where T: Send {}
+unsafe impl<T> Sync for SyncMutPtr<T> where T: Send {}
+
+struct SyncConstPtr<T>(*const T);
+
+unsafe impl<T> Send for SyncConstPtr<T> where T: Sync {}
+unsafe impl<T> Sync for SyncConstPtr<T> where T: Sync {}
+
+unsafe fn elem_map_raw<T, U, F>(x_ptr: *const T, x_stride: &[usize], y_ptr: *mut U, y_dim: &[usize], y_stride: &[usize], mut kernel: F) where F: FnMut(usize, *const T, *mut U) {
+  let lanes = Lanes::new(y_dim, x_stride, y_stride, true);
+  for lane in 0 .. lanes.count {
+    let (x_offset, y_offset) = lanes.offsets(lane);
+    kernel(
+        lanes.len,
+        x_ptr.offset(x_offset as isize),
+        y_ptr.offset(y_offset as isize),
+    );
+  }
+}
+
+/// Applies a 1d elementwise kernel `kernel(n, x, y)` over every contiguous
+/// run of `x` and `y`, which may have different strides.
+fn elem_map<T, U, F>(x_buf: &[T], x_dim: &[usize], x_stride: &[usize], y_buf: &mut [U], y_dim: &[usize], y_stride: &[usize], kernel: F) where F: FnMut(usize, *const T, *mut U) {
+  assert_eq!(x_dim, y_dim);
+  check_extent(x_buf.len(), x_dim, x_stride);
+  check_extent(y_buf.len(), y_dim, y_stride);
+  unsafe { elem_map_raw(x_buf.as_ptr(), x_stride, y_buf.as_mut_ptr(), y_dim, y_stride, kernel) };
+}
+
+/// Like `elem_map`, but with the kernel reading from and writing to `buf`.
+fn elem_map_inplace<T, F>(buf: &mut [T], dim: &[usize], stride: &[usize], kernel: F) where F: FnMut(usize, *const T, *mut T) {
+  check_extent(buf.len(), dim, stride);
+  let ptr = buf.as_mut_ptr();
+  unsafe { elem_map_raw(ptr as *const T, stride, ptr, dim, stride, kernel) };
+}
+
+unsafe fn parallel_elem_map_raw<T, U, F, G>(x_ptr: *const T, x_stride: &[usize], y_ptr: *mut U, y_dim: &[usize], y_stride: &[usize], kernel: F, packed_kernel: G) where T: Sync, U: Send, F: Fn(usize, *const T, *mut U) + Sync, G: FnOnce(usize, *const T, *mut U) {
+  let lanes = Lanes::new(y_dim, x_stride, y_stride, true);
+  if lanes.count == 1 {
+    packed_kernel(lanes.len, x_ptr, y_ptr);
+    return;
+  }
+  let x_ptr = SyncConstPtr(x_ptr);
+  let y_ptr = SyncMutPtr(y_ptr);
+  parallel::parallel_for_items(lanes.count, lanes.len, |range| {
+    for lane in range {
+      let (x_offset, y_offset) = lanes.offsets(lane);
+      kernel(
+          lanes.len,
+          x_ptr.0.offset(x_offset as isize),
+          y_ptr.0.offset(y_offset as isize),
+      );
+    }
+  });
+}
+
+/// Like `elem_map`, but in parallel. When `x` and `y` are both contiguous
+/// the single run goes to `packed_kernel`, usually an OpenMP kernel;
+/// otherwise the runs along the outer axes are split across the `parallel`
+/// pool and each is passed to `kernel`.
+fn parallel_elem_map<T, U, F, G>(x_buf: &[T], x_dim: &[usize], x_stride: &[usize], y_buf: &mut [U], y_dim: &[usize], y_stride: &[usize], kernel: F, packed_kernel: G) where T: Sync, U: Send, F: Fn(usize, *const T, *mut U) + Sync, G: FnOnce(usize, *const T, *mut U) {
+  assert_eq!(x_dim, y_dim);
+  check_extent(x_buf.len(), x_dim, x_stride);
+  check_extent(y_buf.len(), y_dim, y_stride);
+  unsafe { parallel_elem_map_raw(x_buf.as_ptr(), x_stride, y_buf.as_mut_ptr(), y_dim, y_stride, kernel, packed_kernel) };
+}
+
+fn parallel_elem_map_inplace<T, F, G>(buf: &mut [T], dim: &[usize], stride: &[usize], kernel: F, packed_kernel: G) where T: Send + Sync, F: Fn(usize, *const T, *mut T) + Sync, G: FnOnce(usize, *const T, *mut T) {
+  check_extent(buf.len(), dim, stride);
+  let ptr = buf.as_mut_ptr();
+  unsafe { parallel_elem_map_raw(ptr as *const T, stride, ptr, dim, stride, kernel, packed_kernel) };
 }
 
 pub trait FlatView<'a, Target> {
@@ -627,7 +899,7 @@ impl<'a, T> View<'a, usize, Array1dView<'a, T>> for Array1dView<'a, T> where T:
   fn view(self, lo: usize, hi: usize) -> Array1dView<'a, T> {
     let new_dim = hi.diff(lo);
     let new_offset = lo.offset(self.stride);
-    let new_offset_end = new_offset + new_dim.flat_len();
+    let new_offset_end = new_offset + new_dim.extent(self.stride);
     Array1dView{
       buf:      &self.buf[new_offset .. new_offset_end],
       dim:      new_dim,
@@ -646,7 +918,7 @@ impl<'a, T> ViewMut<'a, usize, Array1dViewMut<'a, T>> for Array1dViewMut<'a, T>
   fn view_mut(self, lo: usize, hi: usize) -> Array1dViewMut<'a, T> {
     let new_dim = hi.diff(lo);
     let new_offset = lo.offset(self.stride);
-    let new_offset_end = new_offset + new_dim.flat_len();
+    let new_offset_end = new_offset + new_dim.extent(self.stride);
     Array1dViewMut{
       buf:      &mut self.buf[new_offset .. new_offset_end],
       dim:      new_dim,
@@ -676,7 +948,11 @@ impl<'a, T> SetConstant<'a, T> for Array1dViewMut<'a, T> where T: 'a + Copy {
         self.buf[i] = c;
       }
     } else {
-      unimplemented!();
+      elem_map_inplace(self.buf, &[self.dim], &[self.stride], |n, _, y| {
+        for i in 0 .. n {
+          unsafe { *y.offset(i as isize) = c };
+        }
+      });
     }
   }
 }
@@ -690,56 +966,158 @@ impl<'a> SetConstant<'a, f32> for Array1dViewMut<'a, f32> {
           c,
       ) };
     } else {
-      unimplemented!();
+      elem_map_inplace(
+          self.buf, &[self.dim], &[self.stride],
+          |n, _, y| unsafe { densearray_set_scalar_f32(y, n, c) },
+      );
     }
   }
 }
 
-impl<'a> SetConstant<'a, i32> for Array1dViewMut<'a, i32> {
-  fn set_constant(&'a mut self, c: i32) {
+impl<'a> SetConstant<'a, f64> for Array1dViewMut<'a, f64> {
+  fn set_constant(&'a mut self, c: f64) {
     if self.stride == 1 {
-      unsafe { densearray_set_scalar_i32(
+      unsafe { densearray_set_scalar_f64(
           self.buf.as_mut_ptr(),
           self.dim,
           c,
       ) };
     } else {
-      unimplemented!();
+      elem_map_inplace(
+          self.buf, &[self.dim], &[self.stride],
+          |n, _, y| unsafe { densearray_set_scalar_f64(y, n, c) },
+      );
     }
   }
 }
 
-impl<'a> Array1dViewMut<'a, f32> {
-  pub fn parallel_set_constant(&'a mut self, c: f32) {
+impl<'a> SetConstant<'a, i32> for Array1dViewMut<'a, i32> {
+  fn set_constant(&'a mut self, c: i32) {
     if self.stride == 1 {
-      unsafe { densearray_omp_set_scalar_f32(
+      unsafe { densearray_set_scalar_i32(
           self.buf.as_mut_ptr(),
           self.dim,
           c,
       ) };
     } else {
-      unimplemented!();
+      elem_map_inplace(
+          self.buf, &[self.dim], &[self.stride],
+          |n, _, y| unsafe { densearray_set_scalar_i32(y, n, c) },
+      );
     }
   }
 }
 
+impl<'a> Array1dViewMut<'a, f32> {
+  pub fn parallel_set_constant(&'a mut self, c: f32) {
+    parallel_elem_map_inplace(
+        self.buf, &[self.dim], &[self.stride],
+        |n, _, y| unsafe { densearray_set_scalar_f32(y, n, c) },
+        |n, _, y| unsafe { densearray_omp_set_scalar_f32(y, n, c) },
+    );
+  }
+}
+
+impl<'a> Array1dViewMut<'a, f64> {
+  pub fn parallel_set_constant(&'a mut self, c: f64) {
+    parallel_elem_map_inplace(
+        self.buf, &[self.dim], &[self.stride],
+        |n, _, y| unsafe { densearray_set_scalar_f64(y, n, c) },
+        |n, _, y| unsafe { densearray_omp_set_scalar_f64(y, n, c) },
+    );
+  }
+}
+
 impl<'a> Array1dViewMut<'a, f32> {
   pub fn cast_from_u8(&'a mut self, src: Array1dView<'a, u8>) {
-    unsafe { densearray_kernel_cast_1d_u8_to_f32(
-        self.dim,
-        src.as_ptr(),
-        self.as_mut_ptr(),
-    ) };
+    elem_map(
+        src.buf, &[src.dim], &[src.stride],
+        self.buf, &[self.dim], &[self.stride],
+        |n, x, y| unsafe { densearray_kernel_cast_1d_u8_to_f32(n, x, y) },
+    );
+  }
+
+  pub fn cast_from_u16(&'a mut self, src: Array1dView<'a, u16>) {
+    elem_map(
+        src.buf, &[src.dim], &[src.stride],
+        self.buf, &[self.dim], &[self.stride],
+        |n, x, y| unsafe { densearray_kernel_cast_1d_u16_to_f32(n, x, y) },
+    );
+  }
+
+  pub fn cast_from_f64(&'a mut self, src: Array1dView<'a, f64>) {
+    elem_map(
+        src.buf, &[src.dim], &[src.stride],
+        self.buf, &[self.dim], &[self.stride],
+        |n, x, y| unsafe { densearray_kernel_cast_1d_f64_to_f32(n, x, y) },
+    );
+  }
+
+  pub fn clamp(&'a mut self, lo: f32, hi: f32) {
+    assert!(lo <= hi);
+    elem_map_inplace(
+        self.buf, &[self.dim], &[self.stride],
+        |n, x, y| unsafe { densearray_kernel_clamp_1d_f32(n, x, y, lo, hi) },
+    );
+  }
+
+  pub fn clamp_from(&'a mut self, src: Array1dView<'a, f32>, lo: f32, hi: f32) {
+    assert!(lo <= hi);
+    elem_map(
+        src.buf, &[src.dim], &[src.stride],
+        self.buf, &[self.dim], &[self.stride],
+        |n, x, y| unsafe { densearray_kernel_clamp_1d_f32(n, x, y, lo, hi) },
+    );
+  }
+}
+
+impl<'a> Array1dViewMut<'a, f64> {
+  pub fn cast_from_f32(&'a mut self, src: Array1dView<'a, f32>) {
+    elem_map(
+        src.buf, &[src.dim], &[src.stride],
+        self.buf, &[self.dim], &[self.stride],
+        |n, x, y| unsafe { densearray_kernel_cast_1d_f32_to_f64(n, x, y) },
+    );
   }
 }
 
 impl<'a> Array1dViewMut<'a, u8> {
   pub fn round_clamp_from_f32(&'a mut self, src: Array1dView<'a, f32>) {
-    unsafe { densearray_kernel_round_clamp_1d_f32_to_u8_sse4(
-        self.dim,
-        src.as_ptr(),
-        self.as_mut_ptr(),
-    ) };
+    elem_map(
+        src.buf, &[src.dim], &[src.stride],
+        self.buf, &[self.dim], &[self.stride],
+        |n, x, y| unsafe { densearray_kernel_round_clamp_1d_f32_to_u8(n, x, y) },
+    );
+  }
+}
+
+impl<'a> Array1dViewMut<'a, i8> {
+  pub fn round_clamp_from_f32(&'a mut self, src: Array1dView<'a, f32>) {
+    elem_map(
+        src.buf, &[src.dim], &[src.stride],
+        self.buf, &[self.dim], &[self.stride],
+        |n, x, y| unsafe { densearray_kernel_round_clamp_1d_f32_to_i8(n, x, y) },
+    );
+  }
+}
+
+impl<'a> Array1dViewMut<'a, u16> {
+  pub fn round_clamp_from_f32(&'a mut self, src: Array1dView<'a, f32>) {
+    elem_map(
+        src.buf, &[src.dim], &[src.stride],
+        self.buf, &[self.dim], &[self.stride],
+        |n, x, y| unsafe { densearray_kernel_round_clamp_1d_f32_to_u16(n, x, y) },
+    );
+  }
+}
+
+impl<'a> Array1dViewMut<'a, i32> {
+  pub fn round_clamp_from_f32(&'a mut self, src: Array1dView<'a, f32>) {
+    elem_map(
+        src.buf, &[src.dim], &[src.stride],
+        self.buf, &[self.dim], &[self.stride],
+        |n, x, y| unsafe { densearray_kernel_round_clamp_1d_f32_to_i32(n, x, y) },
+    );
   }
 }
 
@@ -859,7 +1237,7 @@ impl<'a, T> View<'a, (usize, usize), Array2dView<'a, T>> for Array2dView<'a, T>
   fn view(self, lo: (usize, usize), hi: (usize, usize)) -> Array2dView<'a, T> {
     let new_dim = hi.diff(lo);
     let new_offset = lo.offset(self.stride);
-    let new_offset_end = new_offset + new_dim.flat_len();
+    let new_offset_end = new_offset + new_dim.extent(self.stride);
     Array2dView{
       buf:      &self.buf[new_offset .. new_offset_end],
       dim:      new_dim,
@@ -878,7 +1256,7 @@ impl<'a, T> ViewMut<'a, (usize, usize), Array2dViewMut<'a, T>> for Array2dViewMu
   fn view_mut(self, lo: (usize, usize), hi: (usize, usize)) -> Array2dViewMut<'a, T> {
     let new_dim = hi.diff(lo);
     let new_offset = lo.offset(self.stride);
-    let new_offset_end = new_offset + new_dim.flat_len();
+    let new_offset_end = new_offset + new_dim.extent(self.stride);
     Array2dViewMut{
       buf:      &mut self.buf[new_offset .. new_offset_end],
       dim:      new_dim,
@@ -906,22 +1284,125 @@ impl<'a, T> Array2dViewMut<'a, T> where T: 'a + Copy {
         self.buf[i] = c;
       }
     } else {
-      unimplemented!();
+      elem_map_inplace(self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1], |n, _, y| {
+        for i in 0 .. n {
+          unsafe { *y.offset(i as isize) = c };
+        }
+      });
     }
   }
 }
 
 impl<'a> Array2dViewMut<'a, f32> {
   pub fn parallel_set_constant(&'a mut self, c: f32) {
-    if self.stride == self.dim.least_stride() {
-      unsafe { densearray_omp_set_scalar_f32(
-          self.buf.as_mut_ptr(),
-          self.dim.flat_len(),
-          c,
-      ) };
-    } else {
-      unimplemented!();
-    }
+    parallel_elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        |n, _, y| unsafe { densearray_set_scalar_f32(y, n, c) },
+        |n, _, y| unsafe { densearray_omp_set_scalar_f32(y, n, c) },
+    );
+  }
+}
+
+impl<'a> Array2dViewMut<'a, f64> {
+  pub fn parallel_set_constant(&'a mut self, c: f64) {
+    parallel_elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        |n, _, y| unsafe { densearray_set_scalar_f64(y, n, c) },
+        |n, _, y| unsafe { densearray_omp_set_scalar_f64(y, n, c) },
+    );
+  }
+}
+
+impl<'a> Array2dViewMut<'a, f32> {
+  pub fn cast_from_u8(&'a mut self, src: Array2dView<'a, u8>) {
+    elem_map(
+        src.buf, &[src.dim.0, src.dim.1], &[src.stride.0, src.stride.1],
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        |n, x, y| unsafe { densearray_kernel_cast_1d_u8_to_f32(n, x, y) },
+    );
+  }
+
+  pub fn cast_from_u16(&'a mut self, src: Array2dView<'a, u16>) {
+    elem_map(
+        src.buf, &[src.dim.0, src.dim.1], &[src.stride.0, src.stride.1],
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        |n, x, y| unsafe { densearray_kernel_cast_1d_u16_to_f32(n, x, y) },
+    );
+  }
+
+  pub fn cast_from_f64(&'a mut self, src: Array2dView<'a, f64>) {
+    elem_map(
+        src.buf, &[src.dim.0, src.dim.1], &[src.stride.0, src.stride.1],
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        |n, x, y| unsafe { densearray_kernel_cast_1d_f64_to_f32(n, x, y) },
+    );
+  }
+
+  pub fn clamp(&'a mut self, lo: f32, hi: f32) {
+    assert!(lo <= hi);
+    elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        |n, x, y| unsafe { densearray_kernel_clamp_1d_f32(n, x, y, lo, hi) },
+    );
+  }
+
+  pub fn clamp_from(&'a mut self, src: Array2dView<'a, f32>, lo: f32, hi: f32) {
+    assert!(lo <= hi);
+    elem_map(
+        src.buf, &[src.dim.0, src.dim.1], &[src.stride.0, src.stride.1],
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        |n, x, y| unsafe { densearray_kernel_clamp_1d_f32(n, x, y, lo, hi) },
+    );
+  }
+}
+
+impl<'a> Array2dViewMut<'a, f64> {
+  pub fn cast_from_f32(&'a mut self, src: Array2dView<'a, f32>) {
+    elem_map(
+        src.buf, &[src.dim.0, src.dim.1], &[src.stride.0, src.stride.1],
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        |n, x, y| unsafe { densearray_kernel_cast_1d_f32_to_f64(n, x, y) },
+    );
+  }
+}
+
+impl<'a> Array2dViewMut<'a, u8> {
+  pub fn round_clamp_from_f32(&'a mut self, src: Array2dView<'a, f32>) {
+    elem_map(
+        src.buf, &[src.dim.0, src.dim.1], &[src.stride.0, src.stride.1],
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        |n, x, y| unsafe { densearray_kernel_round_clamp_1d_f32_to_u8(n, x, y) },
+    );
+  }
+}
+
+impl<'a> Array2dViewMut<'a, i8> {
+  pub fn round_clamp_from_f32(&'a mut self, src: Array2dView<'a, f32>) {
+    elem_map(
+        src.buf, &[src.dim.0, src.dim.1], &[src.stride.0, src.stride.1],
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        |n, x, y| unsafe { densearray_kernel_round_clamp_1d_f32_to_i8(n, x, y) },
+    );
+  }
+}
+
+impl<'a> Array2dViewMut<'a, u16> {
+  pub fn round_clamp_from_f32(&'a mut self, src: Array2dView<'a, f32>) {
+    elem_map(
+        src.buf, &[src.dim.0, src.dim.1], &[src.stride.0, src.stride.1],
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        |n, x, y| unsafe { densearray_kernel_round_clamp_1d_f32_to_u16(n, x, y) },
+    );
+  }
+}
+
+impl<'a> Array2dViewMut<'a, i32> {
+  pub fn round_clamp_from_f32(&'a mut self, src: Array2dView<'a, f32>) {
+    elem_map(
+        src.buf, &[src.dim.0, src.dim.1], &[src.stride.0, src.stride.1],
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        |n, x, y| unsafe { densearray_kernel_round_clamp_1d_f32_to_i32(n, x, y) },
+    );
   }
 }
 
@@ -1010,12 +1491,38 @@ impl<'a, T> Array3dView<'a, T> where T: 'a + Copy {
   }
 }
 
+impl<'a, T> View<'a, (usize, usize, usize), Array3dView<'a, T>> for Array3dView<'a, T> where T: 'a + Copy {
+  fn view(self, lo: (usize, usize, usize), hi: (usize, usize, usize)) -> Array3dView<'a, T> {
+    let new_dim = hi.diff(lo);
+    let new_offset = lo.offset(self.stride);
+    let new_offset_end = new_offset + new_dim.extent(self.stride);
+    Array3dView{
+      buf:      &self.buf[new_offset .. new_offset_end],
+      dim:      new_dim,
+      stride:   self.stride,
+    }
+  }
+}
+
 pub struct Array3dViewMut<'a, T> where T: 'a + Copy {
   buf:      &'a mut [T],
   dim:      (usize, usize, usize),
   stride:   (usize, usize, usize),
 }
 
+impl<'a, T> ViewMut<'a, (usize, usize, usize), Array3dViewMut<'a, T>> for Array3dViewMut<'a, T> where T: 'a + Copy {
+  fn view_mut(self, lo: (usize, usize, usize), hi: (usize, usize, usize)) -> Array3dViewMut<'a, T> {
+    let new_dim = hi.diff(lo);
+    let new_offset = lo.offset(self.stride);
+    let new_offset_end = new_offset + new_dim.extent(self.stride);
+    Array3dViewMut{
+      buf:      &mut self.buf[new_offset .. new_offset_end],
+      dim:      new_dim,
+      stride:   self.stride,
+    }
+  }
+}
+
 impl<'a, T> Array3dViewMut<'a, T> where T: 'a + Copy {
   pub fn dim(&self) -> (usize, usize, usize) {
     self.dim
@@ -1035,7 +1542,11 @@ impl<'a, T> Array3dViewMut<'a, T> where T: 'a + Copy {
         self.buf[i] = c;
       }
     } else {
-      unimplemented!();
+      elem_map_inplace(self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2], |n, _, y| {
+        for i in 0 .. n {
+          unsafe { *y.offset(i as isize) = c };
+        }
+      });
     }
   }
 }
@@ -1060,6 +1571,119 @@ impl<'a, T, S> AsViewMut<'a, Array3dViewMut<'a, T>> for Array3d<T, S> where T: C
   }
 }
 
+impl<'a> Array3dViewMut<'a, f32> {
+  pub fn parallel_set_constant(&'a mut self, c: f32) {
+    parallel_elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        |n, _, y| unsafe { densearray_set_scalar_f32(y, n, c) },
+        |n, _, y| unsafe { densearray_omp_set_scalar_f32(y, n, c) },
+    );
+  }
+}
+
+impl<'a> Array3dViewMut<'a, f64> {
+  pub fn parallel_set_constant(&'a mut self, c: f64) {
+    parallel_elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        |n, _, y| unsafe { densearray_set_scalar_f64(y, n, c) },
+        |n, _, y| unsafe { densearray_omp_set_scalar_f64(y, n, c) },
+    );
+  }
+}
+
+impl<'a> Array3dViewMut<'a, f32> {
+  pub fn cast_from_u8(&'a mut self, src: Array3dView<'a, u8>) {
+    elem_map(
+        src.buf, &[src.dim.0, src.dim.1, src.dim.2], &[src.stride.0, src.stride.1, src.stride.2],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        |n, x, y| unsafe { densearray_kernel_cast_1d_u8_to_f32(n, x, y) },
+    );
+  }
+
+  pub fn cast_from_u16(&'a mut self, src: Array3dView<'a, u16>) {
+    elem_map(
+        src.buf, &[src.dim.0, src.dim.1, src.dim.2], &[src.stride.0, src.stride.1, src.stride.2],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        |n, x, y| unsafe { densearray_kernel_cast_1d_u16_to_f32(n, x, y) },
+    );
+  }
+
+  pub fn cast_from_f64(&'a mut self, src: Array3dView<'a, f64>) {
+    elem_map(
+        src.buf, &[src.dim.0, src.dim.1, src.dim.2], &[src.stride.0, src.stride.1, src.stride.2],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        |n, x, y| unsafe { densearray_kernel_cast_1d_f64_to_f32(n, x, y) },
+    );
+  }
+
+  pub fn clamp(&'a mut self, lo: f32, hi: f32) {
+    assert!(lo <= hi);
+    elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        |n, x, y| unsafe { densearray_kernel_clamp_1d_f32(n, x, y, lo, hi) },
+    );
+  }
+
+  pub fn clamp_from(&'a mut self, src: Array3dView<'a, f32>, lo: f32, hi: f32) {
+    assert!(lo <= hi);
+    elem_map(
+        src.buf, &[src.dim.0, src.dim.1, src.dim.2], &[src.stride.0, src.stride.1, src.stride.2],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        |n, x, y| unsafe { densearray_kernel_clamp_1d_f32(n, x, y, lo, hi) },
+    );
+  }
+}
+
+impl<'a> Array3dViewMut<'a, f64> {
+  pub fn cast_from_f32(&'a mut self, src: Array3dView<'a, f32>) {
+    elem_map(
+        src.buf, &[src.dim.0, src.dim.1, src.dim.2], &[src.stride.0, src.stride.1, src.stride.2],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        |n, x, y| unsafe { densearray_kernel_cast_1d_f32_to_f64(n, x, y) },
+    );
+  }
+}
+
+impl<'a> Array3dViewMut<'a, u8> {
+  pub fn round_clamp_from_f32(&'a mut self, src: Array3dView<'a, f32>) {
+    elem_map(
+        src.buf, &[src.dim.0, src.dim.1, src.dim.2], &[src.stride.0, src.stride.1, src.stride.2],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        |n, x, y| unsafe { densearray_kernel_round_clamp_1d_f32_to_u8(n, x, y) },
+    );
+  }
+}
+
+impl<'a> Array3dViewMut<'a, i8> {
+  pub fn round_clamp_from_f32(&'a mut self, src: Array3dView<'a, f32>) {
+    elem_map(
+        src.buf, &[src.dim.0, src.dim.1, src.dim.2], &[src.stride.0, src.stride.1, src.stride.2],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        |n, x, y| unsafe { densearray_kernel_round_clamp_1d_f32_to_i8(n, x, y) },
+    );
+  }
+}
+
+impl<'a> Array3dViewMut<'a, u16> {
+  pub fn round_clamp_from_f32(&'a mut self, src: Array3dView<'a, f32>) {
+    elem_map(
+        src.buf, &[src.dim.0, src.dim.1, src.dim.2], &[src.stride.0, src.stride.1, src.stride.2],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        |n, x, y| unsafe { densearray_kernel_round_clamp_1d_f32_to_u16(n, x, y) },
+    );
+  }
+}
+
+impl<'a> Array3dViewMut<'a, i32> {
+  pub fn round_clamp_from_f32(&'a mut self, src: Array3dView<'a, f32>) {
+    elem_map(
+        src.buf, &[src.dim.0, src.dim.1, src.dim.2], &[src.stride.0, src.stride.1, src.stride.2],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        |n, x, y| unsafe { densearray_kernel_round_clamp_1d_f32_to_i32(n, x, y) },
+    );
+  }
+}
+
 #[derive(Clone)]
 pub struct Array4d<T, S=Vec<T>> where T: Copy, S: Deref<Target=[T]> {
   buf:      S,
@@ -1157,12 +1781,38 @@ impl<'a, T> Array4dView<'a, T> where T: 'a + Copy {
   }
 }
 
+impl<'a, T> View<'a, (usize, usize, usize, usize), Array4dView<'a, T>> for Array4dView<'a, T> where T: 'a + Copy {
+  fn view(self, lo: (usize, usize, usize, usize), hi: (usize, usize, usize, usize)) -> Array4dView<'a, T> {
+    let new_dim = hi.diff(lo);
+    let new_offset = lo.offset(self.stride);
+    let new_offset_end = new_offset + new_dim.extent(self.stride);
+    Array4dView{
+      buf:      &self.buf[new_offset .. new_offset_end],
+      dim:      new_dim,
+      stride:   self.stride,
+    }
+  }
+}
+
 pub struct Array4dViewMut<'a, T> where T: 'a + Copy {
   buf:      &'a mut [T],
   dim:      (usize, usize, usize, usize),
   stride:   (usize, usize, usize, usize),
 }
 
+impl<'a, T> ViewMut<'a, (usize, usize, usize, usize), Array4dViewMut<'a, T>> for Array4dViewMut<'a, T> where T: 'a + Copy {
+  fn view_mut(self, lo: (usize, usize, usize, usize), hi: (usize, usize, usize, usize)) -> Array4dViewMut<'a, T> {
+    let new_dim = hi.diff(lo);
+    let new_offset = lo.offset(self.stride);
+    let new_offset_end = new_offset + new_dim.extent(self.stride);
+    Array4dViewMut{
+      buf:      &mut self.buf[new_offset .. new_offset_end],
+      dim:      new_dim,
+      stride:   self.stride,
+    }
+  }
+}
+
 impl<'a, T> Array4dViewMut<'a, T> where T: 'a + Copy {
   pub fn dim(&self) -> (usize, usize, usize, usize) {
     self.dim
@@ -1182,7 +1832,11 @@ impl<'a, T> Array4dViewMut<'a, T> where T: 'a + Copy {
         self.buf[i] = c;
       }
     } else {
-      unimplemented!();
+      elem_map_inplace(self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3], |n, _, y| {
+        for i in 0 .. n {
+          unsafe { *y.offset(i as isize) = c };
+        }
+      });
     }
   }
 
@@ -1193,15 +1847,114 @@ impl<'a, T> Array4dViewMut<'a, T> where T: 'a + Copy {
 
 impl<'a> Array4dViewMut<'a, f32> {
   pub fn parallel_set_constant(&'a mut self, c: f32) {
-    if self.stride == self.dim.least_stride() {
-      unsafe { densearray_omp_set_scalar_f32(
-          self.buf.as_mut_ptr(),
-          self.dim.flat_len(),
-          c,
-      ) };
-    } else {
-      unimplemented!();
-    }
+    parallel_elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        |n, _, y| unsafe { densearray_set_scalar_f32(y, n, c) },
+        |n, _, y| unsafe { densearray_omp_set_scalar_f32(y, n, c) },
+    );
+  }
+}
+
+impl<'a> Array4dViewMut<'a, f64> {
+  pub fn parallel_set_constant(&'a mut self, c: f64) {
+    parallel_elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        |n, _, y| unsafe { densearray_set_scalar_f64(y, n, c) },
+        |n, _, y| unsafe { densearray_omp_set_scalar_f64(y, n, c) },
+    );
+  }
+}
+
+impl<'a> Array4dViewMut<'a, f32> {
+  pub fn cast_from_u8(&'a mut self, src: Array4dView<'a, u8>) {
+    elem_map(
+        src.buf, &[src.dim.0, src.dim.1, src.dim.2, src.dim.3], &[src.stride.0, src.stride.1, src.stride.2, src.stride.3],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        |n, x, y| unsafe { densearray_kernel_cast_1d_u8_to_f32(n, x, y) },
+    );
+  }
+
+  pub fn cast_from_u16(&'a mut self, src: Array4dView<'a, u16>) {
+    elem_map(
+        src.buf, &[src.dim.0, src.dim.1, src.dim.2, src.dim.3], &[src.stride.0, src.stride.1, src.stride.2, src.stride.3],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        |n, x, y| unsafe { densearray_kernel_cast_1d_u16_to_f32(n, x, y) },
+    );
+  }
+
+  pub fn cast_from_f64(&'a mut self, src: Array4dView<'a, f64>) {
+    elem_map(
+        src.buf, &[src.dim.0, src.dim.1, src.dim.2, src.dim.3], &[src.stride.0, src.stride.1, src.stride.2, src.stride.3],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        |n, x, y| unsafe { densearray_kernel_cast_1d_f64_to_f32(n, x, y) },
+    );
+  }
+
+  pub fn clamp(&'a mut self, lo: f32, hi: f32) {
+    assert!(lo <= hi);
+    elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        |n, x, y| unsafe { densearray_kernel_clamp_1d_f32(n, x, y, lo, hi) },
+    );
+  }
+
+  pub fn clamp_from(&'a mut self, src: Array4dView<'a, f32>, lo: f32, hi: f32) {
+    assert!(lo <= hi);
+    elem_map(
+        src.buf, &[src.dim.0, src.dim.1, src.dim.2, src.dim.3], &[src.stride.0, src.stride.1, src.stride.2, src.stride.3],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        |n, x, y| unsafe { densearray_kernel_clamp_1d_f32(n, x, y, lo, hi) },
+    );
+  }
+}
+
+impl<'a> Array4dViewMut<'a, f64> {
+  pub fn cast_from_f32(&'a mut self, src: Array4dView<'a, f32>) {
+    elem_map(
+        src.buf, &[src.dim.0, src.dim.1, src.dim.2, src.dim.3], &[src.stride.0, src.stride.1, src.stride.2, src.stride.3],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        |n, x, y| unsafe { densearray_kernel_cast_1d_f32_to_f64(n, x, y) },
+    );
+  }
+}
+
+impl<'a> Array4dViewMut<'a, u8> {
+  pub fn round_clamp_from_f32(&'a mut self, src: Array4dView<'a, f32>) {
+    elem_map(
+        src.buf, &[src.dim.0, src.dim.1, src.dim.2, src.dim.3], &[src.stride.0, src.stride.1, src.stride.2, src.stride.3],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        |n, x, y| unsafe { densearray_kernel_round_clamp_1d_f32_to_u8(n, x, y) },
+    );
+  }
+}
+
+impl<'a> Array4dViewMut<'a, i8> {
+  pub fn round_clamp_from_f32(&'a mut self, src: Array4dView<'a, f32>) {
+    elem_map(
+        src.buf, &[src.dim.0, src.dim.1, src.dim.2, src.dim.3], &[src.stride.0, src.stride.1, src.stride.2, src.stride.3],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        |n, x, y| unsafe { densearray_kernel_round_clamp_1d_f32_to_i8(n, x, y) },
+    );
+  }
+}
+
+impl<'a> Array4dViewMut<'a, u16> {
+  pub fn round_clamp_from_f32(&'a mut self, src: Array4dView<'a, f32>) {
+    elem_map(
+        src.buf, &[src.dim.0, src.dim.1, src.dim.2, src.dim.3], &[src.stride.0, src.stride.1, src.stride.2, src.stride.3],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        |n, x, y| unsafe { densearray_kernel_round_clamp_1d_f32_to_u16(n, x, y) },
+    );
+  }
+}
+
+impl<'a> Array4dViewMut<'a, i32> {
+  pub fn round_clamp_from_f32(&'a mut self, src: Array4dView<'a, f32>) {
+    elem_map(
+        src.buf, &[src.dim.0, src.dim.1, src.dim.2, src.dim.3], &[src.stride.0, src.stride.1, src.stride.2, src.stride.3],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        |n, x, y| unsafe { densearray_kernel_round_clamp_1d_f32_to_i32(n, x, y) },
+    );
   }
 }
 
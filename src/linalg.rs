@@ -1,8 +1,9 @@
-use super::{Array1dView, Array1dViewMut, Array2dView, Array2dViewMut};
+use super::{ZeroBits, AsView, AsViewMut, Array1dView, Array1dViewMut, Array2d, Array2dView, Array2dViewMut, Array3dView, Array3dViewMut, Array4dView, Array4dViewMut, Lanes, axis_reduce, check_extent, combine_norms, elem_map, elem_map_inplace};
+use backend::{ReferenceScalar};
+use blas::{BlasScalar};
 use kernels::*;
 
-use cblas_ffi::*;
-use openblas_ffi::*;
+use std::cmp::{max, min};
 
 #[derive(Clone, Copy)]
 pub enum Transpose {
@@ -10,330 +11,1913 @@ pub enum Transpose {
   T,
 }
 
-impl<'a> Array1dView<'a, f32> {
-  pub fn l1_norm(&'a self) -> f32 {
-    let x_n = self.dim();
-    let incx = self.stride();
-    let mut p = 0;
-    let mut x_sum = 0.0;
-    for _ in 0 .. x_n {
-      let x_i = self.buf[p].abs();
-      x_sum += x_i;
-      p += incx;
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Uplo {
+  Lower,
+  Upper,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Summation {
+  Naive,
+  Pairwise,
+  Compensated,
+  F64Accum,
+}
+
+/// Errors from the LAPACK-style routines.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LinalgError {
+  /// The routine rejected its `i`-th argument (counting from 1).
+  InvalidArgument(usize),
+  /// The leading minor of order `k` is not positive definite.
+  NotPositiveDefinite(usize),
+  /// The `k`-th pivot of an LU factorization is exactly zero.
+  Singular(usize),
+  /// The `k`-th column of a least squares matrix is numerically dependent on
+  /// the previous ones.
+  RankDeficient(usize),
+  /// An iterative routine did not converge; the count is routine specific.
+  NoConvergence(usize),
+}
+
+/// Maps a negative LAPACK `info` to an error and returns the non-negative
+/// ones, which are specific to each routine.
+fn lapack_status(status: i32) -> Result<usize, LinalgError> {
+  if status < 0 {
+    Err(LinalgError::InvalidArgument((-status) as usize))
+  } else {
+    Ok(status as usize)
+  }
+}
+
+/// Copies `a` into a packed column-major buffer with leading dimension `ld`
+/// and `cols` columns, which is zero outside of `a`.
+fn packed_copy<T>(a: Array2dView<T>, ld: usize, cols: usize) -> Vec<T> where T: ZeroBits {
+  let (m, n) = a.dim();
+  assert!(m <= ld && n <= cols);
+  let (a_inc, lda) = a.stride();
+  let mut buf = vec![T::zero_bits(); ld * cols];
+  for j in 0 .. n {
+    for i in 0 .. m {
+      buf[i + j * ld] = a.buf[i * a_inc + j * lda];
+    }
+  }
+  buf
+}
+
+/// Copies the `uplo` triangle of the `n x n` column-major matrix in `buf`
+/// into the other triangle.
+fn mirror_triangle<T>(buf: &mut [T], n: usize, ld: usize, uplo: Uplo) where T: Copy {
+  for j in 0 .. n {
+    for i in j + 1 .. n {
+      match uplo {
+        Uplo::Lower => buf[j + i * ld] = buf[i + j * ld],
+        Uplo::Upper => buf[i + j * ld] = buf[j + i * ld],
+      }
+    }
+  }
+}
+
+const PAIRWISE_BLOCK_SZ: usize = 128;
+
+fn naive_sum<T, F>(buf: &[T], n: usize, inc: usize, f: &F) -> T where T: ReferenceScalar, F: Fn(T) -> T {
+  let mut p = 0;
+  let mut x_sum = T::zero();
+  for _ in 0 .. n {
+    x_sum = x_sum + f(buf[p]);
+    p += inc;
+  }
+  x_sum
+}
+
+fn pairwise_sum<T, F>(buf: &[T], n: usize, inc: usize, f: &F) -> T where T: ReferenceScalar, F: Fn(T) -> T {
+  // The error of pairwise summation grows as O(log n) rather than O(n).
+  if n <= PAIRWISE_BLOCK_SZ {
+    naive_sum(buf, n, inc, f)
+  } else {
+    let half_n = n / 2;
+    let lo_sum = pairwise_sum(buf, half_n, inc, f);
+    let hi_sum = pairwise_sum(&buf[half_n * inc .. ], n - half_n, inc, f);
+    lo_sum + hi_sum
+  }
+}
+
+/// Adds `x` to `sum` and returns the rounding error of the addition.
+fn two_sum<T>(sum: &mut T, x: T) -> T where T: ReferenceScalar {
+  let t = *sum + x;
+  let err = if sum.abs() >= x.abs() {
+    (*sum - t) + x
+  } else {
+    (x - t) + *sum
+  };
+  *sum = t;
+  err
+}
+
+fn compensated_sum<T, F>(buf: &[T], n: usize, inc: usize, f: &F) -> T where T: ReferenceScalar, F: Fn(T) -> T {
+  // Neumaier's variant of Kahan summation, which also handles the case where
+  // the next term is larger in magnitude than the running sum. The rounding
+  // errors are summed in turn with Kahan's feedback of each error into the
+  // next addition, since many tiny errors summed naively would again lose
+  // accuracy.
+  let mut p = 0;
+  let mut x_sum = T::zero();
+  let mut x_comp = T::zero();
+  let mut x_comp_err = T::zero();
+  for _ in 0 .. n {
+    let err = two_sum(&mut x_sum, f(buf[p]));
+    x_comp_err = two_sum(&mut x_comp, err + x_comp_err);
+    p += inc;
+  }
+  x_sum + (x_comp + x_comp_err)
+}
+
+fn f64_accum_sum<T, F>(buf: &[T], n: usize, inc: usize, f: &F) -> T where T: ReferenceScalar, F: Fn(T) -> T {
+  let mut p = 0;
+  let mut x_sum: f64 = 0.0;
+  for _ in 0 .. n {
+    x_sum += f(buf[p]).to_f64();
+    p += inc;
+  }
+  T::from_f64(x_sum)
+}
+
+fn strided_sum<T, F>(buf: &[T], n: usize, inc: usize, mode: Summation, f: F) -> T where T: ReferenceScalar, F: Fn(T) -> T {
+  match mode {
+    Summation::Naive        => naive_sum(buf, n, inc, &f),
+    Summation::Pairwise     => pairwise_sum(buf, n, inc, &f),
+    Summation::Compensated  => compensated_sum(buf, n, inc, &f),
+    Summation::F64Accum     => f64_accum_sum(buf, n, inc, &f),
+  }
+}
+
+/// Reduces each 1d lane (see `Lanes`) of a strided array `x`, or of `x` and
+/// an equally shaped `y`, with `f(x, n, incx, y, incy)`.
+fn lane_reduce<T, F>(x_buf: &[T], x_dim: &[usize], x_stride: &[usize], y_buf: &[T], y_stride: &[usize], mut f: F) -> Vec<T> where F: FnMut(&[T], usize, usize, &[T], usize) -> T {
+  check_extent(x_buf.len(), x_dim, x_stride);
+  check_extent(y_buf.len(), x_dim, y_stride);
+  let lanes = Lanes::new(x_dim, x_stride, y_stride, false);
+  (0 .. lanes.count).map(|lane| {
+    let (x_offset, y_offset) = lanes.offsets(lane);
+    f(&x_buf[x_offset .. ], lanes.len, lanes.x_inc, &y_buf[y_offset .. ], lanes.y_inc)
+  }).collect()
+}
+
+/// Sums `f` of the elements of a strided array, lane by lane, with the
+/// lane sums combined using the same `mode`.
+fn nd_sum<T, F>(buf: &[T], dim: &[usize], stride: &[usize], mode: Summation, f: F) -> T where T: ReferenceScalar, F: Fn(T) -> T {
+  let partials = lane_reduce(
+      buf, dim, stride,
+      buf, stride,
+      |x, n, incx, _, _| strided_sum(x, n, incx, mode, &f),
+  );
+  strided_sum(&partials, partials.len(), 1, mode, |p| p)
+}
+
+/// Sums `x` along `axis` into `y`, each fiber with `mode`, and divides by the
+/// length of the axis if `mean`.
+fn nd_axis_sum<T>(x_buf: &[T], x_dim: &[usize], x_stride: &[usize], axis: usize, y_buf: &mut [T], y_dim: &[usize], y_stride: &[usize], mode: Summation, mean: bool) where T: ReferenceScalar {
+  let ndim = x_dim.len();
+  assert!(axis < ndim);
+  assert_eq!(ndim, y_dim.len() + 1);
+  assert_eq!(&x_dim[ .. axis], &y_dim[ .. axis]);
+  assert_eq!(&x_dim[axis + 1 .. ], &y_dim[axis .. ]);
+  check_extent(x_buf.len(), x_dim, x_stride);
+  check_extent(y_buf.len(), y_dim, y_stride);
+  let red_dim = x_dim[axis];
+  let red_stride = x_stride[axis];
+  let mut outer_stride = x_stride.to_vec();
+  outer_stride.remove(axis);
+  let lanes = Lanes::new(y_dim, &outer_stride, y_stride, false);
+  for lane in 0 .. lanes.count {
+    let (x_offset, y_offset) = lanes.offsets(lane);
+    for i in 0 .. lanes.len {
+      let sum = if red_dim == 0 {
+        T::zero()
+      } else {
+        strided_sum(&x_buf[x_offset + i * lanes.x_inc .. ], red_dim, red_stride, mode, |x_i| x_i)
+      };
+      // An empty axis gives `0 / 0`, the same NaN as `axis_mean`.
+      y_buf[y_offset + i * lanes.y_inc] = if mean { sum / T::from_f64(red_dim as f64) } else { sum };
     }
-    x_sum
   }
+}
+
+fn nd_l2_norm<T>(buf: &[T], dim: &[usize], stride: &[usize]) -> T where T: BlasScalar {
+  let partials = lane_reduce(
+      buf, dim, stride,
+      buf, stride,
+      |x, n, incx, _, _| unsafe { T::nrm2(n, x.as_ptr(), incx) },
+  );
+  combine_norms(&partials)
+}
+
+fn nd_inner_prod<T>(alpha: T, x_buf: &[T], dim: &[usize], x_stride: &[usize], y_buf: &[T], y_stride: &[usize]) -> T where T: BlasScalar {
+  let partials = lane_reduce(
+      x_buf, dim, x_stride,
+      y_buf, y_stride,
+      |x, n, incx, y, incy| unsafe { T::dot(n, alpha, x.as_ptr(), incx, y.as_ptr(), incy) },
+  );
+  naive_sum(&partials, partials.len(), 1, &|p| p)
+}
 
-  pub fn l2_norm(&'a self) -> f32 {
+impl<'a, T> Array1dView<'a, T> where T: 'a + BlasScalar {
+  pub fn l2_norm(&'a self) -> T {
     let n = self.dim();
     let incx = self.stride();
-    unsafe { openblas_sequential_cblas_snrm2(
-        n as _,
+    unsafe { T::nrm2(
+        n,
         self.buf.as_ptr(),
-        incx as _,
+        incx,
     ) }
   }
 
-  pub fn elem_sum(&'a self) -> f32 {
-    let x_n = self.dim();
-    let incx = self.stride();
-    let mut p = 0;
-    let mut x_sum = 0.0;
-    for _ in 0 .. x_n {
-      let x_i = self.buf[p];
-      x_sum += x_i;
-      p += incx;
-    }
-    x_sum
-  }
-
-  pub fn inner_prod(&'a self, alpha: f32, y: Array1dView<'a, f32>) -> f32 {
+  pub fn inner_prod(&'a self, alpha: T, y: Array1dView<'a, T>) -> T {
     let x_n = self.dim();
     let y_n = y.dim();
     assert_eq!(x_n, y_n);
     let incx = self.stride();
     let incy = y.stride();
-    unsafe { openblas_sequential_cblas_sdot(
-        x_n as _,
+    unsafe { T::dot(
+        x_n,
         alpha,
         self.buf.as_ptr(),
-        incx as _,
+        incx,
         y.as_ptr(),
-        incy as _,
+        incy,
     ) }
   }
 }
 
-impl<'a> Array1dViewMut<'a, f32> {
-  pub fn copy(&'a mut self, src: Array1dView<'a, f32>) {
-    assert_eq!(self.dim(), src.dim());
-    if self.stride() == 1 {
-      unsafe { densearray_copy_f32(
+impl<'a, T> Array1dViewMut<'a, T> where T: 'a + BlasScalar {
+  pub fn matrix_vector_prod(&'a mut self, alpha: T, a: Array2dView<'a, T>, a_trans: Transpose, x: Array1dView<'a, T>, beta: T) {
+    let (a_m, a_n) = a.dim();
+    let x_n = x.dim();
+    let y_m = self.dim();
+    let (at_m, at_n) = match a_trans {
+      Transpose::N => (a_m, a_n),
+      Transpose::T => (a_n, a_m),
+    };
+    assert_eq!(y_m, at_m);
+    assert_eq!(x_n, at_n);
+    let (a_inc, lda) = a.stride();
+    let x_inc = x.stride();
+    let y_inc = self.stride();
+    assert_eq!(1, a_inc);
+    unsafe { T::gemv(
+        a_trans,
+        a_m, a_n,
+        alpha,
+        a.buf.as_ptr(), lda,
+        x.buf.as_ptr(), x_inc,
+        beta,
+        self.buf.as_mut_ptr(), y_inc,
+    ) };
+  }
+
+  /// Solves `op(A) * x = b` in place, where `self` holds `b` on entry and
+  /// `A` is triangular. Only the `uplo` triangle of `A` is read, and the
+  /// diagonal is not checked for zeros.
+  pub fn triangular_solve(&'a mut self, a: Array2dView<'a, T>, uplo: Uplo, a_trans: Transpose) {
+    let (a_m, n) = a.dim();
+    let x_n = self.dim();
+    assert_eq!(a_m, n);
+    assert_eq!(x_n, n);
+    let (a_inc, lda) = a.stride();
+    let x_inc = self.stride();
+    assert_eq!(1, a_inc);
+    if n == 0 {
+      return;
+    }
+    unsafe { T::trsv(
+        uplo,
+        a_trans,
+        n,
+        a.buf.as_ptr(), lda,
+        self.buf.as_mut_ptr(), x_inc,
+    ) };
+  }
+
+  /// Solves `A * x = b` for a symmetric positive definite `A`, of which only
+  /// the lower triangle is read. `A` is factored into a copy, so for several
+  /// solves with the same matrix use `CholeskyFactor` instead.
+  pub fn symm_linear_solve(&'a mut self, a: Array2dView<'a, T>, b: Array1dView<'a, T>) -> Result<(), LinalgError> {
+    let (a_m, n) = a.dim();
+    let b_n = b.dim();
+    let x_n = self.dim();
+    assert_eq!(a_m, n);
+    assert_eq!(b_n, n);
+    assert_eq!(x_n, n);
+    let factor = CholeskyFactor::new(a, Uplo::Lower)?;
+    let b_inc = b.stride();
+    let x_inc = self.stride();
+    for i in 0 .. n {
+      self.buf[i * x_inc] = b.buf[i * b_inc];
+    }
+    factor.solve(Array1dViewMut{
+      buf:      &mut *self.buf,
+      dim:      self.dim,
+      stride:   self.stride,
+    });
+    Ok(())
+  }
+}
+
+impl<'a, T> Array2dViewMut<'a, T> where T: 'a + BlasScalar {
+  /// Overwrites the `uplo` triangle of the symmetric positive definite
+  /// matrix in `self` with its Cholesky factor: `L` with `A = L * L^T`, or
+  /// `U` with `A = U^T * U`. The other triangle is not referenced.
+  pub fn cholesky_in_place(&'a mut self, uplo: Uplo) -> Result<(), LinalgError> {
+    let (m, n) = self.dim();
+    assert_eq!(m, n);
+    let (a_inc, lda) = self.stride();
+    assert_eq!(1, a_inc);
+    if n == 0 {
+      return Ok(());
+    }
+    let status = unsafe { T::potrf(
+        uplo,
+        n,
+        self.buf.as_mut_ptr(), lda,
+    ) };
+    match lapack_status(status)? {
+      0 => Ok(()),
+      k => Err(LinalgError::NotPositiveDefinite(k)),
+    }
+  }
+
+  /// Solves `A * X = B` in place, where `self` holds `B` on entry and
+  /// `factor` holds the Cholesky factor of `A` from `cholesky_in_place` with
+  /// the same `uplo`.
+  pub fn cholesky_solve(&'a mut self, factor: Array2dView<'a, T>, uplo: Uplo) -> Result<(), LinalgError> {
+    let (a_m, n) = factor.dim();
+    let (b_m, nrhs) = self.dim();
+    assert_eq!(a_m, n);
+    assert_eq!(b_m, n);
+    let (a_inc, lda) = factor.stride();
+    let (b_inc, ldb) = self.stride();
+    assert_eq!(1, a_inc);
+    assert_eq!(1, b_inc);
+    if n == 0 || nrhs == 0 {
+      return Ok(());
+    }
+    let status = unsafe { T::potrs(
+        uplo,
+        n, nrhs,
+        factor.buf.as_ptr(), lda,
+        self.buf.as_mut_ptr(), ldb,
+    ) };
+    lapack_status(status).map(|_| ())
+  }
+
+  /// Solves `op(A) * X = alpha * B` in place, where `self` holds `B` on entry
+  /// and `A` is triangular. Only the `uplo` triangle of `A` is read, and the
+  /// diagonal is not checked for zeros.
+  pub fn triangular_solve(&'a mut self, alpha: T, a: Array2dView<'a, T>, uplo: Uplo, a_trans: Transpose) {
+    let (a_m, n) = a.dim();
+    let (b_m, nrhs) = self.dim();
+    assert_eq!(a_m, n);
+    assert_eq!(b_m, n);
+    let (a_inc, lda) = a.stride();
+    let (b_inc, ldb) = self.stride();
+    assert_eq!(1, a_inc);
+    assert_eq!(1, b_inc);
+    if n == 0 || nrhs == 0 {
+      return;
+    }
+    unsafe { T::trsm(
+        uplo,
+        a_trans,
+        n, nrhs,
+        alpha,
+        a.buf.as_ptr(), lda,
+        self.buf.as_mut_ptr(), ldb,
+    ) };
+  }
+
+  /// Overwrites `self` with the outer product `alpha * x * y^T`.
+  pub fn outer_prod(&'a mut self, alpha: T, x: Array1dView<'a, T>, y: Array1dView<'a, T>) {
+    let (m, n) = self.dim();
+    let (a_inc, lda) = self.stride();
+    assert_eq!(1, a_inc);
+    for j in 0 .. n {
+      for i in 0 .. m {
+        self.buf[i + j * lda] = T::zero();
+      }
+    }
+    self.rank1_update_impl(alpha, x, y);
+  }
+
+  /// The rank-1 update `self += alpha * x * y^T`.
+  pub fn rank1_update(&'a mut self, alpha: T, x: Array1dView<'a, T>, y: Array1dView<'a, T>) {
+    self.rank1_update_impl(alpha, x, y);
+  }
+
+  fn rank1_update_impl(&mut self, alpha: T, x: Array1dView<T>, y: Array1dView<T>) {
+    let (m, n) = self.dim();
+    assert_eq!(m, x.dim());
+    assert_eq!(n, y.dim());
+    let (a_inc, lda) = self.stride();
+    assert_eq!(1, a_inc);
+    if m == 0 || n == 0 {
+      return;
+    }
+    unsafe { T::ger(
+        m, n,
+        alpha,
+        x.buf.as_ptr(), x.stride(),
+        y.buf.as_ptr(), y.stride(),
+        self.buf.as_mut_ptr(), lda,
+    ) };
+  }
+
+  /// The symmetric rank-1 update `self += alpha * x * x^T` of the `uplo`
+  /// triangle; with `mirror` the triangle is then copied into the other one,
+  /// otherwise the other triangle is not referenced.
+  pub fn symmetric_rank1_update(&'a mut self, alpha: T, x: Array1dView<'a, T>, uplo: Uplo, mirror: bool) {
+    let (m, n) = self.dim();
+    assert_eq!(m, n);
+    assert_eq!(n, x.dim());
+    let (a_inc, lda) = self.stride();
+    assert_eq!(1, a_inc);
+    if n == 0 {
+      return;
+    }
+    unsafe { T::syr(
+        uplo,
+        n,
+        alpha,
+        x.buf.as_ptr(), x.stride(),
+        self.buf.as_mut_ptr(), lda,
+    ) };
+    if mirror {
+      mirror_triangle(self.buf, n, lda, uplo);
+    }
+  }
+
+  /// The symmetric rank-k update `self = alpha * op(A) * op(A)^T + beta *
+  /// self` of the `uplo` triangle, e.g. the (unnormalized) covariance
+  /// `X^T * X` with `a_trans = Transpose::T`. With `mirror` the triangle is
+  /// then copied into the other one, otherwise the other triangle is not
+  /// referenced.
+  pub fn symmetric_rank_k_update(&'a mut self, alpha: T, a: Array2dView<'a, T>, a_trans: Transpose, beta: T, uplo: Uplo, mirror: bool) {
+    let (a_m, a_n) = a.dim();
+    let (c_m, n) = self.dim();
+    let (at_m, k) = match a_trans {
+      Transpose::N => (a_m, a_n),
+      Transpose::T => (a_n, a_m),
+    };
+    assert_eq!(c_m, n);
+    assert_eq!(at_m, n);
+    let (a_inc, lda) = a.stride();
+    let (c_inc, ldc) = self.stride();
+    assert_eq!(1, a_inc);
+    assert_eq!(1, c_inc);
+    if n == 0 {
+      return;
+    }
+    unsafe { T::syrk(
+        uplo,
+        a_trans,
+        n, k,
+        alpha,
+        a.buf.as_ptr(), lda,
+        beta,
+        self.buf.as_mut_ptr(), ldc,
+    ) };
+    if mirror {
+      mirror_triangle(self.buf, n, ldc, uplo);
+    }
+  }
+
+  pub fn matrix_add(&'a mut self, alpha: T, x: Array2dView<'a, T>) {
+    self.matrix_add_trans(alpha, x, Transpose::N);
+  }
+
+  pub fn matrix_add_trans(&'a mut self, alpha: T, x: Array2dView<'a, T>, x_trans: Transpose) {
+    let (x_m, x_n) = x.dim();
+    let (y_m, y_n) = self.dim();
+    let (xt_m, xt_n) = match x_trans {
+      Transpose::N => (x_m, x_n),
+      Transpose::T => (x_n, x_m),
+    };
+    assert_eq!(xt_m, y_m);
+    assert_eq!(xt_n, y_n);
+    let (incx, ldx) = x.stride();
+    let (incy, ldy) = self.stride();
+    // Strides of `op(X)` laid out in the shape of `Y`.
+    let (xt_inc, xt_ld) = match x_trans {
+      Transpose::N => (incx, ldx),
+      Transpose::T => (ldx, incx),
+    };
+    if y_m == 0 || y_n == 0 {
+      return;
+    }
+    if y_n == 1 {
+      unsafe { T::axpy(
+          y_m,
+          alpha,
+          x.buf.as_ptr(),
+          xt_inc,
           self.buf.as_mut_ptr(),
-          self.dim(),
-          src.buf.as_ptr(),
+          incy,
+      ) };
+    } else if y_m == 1 {
+      unsafe { T::axpy(
+          y_n,
+          alpha,
+          x.buf.as_ptr(),
+          xt_ld,
+          self.buf.as_mut_ptr(),
+          ldy,
+      ) };
+    } else if xt_inc == 1 && xt_ld == y_m && incy == 1 && ldy == y_m {
+      unsafe { T::axpy(
+          y_m * y_n,
+          alpha,
+          x.buf.as_ptr(),
+          1,
+          self.buf.as_mut_ptr(),
+          1,
       ) };
     } else {
-      unimplemented!();
+      for j in 0 .. y_n {
+        unsafe { T::axpy(
+            y_m,
+            alpha,
+            x.buf[j * xt_ld .. ].as_ptr(),
+            xt_inc,
+            self.buf[j * ldy .. ].as_mut_ptr(),
+            incy,
+        ) };
+      }
+    }
+  }
+
+  pub fn matrix_prod(&'a mut self, alpha: T, a: Array2dView<'a, T>, a_trans: Transpose, b: Array2dView<'a, T>, b_trans: Transpose, beta: T) {
+    let (a_m, a_n) = a.dim();
+    let (b_m, b_n) = b.dim();
+    let (c_m, c_n) = self.dim();
+    let (at_m, at_n) = match a_trans {
+      Transpose::N => (a_m, a_n),
+      Transpose::T => (a_n, a_m),
+    };
+    let (bt_m, bt_n) = match b_trans {
+      Transpose::N => (b_m, b_n),
+      Transpose::T => (b_n, b_m),
+    };
+    assert_eq!(c_m, at_m);
+    assert_eq!(c_n, bt_n);
+    assert_eq!(at_n, bt_m);
+    let k = at_n;
+    let (a_inc, lda) = a.stride();
+    let (b_inc, ldb) = b.stride();
+    let (c_inc, ldc) = self.stride();
+    assert_eq!(1, a_inc);
+    assert_eq!(1, b_inc);
+    assert_eq!(1, c_inc);
+    unsafe { T::gemm(
+        a_trans,
+        b_trans,
+        c_m, c_n, k,
+        alpha,
+        a.buf.as_ptr(), lda,
+        b.buf.as_ptr(), ldb,
+        beta,
+        self.buf.as_mut_ptr(), ldc,
+    ) };
+  }
+}
+
+impl<'a, T> Array3dViewMut<'a, T> where T: 'a + BlasScalar {
+  /// The batched matrix product over the outer axis: each matrix
+  /// `self[.., .., l]` is set to `alpha * op(A[.., .., l]) * op(B[.., .., l])
+  /// + beta * self[.., .., l]`. The transposes are the same for the whole
+  /// batch on purpose, as in a stride-batched `gemm`; for mixed transposes,
+  /// split the batch or call `matrix_prod` per matrix. See
+  /// `Backend::gemm_batch` for how the batch is run.
+  pub fn batched_matrix_prod(&'a mut self, alpha: T, a: Array3dView<'a, T>, a_trans: Transpose, b: Array3dView<'a, T>, b_trans: Transpose, beta: T) {
+    let (a_m, a_n, a_batch) = a.dim();
+    let (b_m, b_n, b_batch) = b.dim();
+    let (c_m, c_n, batch_sz) = self.dim();
+    let (a_inc, lda, stride_a) = a.stride();
+    let (b_inc, ldb, stride_b) = b.stride();
+    let (c_inc, ldc, stride_c) = self.stride();
+    assert_eq!(batch_sz, a_batch);
+    assert_eq!(batch_sz, b_batch);
+    batched_gemm(
+        alpha,
+        a.buf, (a_m, a_n), (a_inc, lda, stride_a), a_trans,
+        b.buf, (b_m, b_n), (b_inc, ldb, stride_b), b_trans,
+        beta,
+        self.buf, (c_m, c_n), (c_inc, ldc, stride_c),
+        batch_sz,
+    );
+  }
+}
+
+impl<'a, T> Array4dViewMut<'a, T> where T: 'a + BlasScalar {
+  /// Like the `Array3dViewMut` version, with the two outer axes (e.g. heads
+  /// and samples) as the batch. Each of the arrays must lay out these axes as
+  /// a single strided axis, as packed arrays and their inner sub-views do.
+  pub fn batched_matrix_prod(&'a mut self, alpha: T, a: Array4dView<'a, T>, a_trans: Transpose, b: Array4dView<'a, T>, b_trans: Transpose, beta: T) {
+    let (a_m, a_n, a_b0, a_b1) = a.dim();
+    let (b_m, b_n, b_b0, b_b1) = b.dim();
+    let (c_m, c_n, b0, b1) = self.dim();
+    assert_eq!((b0, b1), (a_b0, a_b1));
+    assert_eq!((b0, b1), (b_b0, b_b1));
+    let (a_inc, lda, _, _) = a.stride();
+    let (b_inc, ldb, _, _) = b.stride();
+    let (c_inc, ldc, _, _) = self.stride();
+    let stride_a = merged_batch_stride(a.dim(), a.stride());
+    let stride_b = merged_batch_stride(b.dim(), b.stride());
+    let stride_c = merged_batch_stride(self.dim(), self.stride());
+    batched_gemm(
+        alpha,
+        a.buf, (a_m, a_n), (a_inc, lda, stride_a), a_trans,
+        b.buf, (b_m, b_n), (b_inc, ldb, stride_b), b_trans,
+        beta,
+        self.buf, (c_m, c_n), (c_inc, ldc, stride_c),
+        b0 * b1,
+    );
+  }
+}
+
+/// The stride of the single batch axis formed by the two outer axes of a 4d
+/// array.
+fn merged_batch_stride(dim: (usize, usize, usize, usize), stride: (usize, usize, usize, usize)) -> usize {
+  if dim.3 <= 1 {
+    stride.2
+  } else if dim.2 <= 1 {
+    stride.3
+  } else {
+    assert_eq!(stride.3, dim.2 * stride.2, "the batch axes cannot be merged into one strided axis");
+    stride.2
+  }
+}
+
+/// Whether two distinct indices into a strided array address the same
+/// element. Layouts whose axes, ordered by stride, each step over the extent
+/// of the smaller ones cannot alias; any others are checked element by
+/// element.
+fn aliases(dim: &[usize], stride: &[usize]) -> bool {
+  let mut axes: Vec<(usize, usize)> = (0 .. dim.len())
+    .filter(|&d| dim[d] > 1)
+    .map(|d| (stride[d], dim[d]))
+    .collect();
+  axes.sort();
+  let mut extent = 0;
+  let mut nested = true;
+  for &(s, d) in axes.iter() {
+    if s <= extent {
+      nested = false;
+    }
+    extent += (d - 1) * s;
+  }
+  if nested {
+    return false;
+  }
+  let len: usize = axes.iter().map(|&(_, d)| d).product();
+  let mut seen = vec![false; extent + 1];
+  for idx in 0 .. len {
+    let mut rem = idx;
+    let mut offset = 0;
+    for &(s, d) in axes.iter() {
+      offset += (rem % d) * s;
+      rem /= d;
+    }
+    if seen[offset] {
+      return true;
+    }
+    seen[offset] = true;
+  }
+  false
+}
+
+fn batched_gemm<T>(alpha: T, a_buf: &[T], a_dim: (usize, usize), a_stride: (usize, usize, usize), a_trans: Transpose, b_buf: &[T], b_dim: (usize, usize), b_stride: (usize, usize, usize), b_trans: Transpose, beta: T, c_buf: &mut [T], c_dim: (usize, usize), c_stride: (usize, usize, usize), batch_sz: usize) where T: BlasScalar {
+  let (at_m, at_n) = match a_trans {
+    Transpose::N => a_dim,
+    Transpose::T => (a_dim.1, a_dim.0),
+  };
+  let (bt_m, bt_n) = match b_trans {
+    Transpose::N => b_dim,
+    Transpose::T => (b_dim.1, b_dim.0),
+  };
+  let (c_m, c_n) = c_dim;
+  assert_eq!(c_m, at_m);
+  assert_eq!(c_n, bt_n);
+  assert_eq!(at_n, bt_m);
+  let k = at_n;
+  assert_eq!(1, a_stride.0);
+  assert_eq!(1, b_stride.0);
+  assert_eq!(1, c_stride.0);
+  if batch_sz == 0 || c_m == 0 || c_n == 0 {
+    return;
+  }
+  // The output matrices are written concurrently, so they must not overlap.
+  assert!(!aliases(&[c_m, c_n, batch_sz], &[c_stride.0, c_stride.1, c_stride.2]), "the output matrices overlap in memory");
+  unsafe { T::gemm_batch(
+      a_trans,
+      b_trans,
+      c_m, c_n, k,
+      alpha,
+      a_buf.as_ptr(), a_stride.1, a_stride.2,
+      b_buf.as_ptr(), b_stride.1, b_stride.2,
+      beta,
+      c_buf.as_mut_ptr(), c_stride.1, c_stride.2,
+      batch_sz,
+  ) };
+}
+
+/// The Cholesky factorization of a symmetric positive definite matrix, kept
+/// for solving several systems with the same matrix.
+pub struct CholeskyFactor<T> where T: BlasScalar {
+  factor:   Array2d<T>,
+  uplo:     Uplo,
+}
+
+impl<T> CholeskyFactor<T> where T: BlasScalar {
+  /// Factors a copy of `a`, of which only the `uplo` triangle is read.
+  pub fn new(a: Array2dView<T>, uplo: Uplo) -> Result<CholeskyFactor<T>, LinalgError> {
+    let (a_m, n) = a.dim();
+    assert_eq!(a_m, n);
+    let (a_inc, lda) = a.stride();
+    let mut factor = Array2d::zeros((n, n));
+    {
+      let f = factor.as_mut_slice();
+      for j in 0 .. n {
+        let (lo, hi) = match uplo {
+          Uplo::Lower => (j, n),
+          Uplo::Upper => (0, j + 1),
+        };
+        for i in lo .. hi {
+          f[i + j * n] = a.buf[i * a_inc + j * lda];
+        }
+      }
     }
+    factor.as_view_mut().cholesky_in_place(uplo)?;
+    Ok(CholeskyFactor{
+      factor:   factor,
+      uplo:     uplo,
+    })
+  }
+
+  pub fn dim(&self) -> usize {
+    self.factor.dim().0
+  }
+
+  pub fn uplo(&self) -> Uplo {
+    self.uplo
+  }
+
+  /// The triangular factor; the other triangle is zero.
+  pub fn factor<'a>(&'a self) -> Array2dView<'a, T> {
+    self.factor.as_view()
+  }
+
+  /// Solves `A * x = b` in place, where `x` holds `b` on entry.
+  pub fn solve(&self, mut x: Array1dViewMut<T>) {
+    let n = self.dim();
+    assert_eq!(x.dim(), n);
+    if n == 0 {
+      return;
+    }
+    let incx = x.stride();
+    // Forward substitution with the lower triangular factor, then back
+    // substitution with its transpose.
+    let (first, second) = match self.uplo {
+      Uplo::Lower => (Transpose::N, Transpose::T),
+      Uplo::Upper => (Transpose::T, Transpose::N),
+    };
+    for &a_trans in &[first, second] {
+      unsafe { T::trsv(
+          self.uplo,
+          a_trans,
+          n,
+          self.factor.as_slice().as_ptr(), n,
+          x.as_mut_ptr(), incx,
+      ) };
+    }
+  }
+
+  /// Solves `A * X = B` in place, where `b` holds `B` on entry.
+  pub fn solve_matrix(&self, mut b: Array2dViewMut<T>) {
+    let n = self.dim();
+    let (b_m, nrhs) = b.dim();
+    assert_eq!(b_m, n);
+    let (b_inc, ldb) = b.stride();
+    assert_eq!(1, b_inc);
+    if n == 0 || nrhs == 0 {
+      return;
+    }
+    let status = unsafe { T::potrs(
+        self.uplo,
+        n, nrhs,
+        self.factor.as_slice().as_ptr(), n,
+        b.as_mut_ptr(), ldb,
+    ) };
+    assert_eq!(0, status);
+  }
+}
+
+/// The LU factorization with partial pivoting `A = P * L * U` of a square
+/// matrix, kept for solving several systems with the same matrix.
+pub struct LuFactor<T> where T: BlasScalar {
+  lu:       Array2d<T>,
+  pivots:   Vec<i32>,
+}
+
+impl<T> LuFactor<T> where T: BlasScalar {
+  /// Factors a copy of `a`. Fails if a pivot is exactly zero; nearly
+  /// singular matrices are not detected.
+  pub fn new(a: Array2dView<T>) -> Result<LuFactor<T>, LinalgError> {
+    let (a_m, n) = a.dim();
+    assert_eq!(a_m, n);
+    let mut lu = Array2d::from_storage((n, n), packed_copy(a, n, n));
+    let mut pivots = vec![0; n];
+    if n > 0 {
+      let status = unsafe { T::getrf(
+          n, n,
+          lu.as_mut_slice().as_mut_ptr(), n,
+          pivots.as_mut_ptr(),
+      ) };
+      match lapack_status(status)? {
+        0 => {}
+        k => return Err(LinalgError::Singular(k)),
+      }
+    }
+    Ok(LuFactor{
+      lu:       lu,
+      pivots:   pivots,
+    })
+  }
+
+  pub fn dim(&self) -> usize {
+    self.lu.dim().0
+  }
+
+  /// `L` below the diagonal, with an implicit unit diagonal, and `U` on and
+  /// above it.
+  pub fn factors<'a>(&'a self) -> Array2dView<'a, T> {
+    self.lu.as_view()
+  }
+
+  /// The 1-based row interchanges: row `i` was swapped with row
+  /// `pivots()[i] - 1`.
+  pub fn pivots(&self) -> &[i32] {
+    &self.pivots
+  }
+
+  /// Solves `A * x = b` in place, where `x` holds `b` on entry.
+  pub fn solve(&self, mut x: Array1dViewMut<T>) {
+    let n = self.dim();
+    assert_eq!(x.dim(), n);
+    if n == 0 {
+      return;
+    }
+    let incx = x.stride();
+    // `getrs` takes a contiguous right-hand side, so a strided `x` is solved
+    // in a packed copy.
+    let mut packed: Vec<T> = if incx == 1 {
+      vec![]
+    } else {
+      (0 .. n).map(|i| x.buf[i * incx]).collect()
+    };
+    let b = if incx == 1 { x.as_mut_ptr() } else { packed.as_mut_ptr() };
+    let status = unsafe { T::getrs(
+        Transpose::N,
+        n, 1,
+        self.lu.as_slice().as_ptr(), n,
+        self.pivots.as_ptr(),
+        b, n,
+    ) };
+    assert_eq!(0, status);
+    for (i, &b_i) in packed.iter().enumerate() {
+      x.buf[i * incx] = b_i;
+    }
+  }
+
+  /// Solves `A * X = B` in place, where `b` holds `B` on entry.
+  pub fn solve_matrix(&self, mut b: Array2dViewMut<T>) {
+    let n = self.dim();
+    let (b_m, nrhs) = b.dim();
+    assert_eq!(b_m, n);
+    let (b_inc, ldb) = b.stride();
+    assert_eq!(1, b_inc);
+    if n == 0 || nrhs == 0 {
+      return;
+    }
+    let status = unsafe { T::getrs(
+        Transpose::N,
+        n, nrhs,
+        self.lu.as_slice().as_ptr(), n,
+        self.pivots.as_ptr(),
+        b.as_mut_ptr(), ldb,
+    ) };
+    assert_eq!(0, status);
+  }
+
+  pub fn inverse(&self) -> Array2d<T> {
+    let n = self.dim();
+    let mut inv = Array2d::from_storage((n, n), self.lu.as_slice().to_vec());
+    if n > 0 {
+      let status = unsafe { T::getri(
+          n,
+          inv.as_mut_slice().as_mut_ptr(), n,
+          self.pivots.as_ptr(),
+      ) };
+      assert_eq!(0, status);
+    }
+    inv
+  }
+
+  /// The determinant of `A`. This easily overflows or underflows for large
+  /// matrices; see `log_abs_determinant`.
+  pub fn determinant(&self) -> T {
+    let n = self.dim();
+    let u = self.lu.as_slice();
+    let mut det = self.determinant_sign();
+    for i in 0 .. n {
+      det = det * u[i + i * n].abs();
+    }
+    det
+  }
+
+  /// The sign of the determinant of `A`, as `1.0` or `-1.0`.
+  pub fn determinant_sign(&self) -> T {
+    let n = self.dim();
+    let u = self.lu.as_slice();
+    let mut sign = T::one();
+    for i in 0 .. n {
+      if u[i + i * n] < T::zero() {
+        sign = -sign;
+      }
+      if self.pivots[i] as usize != i + 1 {
+        sign = -sign;
+      }
+    }
+    sign
+  }
+
+  /// The natural logarithm of the absolute value of the determinant of `A`.
+  pub fn log_abs_determinant(&self) -> T {
+    let n = self.dim();
+    let u = self.lu.as_slice();
+    let mut log_det = 0.0;
+    for i in 0 .. n {
+      log_det += u[i + i * n].abs().to_f64().ln();
+    }
+    T::from_f64(log_det)
+  }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum QrMode {
+  /// `Q` is `m x m` and `R` is `m x n`.
+  Full,
+  /// `Q` is `m x k` and `R` is `k x n`, where `k = min(m, n)`.
+  Thin,
+}
+
+/// The factors of `A = Q * R`, where `Q` has orthonormal columns and `R` is
+/// upper triangular.
+pub struct Qr<T> where T: Copy {
+  pub q:    Array2d<T>,
+  pub r:    Array2d<T>,
+}
+
+/// The QR factorization of the `m x n` matrix `a`, without pivoting.
+pub fn qr<T>(a: Array2dView<T>, mode: QrMode) -> Qr<T> where T: BlasScalar {
+  let (m, n) = a.dim();
+  let k = min(m, n);
+  let q_n = match mode {
+    QrMode::Full => m,
+    QrMode::Thin => k,
+  };
+  // Column `j >= n` of the buffer is only used for the full `Q`.
+  let mut buf = packed_copy(a, m, max(n, q_n));
+  let mut tau = vec![T::zero_bits(); k];
+  if k > 0 {
+    let status = unsafe { T::geqrf(
+        m, n,
+        buf.as_mut_ptr(), m,
+        tau.as_mut_ptr(),
+    ) };
+    assert_eq!(0, status);
+  }
+  let mut r = Array2d::zeros((q_n, n));
+  {
+    let r_buf = r.as_mut_slice();
+    for j in 0 .. n {
+      for i in 0 .. min(j + 1, q_n) {
+        r_buf[i + j * q_n] = buf[i + j * m];
+      }
+    }
+  }
+  if q_n > 0 {
+    let status = unsafe { T::orgqr(
+        m, q_n, k,
+        buf.as_mut_ptr(), m,
+        tau.as_ptr(),
+    ) };
+    assert_eq!(0, status);
+  }
+  buf.truncate(m * q_n);
+  Qr{
+    q:    Array2d::from_storage((m, q_n), buf),
+    r:    r,
+  }
+}
+
+/// The solution of the least squares problem `min ||A * X - B||`.
+pub struct LeastSquares<T> where T: Copy {
+  /// The `n x nrhs` minimizer `X`.
+  pub solution:       Array2d<T>,
+  /// The residual norm `||A * x - b||` for each column of `B`.
+  pub residual_norms: Vec<T>,
+}
+
+/// Solves `min ||A * X - B||` for an `m x n` matrix `a` with `m >= n` and an
+/// `m x nrhs` matrix `b` via the QR factorization of `a`.
+///
+/// Fails with `RankDeficient(k)` if `|R(k, k)|` is below `m * epsilon`
+/// relative to the largest diagonal entry of `R`. Without column pivoting
+/// this catches most, but not all, nearly rank deficient matrices.
+pub fn least_squares<T>(a: Array2dView<T>, b: Array2dView<T>) -> Result<LeastSquares<T>, LinalgError> where T: BlasScalar {
+  let (m, n) = a.dim();
+  let (b_m, nrhs) = b.dim();
+  assert_eq!(b_m, m);
+  assert!(m >= n, "least squares needs at least as many rows as columns");
+  let mut a_buf = packed_copy(a, m, n);
+  let mut b_buf = packed_copy(b, m, nrhs);
+  if n > 0 && nrhs > 0 {
+    let status = unsafe { T::gels(
+        m, n, nrhs,
+        a_buf.as_mut_ptr(), m,
+        b_buf.as_mut_ptr(), m,
+    ) };
+    match lapack_status(status)? {
+      0 => {}
+      k => return Err(LinalgError::RankDeficient(k)),
+    }
+  } else if n > 0 {
+    let mut tau = vec![T::zero_bits(); n];
+    let status = unsafe { T::geqrf(
+        m, n,
+        a_buf.as_mut_ptr(), m,
+        tau.as_mut_ptr(),
+    ) };
+    assert_eq!(0, status);
+  }
+  // Either way `R` is now in the upper triangle of `a_buf`.
+  let mut r_max = T::zero();
+  for j in 0 .. n {
+    let r_jj = a_buf[j + j * m].abs();
+    if r_jj > r_max {
+      r_max = r_jj;
+    }
+  }
+  let tol = T::from_f64(m as f64) * T::epsilon() * r_max;
+  for j in 0 .. n {
+    if !(a_buf[j + j * m].abs() > tol) {
+      return Err(LinalgError::RankDeficient(j + 1));
+    }
+  }
+  let mut solution = Array2d::zeros((n, nrhs));
+  let mut residual_norms = Vec::with_capacity(nrhs);
+  {
+    let x_buf = solution.as_mut_slice();
+    for r in 0 .. nrhs {
+      x_buf[r * n .. (r + 1) * n].copy_from_slice(&b_buf[r * m .. r * m + n]);
+      // The rows of `Q^T * B` below `n` hold the residual.
+      residual_norms.push(unsafe { T::nrm2(
+          m - n,
+          b_buf[r * m + n .. ].as_ptr(),
+          1,
+      ) });
+    }
+  }
+  Ok(LeastSquares{
+    solution:       solution,
+    residual_norms: residual_norms,
+  })
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SvdMode {
+  /// `U` is `m x m` and `V^T` is `n x n`.
+  Full,
+  /// `U` is `m x k` and `V^T` is `k x n`, where `k = min(m, n)`.
+  Thin,
+  /// Only the singular values are computed.
+  ValuesOnly,
+}
+
+/// The singular value decomposition `A = U * diag(s) * V^T`.
+pub struct Svd<T> where T: Copy {
+  /// The left singular vectors as columns, unless only values were computed.
+  pub u:    Option<Array2d<T>>,
+  /// The `min(m, n)` singular values in descending order.
+  pub s:    Vec<T>,
+  /// The right singular vectors as rows, unless only values were computed.
+  pub vt:   Option<Array2d<T>>,
+}
+
+/// The singular value decomposition of the `m x n` matrix `a`.
+pub fn svd<T>(a: Array2dView<T>, mode: SvdMode) -> Result<Svd<T>, LinalgError> where T: BlasScalar {
+  let (m, n) = a.dim();
+  let k = min(m, n);
+  let (u_dim, vt_dim) = match mode {
+    SvdMode::Full       => ((m, m), (n, n)),
+    SvdMode::Thin       => ((m, k), (k, n)),
+    SvdMode::ValuesOnly => ((0, 0), (0, 0)),
+  };
+  let mut a_buf = packed_copy(a, m, n);
+  let mut s = vec![T::zero_bits(); k];
+  let mut u = Array2d::zeros(u_dim);
+  let mut vt = Array2d::zeros(vt_dim);
+  if k > 0 {
+    let status = unsafe { T::gesdd(
+        mode,
+        m, n,
+        a_buf.as_mut_ptr(), m,
+        s.as_mut_ptr(),
+        u.as_mut_slice().as_mut_ptr(), max(1, u_dim.0),
+        vt.as_mut_slice().as_mut_ptr(), max(1, vt_dim.0),
+    ) };
+    match lapack_status(status)? {
+      0 => {}
+      k => return Err(LinalgError::NoConvergence(k)),
+    }
+  } else if mode == SvdMode::Full {
+    // An empty `A` leaves the singular vectors arbitrary.
+    for i in 0 .. m {
+      u.as_mut_slice()[i + i * m] = T::one();
+    }
+    for i in 0 .. n {
+      vt.as_mut_slice()[i + i * n] = T::one();
+    }
+  }
+  let (u, vt) = match mode {
+    SvdMode::ValuesOnly => (None, None),
+    _ => (Some(u), Some(vt)),
+  };
+  Ok(Svd{
+    u:    u,
+    s:    s,
+    vt:   vt,
+  })
+}
+
+/// The singular values of `a` in descending order.
+pub fn singular_values<T>(a: Array2dView<T>) -> Result<Vec<T>, LinalgError> where T: BlasScalar {
+  svd(a, SvdMode::ValuesOnly).map(|svd| svd.s)
+}
+
+/// The singular values at or below this are treated as zero by `matrix_rank`
+/// and `pseudo_inverse`.
+fn rank_tol<T>(m: usize, n: usize, s: &[T]) -> T where T: BlasScalar {
+  let s_max = if s.is_empty() { T::zero() } else { s[0] };
+  T::from_f64(max(m, n) as f64) * T::epsilon() * s_max
+}
+
+/// The numerical rank of `a`: the number of singular values greater than
+/// `max(m, n) * epsilon` times the largest one.
+pub fn matrix_rank<T>(a: Array2dView<T>) -> Result<usize, LinalgError> where T: BlasScalar {
+  let (m, n) = a.dim();
+  let s = singular_values(a)?;
+  let tol = rank_tol(m, n, &s);
+  Ok(s.iter().filter(|&&s_i| s_i > tol).count())
+}
+
+/// The largest singular value of `a`, which is its operator 2-norm.
+pub fn spectral_norm<T>(a: Array2dView<T>) -> Result<T, LinalgError> where T: BlasScalar {
+  let s = singular_values(a)?;
+  Ok(if s.is_empty() { T::zero() } else { s[0] })
+}
+
+/// The sum of the singular values of `a`.
+pub fn nuclear_norm<T>(a: Array2dView<T>) -> Result<T, LinalgError> where T: BlasScalar {
+  let s = singular_values(a)?;
+  Ok(s.iter().fold(T::zero(), |sum, &s_i| sum + s_i))
+}
+
+/// The `n x m` Moore-Penrose pseudo-inverse of the `m x n` matrix `a`, with
+/// the singular values at or below the `matrix_rank` tolerance treated as
+/// zero.
+pub fn pseudo_inverse<T>(a: Array2dView<T>) -> Result<Array2d<T>, LinalgError> where T: BlasScalar {
+  let (m, n) = a.dim();
+  let Svd{u, s, vt} = svd(a, SvdMode::Thin)?;
+  let (u, mut vt) = (u.unwrap(), vt.unwrap());
+  let k = s.len();
+  let mut pinv = Array2d::zeros((n, m));
+  if k == 0 {
+    return Ok(pinv);
+  }
+  let tol = rank_tol(m, n, &s);
+  {
+    let vt_buf = vt.as_mut_slice();
+    for (i, &s_i) in s.iter().enumerate() {
+      let r = if s_i > tol { T::one() / s_i } else { T::zero() };
+      for j in 0 .. n {
+        vt_buf[i + j * k] = r * vt_buf[i + j * k];
+      }
+    }
+  }
+  // `A^+ = V * S^+ * U^T`, with `S^+` already applied to the rows of `V^T`.
+  pinv.as_view_mut().matrix_prod(T::one(), vt.as_view(), Transpose::T, u.as_view(), Transpose::T, T::zero());
+  Ok(pinv)
+}
+
+/// Which eigenvalues of a symmetric matrix to compute.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum EigenRange<T> {
+  All,
+  /// The eigenvalues in the half-open interval `(lo, hi]`.
+  Values(T, T),
+  /// The eigenvalues with the 0-based indices `lo .. hi` in ascending order.
+  Indices(usize, usize),
+}
+
+/// Eigenvalues and eigenvectors of a symmetric matrix.
+pub struct SymmetricEigen<T> where T: Copy {
+  /// The selected eigenvalues in ascending order.
+  pub values:   Vec<T>,
+  /// The orthonormal eigenvectors, as the columns of an `n x values.len()`
+  /// matrix.
+  pub vectors:  Array2d<T>,
+}
+
+fn symmetric_eigen_impl<T>(a: Array2dView<T>, uplo: Uplo, range: EigenRange<T>, vectors: bool) -> Result<(Vec<T>, Vec<T>), LinalgError> where T: BlasScalar {
+  let (n, a_n) = a.dim();
+  assert_eq!(n, a_n);
+  let max_m = match range {
+    EigenRange::All => n,
+    EigenRange::Values(lo, hi) => {
+      assert!(lo < hi);
+      n
+    }
+    EigenRange::Indices(lo, hi) => {
+      assert!(lo <= hi && hi <= n);
+      hi - lo
+    }
+  };
+  let mut a_buf = packed_copy(a, n, n);
+  let mut w = vec![T::zero_bits(); n];
+  let mut z = if vectors { vec![T::zero_bits(); n * max_m] } else { vec![] };
+  let mut m = 0;
+  if max_m > 0 {
+    let status = unsafe { T::syevr(
+        vectors,
+        range,
+        uplo,
+        n,
+        a_buf.as_mut_ptr(), n,
+        T::min_positive(),
+        &mut m,
+        w.as_mut_ptr(),
+        z.as_mut_ptr(), n,
+    ) };
+    match lapack_status(status)? {
+      0 => {}
+      k => return Err(LinalgError::NoConvergence(k)),
+    }
+  }
+  w.truncate(m);
+  z.truncate(n * m);
+  Ok((w, z))
+}
+
+/// The eigenvalues selected by `range` and their eigenvectors of the
+/// symmetric matrix `a`, of which only the `uplo` triangle is read.
+pub fn symmetric_eigen<T>(a: Array2dView<T>, uplo: Uplo, range: EigenRange<T>) -> Result<SymmetricEigen<T>, LinalgError> where T: BlasScalar {
+  let n = a.dim().0;
+  let (values, vectors) = symmetric_eigen_impl(a, uplo, range, true)?;
+  let m = values.len();
+  Ok(SymmetricEigen{
+    values:   values,
+    vectors:  Array2d::from_storage((n, m), vectors),
+  })
+}
+
+/// Like `symmetric_eigen`, but only computes the eigenvalues.
+pub fn symmetric_eigenvalues<T>(a: Array2dView<T>, uplo: Uplo, range: EigenRange<T>) -> Result<Vec<T>, LinalgError> where T: BlasScalar {
+  symmetric_eigen_impl(a, uplo, range, false).map(|(values, _)| values)
+}
+
+impl<'a, T> Array1dView<'a, T> where T: 'a + KernelScalar {
+  pub fn l1_norm(&'a self) -> T {
+    self.l1_norm_with(Summation::Naive)
+  }
+
+  pub fn l1_norm_with(&'a self, mode: Summation) -> T {
+    strided_sum(self.buf, self.dim(), self.stride(), mode, |x_i| x_i.abs())
+  }
+
+  pub fn elem_sum(&'a self) -> T {
+    self.elem_sum_with(Summation::Naive)
+  }
+
+  pub fn elem_sum_with(&'a self, mode: Summation) -> T {
+    strided_sum(self.buf, self.dim(), self.stride(), mode, |x_i| x_i)
+  }
+}
+
+impl<'a, T> Array1dViewMut<'a, T> where T: 'a + KernelScalar {
+  pub fn copy(&'a mut self, src: Array1dView<'a, T>) {
+    elem_map(
+        src.buf, &[src.dim], &[src.stride],
+        self.buf, &[self.dim], &[self.stride],
+        |n, x, y| unsafe { T::copy(y, n, x) },
+    );
+  }
+
+  pub fn cast(&'a mut self, src: Array1dView<'a, u8>) {
+    elem_map(
+        src.buf, &[src.dim], &[src.stride],
+        self.buf, &[self.dim], &[self.stride],
+        |n, x, y| unsafe { T::cast_u8(y, n, x) },
+    );
+  }
+
+  pub fn add_scalar(&'a mut self, c: T) {
+    elem_map_inplace(
+        self.buf, &[self.dim], &[self.stride],
+        |n, _, y| unsafe { T::add_scalar(y, n, c) },
+    );
+  }
+
+  pub fn vector_add_scalar(&'a mut self, c: T) {
+    self.add_scalar(c);
+  }
+
+  pub fn scale(&'a mut self, alpha: T) {
+    if self.stride() == 1 {
+      unsafe { T::scale(
+          self.buf.as_mut_ptr(),
+          self.dim(),
+          alpha,
+      ) };
+    } else {
+      let n = self.dim();
+      let incx = self.stride();
+      unsafe { <T as BlasScalar>::scal(
+          n,
+          alpha,
+          self.buf.as_mut_ptr(),
+          incx,
+      ) };
+    }
+  }
+
+  pub fn div_scalar(&'a mut self, c: T) {
+    elem_map_inplace(
+        self.buf, &[self.dim], &[self.stride],
+        |n, _, y| unsafe { T::div_scalar(y, n, c) },
+    );
+  }
+
+  pub fn square(&'a mut self) {
+    elem_map_inplace(
+        self.buf, &[self.dim], &[self.stride],
+        |n, _, y| unsafe { T::square(y, n) },
+    );
+  }
+
+  pub fn vector_square(&'a mut self) {
+    self.square();
+  }
+
+  pub fn cube(&'a mut self) {
+    elem_map_inplace(
+        self.buf, &[self.dim], &[self.stride],
+        |n, _, y| unsafe { T::cube(y, n) },
+    );
+  }
+
+  pub fn sqrt(&'a mut self) {
+    elem_map_inplace(
+        self.buf, &[self.dim], &[self.stride],
+        |n, _, y| unsafe { <T as KernelScalar>::sqrt(y, n) },
+    );
+  }
+
+  pub fn vector_sqrt(&'a mut self) {
+    self.sqrt();
+  }
+
+  pub fn reciprocal(&'a mut self) {
+    elem_map_inplace(
+        self.buf, &[self.dim], &[self.stride],
+        |n, _, y| unsafe { T::reciprocal(y, n) },
+    );
+  }
+
+  pub fn vector_recip(&'a mut self) {
+    self.reciprocal();
+  }
+
+  pub fn exp(&mut self) {
+    let n = self.dim();
+    let incx = self.stride();
+    let mut p = 0;
+    for _ in 0 .. n {
+      let x_i = self.buf[p];
+      self.buf[p] = x_i.exp();
+      p += incx;
+    }
+  }
+
+  pub fn add(&'a mut self, alpha: T, x: Array1dView<'a, T>) {
+    elem_map(
+        x.buf, &[x.dim], &[x.stride],
+        self.buf, &[self.dim], &[self.stride],
+        |n, x, y| unsafe { T::vector_add(y, n, x, alpha) },
+    );
+  }
+
+  pub fn vector_add(&'a mut self, alpha: T, x: Array1dView<'a, T>) {
+    self.add(alpha, x);
+  }
+
+  pub fn average(&'a mut self, alpha: T, x: Array1dView<'a, T>) {
+    elem_map(
+        x.buf, &[x.dim], &[x.stride],
+        self.buf, &[self.dim], &[self.stride],
+        |n, x, y| unsafe { T::vector_average(y, n, x, alpha) },
+    );
+  }
+
+  pub fn elem_mult(&'a mut self, /*alpha: T,*/ x: Array1dView<'a, T>) {
+    elem_map(
+        x.buf, &[x.dim], &[x.stride],
+        self.buf, &[self.dim], &[self.stride],
+        |n, x, y| unsafe { T::elem_mult(y, n, x) },
+    );
+  }
+
+  pub fn vector_elem_mult(&'a mut self, x: Array1dView<'a, T>) {
+    self.elem_mult(x);
+  }
+
+  pub fn elem_div(&'a mut self, x: Array1dView<'a, T>) {
+    elem_map(
+        x.buf, &[x.dim], &[x.stride],
+        self.buf, &[self.dim], &[self.stride],
+        |n, x, y| unsafe { T::elem_div(y, n, x) },
+    );
+  }
+
+  pub fn elem_ldiv(&'a mut self, x: Array1dView<'a, T>) {
+    elem_map(
+        x.buf, &[x.dim], &[x.stride],
+        self.buf, &[self.dim], &[self.stride],
+        |n, x, y| unsafe { T::elem_ldiv(y, n, x) },
+    );
+  }
+}
+
+impl<'a, T> Array2dView<'a, T> where T: 'a + KernelScalar {
+  pub fn l1_norm(&'a self) -> T {
+    self.l1_norm_with(Summation::Naive)
+  }
+
+  pub fn l1_norm_with(&'a self, mode: Summation) -> T {
+    nd_sum(self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1], mode, |x_i| x_i.abs())
+  }
+
+  pub fn elem_sum(&'a self) -> T {
+    self.elem_sum_with(Summation::Naive)
+  }
+
+  pub fn elem_sum_with(&'a self, mode: Summation) -> T {
+    nd_sum(self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1], mode, |x_i| x_i)
+  }
+
+  pub fn l2_norm(&'a self) -> T {
+    nd_l2_norm(self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1])
+  }
+
+  pub fn inner_prod(&'a self, alpha: T, y: Array2dView<'a, T>) -> T {
+    assert_eq!(self.dim, y.dim);
+    nd_inner_prod(alpha, self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1], y.buf, &[y.stride.0, y.stride.1])
+  }
+}
+
+impl<'a, T> Array2dViewMut<'a, T> where T: 'a + KernelScalar {
+  pub fn copy(&'a mut self, src: Array2dView<'a, T>) {
+    elem_map(
+        src.buf, &[src.dim.0, src.dim.1], &[src.stride.0, src.stride.1],
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        |n, x, y| unsafe { T::copy(y, n, x) },
+    );
+  }
+
+  pub fn cast(&'a mut self, src: Array2dView<'a, u8>) {
+    elem_map(
+        src.buf, &[src.dim.0, src.dim.1], &[src.stride.0, src.stride.1],
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        |n, x, y| unsafe { T::cast_u8(y, n, x) },
+    );
+  }
+
+  pub fn add_scalar(&'a mut self, c: T) {
+    elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        |n, _, y| unsafe { T::add_scalar(y, n, c) },
+    );
+  }
+
+  pub fn scale(&'a mut self, alpha: T) {
+    elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        |n, _, y| unsafe { T::scale(y, n, alpha) },
+    );
+  }
+
+  pub fn div_scalar(&'a mut self, c: T) {
+    elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        |n, _, y| unsafe { T::div_scalar(y, n, c) },
+    );
+  }
+
+  pub fn square(&'a mut self) {
+    elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        |n, _, y| unsafe { T::square(y, n) },
+    );
+  }
+
+  pub fn cube(&'a mut self) {
+    elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        |n, _, y| unsafe { T::cube(y, n) },
+    );
+  }
+
+  pub fn sqrt(&'a mut self) {
+    elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        |n, _, y| unsafe { <T as KernelScalar>::sqrt(y, n) },
+    );
+  }
+
+  pub fn reciprocal(&'a mut self) {
+    elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        |n, _, y| unsafe { T::reciprocal(y, n) },
+    );
+  }
+
+  pub fn exp(&mut self) {
+    elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        |n, _, y| {
+          for i in 0 .. n {
+            let y_i = unsafe { &mut *y.offset(i as isize) };
+            *y_i = y_i.exp();
+          }
+        },
+    );
+  }
+
+  pub fn add(&'a mut self, alpha: T, x: Array2dView<'a, T>) {
+    elem_map(
+        x.buf, &[x.dim.0, x.dim.1], &[x.stride.0, x.stride.1],
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        |n, x, y| unsafe { T::vector_add(y, n, x, alpha) },
+    );
+  }
+
+  pub fn average(&'a mut self, alpha: T, x: Array2dView<'a, T>) {
+    elem_map(
+        x.buf, &[x.dim.0, x.dim.1], &[x.stride.0, x.stride.1],
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        |n, x, y| unsafe { T::vector_average(y, n, x, alpha) },
+    );
+  }
+
+  pub fn elem_mult(&'a mut self, /*alpha: T,*/ x: Array2dView<'a, T>) {
+    elem_map(
+        x.buf, &[x.dim.0, x.dim.1], &[x.stride.0, x.stride.1],
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        |n, x, y| unsafe { T::elem_mult(y, n, x) },
+    );
+  }
+
+  pub fn elem_div(&'a mut self, x: Array2dView<'a, T>) {
+    elem_map(
+        x.buf, &[x.dim.0, x.dim.1], &[x.stride.0, x.stride.1],
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        |n, x, y| unsafe { T::elem_div(y, n, x) },
+    );
+  }
+
+  pub fn elem_ldiv(&'a mut self, x: Array2dView<'a, T>) {
+    elem_map(
+        x.buf, &[x.dim.0, x.dim.1], &[x.stride.0, x.stride.1],
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        |n, x, y| unsafe { T::elem_ldiv(y, n, x) },
+    );
+  }
+}
+
+impl<'a, T> Array3dView<'a, T> where T: 'a + KernelScalar {
+  pub fn l1_norm(&'a self) -> T {
+    self.l1_norm_with(Summation::Naive)
+  }
+
+  pub fn l1_norm_with(&'a self, mode: Summation) -> T {
+    nd_sum(self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2], mode, |x_i| x_i.abs())
+  }
+
+  pub fn elem_sum(&'a self) -> T {
+    self.elem_sum_with(Summation::Naive)
+  }
+
+  pub fn elem_sum_with(&'a self, mode: Summation) -> T {
+    nd_sum(self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2], mode, |x_i| x_i)
+  }
+
+  pub fn l2_norm(&'a self) -> T {
+    nd_l2_norm(self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2])
+  }
+
+  pub fn inner_prod(&'a self, alpha: T, y: Array3dView<'a, T>) -> T {
+    assert_eq!(self.dim, y.dim);
+    nd_inner_prod(alpha, self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2], y.buf, &[y.stride.0, y.stride.1, y.stride.2])
+  }
+}
+
+impl<'a, T> Array3dViewMut<'a, T> where T: 'a + KernelScalar {
+  pub fn copy(&'a mut self, src: Array3dView<'a, T>) {
+    elem_map(
+        src.buf, &[src.dim.0, src.dim.1, src.dim.2], &[src.stride.0, src.stride.1, src.stride.2],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        |n, x, y| unsafe { T::copy(y, n, x) },
+    );
+  }
+
+  pub fn cast(&'a mut self, src: Array3dView<'a, u8>) {
+    elem_map(
+        src.buf, &[src.dim.0, src.dim.1, src.dim.2], &[src.stride.0, src.stride.1, src.stride.2],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        |n, x, y| unsafe { T::cast_u8(y, n, x) },
+    );
+  }
+
+  pub fn add_scalar(&'a mut self, c: T) {
+    elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        |n, _, y| unsafe { T::add_scalar(y, n, c) },
+    );
+  }
+
+  pub fn scale(&'a mut self, alpha: T) {
+    elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        |n, _, y| unsafe { T::scale(y, n, alpha) },
+    );
+  }
+
+  pub fn div_scalar(&'a mut self, c: T) {
+    elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        |n, _, y| unsafe { T::div_scalar(y, n, c) },
+    );
+  }
+
+  pub fn square(&'a mut self) {
+    elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        |n, _, y| unsafe { T::square(y, n) },
+    );
+  }
+
+  pub fn cube(&'a mut self) {
+    elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        |n, _, y| unsafe { T::cube(y, n) },
+    );
+  }
+
+  pub fn sqrt(&'a mut self) {
+    elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        |n, _, y| unsafe { <T as KernelScalar>::sqrt(y, n) },
+    );
+  }
+
+  pub fn reciprocal(&'a mut self) {
+    elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        |n, _, y| unsafe { T::reciprocal(y, n) },
+    );
+  }
+
+  pub fn exp(&mut self) {
+    elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        |n, _, y| {
+          for i in 0 .. n {
+            let y_i = unsafe { &mut *y.offset(i as isize) };
+            *y_i = y_i.exp();
+          }
+        },
+    );
+  }
+
+  pub fn add(&'a mut self, alpha: T, x: Array3dView<'a, T>) {
+    elem_map(
+        x.buf, &[x.dim.0, x.dim.1, x.dim.2], &[x.stride.0, x.stride.1, x.stride.2],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        |n, x, y| unsafe { T::vector_add(y, n, x, alpha) },
+    );
   }
 
-  pub fn cast(&'a mut self, src: Array1dView<'a, u8>) {
-    assert_eq!(self.dim(), src.dim());
-    if self.stride() == 1 {
-      unsafe { densearray_cast_u8_to_f32(
-          self.buf.as_mut_ptr(),
-          self.dim(),
-          src.buf.as_ptr(),
-      ) };
-    } else {
-      unimplemented!();
-    }
+  pub fn average(&'a mut self, alpha: T, x: Array3dView<'a, T>) {
+    elem_map(
+        x.buf, &[x.dim.0, x.dim.1, x.dim.2], &[x.stride.0, x.stride.1, x.stride.2],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        |n, x, y| unsafe { T::vector_average(y, n, x, alpha) },
+    );
   }
 
-  pub fn add_scalar(&'a mut self, c: f32) {
-    if self.stride() == 1 {
-      unsafe { densearray_add_scalar_f32(
-          self.buf.as_mut_ptr(),
-          self.dim(),
-          c,
-      ) };
-    } else {
-      unimplemented!();
-    }
+  pub fn elem_mult(&'a mut self, /*alpha: T,*/ x: Array3dView<'a, T>) {
+    elem_map(
+        x.buf, &[x.dim.0, x.dim.1, x.dim.2], &[x.stride.0, x.stride.1, x.stride.2],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        |n, x, y| unsafe { T::elem_mult(y, n, x) },
+    );
   }
 
-  pub fn vector_add_scalar(&'a mut self, c: f32) {
-    self.add_scalar(c);
+  pub fn elem_div(&'a mut self, x: Array3dView<'a, T>) {
+    elem_map(
+        x.buf, &[x.dim.0, x.dim.1, x.dim.2], &[x.stride.0, x.stride.1, x.stride.2],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        |n, x, y| unsafe { T::elem_div(y, n, x) },
+    );
   }
 
-  pub fn scale(&'a mut self, alpha: f32) {
-    if self.stride() == 1 {
-      unsafe { densearray_scale_f32(
-          self.buf.as_mut_ptr(),
-          self.dim(),
-          alpha,
-      ) };
-    } else {
-      unimplemented!();
-    }
+  pub fn elem_ldiv(&'a mut self, x: Array3dView<'a, T>) {
+    elem_map(
+        x.buf, &[x.dim.0, x.dim.1, x.dim.2], &[x.stride.0, x.stride.1, x.stride.2],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        |n, x, y| unsafe { T::elem_ldiv(y, n, x) },
+    );
   }
+}
 
-  pub fn vector_scale(&'a mut self, alpha: f32) {
-    self.scale(alpha);
+impl<'a, T> Array4dView<'a, T> where T: 'a + KernelScalar {
+  pub fn l1_norm(&'a self) -> T {
+    self.l1_norm_with(Summation::Naive)
   }
 
-  pub fn div_scalar(&'a mut self, c: f32) {
-    if self.stride() == 1 {
-      unsafe { densearray_div_scalar_f32(
-          self.buf.as_mut_ptr(),
-          self.dim(),
-          c,
-      ) };
-    } else {
-      unimplemented!();
-    }
+  pub fn l1_norm_with(&'a self, mode: Summation) -> T {
+    nd_sum(self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3], mode, |x_i| x_i.abs())
   }
 
-  pub fn square(&'a mut self) {
-    if self.stride() == 1 {
-      unsafe { densearray_square_f32(
-          self.buf.as_mut_ptr(),
-          self.dim(),
-      ) };
-    } else {
-      unimplemented!();
-    }
+  pub fn elem_sum(&'a self) -> T {
+    self.elem_sum_with(Summation::Naive)
   }
 
-  pub fn vector_square(&'a mut self) {
-    self.square();
+  pub fn elem_sum_with(&'a self, mode: Summation) -> T {
+    nd_sum(self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3], mode, |x_i| x_i)
   }
 
-  pub fn cube(&'a mut self) {
-    if self.stride() == 1 {
-      unsafe { densearray_cube_f32(
-          self.buf.as_mut_ptr(),
-          self.dim(),
-      ) };
-    } else {
-      unimplemented!();
-    }
+  pub fn l2_norm(&'a self) -> T {
+    nd_l2_norm(self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3])
   }
 
-  pub fn sqrt(&'a mut self) {
-    if self.stride() == 1 {
-      unsafe { densearray_sqrt_f32(
-          self.buf.as_mut_ptr(),
-          self.dim(),
-      ) };
-    } else {
-      unimplemented!();
-    }
+  pub fn inner_prod(&'a self, alpha: T, y: Array4dView<'a, T>) -> T {
+    assert_eq!(self.dim, y.dim);
+    nd_inner_prod(alpha, self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3], y.buf, &[y.stride.0, y.stride.1, y.stride.2, y.stride.3])
   }
+}
 
-  pub fn vector_sqrt(&'a mut self) {
-    self.sqrt();
+impl<'a, T> Array4dViewMut<'a, T> where T: 'a + KernelScalar {
+  pub fn copy(&'a mut self, src: Array4dView<'a, T>) {
+    elem_map(
+        src.buf, &[src.dim.0, src.dim.1, src.dim.2, src.dim.3], &[src.stride.0, src.stride.1, src.stride.2, src.stride.3],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        |n, x, y| unsafe { T::copy(y, n, x) },
+    );
   }
 
-  pub fn reciprocal(&'a mut self) {
-    if self.stride() == 1 {
-      unsafe { densearray_reciprocal_f32(
-          self.buf.as_mut_ptr(),
-          self.dim(),
-      ) };
-    } else {
-      unimplemented!();
-    }
+  pub fn cast(&'a mut self, src: Array4dView<'a, u8>) {
+    elem_map(
+        src.buf, &[src.dim.0, src.dim.1, src.dim.2, src.dim.3], &[src.stride.0, src.stride.1, src.stride.2, src.stride.3],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        |n, x, y| unsafe { T::cast_u8(y, n, x) },
+    );
   }
 
-  pub fn vector_recip(&'a mut self) {
-    self.reciprocal();
+  pub fn add_scalar(&'a mut self, c: T) {
+    elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        |n, _, y| unsafe { T::add_scalar(y, n, c) },
+    );
   }
 
-  pub fn exp(&mut self) {
-    let n = self.dim();
-    let incx = self.stride();
-    let mut p = 0;
-    for _ in 0 .. n {
-      let x_i = self.buf[p];
-      self.buf[p] = x_i.exp();
-      p += incx;
-    }
+  pub fn scale(&'a mut self, alpha: T) {
+    elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        |n, _, y| unsafe { T::scale(y, n, alpha) },
+    );
   }
 
-  pub fn add(&'a mut self, alpha: f32, x: Array1dView<'a, f32>) {
-    if self.stride() == 1 {
-      unsafe { densearray_vector_add_f32(
-          self.buf.as_mut_ptr(),
-          self.dim(),
-          x.as_ptr(),
-          alpha,
-      ) };
-    } else {
-      unimplemented!();
-    }
+  pub fn div_scalar(&'a mut self, c: T) {
+    elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        |n, _, y| unsafe { T::div_scalar(y, n, c) },
+    );
   }
 
-  pub fn vector_add(&'a mut self, alpha: f32, x: Array1dView<'a, f32>) {
-    self.add(alpha, x);
+  pub fn square(&'a mut self) {
+    elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        |n, _, y| unsafe { T::square(y, n) },
+    );
   }
 
-  pub fn average(&'a mut self, alpha: f32, x: Array1dView<'a, f32>) {
-    if self.stride() == 1 {
-      unsafe { densearray_vector_average_f32(
-          self.buf.as_mut_ptr(),
-          self.dim(),
-          x.as_ptr(),
-          alpha,
-      ) };
-    } else {
-      unimplemented!();
-    }
+  pub fn cube(&'a mut self) {
+    elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        |n, _, y| unsafe { T::cube(y, n) },
+    );
   }
 
-  pub fn elem_mult(&'a mut self, /*alpha: f32,*/ x: Array1dView<'a, f32>) {
-    if self.stride() == 1 {
-      unsafe { densearray_elem_mult_f32(
-          self.buf.as_mut_ptr(),
-          self.dim(),
-          x.as_ptr(),
-      ) };
-    } else {
-      unimplemented!();
-    }
+  pub fn sqrt(&'a mut self) {
+    elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        |n, _, y| unsafe { <T as KernelScalar>::sqrt(y, n) },
+    );
   }
 
-  pub fn vector_elem_mult(&'a mut self, x: Array1dView<'a, f32>) {
-    self.elem_mult(x);
+  pub fn reciprocal(&'a mut self) {
+    elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        |n, _, y| unsafe { T::reciprocal(y, n) },
+    );
   }
 
-  pub fn elem_div(&'a mut self, x: Array1dView<'a, f32>) {
-    if self.stride() == 1 {
-      unsafe { densearray_elem_div_f32(
-          self.buf.as_mut_ptr(),
-          self.dim(),
-          x.as_ptr(),
-      ) };
-    } else {
-      unimplemented!();
-    }
+  pub fn exp(&mut self) {
+    elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        |n, _, y| {
+          for i in 0 .. n {
+            let y_i = unsafe { &mut *y.offset(i as isize) };
+            *y_i = y_i.exp();
+          }
+        },
+    );
   }
 
-  pub fn elem_ldiv(&'a mut self, x: Array1dView<'a, f32>) {
-    if self.stride() == 1 {
-      unsafe { densearray_elem_ldiv_f32(
-          self.buf.as_mut_ptr(),
-          self.dim(),
-          x.as_ptr(),
-      ) };
-    } else {
-      unimplemented!();
-    }
+  pub fn add(&'a mut self, alpha: T, x: Array4dView<'a, T>) {
+    elem_map(
+        x.buf, &[x.dim.0, x.dim.1, x.dim.2, x.dim.3], &[x.stride.0, x.stride.1, x.stride.2, x.stride.3],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        |n, x, y| unsafe { T::vector_add(y, n, x, alpha) },
+    );
   }
 
-  pub fn matrix_vector_prod(&'a mut self, alpha: f32, a: Array2dView<'a, f32>, a_trans: Transpose, x: Array1dView<'a, f32>, beta: f32) {
-    let (a_m, a_n) = a.dim();
-    let x_n = x.dim();
-    let y_m = self.dim();
-    let (at_m, at_n) = match a_trans {
-      Transpose::N => (a_m, a_n),
-      Transpose::T => (a_n, a_m),
-    };
-    assert_eq!(y_m, at_m);
-    assert_eq!(x_n, at_n);
-    let k = at_n;
-    let (a_inc, lda) = a.stride();
-    let x_inc = x.stride();
-    let y_inc = self.stride();
-    assert_eq!(1, a_inc);
-    unsafe { openblas_sequential_cblas_sgemv(
-        CblasOrder::ColMajor,
-        match a_trans {
-          Transpose::N => CblasTranspose::NoTrans,
-          Transpose::T => CblasTranspose::Trans,
-        },
-        a_m as _, a_n as _,
-        alpha,
-        a.buf.as_ptr(), lda as _,
-        x.buf.as_ptr(), x_inc as _,
-        beta,
-        self.buf.as_mut_ptr(), y_inc as _,
-    ) };
+  pub fn average(&'a mut self, alpha: T, x: Array4dView<'a, T>) {
+    elem_map(
+        x.buf, &[x.dim.0, x.dim.1, x.dim.2, x.dim.3], &[x.stride.0, x.stride.1, x.stride.2, x.stride.3],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        |n, x, y| unsafe { T::vector_average(y, n, x, alpha) },
+    );
   }
 
-  pub fn symm_linear_solve(&'a mut self, a: Array2dViewMut<'a, f32>, b: Array1dView<'a, f32>) {
-    let (a_m, n) = a.dim();
-    let b_n = b.dim();
-    let x_n = self.dim();
-    assert_eq!(a_m, n);
-    assert_eq!(b_n, n);
-    assert_eq!(x_n, n);
-    let (a_inc, lda) = a.stride();
-    let b_inc = b.stride();
-    let x_inc = self.stride();
-    assert_eq!(1, a_inc);
-    assert_eq!(1, b_inc);
-    assert_eq!(1, x_inc);
-    unsafe { openblas_sequential_LAPACKE_spotrf(
-        CblasOrder::ColMajor as i32,
-        'L' as i8,
-        n as _,
-        a.buf.as_mut_ptr(), lda as _,
-    ) };
-    { self.buf.copy_from_slice(b.buf) };
-    unsafe { openblas_sequential_LAPACKE_spotrs(
-        CblasOrder::ColMajor as i32,
-        'L' as i8,
-        n as _, 1,
-        a.buf.as_ptr(), lda as _,
-        self.buf.as_mut_ptr(), n as _,
-    ) };
+  pub fn elem_mult(&'a mut self, /*alpha: T,*/ x: Array4dView<'a, T>) {
+    elem_map(
+        x.buf, &[x.dim.0, x.dim.1, x.dim.2, x.dim.3], &[x.stride.0, x.stride.1, x.stride.2, x.stride.3],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        |n, x, y| unsafe { T::elem_mult(y, n, x) },
+    );
+  }
+
+  pub fn elem_div(&'a mut self, x: Array4dView<'a, T>) {
+    elem_map(
+        x.buf, &[x.dim.0, x.dim.1, x.dim.2, x.dim.3], &[x.stride.0, x.stride.1, x.stride.2, x.stride.3],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        |n, x, y| unsafe { T::elem_div(y, n, x) },
+    );
+  }
+
+  pub fn elem_ldiv(&'a mut self, x: Array4dView<'a, T>) {
+    elem_map(
+        x.buf, &[x.dim.0, x.dim.1, x.dim.2, x.dim.3], &[x.stride.0, x.stride.1, x.stride.2, x.stride.3],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        |n, x, y| unsafe { T::elem_ldiv(y, n, x) },
+    );
   }
 }
 
-impl<'a> Array2dView<'a, f32> {
-  pub fn matrix_diag(&'a self, y: Array1dViewMut<'a, f32>) {
+impl<'a, T> Array2dView<'a, T> where T: 'a + KernelScalar {
+  pub fn matrix_diag(&'a self, y: Array1dViewMut<'a, T>) {
     let (a_m, a_n) = self.dim();
     let y_m = y.dim();
     assert_eq!(a_m, a_n);
@@ -351,122 +1935,419 @@ impl<'a> Array2dView<'a, f32> {
   }
 }
 
-impl<'a> Array2dViewMut<'a, f32> {
-  pub fn matrix_add(&'a mut self, alpha: f32, x: Array2dView<'a, f32>) {
-    let (x_m, x_n) = x.dim();
-    let (y_m, y_n) = self.dim();
-    assert_eq!(x_m, y_m);
-    assert_eq!(x_n, y_n);
-    let (incx, ldx) = x.stride();
-    let (incy, ldy) = self.stride();
-    if x_n == 1 {
-      unsafe { openblas_sequential_cblas_saxpy(
-          x_m as _,
-          alpha,
-          x.buf.as_ptr(),
-          incx as _,
-          self.buf.as_mut_ptr(),
-          incy as _,
-      ) };
-    } else if x_m == 1 {
-      unimplemented!();
-    } else {
-      unimplemented!();
-    }
+impl<'a, T> Array2dView<'a, T> where T: 'a + KernelScalar {
+  /// Sums along `axis`; an empty axis gives zeros.
+  pub fn axis_sum(&'a self, axis: usize, y: Array1dViewMut<'a, T>) {
+    axis_reduce(
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        axis,
+        y.buf, &[y.dim], &[y.stride],
+        Some(T::zero()),
+        T::reduce_axis_sum,
+    );
   }
 
-  pub fn matrix_prod(&'a mut self, alpha: f32, a: Array2dView<'a, f32>, a_trans: Transpose, b: Array2dView<'a, f32>, b_trans: Transpose, beta: f32) {
-    let (a_m, a_n) = a.dim();
-    let (b_m, b_n) = b.dim();
-    let (c_m, c_n) = self.dim();
-    let (at_m, at_n) = match a_trans {
-      Transpose::N => (a_m, a_n),
-      Transpose::T => (a_n, a_m),
-    };
-    let (bt_m, bt_n) = match b_trans {
-      Transpose::N => (b_m, b_n),
-      Transpose::T => (b_n, b_m),
-    };
-    assert_eq!(c_m, at_m);
-    assert_eq!(c_n, bt_n);
-    assert_eq!(at_n, bt_m);
-    let k = at_n;
-    let (a_inc, lda) = a.stride();
-    let (b_inc, ldb) = b.stride();
-    let (c_inc, ldc) = self.stride();
-    assert_eq!(1, a_inc);
-    assert_eq!(1, b_inc);
-    assert_eq!(1, c_inc);
-    unsafe { openblas_sequential_cblas_sgemm(
-        CblasOrder::ColMajor,
-        match a_trans {
-          Transpose::N => CblasTranspose::NoTrans,
-          Transpose::T => CblasTranspose::Trans,
-        },
-        match b_trans {
-          Transpose::N => CblasTranspose::NoTrans,
-          Transpose::T => CblasTranspose::Trans,
-        },
-        c_m as _, c_n as _, k as _,
-        alpha,
-        a.buf.as_ptr(), lda as _,
-        b.buf.as_ptr(), ldb as _,
-        beta,
-        self.buf.as_mut_ptr(), ldc as _,
-    ) };
+  /// Averages along `axis`; an empty axis gives NaNs.
+  pub fn axis_mean(&'a self, axis: usize, y: Array1dViewMut<'a, T>) {
+    axis_reduce(
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        axis,
+        y.buf, &[y.dim], &[y.stride],
+        Some(T::from_f64(f64::NAN)),
+        T::reduce_axis_mean,
+    );
+  }
+
+  /// Like `axis_sum`, but sums each fiber in the order given by `mode`.
+  pub fn axis_sum_with(&'a self, axis: usize, y: Array1dViewMut<'a, T>, mode: Summation) {
+    nd_axis_sum(
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        axis,
+        y.buf, &[y.dim], &[y.stride],
+        mode, false,
+    );
+  }
+
+  /// Like `axis_mean`, but sums each fiber in the order given by `mode`.
+  pub fn axis_mean_with(&'a self, axis: usize, y: Array1dViewMut<'a, T>, mode: Summation) {
+    nd_axis_sum(
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        axis,
+        y.buf, &[y.dim], &[y.stride],
+        mode, true,
+    );
+  }
+
+  /// The maximum along `axis`. Panics if the axis is empty.
+  pub fn axis_max(&'a self, axis: usize, y: Array1dViewMut<'a, T>) {
+    axis_reduce(
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        axis,
+        y.buf, &[y.dim], &[y.stride],
+        None,
+        T::reduce_axis_max,
+    );
+  }
+
+  /// The minimum along `axis`. Panics if the axis is empty.
+  pub fn axis_min(&'a self, axis: usize, y: Array1dViewMut<'a, T>) {
+    axis_reduce(
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        axis,
+        y.buf, &[y.dim], &[y.stride],
+        None,
+        T::reduce_axis_min,
+    );
+  }
+
+  /// The index of the maximum along `axis`. Panics if the axis is empty.
+  pub fn axis_argmax(&'a self, axis: usize, y: Array1dViewMut<'a, u32>) {
+    axis_reduce(
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        axis,
+        y.buf, &[y.dim], &[y.stride],
+        None,
+        T::reduce_axis_argmax,
+    );
+  }
+
+  /// The index of the minimum along `axis`. Panics if the axis is empty.
+  pub fn axis_argmin(&'a self, axis: usize, y: Array1dViewMut<'a, u32>) {
+    axis_reduce(
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        axis,
+        y.buf, &[y.dim], &[y.stride],
+        None,
+        T::reduce_axis_argmin,
+    );
+  }
+}
+
+impl<'a, T> Array3dView<'a, T> where T: 'a + KernelScalar {
+  /// Sums along `axis`; an empty axis gives zeros.
+  pub fn axis_sum(&'a self, axis: usize, y: Array2dViewMut<'a, T>) {
+    axis_reduce(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        axis,
+        y.buf, &[y.dim.0, y.dim.1], &[y.stride.0, y.stride.1],
+        Some(T::zero()),
+        T::reduce_axis_sum,
+    );
+  }
+
+  /// Averages along `axis`; an empty axis gives NaNs.
+  pub fn axis_mean(&'a self, axis: usize, y: Array2dViewMut<'a, T>) {
+    axis_reduce(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        axis,
+        y.buf, &[y.dim.0, y.dim.1], &[y.stride.0, y.stride.1],
+        Some(T::from_f64(f64::NAN)),
+        T::reduce_axis_mean,
+    );
+  }
+
+  /// Like `axis_sum`, but sums each fiber in the order given by `mode`.
+  pub fn axis_sum_with(&'a self, axis: usize, y: Array2dViewMut<'a, T>, mode: Summation) {
+    nd_axis_sum(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        axis,
+        y.buf, &[y.dim.0, y.dim.1], &[y.stride.0, y.stride.1],
+        mode, false,
+    );
+  }
+
+  /// Like `axis_mean`, but sums each fiber in the order given by `mode`.
+  pub fn axis_mean_with(&'a self, axis: usize, y: Array2dViewMut<'a, T>, mode: Summation) {
+    nd_axis_sum(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        axis,
+        y.buf, &[y.dim.0, y.dim.1], &[y.stride.0, y.stride.1],
+        mode, true,
+    );
+  }
+
+  /// The maximum along `axis`. Panics if the axis is empty.
+  pub fn axis_max(&'a self, axis: usize, y: Array2dViewMut<'a, T>) {
+    axis_reduce(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        axis,
+        y.buf, &[y.dim.0, y.dim.1], &[y.stride.0, y.stride.1],
+        None,
+        T::reduce_axis_max,
+    );
+  }
+
+  /// The minimum along `axis`. Panics if the axis is empty.
+  pub fn axis_min(&'a self, axis: usize, y: Array2dViewMut<'a, T>) {
+    axis_reduce(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        axis,
+        y.buf, &[y.dim.0, y.dim.1], &[y.stride.0, y.stride.1],
+        None,
+        T::reduce_axis_min,
+    );
+  }
+
+  /// The index of the maximum along `axis`. Panics if the axis is empty.
+  pub fn axis_argmax(&'a self, axis: usize, y: Array2dViewMut<'a, u32>) {
+    axis_reduce(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        axis,
+        y.buf, &[y.dim.0, y.dim.1], &[y.stride.0, y.stride.1],
+        None,
+        T::reduce_axis_argmax,
+    );
+  }
+
+  /// The index of the minimum along `axis`. Panics if the axis is empty.
+  pub fn axis_argmin(&'a self, axis: usize, y: Array2dViewMut<'a, u32>) {
+    axis_reduce(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        axis,
+        y.buf, &[y.dim.0, y.dim.1], &[y.stride.0, y.stride.1],
+        None,
+        T::reduce_axis_argmin,
+    );
   }
 }
 
-impl<'a> Array2dViewMut<'a, f64> {
-  pub fn matrix_prod(&'a mut self) {
-    unimplemented!();
+impl<'a, T> Array4dView<'a, T> where T: 'a + KernelScalar {
+  /// Sums along `axis`; an empty axis gives zeros.
+  pub fn axis_sum(&'a self, axis: usize, y: Array3dViewMut<'a, T>) {
+    axis_reduce(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        axis,
+        y.buf, &[y.dim.0, y.dim.1, y.dim.2], &[y.stride.0, y.stride.1, y.stride.2],
+        Some(T::zero()),
+        T::reduce_axis_sum,
+    );
+  }
+
+  /// Averages along `axis`; an empty axis gives NaNs.
+  pub fn axis_mean(&'a self, axis: usize, y: Array3dViewMut<'a, T>) {
+    axis_reduce(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        axis,
+        y.buf, &[y.dim.0, y.dim.1, y.dim.2], &[y.stride.0, y.stride.1, y.stride.2],
+        Some(T::from_f64(f64::NAN)),
+        T::reduce_axis_mean,
+    );
+  }
+
+  /// Like `axis_sum`, but sums each fiber in the order given by `mode`.
+  pub fn axis_sum_with(&'a self, axis: usize, y: Array3dViewMut<'a, T>, mode: Summation) {
+    nd_axis_sum(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        axis,
+        y.buf, &[y.dim.0, y.dim.1, y.dim.2], &[y.stride.0, y.stride.1, y.stride.2],
+        mode, false,
+    );
+  }
+
+  /// Like `axis_mean`, but sums each fiber in the order given by `mode`.
+  pub fn axis_mean_with(&'a self, axis: usize, y: Array3dViewMut<'a, T>, mode: Summation) {
+    nd_axis_sum(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        axis,
+        y.buf, &[y.dim.0, y.dim.1, y.dim.2], &[y.stride.0, y.stride.1, y.stride.2],
+        mode, true,
+    );
+  }
+
+  /// The maximum along `axis`. Panics if the axis is empty.
+  pub fn axis_max(&'a self, axis: usize, y: Array3dViewMut<'a, T>) {
+    axis_reduce(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        axis,
+        y.buf, &[y.dim.0, y.dim.1, y.dim.2], &[y.stride.0, y.stride.1, y.stride.2],
+        None,
+        T::reduce_axis_max,
+    );
+  }
+
+  /// The minimum along `axis`. Panics if the axis is empty.
+  pub fn axis_min(&'a self, axis: usize, y: Array3dViewMut<'a, T>) {
+    axis_reduce(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        axis,
+        y.buf, &[y.dim.0, y.dim.1, y.dim.2], &[y.stride.0, y.stride.1, y.stride.2],
+        None,
+        T::reduce_axis_min,
+    );
+  }
+
+  /// The index of the maximum along `axis`. Panics if the axis is empty.
+  pub fn axis_argmax(&'a self, axis: usize, y: Array3dViewMut<'a, u32>) {
+    axis_reduce(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        axis,
+        y.buf, &[y.dim.0, y.dim.1, y.dim.2], &[y.stride.0, y.stride.1, y.stride.2],
+        None,
+        T::reduce_axis_argmax,
+    );
+  }
+
+  /// The index of the minimum along `axis`. Panics if the axis is empty.
+  pub fn axis_argmin(&'a self, axis: usize, y: Array3dViewMut<'a, u32>) {
+    axis_reduce(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        axis,
+        y.buf, &[y.dim.0, y.dim.1, y.dim.2], &[y.stride.0, y.stride.1, y.stride.2],
+        None,
+        T::reduce_axis_argmin,
+    );
   }
 }
 
-/*pub fn symmetric_tridiagonal_eigenvalues_workspace_size(dim: usize) -> usize {
-  // TODO: LAPACK `sstebz`.
-  unimplemented!();
-}*/
+/// Reusable buffers for `solve_symmetric_tridiagonal_eigenvalues`. They grow
+/// to the largest dimension seen, after which solves do not allocate. The
+/// workspace also keeps the blocks into which the last matrix split, which
+/// `solve_symmetric_tridiagonal_eigenvectors` needs.
+#[derive(Clone, Debug)]
+pub struct SymmetricTridiagonalEigenWorkspace {
+  iblock:         Vec<i32>,
+  isplit:         Vec<i32>,
+  num_eigenvals:  usize,
+}
+
+impl SymmetricTridiagonalEigenWorkspace {
+  /// A workspace for matrices of dimension up to `max_dim`.
+  pub fn new(max_dim: usize) -> SymmetricTridiagonalEigenWorkspace {
+    SymmetricTridiagonalEigenWorkspace{
+      iblock:         vec![0; max_dim],
+      isplit:         vec![0; max_dim],
+      num_eigenvals:  0,
+    }
+  }
+
+  pub fn max_dim(&self) -> usize {
+    self.iblock.len()
+  }
+
+  fn reserve(&mut self, dim: usize) {
+    if self.iblock.len() < dim {
+      self.iblock.resize(dim, 0);
+      self.isplit.resize(dim, 0);
+    }
+  }
+}
 
-pub fn solve_symmetric_tridiagonal_eigenvalues(
-    diag: &[f32],
-    offdiag: &[f32],
-    eigenvals: &mut [f32],
-    //workspace: &mut [u8],
-    abs_tol: f32,
-) -> Result<usize, ()>
+/// Computes the eigenvalues selected by `range` of the symmetric tridiagonal
+/// matrix with diagonal `diag` (length `n`) and off-diagonal `offdiag`
+/// (length `n - 1`) by bisection, into the front of `eigenvals` in ascending
+/// order, and returns how many there are. `eigenvals` needs room for `n`
+/// values, whatever the range. The rest of `eigenvals` is zeroed.
+pub fn solve_symmetric_tridiagonal_eigenvalues<T>(
+    diag: &[T],
+    offdiag: &[T],
+    range: EigenRange<T>,
+    eigenvals: &mut [T],
+    workspace: &mut SymmetricTridiagonalEigenWorkspace,
+    abs_tol: T,
+) -> Result<usize, LinalgError>
+where T: BlasScalar
 {
   let n = diag.len();
-  assert_eq!(n - 1, offdiag.len());
-  assert_eq!(n, eigenvals.len());
-  for i in 0 .. n {
-    eigenvals[i] = 0.0;
-  }
-  let mut m: i32 = 0;
-  let mut nsplit: i32 = 0;
-  /*let mut w: Vec<f32> = Vec::with_capacity(n);
-  w.resize(n, 0.0);*/
-  let mut iblock: Vec<i32> = Vec::with_capacity(n);
-  iblock.resize(n, 0);
-  let mut isplit: Vec<i32> = Vec::with_capacity(n);
-  isplit.resize(n, 0);
-  let status = unsafe { openblas_sequential_LAPACKE_sstebz(
-      'A' as i8,
-      'E' as i8,
-      n as _,
-      0.0, 0.0,
-      0, 0,
+  assert_eq!(max(n, 1), offdiag.len() + 1);
+  assert!(n <= eigenvals.len());
+  match range {
+    EigenRange::All => {}
+    EigenRange::Values(lo, hi) => assert!(lo < hi),
+    EigenRange::Indices(lo, hi) => assert!(lo <= hi && hi <= n),
+  }
+  for i in 0 .. eigenvals.len() {
+    eigenvals[i] = T::zero_bits();
+  }
+  workspace.num_eigenvals = 0;
+  if n == 0 {
+    return Ok(0);
+  }
+  if let EigenRange::Indices(lo, hi) = range {
+    if lo == hi {
+      return Ok(0);
+    }
+  }
+  workspace.reserve(n);
+  let mut m: usize = 0;
+  let mut nsplit: usize = 0;
+  let status = unsafe { T::stebz(
+      range,
+      n,
       abs_tol,
       diag.as_ptr(),
       offdiag.as_ptr(),
-      &mut m as *mut _,
-      &mut nsplit as *mut _,
+      &mut m,
+      &mut nsplit,
       eigenvals.as_mut_ptr(),
-      iblock.as_mut_ptr(),
-      isplit.as_mut_ptr(),
+      workspace.iblock.as_mut_ptr(),
+      workspace.isplit.as_mut_ptr(),
   ) };
-  if status < 0 {
-    return Err(());
+  match lapack_status(status)? {
+    0 => {
+      workspace.num_eigenvals = m;
+      Ok(m)
+    }
+    k => Err(LinalgError::NoConvergence(k)),
+  }
+}
+
+/// Computes by inverse iteration the eigenvectors of the symmetric
+/// tridiagonal matrix with diagonal `diag` and off-diagonal `offdiag` for
+/// the ascending `eigenvals` found by the last
+/// `solve_symmetric_tridiagonal_eigenvalues` call with `workspace`, into the
+/// columns of the `n x eigenvals.len()` matrix `eigenvecs`.
+pub fn solve_symmetric_tridiagonal_eigenvectors<T>(
+    diag: &[T],
+    offdiag: &[T],
+    eigenvals: &[T],
+    workspace: &SymmetricTridiagonalEigenWorkspace,
+    mut eigenvecs: Array2dViewMut<T>,
+) -> Result<(), LinalgError>
+where T: BlasScalar
+{
+  let n = diag.len();
+  let m = eigenvals.len();
+  assert_eq!(max(n, 1), offdiag.len() + 1);
+  assert_eq!((n, m), eigenvecs.dim());
+  assert_eq!(workspace.num_eigenvals, m);
+  let (z_inc, ldz) = eigenvecs.stride();
+  assert_eq!(1, z_inc);
+  if n == 0 || m == 0 {
+    return Ok(());
+  }
+  // `stein` wants the eigenvalues grouped by block, while `stebz` orders
+  // them over the whole matrix. If the matrix split, the eigenvectors are
+  // computed in block order into a packed matrix and then scattered back.
+  let iblock = &workspace.iblock[ .. m];
+  let grouped = iblock.windows(2).all(|b| b[0] <= b[1]);
+  let mut order: Vec<usize> = (0 .. m).collect();
+  let mut grouped_w = vec![];
+  let mut grouped_iblock = vec![];
+  let mut grouped_z = vec![];
+  if !grouped {
+    order.sort_by_key(|&k| iblock[k]);
+    grouped_w = order.iter().map(|&k| eigenvals[k]).collect();
+    grouped_iblock = order.iter().map(|&k| iblock[k]).collect();
+    grouped_z.resize(n * m, T::zero_bits());
+  }
+  let status = unsafe { T::stein(
+      n,
+      diag.as_ptr(),
+      offdiag.as_ptr(),
+      m,
+      if grouped { eigenvals.as_ptr() } else { grouped_w.as_ptr() },
+      if grouped { iblock.as_ptr() } else { grouped_iblock.as_ptr() },
+      workspace.isplit.as_ptr(),
+      if grouped { eigenvecs.as_mut_ptr() } else { grouped_z.as_mut_ptr() },
+      if grouped { ldz } else { n },
+  ) };
+  if !grouped {
+    for (c, &k) in order.iter().enumerate() {
+      eigenvecs.buf[k * ldz .. k * ldz + n].copy_from_slice(&grouped_z[c * n .. (c + 1) * n]);
+    }
+  }
+  match lapack_status(status)? {
+    0 => Ok(()),
+    k => Err(LinalgError::NoConvergence(k)),
   }
-  Ok(m as usize)
 }
@@ -0,0 +1,93 @@
+//! Rayon parallel iterators over views and batches (the `rayon` feature).
+//!
+//! A view is split along an axis into disjoint mutable sub-views, which are
+//! then handed out as a rayon `IndexedParallelIterator`. Splitting only goes
+//! through `split_at_mut`, so it requires that the sub-views along the axis
+//! occupy disjoint ranges of the buffer; e.g. the samples of a
+//! `BatchArray3d` (axis 3 of its 4d view) or the columns of a matrix do, but
+//! the rows of a column-major matrix interleave and are rejected.
+
+use super::{Array1dViewMut, Array2dViewMut, Array3dViewMut, Array4dViewMut, ArrayIndex, Batch};
+
+use rayon::prelude::*;
+use rayon::slice::{IterMut};
+use rayon::vec::{IntoIter};
+
+/// Splits `buf` into `len` disjoint slices of `sub_extent` elements which
+/// start `axis_stride` elements apart.
+fn split_axis_mut<'a, T>(buf: &'a mut [T], len: usize, axis_stride: usize, sub_extent: usize) -> Vec<&'a mut [T]> {
+  let mut bufs = Vec::with_capacity(len);
+  if sub_extent == 0 {
+    for _ in 0 .. len {
+      bufs.push(&mut [][..]);
+    }
+    return bufs;
+  }
+  assert!(len <= 1 || sub_extent <= axis_stride, "sub-views along the axis overlap in memory");
+  let mut rest = buf;
+  for i in 0 .. len {
+    if i + 1 < len {
+      let (head, tail) = {rest}.split_at_mut(axis_stride);
+      bufs.push(&mut head[ .. sub_extent]);
+      rest = tail;
+    } else {
+      bufs.push(&mut rest[ .. sub_extent]);
+      break;
+    }
+  }
+  bufs
+}
+
+impl<'a, T> Array2dViewMut<'a, T> where T: 'a + Copy + Send {
+  /// A parallel iterator over the 1d sub-views along `axis`. Panics if the
+  /// sub-views overlap in memory.
+  pub fn par_axis_iter_mut(self, axis: usize) -> IntoIter<Array1dViewMut<'a, T>> {
+    let (len, axis_stride, sub_dim, sub_stride) = match axis {
+      0 => (self.dim.0, self.stride.0, self.dim.1, self.stride.1),
+      1 => (self.dim.1, self.stride.1, self.dim.0, self.stride.0),
+      _ => panic!("invalid axis: {}", axis),
+    };
+    let sub_extent = sub_dim.extent(sub_stride);
+    let views: Vec<_> = split_axis_mut(self.buf, len, axis_stride, sub_extent).into_iter()
+      .map(|buf| Array1dViewMut{
+        buf:      buf,
+        dim:      sub_dim,
+        stride:   sub_stride,
+      })
+      .collect();
+    views.into_par_iter()
+  }
+}
+
+impl<'a, T> Array4dViewMut<'a, T> where T: 'a + Copy + Send {
+  /// A parallel iterator over the 3d sub-views along `axis`; axis 3 of a
+  /// `BatchArray3d` view iterates over its samples. Panics if the sub-views
+  /// overlap in memory.
+  pub fn par_axis_iter_mut(self, axis: usize) -> IntoIter<Array3dViewMut<'a, T>> {
+    let (d, s) = (self.dim, self.stride);
+    let (len, axis_stride, sub_dim, sub_stride) = match axis {
+      0 => (d.0, s.0, (d.1, d.2, d.3), (s.1, s.2, s.3)),
+      1 => (d.1, s.1, (d.0, d.2, d.3), (s.0, s.2, s.3)),
+      2 => (d.2, s.2, (d.0, d.1, d.3), (s.0, s.1, s.3)),
+      3 => (d.3, s.3, (d.0, d.1, d.2), (s.0, s.1, s.2)),
+      _ => panic!("invalid axis: {}", axis),
+    };
+    let sub_extent = sub_dim.extent(sub_stride);
+    let views: Vec<_> = split_axis_mut(self.buf, len, axis_stride, sub_extent).into_iter()
+      .map(|buf| Array3dViewMut{
+        buf:      buf,
+        dim:      sub_dim,
+        stride:   sub_stride,
+      })
+      .collect();
+    views.into_par_iter()
+  }
+}
+
+impl<A> Batch<A> where A: Send {
+  /// A parallel iterator over the first `batch_size()` elements.
+  pub fn par_iter_mut<'a>(&'a mut self) -> IterMut<'a, A> {
+    let batch_sz = self.batch_sz;
+    self.elems[ .. batch_sz].par_iter_mut()
+  }
+}
@@ -0,0 +1,353 @@
+//! A small persistent thread pool for the `parallel_*` ops which are
+//! implemented in Rust rather than by the OpenMP kernels.
+//!
+//! Work is split into chunks of at least `min_chunk_len()` elements, so that
+//! small arrays are processed on the calling thread without any
+//! synchronization. The calling thread always takes part in the work.
+//!
+//! `set_num_threads` and `with_num_threads` configure this pool, the OpenMP
+//! kernels, and the threaded BLAS backend together. Until then each uses its
+//! own default; for this pool that is the `DENSEARRAY_NUM_THREADS`
+//! environment variable, or else the available parallelism.
+
+use std::cell::{Cell};
+use std::cmp::{max, min};
+use std::collections::{VecDeque};
+use std::env;
+use std::mem::{transmute};
+use std::ops::{Range};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Condvar, Mutex, OnceLock};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread;
+
+use super::{SyncMutPtr};
+use backend::{BackendThreads, ParallelBackend};
+use kernels::{densearray_omp_get_num_threads_setting, densearray_omp_num_threads, densearray_omp_set_num_threads};
+
+const DEFAULT_MIN_CHUNK_LEN: usize = 16384;
+const CHUNKS_PER_THREAD: usize = 4;
+
+static MIN_CHUNK_LEN: AtomicUsize = AtomicUsize::new(DEFAULT_MIN_CHUNK_LEN);
+
+static NUM_THREADS: AtomicUsize = AtomicUsize::new(0);
+
+static POOL: OnceLock<&'static Pool> = OnceLock::new();
+
+static SCOPE_LOCK: Mutex<()> = Mutex::new(());
+
+thread_local!(static SCOPE_DEPTH: Cell<usize> = const { Cell::new(0) });
+
+/// Sets the minimum number of elements per chunk of work; arrays shorter
+/// than twice this are not split.
+pub fn set_min_chunk_len(len: usize) {
+  assert!(len > 0);
+  MIN_CHUNK_LEN.store(len, Ordering::SeqCst);
+}
+
+pub fn min_chunk_len() -> usize {
+  MIN_CHUNK_LEN.load(Ordering::SeqCst)
+}
+
+/// Runs `f` with `set_min_chunk_len(len)` in effect, and restores the
+/// previous setting afterwards, also if `f` panics. Like `with_num_threads`,
+/// concurrent calls from several threads are serialized.
+pub fn with_min_chunk_len<F, R>(len: usize, f: F) -> R where F: FnOnce() -> R {
+  struct Restore(usize);
+
+  impl Drop for Restore {
+    fn drop(&mut self) {
+      MIN_CHUNK_LEN.store(self.0, Ordering::SeqCst);
+    }
+  }
+
+  scoped(|| {
+    let _restore = Restore(min_chunk_len());
+    set_min_chunk_len(len);
+    f()
+  })
+}
+
+/// The number of threads, including the calling thread, that the Rust
+/// `parallel_*` ops are split across.
+pub fn num_threads() -> usize {
+  let num_threads = NUM_THREADS.load(Ordering::SeqCst);
+  if num_threads > 0 {
+    return num_threads;
+  }
+  let default_num_threads = env::var("DENSEARRAY_NUM_THREADS").ok()
+    .and_then(|s| s.parse::<usize>().ok())
+    .filter(|&n| n > 0)
+    .unwrap_or_else(|| thread::available_parallelism().map(|n| n.get()).unwrap_or(1));
+  match NUM_THREADS.compare_exchange(0, default_num_threads, Ordering::SeqCst, Ordering::SeqCst) {
+    Ok(_) => default_num_threads,
+    Err(num_threads) => num_threads,
+  }
+}
+
+/// Sets the number of threads used by the Rust `parallel_*` ops, the OpenMP
+/// kernels, and the threaded BLAS backend. This is process-wide.
+pub fn set_num_threads(num_threads: usize) {
+  assert!(num_threads > 0);
+  NUM_THREADS.store(num_threads, Ordering::SeqCst);
+  unsafe { densearray_omp_set_num_threads(num_threads as _) };
+  <ParallelBackend as BackendThreads>::set_num_threads(num_threads);
+}
+
+/// Runs `f` with `set_num_threads(num_threads)` in effect, and restores the
+/// previous settings afterwards, also if `f` panics. As the settings are
+/// process-wide, concurrent calls from several threads are serialized.
+pub fn with_num_threads<F, R>(num_threads: usize, f: F) -> R where F: FnOnce() -> R {
+  scoped(|| with_num_threads_impl(num_threads, f))
+}
+
+/// Runs `f` holding a process-wide lock, so that the scoped settings of
+/// concurrent threads are not interleaved. Nested scopes on the same thread
+/// do not take the lock again. The `with_*` setting wrappers here and in
+/// `parallel_linalg` all run under this lock.
+pub fn scoped<F, R>(f: F) -> R where F: FnOnce() -> R {
+  struct Exit;
+
+  impl Drop for Exit {
+    fn drop(&mut self) {
+      SCOPE_DEPTH.with(|depth| depth.set(depth.get() - 1));
+    }
+  }
+
+  let _guard = if SCOPE_DEPTH.with(|depth| depth.get()) == 0 {
+    Some(SCOPE_LOCK.lock().unwrap_or_else(|e| e.into_inner()))
+  } else {
+    None
+  };
+  SCOPE_DEPTH.with(|depth| depth.set(depth.get() + 1));
+  let _exit = Exit;
+  f()
+}
+
+fn with_num_threads_impl<F, R>(num_threads: usize, f: F) -> R where F: FnOnce() -> R {
+  struct Restore {
+    num_threads:      usize,
+    omp_num_threads:  usize,
+    blas_num_threads: usize,
+  }
+
+  impl Drop for Restore {
+    fn drop(&mut self) {
+      NUM_THREADS.store(self.num_threads, Ordering::SeqCst);
+      unsafe { densearray_omp_set_num_threads(self.omp_num_threads as _) };
+      <ParallelBackend as BackendThreads>::set_num_threads(self.blas_num_threads);
+    }
+  }
+
+  let _restore = Restore{
+    num_threads:      self::num_threads(),
+    omp_num_threads:  unsafe { densearray_omp_get_num_threads_setting() as usize },
+    blas_num_threads: <ParallelBackend as BackendThreads>::num_threads(),
+  };
+  set_num_threads(num_threads);
+  f()
+}
+
+/// The thread counts currently in effect.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ThreadSettings {
+  /// Threads used by the Rust `parallel_*` ops.
+  pub num_threads:      usize,
+  /// Threads used by the OpenMP kernels.
+  pub omp_num_threads:  usize,
+  /// Threads used by the `parallel_*` BLAS routines.
+  pub blas_num_threads: usize,
+}
+
+pub fn thread_settings() -> ThreadSettings {
+  ThreadSettings{
+    num_threads:      num_threads(),
+    omp_num_threads:  unsafe { densearray_omp_num_threads() as usize },
+    blas_num_threads: <ParallelBackend as BackendThreads>::num_threads(),
+  }
+}
+
+struct ScopeState {
+  closed:   bool,
+  active:   usize,
+}
+
+/// A single `parallel_for` call. The task pointer is only dereferenced by
+/// participants counted in `state.active`, and the caller closes the scope
+/// and waits for them before the task goes out of scope.
+struct Scope {
+  task:       *const (dyn Fn(usize) + Sync + 'static),
+  num_chunks: usize,
+  next:       AtomicUsize,
+  panicked:   AtomicBool,
+  state:      Mutex<ScopeState>,
+  done:       Condvar,
+}
+
+unsafe impl Send for Scope {}
+unsafe impl Sync for Scope {}
+
+impl Scope {
+  fn run_chunks(&self) {
+    let task = unsafe { &*self.task };
+    loop {
+      let chunk = self.next.fetch_add(1, Ordering::Relaxed);
+      if chunk >= self.num_chunks || self.panicked.load(Ordering::Relaxed) {
+        break;
+      }
+      if panic::catch_unwind(AssertUnwindSafe(|| task(chunk))).is_err() {
+        self.panicked.store(true, Ordering::Relaxed);
+      }
+    }
+  }
+
+  fn join(&self) {
+    {
+      let mut state = self.state.lock().unwrap();
+      if state.closed {
+        return;
+      }
+      state.active += 1;
+    }
+    self.run_chunks();
+    let mut state = self.state.lock().unwrap();
+    state.active -= 1;
+    if state.active == 0 {
+      self.done.notify_all();
+    }
+  }
+
+  fn close_and_wait(&self) {
+    let mut state = self.state.lock().unwrap();
+    state.closed = true;
+    while state.active > 0 {
+      state = self.done.wait(state).unwrap();
+    }
+  }
+}
+
+struct Pool {
+  queue:        Mutex<VecDeque<Arc<Scope>>>,
+  ready:        Condvar,
+  num_workers:  Mutex<usize>,
+}
+
+impl Pool {
+  fn worker_loop(&'static self) {
+    loop {
+      let scope = {
+        let mut queue = self.queue.lock().unwrap();
+        loop {
+          match queue.pop_front() {
+            Some(scope) => break scope,
+            None => queue = self.ready.wait(queue).unwrap(),
+          }
+        }
+      };
+      scope.join();
+    }
+  }
+
+  /// Spawns workers until there are at least `num_workers`. Workers are
+  /// never shut down; extra ones just stay idle.
+  fn reserve_workers(&'static self, num_workers: usize) {
+    let mut current = self.num_workers.lock().unwrap();
+    while *current < num_workers {
+      thread::Builder::new()
+        .name(format!("densearray-worker-{}", *current))
+        .spawn(move || self.worker_loop())
+        .unwrap();
+      *current += 1;
+    }
+  }
+}
+
+fn pool() -> &'static Pool {
+  *POOL.get_or_init(|| {
+    Box::leak(Box::new(Pool{
+      queue:        Mutex::new(VecDeque::new()),
+      ready:        Condvar::new(),
+      num_workers:  Mutex::new(0),
+    }))
+  })
+}
+
+/// Calls `f(chunk)` for each `chunk` in `0 .. num_chunks`, on the pool
+/// threads and the calling thread. Panics in `f` are propagated to the
+/// caller once all participants have finished.
+pub fn parallel_chunks<F>(num_chunks: usize, f: F) where F: Fn(usize) + Sync {
+  if num_chunks == 0 {
+    return;
+  }
+  let num_helpers = min(num_threads() - 1, num_chunks - 1);
+  if num_helpers == 0 {
+    for chunk in 0 .. num_chunks {
+      f(chunk);
+    }
+    return;
+  }
+  let task: &(dyn Fn(usize) + Sync) = &f;
+  let scope = Arc::new(Scope{
+    task:       unsafe { transmute::<&(dyn Fn(usize) + Sync), &'static (dyn Fn(usize) + Sync)>(task) },
+    num_chunks: num_chunks,
+    next:       AtomicUsize::new(0),
+    panicked:   AtomicBool::new(false),
+    state:      Mutex::new(ScopeState{closed: false, active: 0}),
+    done:       Condvar::new(),
+  });
+  let pool = pool();
+  pool.reserve_workers(num_helpers);
+  {
+    let mut queue = pool.queue.lock().unwrap();
+    for _ in 0 .. num_helpers {
+      queue.push_back(scope.clone());
+    }
+  }
+  pool.ready.notify_all();
+  scope.join();
+  scope.close_and_wait();
+  if scope.panicked.load(Ordering::SeqCst) {
+    panic!("a parallel_chunks task panicked");
+  }
+}
+
+/// Splits `0 .. len` into contiguous ranges of at least `min_chunk_len()`
+/// elements and calls `f` on each of them in parallel.
+pub fn parallel_for<F>(len: usize, f: F) where F: Fn(Range<usize>) + Sync {
+  parallel_for_items(len, 1, f);
+}
+
+/// Like `parallel_for`, but for `len` items which each stand for `item_len`
+/// elements, e.g. the rows of a matrix; the ranges then cover at least
+/// `min_chunk_len()` elements.
+pub fn parallel_for_items<F>(len: usize, item_len: usize, f: F) where F: Fn(Range<usize>) + Sync {
+  let num_chunks = max(1, min(min(len.saturating_mul(item_len) / min_chunk_len(), len), CHUNKS_PER_THREAD * num_threads()));
+  if num_chunks == 1 {
+    f(0 .. len);
+    return;
+  }
+  let chunk_len = (len + num_chunks - 1) / num_chunks;
+  parallel_chunks(num_chunks, |chunk| {
+    let start = chunk * chunk_len;
+    let end = min(start + chunk_len, len);
+    if start < end {
+      f(start .. end);
+    }
+  });
+}
+
+/// Calls `f` on each element of the strided 1d array `(buf, dim, stride)`
+/// in parallel.
+pub fn parallel_for_each_mut<T, F>(buf: &mut [T], dim: usize, stride: usize, f: F) where T: Send, F: Fn(&mut T) + Sync {
+  if dim == 0 {
+    return;
+  }
+  assert!(stride > 0 || dim == 1);
+  assert!(1 + (dim - 1) * stride <= buf.len());
+  let ptr = SyncMutPtr(buf.as_mut_ptr());
+  parallel_for(dim, |range| {
+    for i in range {
+      f(unsafe { &mut *ptr.0.offset((i * stride) as isize) });
+    }
+  });
+}
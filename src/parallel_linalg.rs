@@ -1,229 +1,1205 @@
-use super::{Array1dView, Array1dViewMut, Array2dView, Array2dViewMut};
+use super::{ZeroBits, Array1dView, Array1dViewMut, Array2dView, Array2dViewMut, Array3dView, Array3dViewMut, Array4dView, Array4dViewMut, Lanes, SyncConstPtr, SyncMutPtr, axis_reduce, check_extent, combine_norms, parallel_elem_map, parallel_elem_map_inplace};
+use blas::{BlasScalar};
 use kernels::*;
 use linalg::{Transpose};
+use parallel::{num_threads, parallel_for_each_mut, parallel_for_items, scoped};
 
-use cblas_ffi::*;
-use openblas_ffi::*;
+use std::cmp::{max, min};
+use std::ops::{Add};
+use std::slice::{from_raw_parts_mut};
+use std::sync::atomic::{AtomicBool, Ordering};
 
-impl<'a> Array1dView<'a, f32> {
-  pub fn parallel_l2_norm(&'a self) -> f32 {
+const DETERMINISTIC_CHUNK_SZ: usize = 4096;
+
+static DETERMINISTIC_REDUCTIONS: AtomicBool = AtomicBool::new(false);
+
+/// When enabled, `parallel_*` sums, inner products and norms are computed
+/// over a fixed chunk tree, so their results are bit-identical regardless of
+/// the number of threads. Otherwise sums and l1 norms are chunked per
+/// thread, and l2 norms and inner products defer to the threaded BLAS.
+pub fn set_deterministic_reductions(flag: bool) {
+  DETERMINISTIC_REDUCTIONS.store(flag, Ordering::SeqCst);
+}
+
+pub fn deterministic_reductions() -> bool {
+  DETERMINISTIC_REDUCTIONS.load(Ordering::SeqCst)
+}
+
+/// Runs `f` with `set_deterministic_reductions(flag)` in effect, and restores
+/// the previous setting afterwards, also if `f` panics. Like
+/// `parallel::with_num_threads`, concurrent calls from several threads are
+/// serialized.
+pub fn with_deterministic_reductions<F, R>(flag: bool, f: F) -> R where F: FnOnce() -> R {
+  struct Restore(bool);
+
+  impl Drop for Restore {
+    fn drop(&mut self) {
+      set_deterministic_reductions(self.0);
+    }
+  }
+
+  scoped(|| {
+    let _restore = Restore(deterministic_reductions());
+    set_deterministic_reductions(flag);
+    f()
+  })
+}
+
+fn tree_combine<T>(mut partials: Vec<T>) -> T where T: ZeroBits + Add<Output=T> {
+  if partials.is_empty() {
+    return T::zero_bits();
+  }
+  let mut len = partials.len();
+  while len > 1 {
+    for i in 0 .. len / 2 {
+      partials[i] = partials[2 * i] + partials[2 * i + 1];
+    }
+    if len % 2 == 1 {
+      partials[len / 2] = partials[len - 1];
+    }
+    len = (len + 1) / 2;
+  }
+  partials[0]
+}
+
+/// Reduces `dim` elements with `chunk_kernel(chunk_sz, partials)`, which
+/// writes one partial result per chunk of `chunk_sz` elements, and combines
+/// the partials over a fixed tree.
+fn chunked_reduce<T, F>(dim: usize, chunk_sz: usize, chunk_kernel: F) -> T where T: ZeroBits + Add<Output=T>, F: FnOnce(usize, *mut T) {
+  let num_chunks = (dim + chunk_sz - 1) / chunk_sz;
+  let mut partials = Vec::with_capacity(num_chunks);
+  partials.resize(num_chunks, T::zero_bits());
+  chunk_kernel(chunk_sz, partials.as_mut_ptr());
+  tree_combine(partials)
+}
+
+fn deterministic_reduce<T, F>(dim: usize, chunk_kernel: F) -> T where T: ZeroBits + Add<Output=T>, F: FnOnce(usize, *mut T) {
+  chunked_reduce(dim, DETERMINISTIC_CHUNK_SZ, chunk_kernel)
+}
+
+/// Like `deterministic_reduce` when `deterministic_reductions()` is enabled;
+/// otherwise the chunks are sized to give one per thread.
+fn switched_reduce<T, F>(dim: usize, chunk_kernel: F) -> T where T: ZeroBits + Add<Output=T>, F: FnOnce(usize, *mut T) {
+  if deterministic_reductions() {
+    deterministic_reduce(dim, chunk_kernel)
+  } else {
+    let num_threads = num_threads();
+    chunked_reduce(dim, max(DETERMINISTIC_CHUNK_SZ, (dim + num_threads - 1) / num_threads), chunk_kernel)
+  }
+}
+
+/// The l2 norm of a strided vector, from the norms of its chunks of
+/// `DETERMINISTIC_CHUNK_SZ` elements, which are combined with scaling like
+/// the serial `nrm2` so that the result does not overflow either.
+fn deterministic_l2_norm<T>(buf: &[T], n: usize, incx: usize) -> T where T: BlasScalar + ZeroBits + Send + Sync {
+  let num_chunks = (n + DETERMINISTIC_CHUNK_SZ - 1) / DETERMINISTIC_CHUNK_SZ;
+  let mut norms = Vec::with_capacity(num_chunks);
+  norms.resize(num_chunks, T::zero_bits());
+  {
+    let x_ptr = SyncConstPtr(buf.as_ptr());
+    let norms_ptr = SyncMutPtr(norms.as_mut_ptr());
+    parallel_for_items(num_chunks, DETERMINISTIC_CHUNK_SZ, |range| {
+      for chunk in range {
+        let offset = chunk * DETERMINISTIC_CHUNK_SZ;
+        unsafe { *norms_ptr.0.offset(chunk as isize) = T::nrm2(
+            min(DETERMINISTIC_CHUNK_SZ, n - offset),
+            x_ptr.0.offset((offset * incx) as isize),
+            incx,
+        ) };
+      }
+    });
+  }
+  combine_norms(&norms)
+}
+
+/// Reduces each 1d lane (see `Lanes`) of a strided array `x`, or of `x` and
+/// an equally shaped `y`, with `kernel(n, x, incx, y, incy)`. The lanes are
+/// split across the `parallel` pool, and their results are combined over a
+/// fixed tree, so the result does not depend on the number of threads.
+fn parallel_lane_reduce<T, F>(x_buf: &[T], x_dim: &[usize], x_stride: &[usize], y_buf: &[T], y_stride: &[usize], kernel: F) -> T where T: ZeroBits + Add<Output=T> + Send + Sync, F: Fn(usize, *const T, usize, *const T, usize) -> T + Sync {
+  tree_combine(parallel_lane_partials(x_buf, x_dim, x_stride, y_buf, y_stride, kernel))
+}
+
+/// The per-lane results of `parallel_lane_reduce`, in lane order.
+fn parallel_lane_partials<T, F>(x_buf: &[T], x_dim: &[usize], x_stride: &[usize], y_buf: &[T], y_stride: &[usize], kernel: F) -> Vec<T> where T: ZeroBits + Send + Sync, F: Fn(usize, *const T, usize, *const T, usize) -> T + Sync {
+  check_extent(x_buf.len(), x_dim, x_stride);
+  check_extent(y_buf.len(), x_dim, y_stride);
+  let lanes = Lanes::new(x_dim, x_stride, y_stride, false);
+  let mut partials = Vec::with_capacity(lanes.count);
+  partials.resize(lanes.count, T::zero_bits());
+  {
+    let x_ptr = SyncConstPtr(x_buf.as_ptr());
+    let y_ptr = SyncConstPtr(y_buf.as_ptr());
+    let partials_ptr = SyncMutPtr(partials.as_mut_ptr());
+    parallel_for_items(lanes.count, lanes.len, |range| {
+      for lane in range {
+        let (x_offset, y_offset) = lanes.offsets(lane);
+        unsafe { *partials_ptr.0.offset(lane as isize) = kernel(
+            lanes.len,
+            x_ptr.0.offset(x_offset as isize),
+            lanes.x_inc,
+            y_ptr.0.offset(y_offset as isize),
+            lanes.y_inc,
+        ) };
+      }
+    });
+  }
+  partials
+}
+
+/// Like `parallel_lane_reduce`, but an array which collapses to a single
+/// lane is reduced as a 1d view by `packed_kernel` instead, which may itself
+/// be threaded.
+fn parallel_nd_reduce<T, F, G>(x_buf: &[T], x_dim: &[usize], x_stride: &[usize], y_buf: &[T], y_stride: &[usize], kernel: F, packed_kernel: G) -> T where T: ZeroBits + Add<Output=T> + Send + Sync, F: Fn(usize, *const T, usize, *const T, usize) -> T + Sync, G: FnOnce(Array1dView<T>, Array1dView<T>) -> T {
+  let lanes = Lanes::new(x_dim, x_stride, y_stride, false);
+  if lanes.count == 1 {
+    let x = Array1dView{buf: x_buf, dim: lanes.len, stride: lanes.x_inc};
+    let y = Array1dView{buf: y_buf, dim: lanes.len, stride: lanes.y_inc};
+    return packed_kernel(x, y);
+  }
+  parallel_lane_reduce(x_buf, x_dim, x_stride, y_buf, y_stride, kernel)
+}
+
+impl<'a, T> Array1dView<'a, T> where T: 'a + KernelScalar {
+  pub fn parallel_l1_norm(&'a self) -> T {
     let n = self.dim();
     let incx = self.stride();
-    if cfg!(not(feature = "mkl_parallel")) {
-      unsafe { openblas_parallel_cblas_snrm2(
-          n as _,
-          self.buf.as_ptr(),
-          incx as _,
-      ) }
+    switched_reduce(n, |chunk_sz, partials| unsafe { T::omp_chunk_abs_sum(
+        self.buf.as_ptr(),
+        n,
+        incx,
+        chunk_sz,
+        partials,
+    ) })
+  }
+
+  pub fn parallel_l2_norm(&'a self) -> T {
+    let n = self.dim();
+    let incx = self.stride();
+    if deterministic_reductions() {
+      deterministic_l2_norm(self.buf, n, incx)
     } else {
-      unsafe { cblas_snrm2(
-          n as _,
+      unsafe { T::parallel_nrm2(
+          n,
           self.buf.as_ptr(),
-          incx as _,
+          incx,
       ) }
     }
   }
 
-  pub fn parallel_inner_prod(&'a self, alpha: f32, y: Array1dView<'a, f32>) -> f32 {
+  pub fn parallel_inner_prod(&'a self, alpha: T, y: Array1dView<'a, T>) -> T {
     let x_n = self.dim();
     let y_n = y.dim();
     assert_eq!(x_n, y_n);
     let incx = self.stride();
     let incy = y.stride();
-    if cfg!(not(feature = "mkl_parallel")) {
-      unsafe { openblas_parallel_cblas_sdot(
-          x_n as _,
-          alpha,
+    if deterministic_reductions() {
+      alpha * deterministic_reduce(x_n, |chunk_sz, partials| unsafe { T::omp_chunk_inner_prod(
           self.buf.as_ptr(),
-          incx as _,
+          x_n,
+          incx,
           y.as_ptr(),
-          incy as _,
-      ) }
+          incy,
+          chunk_sz,
+          partials,
+      ) })
     } else {
-      unsafe { cblas_sdot(
-          x_n as _,
+      unsafe { T::parallel_dot(
+          x_n,
           alpha,
           self.buf.as_ptr(),
-          incx as _,
+          incx,
           y.as_ptr(),
-          incy as _,
+          incy,
       ) }
     }
   }
+
+  pub fn parallel_elem_sum(&'a self) -> T {
+    let n = self.dim();
+    let incx = self.stride();
+    switched_reduce(n, |chunk_sz, partials| unsafe { T::omp_chunk_sum(
+        self.buf.as_ptr(),
+        n,
+        incx,
+        chunk_sz,
+        partials,
+    ) })
+  }
 }
 
-impl<'a> Array1dViewMut<'a, f32> {
-  pub fn parallel_copy(&'a mut self, src: Array1dView<'a, f32>) {
-    assert_eq!(self.dim(), src.dim());
-    if self.stride() == 1 {
-      unsafe { densearray_omp_copy_f32(
-          self.buf.as_mut_ptr(),
-          self.dim(),
-          src.buf.as_ptr(),
-      ) };
-    } else {
-      unimplemented!();
-    }
+impl<'a, T> Array1dViewMut<'a, T> where T: 'a + KernelScalar {
+  pub fn parallel_copy(&'a mut self, src: Array1dView<'a, T>) {
+    parallel_elem_map(
+        src.buf, &[src.dim], &[src.stride],
+        self.buf, &[self.dim], &[self.stride],
+        |n, x, y| unsafe { T::copy(y, n, x) },
+        |n, x, y| unsafe { T::omp_copy(y, n, x) },
+    );
   }
 
   pub fn parallel_cast(&'a mut self, src: Array1dView<'a, u8>) {
-    assert_eq!(self.dim(), src.dim());
-    if self.stride() == 1 {
-      unsafe { densearray_omp_cast_u8_to_f32(
-          self.buf.as_mut_ptr(),
-          self.dim(),
-          src.buf.as_ptr(),
-      ) };
-    } else {
-      unimplemented!();
-    }
+    parallel_elem_map(
+        src.buf, &[src.dim], &[src.stride],
+        self.buf, &[self.dim], &[self.stride],
+        |n, x, y| unsafe { T::cast_u8(y, n, x) },
+        |n, x, y| unsafe { T::omp_cast_u8(y, n, x) },
+    );
   }
 
-  pub fn parallel_add_scalar(&'a mut self, c: f32) {
-    if self.stride() == 1 {
-      unsafe { densearray_omp_add_scalar_f32(
-          self.buf.as_mut_ptr(),
-          self.dim(),
-          c,
-      ) };
-    } else {
-      unimplemented!();
-    }
+  pub fn parallel_add_scalar(&'a mut self, c: T) {
+    parallel_elem_map_inplace(
+        self.buf, &[self.dim], &[self.stride],
+        |n, _, y| unsafe { T::add_scalar(y, n, c) },
+        |n, _, y| unsafe { T::omp_add_scalar(y, n, c) },
+    );
   }
 
-  pub fn parallel_scale(&'a mut self, alpha: f32) {
+  pub fn parallel_scale(&'a mut self, alpha: T) {
     if self.stride() == 1 {
-      unsafe { densearray_omp_scale_f32(
+      unsafe { T::omp_scale(
           self.buf.as_mut_ptr(),
           self.dim(),
           alpha,
       ) };
     } else {
-      unimplemented!();
-    }
-  }
-
-  pub fn parallel_div_scalar(&'a mut self, c: f32) {
-    if self.stride() == 1 {
-      unsafe { densearray_omp_div_scalar_f32(
+      let n = self.dim();
+      let incx = self.stride();
+      unsafe { <T as BlasScalar>::parallel_scal(
+          n,
+          alpha,
           self.buf.as_mut_ptr(),
-          self.dim(),
-          c,
+          incx,
       ) };
-    } else {
-      unimplemented!();
     }
   }
 
+  pub fn parallel_div_scalar(&'a mut self, c: T) {
+    parallel_elem_map_inplace(
+        self.buf, &[self.dim], &[self.stride],
+        |n, _, y| unsafe { T::div_scalar(y, n, c) },
+        |n, _, y| unsafe { T::omp_div_scalar(y, n, c) },
+    );
+  }
+
   pub fn parallel_square(&'a mut self) {
-    if self.stride() == 1 {
-      unsafe { densearray_omp_square_f32(
-          self.buf.as_mut_ptr(),
-          self.dim(),
-      ) };
-    } else {
-      unimplemented!();
-    }
+    parallel_elem_map_inplace(
+        self.buf, &[self.dim], &[self.stride],
+        |n, _, y| unsafe { T::square(y, n) },
+        |n, _, y| unsafe { T::omp_square(y, n) },
+    );
+  }
+
+  pub fn parallel_cube(&'a mut self) {
+    parallel_elem_map_inplace(
+        self.buf, &[self.dim], &[self.stride],
+        |n, _, y| unsafe { T::cube(y, n) },
+        |n, _, y| unsafe { T::omp_cube(y, n) },
+    );
   }
 
   pub fn parallel_sqrt(&'a mut self) {
-    if self.stride() == 1 {
-      unsafe { densearray_omp_sqrt_f32(
-          self.buf.as_mut_ptr(),
-          self.dim(),
-      ) };
-    } else {
-      unimplemented!();
-    }
+    parallel_elem_map_inplace(
+        self.buf, &[self.dim], &[self.stride],
+        |n, _, y| unsafe { <T as KernelScalar>::sqrt(y, n) },
+        |n, _, y| unsafe { T::omp_sqrt(y, n) },
+    );
   }
 
   pub fn parallel_reciprocal(&'a mut self) {
-    if self.stride() == 1 {
-      unsafe { densearray_omp_reciprocal_f32(
-          self.buf.as_mut_ptr(),
-          self.dim(),
-      ) };
-    } else {
-      unimplemented!();
-    }
+    parallel_elem_map_inplace(
+        self.buf, &[self.dim], &[self.stride],
+        |n, _, y| unsafe { T::reciprocal(y, n) },
+        |n, _, y| unsafe { T::omp_reciprocal(y, n) },
+    );
   }
 
   pub fn parallel_exp(&mut self) {
     let n = self.dim();
     let incx = self.stride();
-    let mut p = 0;
-    for _ in 0 .. n {
-      let x_i = self.buf[p];
-      self.buf[p] = x_i.exp();
-      p += incx;
-    }
+    parallel_for_each_mut(self.buf, n, incx, |x_i| *x_i = x_i.exp());
   }
 
-  pub fn parallel_add(&'a mut self, alpha: f32, x: Array1dView<'a, f32>) {
-    if self.stride() == 1 {
-      unsafe { densearray_omp_vector_add_f32(
-          self.buf.as_mut_ptr(),
-          self.dim(),
-          x.as_ptr(),
-          alpha,
-      ) };
-    } else {
-      unimplemented!();
-    }
+  pub fn parallel_add(&'a mut self, alpha: T, x: Array1dView<'a, T>) {
+    parallel_elem_map(
+        x.buf, &[x.dim], &[x.stride],
+        self.buf, &[self.dim], &[self.stride],
+        |n, x, y| unsafe { T::vector_add(y, n, x, alpha) },
+        |n, x, y| unsafe { T::omp_vector_add(y, n, x, alpha) },
+    );
   }
 
-  pub fn parallel_average(&'a mut self, alpha: f32, x: Array1dView<'a, f32>) {
-    if self.stride() == 1 {
-      unsafe { densearray_omp_vector_average_f32(
-          self.buf.as_mut_ptr(),
-          self.dim(),
-          x.as_ptr(),
-          alpha,
-      ) };
-    } else {
-      unimplemented!();
+  pub fn parallel_average(&'a mut self, alpha: T, x: Array1dView<'a, T>) {
+    parallel_elem_map(
+        x.buf, &[x.dim], &[x.stride],
+        self.buf, &[self.dim], &[self.stride],
+        |n, x, y| unsafe { T::vector_average(y, n, x, alpha) },
+        |n, x, y| unsafe { T::omp_vector_average(y, n, x, alpha) },
+    );
+  }
+
+  pub fn parallel_elem_mult(&'a mut self, /*alpha: T,*/ x: Array1dView<'a, T>) {
+    parallel_elem_map(
+        x.buf, &[x.dim], &[x.stride],
+        self.buf, &[self.dim], &[self.stride],
+        |n, x, y| unsafe { T::elem_mult(y, n, x) },
+        |n, x, y| unsafe { T::omp_elem_mult(y, n, x) },
+    );
+  }
+
+  pub fn parallel_elem_div(&'a mut self, x: Array1dView<'a, T>) {
+    parallel_elem_map(
+        x.buf, &[x.dim], &[x.stride],
+        self.buf, &[self.dim], &[self.stride],
+        |n, x, y| unsafe { T::elem_div(y, n, x) },
+        |n, x, y| unsafe { T::omp_elem_div(y, n, x) },
+    );
+  }
+
+  pub fn parallel_elem_ldiv(&'a mut self, x: Array1dView<'a, T>) {
+    parallel_elem_map(
+        x.buf, &[x.dim], &[x.stride],
+        self.buf, &[self.dim], &[self.stride],
+        |n, x, y| unsafe { T::elem_ldiv(y, n, x) },
+        |n, x, y| unsafe { T::omp_elem_ldiv(y, n, x) },
+    );
+  }
+}
+
+impl<'a, T> Array2dView<'a, T> where T: 'a + KernelScalar {
+  pub fn parallel_l1_norm(&'a self) -> T {
+    parallel_nd_reduce(
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        self.buf, &[self.stride.0, self.stride.1],
+        |n, x, incx, _, _| {
+          let mut p = T::zero();
+          unsafe { T::chunk_abs_sum(x, n, incx, n, &mut p) };
+          p
+        },
+        |x, _| x.parallel_l1_norm(),
+    )
+  }
+
+  pub fn parallel_l2_norm(&'a self) -> T {
+    let lanes = Lanes::new(&[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1], &[self.stride.0, self.stride.1], false);
+    if lanes.count == 1 {
+      return Array1dView{buf: self.buf, dim: lanes.len, stride: lanes.x_inc}.parallel_l2_norm();
     }
+    combine_norms(&parallel_lane_partials(
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        self.buf, &[self.stride.0, self.stride.1],
+        |n, x, incx, _, _| unsafe { T::nrm2(n, x, incx) },
+    ))
   }
 
-  pub fn parallel_elem_mult(&'a mut self, /*alpha: f32,*/ x: Array1dView<'a, f32>) {
-    if self.stride() == 1 {
-      unsafe { densearray_omp_elem_mult_f32(
-          self.buf.as_mut_ptr(),
-          self.dim(),
-          x.as_ptr(),
-      ) };
-    } else {
-      unimplemented!();
+  pub fn parallel_inner_prod(&'a self, alpha: T, y: Array2dView<'a, T>) -> T {
+    assert_eq!(self.dim, y.dim);
+    alpha * parallel_nd_reduce(
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        y.buf, &[y.stride.0, y.stride.1],
+        |n, x, incx, y, incy| {
+          let mut p = T::zero();
+          unsafe { T::chunk_inner_prod(x, n, incx, y, incy, n, &mut p) };
+          p
+        },
+        |x, y| x.parallel_inner_prod(T::one(), y),
+    )
+  }
+
+  pub fn parallel_elem_sum(&'a self) -> T {
+    parallel_nd_reduce(
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        self.buf, &[self.stride.0, self.stride.1],
+        |n, x, incx, _, _| {
+          let mut p = T::zero();
+          unsafe { T::chunk_sum(x, n, incx, n, &mut p) };
+          p
+        },
+        |x, _| x.parallel_elem_sum(),
+    )
+  }
+}
+
+impl<'a, T> Array2dViewMut<'a, T> where T: 'a + KernelScalar {
+  pub fn parallel_copy(&'a mut self, src: Array2dView<'a, T>) {
+    parallel_elem_map(
+        src.buf, &[src.dim.0, src.dim.1], &[src.stride.0, src.stride.1],
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        |n, x, y| unsafe { T::copy(y, n, x) },
+        |n, x, y| unsafe { T::omp_copy(y, n, x) },
+    );
+  }
+
+  pub fn parallel_cast(&'a mut self, src: Array2dView<'a, u8>) {
+    parallel_elem_map(
+        src.buf, &[src.dim.0, src.dim.1], &[src.stride.0, src.stride.1],
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        |n, x, y| unsafe { T::cast_u8(y, n, x) },
+        |n, x, y| unsafe { T::omp_cast_u8(y, n, x) },
+    );
+  }
+
+  pub fn parallel_add_scalar(&'a mut self, c: T) {
+    parallel_elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        |n, _, y| unsafe { T::add_scalar(y, n, c) },
+        |n, _, y| unsafe { T::omp_add_scalar(y, n, c) },
+    );
+  }
+
+  pub fn parallel_scale(&'a mut self, alpha: T) {
+    parallel_elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        |n, _, y| unsafe { T::scale(y, n, alpha) },
+        |n, _, y| unsafe { T::omp_scale(y, n, alpha) },
+    );
+  }
+
+  pub fn parallel_div_scalar(&'a mut self, c: T) {
+    parallel_elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        |n, _, y| unsafe { T::div_scalar(y, n, c) },
+        |n, _, y| unsafe { T::omp_div_scalar(y, n, c) },
+    );
+  }
+
+  pub fn parallel_square(&'a mut self) {
+    parallel_elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        |n, _, y| unsafe { T::square(y, n) },
+        |n, _, y| unsafe { T::omp_square(y, n) },
+    );
+  }
+
+  pub fn parallel_cube(&'a mut self) {
+    parallel_elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        |n, _, y| unsafe { T::cube(y, n) },
+        |n, _, y| unsafe { T::omp_cube(y, n) },
+    );
+  }
+
+  pub fn parallel_sqrt(&'a mut self) {
+    parallel_elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        |n, _, y| unsafe { <T as KernelScalar>::sqrt(y, n) },
+        |n, _, y| unsafe { T::omp_sqrt(y, n) },
+    );
+  }
+
+  pub fn parallel_reciprocal(&'a mut self) {
+    parallel_elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        |n, _, y| unsafe { T::reciprocal(y, n) },
+        |n, _, y| unsafe { T::omp_reciprocal(y, n) },
+    );
+  }
+
+  pub fn parallel_exp(&mut self) {
+    parallel_elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        |n, _, y| {
+          for i in 0 .. n {
+            let y_i = unsafe { &mut *y.offset(i as isize) };
+            *y_i = y_i.exp();
+          }
+        },
+        |n, _, y| parallel_for_each_mut(unsafe { from_raw_parts_mut(y, n) }, n, 1, |y_i| *y_i = y_i.exp()),
+    );
+  }
+
+  pub fn parallel_add(&'a mut self, alpha: T, x: Array2dView<'a, T>) {
+    parallel_elem_map(
+        x.buf, &[x.dim.0, x.dim.1], &[x.stride.0, x.stride.1],
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        |n, x, y| unsafe { T::vector_add(y, n, x, alpha) },
+        |n, x, y| unsafe { T::omp_vector_add(y, n, x, alpha) },
+    );
+  }
+
+  pub fn parallel_average(&'a mut self, alpha: T, x: Array2dView<'a, T>) {
+    parallel_elem_map(
+        x.buf, &[x.dim.0, x.dim.1], &[x.stride.0, x.stride.1],
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        |n, x, y| unsafe { T::vector_average(y, n, x, alpha) },
+        |n, x, y| unsafe { T::omp_vector_average(y, n, x, alpha) },
+    );
+  }
+
+  pub fn parallel_elem_mult(&'a mut self, /*alpha: T,*/ x: Array2dView<'a, T>) {
+    parallel_elem_map(
+        x.buf, &[x.dim.0, x.dim.1], &[x.stride.0, x.stride.1],
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        |n, x, y| unsafe { T::elem_mult(y, n, x) },
+        |n, x, y| unsafe { T::omp_elem_mult(y, n, x) },
+    );
+  }
+
+  pub fn parallel_elem_div(&'a mut self, x: Array2dView<'a, T>) {
+    parallel_elem_map(
+        x.buf, &[x.dim.0, x.dim.1], &[x.stride.0, x.stride.1],
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        |n, x, y| unsafe { T::elem_div(y, n, x) },
+        |n, x, y| unsafe { T::omp_elem_div(y, n, x) },
+    );
+  }
+
+  pub fn parallel_elem_ldiv(&'a mut self, x: Array2dView<'a, T>) {
+    parallel_elem_map(
+        x.buf, &[x.dim.0, x.dim.1], &[x.stride.0, x.stride.1],
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        |n, x, y| unsafe { T::elem_ldiv(y, n, x) },
+        |n, x, y| unsafe { T::omp_elem_ldiv(y, n, x) },
+    );
+  }
+}
+
+impl<'a, T> Array3dView<'a, T> where T: 'a + KernelScalar {
+  pub fn parallel_l1_norm(&'a self) -> T {
+    parallel_nd_reduce(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        self.buf, &[self.stride.0, self.stride.1, self.stride.2],
+        |n, x, incx, _, _| {
+          let mut p = T::zero();
+          unsafe { T::chunk_abs_sum(x, n, incx, n, &mut p) };
+          p
+        },
+        |x, _| x.parallel_l1_norm(),
+    )
+  }
+
+  pub fn parallel_l2_norm(&'a self) -> T {
+    let lanes = Lanes::new(&[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2], &[self.stride.0, self.stride.1, self.stride.2], false);
+    if lanes.count == 1 {
+      return Array1dView{buf: self.buf, dim: lanes.len, stride: lanes.x_inc}.parallel_l2_norm();
     }
+    combine_norms(&parallel_lane_partials(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        self.buf, &[self.stride.0, self.stride.1, self.stride.2],
+        |n, x, incx, _, _| unsafe { T::nrm2(n, x, incx) },
+    ))
   }
 
-  pub fn parallel_elem_div(&'a mut self, x: Array1dView<'a, f32>) {
-    if self.stride() == 1 {
-      unsafe { densearray_omp_elem_div_f32(
-          self.buf.as_mut_ptr(),
-          self.dim(),
-          x.as_ptr(),
-      ) };
-    } else {
-      unimplemented!();
+  pub fn parallel_inner_prod(&'a self, alpha: T, y: Array3dView<'a, T>) -> T {
+    assert_eq!(self.dim, y.dim);
+    alpha * parallel_nd_reduce(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        y.buf, &[y.stride.0, y.stride.1, y.stride.2],
+        |n, x, incx, y, incy| {
+          let mut p = T::zero();
+          unsafe { T::chunk_inner_prod(x, n, incx, y, incy, n, &mut p) };
+          p
+        },
+        |x, y| x.parallel_inner_prod(T::one(), y),
+    )
+  }
+
+  pub fn parallel_elem_sum(&'a self) -> T {
+    parallel_nd_reduce(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        self.buf, &[self.stride.0, self.stride.1, self.stride.2],
+        |n, x, incx, _, _| {
+          let mut p = T::zero();
+          unsafe { T::chunk_sum(x, n, incx, n, &mut p) };
+          p
+        },
+        |x, _| x.parallel_elem_sum(),
+    )
+  }
+}
+
+impl<'a, T> Array3dViewMut<'a, T> where T: 'a + KernelScalar {
+  pub fn parallel_copy(&'a mut self, src: Array3dView<'a, T>) {
+    parallel_elem_map(
+        src.buf, &[src.dim.0, src.dim.1, src.dim.2], &[src.stride.0, src.stride.1, src.stride.2],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        |n, x, y| unsafe { T::copy(y, n, x) },
+        |n, x, y| unsafe { T::omp_copy(y, n, x) },
+    );
+  }
+
+  pub fn parallel_cast(&'a mut self, src: Array3dView<'a, u8>) {
+    parallel_elem_map(
+        src.buf, &[src.dim.0, src.dim.1, src.dim.2], &[src.stride.0, src.stride.1, src.stride.2],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        |n, x, y| unsafe { T::cast_u8(y, n, x) },
+        |n, x, y| unsafe { T::omp_cast_u8(y, n, x) },
+    );
+  }
+
+  pub fn parallel_add_scalar(&'a mut self, c: T) {
+    parallel_elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        |n, _, y| unsafe { T::add_scalar(y, n, c) },
+        |n, _, y| unsafe { T::omp_add_scalar(y, n, c) },
+    );
+  }
+
+  pub fn parallel_scale(&'a mut self, alpha: T) {
+    parallel_elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        |n, _, y| unsafe { T::scale(y, n, alpha) },
+        |n, _, y| unsafe { T::omp_scale(y, n, alpha) },
+    );
+  }
+
+  pub fn parallel_div_scalar(&'a mut self, c: T) {
+    parallel_elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        |n, _, y| unsafe { T::div_scalar(y, n, c) },
+        |n, _, y| unsafe { T::omp_div_scalar(y, n, c) },
+    );
+  }
+
+  pub fn parallel_square(&'a mut self) {
+    parallel_elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        |n, _, y| unsafe { T::square(y, n) },
+        |n, _, y| unsafe { T::omp_square(y, n) },
+    );
+  }
+
+  pub fn parallel_cube(&'a mut self) {
+    parallel_elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        |n, _, y| unsafe { T::cube(y, n) },
+        |n, _, y| unsafe { T::omp_cube(y, n) },
+    );
+  }
+
+  pub fn parallel_sqrt(&'a mut self) {
+    parallel_elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        |n, _, y| unsafe { <T as KernelScalar>::sqrt(y, n) },
+        |n, _, y| unsafe { T::omp_sqrt(y, n) },
+    );
+  }
+
+  pub fn parallel_reciprocal(&'a mut self) {
+    parallel_elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        |n, _, y| unsafe { T::reciprocal(y, n) },
+        |n, _, y| unsafe { T::omp_reciprocal(y, n) },
+    );
+  }
+
+  pub fn parallel_exp(&mut self) {
+    parallel_elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        |n, _, y| {
+          for i in 0 .. n {
+            let y_i = unsafe { &mut *y.offset(i as isize) };
+            *y_i = y_i.exp();
+          }
+        },
+        |n, _, y| parallel_for_each_mut(unsafe { from_raw_parts_mut(y, n) }, n, 1, |y_i| *y_i = y_i.exp()),
+    );
+  }
+
+  pub fn parallel_add(&'a mut self, alpha: T, x: Array3dView<'a, T>) {
+    parallel_elem_map(
+        x.buf, &[x.dim.0, x.dim.1, x.dim.2], &[x.stride.0, x.stride.1, x.stride.2],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        |n, x, y| unsafe { T::vector_add(y, n, x, alpha) },
+        |n, x, y| unsafe { T::omp_vector_add(y, n, x, alpha) },
+    );
+  }
+
+  pub fn parallel_average(&'a mut self, alpha: T, x: Array3dView<'a, T>) {
+    parallel_elem_map(
+        x.buf, &[x.dim.0, x.dim.1, x.dim.2], &[x.stride.0, x.stride.1, x.stride.2],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        |n, x, y| unsafe { T::vector_average(y, n, x, alpha) },
+        |n, x, y| unsafe { T::omp_vector_average(y, n, x, alpha) },
+    );
+  }
+
+  pub fn parallel_elem_mult(&'a mut self, /*alpha: T,*/ x: Array3dView<'a, T>) {
+    parallel_elem_map(
+        x.buf, &[x.dim.0, x.dim.1, x.dim.2], &[x.stride.0, x.stride.1, x.stride.2],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        |n, x, y| unsafe { T::elem_mult(y, n, x) },
+        |n, x, y| unsafe { T::omp_elem_mult(y, n, x) },
+    );
+  }
+
+  pub fn parallel_elem_div(&'a mut self, x: Array3dView<'a, T>) {
+    parallel_elem_map(
+        x.buf, &[x.dim.0, x.dim.1, x.dim.2], &[x.stride.0, x.stride.1, x.stride.2],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        |n, x, y| unsafe { T::elem_div(y, n, x) },
+        |n, x, y| unsafe { T::omp_elem_div(y, n, x) },
+    );
+  }
+
+  pub fn parallel_elem_ldiv(&'a mut self, x: Array3dView<'a, T>) {
+    parallel_elem_map(
+        x.buf, &[x.dim.0, x.dim.1, x.dim.2], &[x.stride.0, x.stride.1, x.stride.2],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        |n, x, y| unsafe { T::elem_ldiv(y, n, x) },
+        |n, x, y| unsafe { T::omp_elem_ldiv(y, n, x) },
+    );
+  }
+}
+
+impl<'a, T> Array4dView<'a, T> where T: 'a + KernelScalar {
+  pub fn parallel_l1_norm(&'a self) -> T {
+    parallel_nd_reduce(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        self.buf, &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        |n, x, incx, _, _| {
+          let mut p = T::zero();
+          unsafe { T::chunk_abs_sum(x, n, incx, n, &mut p) };
+          p
+        },
+        |x, _| x.parallel_l1_norm(),
+    )
+  }
+
+  pub fn parallel_l2_norm(&'a self) -> T {
+    let lanes = Lanes::new(&[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3], false);
+    if lanes.count == 1 {
+      return Array1dView{buf: self.buf, dim: lanes.len, stride: lanes.x_inc}.parallel_l2_norm();
     }
+    combine_norms(&parallel_lane_partials(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        self.buf, &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        |n, x, incx, _, _| unsafe { T::nrm2(n, x, incx) },
+    ))
   }
 
-  pub fn parallel_elem_ldiv(&'a mut self, x: Array1dView<'a, f32>) {
-    if self.stride() == 1 {
-      unsafe { densearray_omp_elem_ldiv_f32(
+  pub fn parallel_inner_prod(&'a self, alpha: T, y: Array4dView<'a, T>) -> T {
+    assert_eq!(self.dim, y.dim);
+    alpha * parallel_nd_reduce(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        y.buf, &[y.stride.0, y.stride.1, y.stride.2, y.stride.3],
+        |n, x, incx, y, incy| {
+          let mut p = T::zero();
+          unsafe { T::chunk_inner_prod(x, n, incx, y, incy, n, &mut p) };
+          p
+        },
+        |x, y| x.parallel_inner_prod(T::one(), y),
+    )
+  }
+
+  pub fn parallel_elem_sum(&'a self) -> T {
+    parallel_nd_reduce(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        self.buf, &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        |n, x, incx, _, _| {
+          let mut p = T::zero();
+          unsafe { T::chunk_sum(x, n, incx, n, &mut p) };
+          p
+        },
+        |x, _| x.parallel_elem_sum(),
+    )
+  }
+}
+
+impl<'a, T> Array4dViewMut<'a, T> where T: 'a + KernelScalar {
+  pub fn parallel_copy(&'a mut self, src: Array4dView<'a, T>) {
+    parallel_elem_map(
+        src.buf, &[src.dim.0, src.dim.1, src.dim.2, src.dim.3], &[src.stride.0, src.stride.1, src.stride.2, src.stride.3],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        |n, x, y| unsafe { T::copy(y, n, x) },
+        |n, x, y| unsafe { T::omp_copy(y, n, x) },
+    );
+  }
+
+  pub fn parallel_cast(&'a mut self, src: Array4dView<'a, u8>) {
+    parallel_elem_map(
+        src.buf, &[src.dim.0, src.dim.1, src.dim.2, src.dim.3], &[src.stride.0, src.stride.1, src.stride.2, src.stride.3],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        |n, x, y| unsafe { T::cast_u8(y, n, x) },
+        |n, x, y| unsafe { T::omp_cast_u8(y, n, x) },
+    );
+  }
+
+  pub fn parallel_add_scalar(&'a mut self, c: T) {
+    parallel_elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        |n, _, y| unsafe { T::add_scalar(y, n, c) },
+        |n, _, y| unsafe { T::omp_add_scalar(y, n, c) },
+    );
+  }
+
+  pub fn parallel_scale(&'a mut self, alpha: T) {
+    parallel_elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        |n, _, y| unsafe { T::scale(y, n, alpha) },
+        |n, _, y| unsafe { T::omp_scale(y, n, alpha) },
+    );
+  }
+
+  pub fn parallel_div_scalar(&'a mut self, c: T) {
+    parallel_elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        |n, _, y| unsafe { T::div_scalar(y, n, c) },
+        |n, _, y| unsafe { T::omp_div_scalar(y, n, c) },
+    );
+  }
+
+  pub fn parallel_square(&'a mut self) {
+    parallel_elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        |n, _, y| unsafe { T::square(y, n) },
+        |n, _, y| unsafe { T::omp_square(y, n) },
+    );
+  }
+
+  pub fn parallel_cube(&'a mut self) {
+    parallel_elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        |n, _, y| unsafe { T::cube(y, n) },
+        |n, _, y| unsafe { T::omp_cube(y, n) },
+    );
+  }
+
+  pub fn parallel_sqrt(&'a mut self) {
+    parallel_elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        |n, _, y| unsafe { <T as KernelScalar>::sqrt(y, n) },
+        |n, _, y| unsafe { T::omp_sqrt(y, n) },
+    );
+  }
+
+  pub fn parallel_reciprocal(&'a mut self) {
+    parallel_elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        |n, _, y| unsafe { T::reciprocal(y, n) },
+        |n, _, y| unsafe { T::omp_reciprocal(y, n) },
+    );
+  }
+
+  pub fn parallel_exp(&mut self) {
+    parallel_elem_map_inplace(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        |n, _, y| {
+          for i in 0 .. n {
+            let y_i = unsafe { &mut *y.offset(i as isize) };
+            *y_i = y_i.exp();
+          }
+        },
+        |n, _, y| parallel_for_each_mut(unsafe { from_raw_parts_mut(y, n) }, n, 1, |y_i| *y_i = y_i.exp()),
+    );
+  }
+
+  pub fn parallel_add(&'a mut self, alpha: T, x: Array4dView<'a, T>) {
+    parallel_elem_map(
+        x.buf, &[x.dim.0, x.dim.1, x.dim.2, x.dim.3], &[x.stride.0, x.stride.1, x.stride.2, x.stride.3],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        |n, x, y| unsafe { T::vector_add(y, n, x, alpha) },
+        |n, x, y| unsafe { T::omp_vector_add(y, n, x, alpha) },
+    );
+  }
+
+  pub fn parallel_average(&'a mut self, alpha: T, x: Array4dView<'a, T>) {
+    parallel_elem_map(
+        x.buf, &[x.dim.0, x.dim.1, x.dim.2, x.dim.3], &[x.stride.0, x.stride.1, x.stride.2, x.stride.3],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        |n, x, y| unsafe { T::vector_average(y, n, x, alpha) },
+        |n, x, y| unsafe { T::omp_vector_average(y, n, x, alpha) },
+    );
+  }
+
+  pub fn parallel_elem_mult(&'a mut self, /*alpha: T,*/ x: Array4dView<'a, T>) {
+    parallel_elem_map(
+        x.buf, &[x.dim.0, x.dim.1, x.dim.2, x.dim.3], &[x.stride.0, x.stride.1, x.stride.2, x.stride.3],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        |n, x, y| unsafe { T::elem_mult(y, n, x) },
+        |n, x, y| unsafe { T::omp_elem_mult(y, n, x) },
+    );
+  }
+
+  pub fn parallel_elem_div(&'a mut self, x: Array4dView<'a, T>) {
+    parallel_elem_map(
+        x.buf, &[x.dim.0, x.dim.1, x.dim.2, x.dim.3], &[x.stride.0, x.stride.1, x.stride.2, x.stride.3],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        |n, x, y| unsafe { T::elem_div(y, n, x) },
+        |n, x, y| unsafe { T::omp_elem_div(y, n, x) },
+    );
+  }
+
+  pub fn parallel_elem_ldiv(&'a mut self, x: Array4dView<'a, T>) {
+    parallel_elem_map(
+        x.buf, &[x.dim.0, x.dim.1, x.dim.2, x.dim.3], &[x.stride.0, x.stride.1, x.stride.2, x.stride.3],
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        |n, x, y| unsafe { T::elem_ldiv(y, n, x) },
+        |n, x, y| unsafe { T::omp_elem_ldiv(y, n, x) },
+    );
+  }
+}
+
+impl<'a, T> Array2dView<'a, T> where T: 'a + KernelScalar {
+  /// Like `axis_sum`, but threaded.
+  pub fn parallel_axis_sum(&'a self, axis: usize, y: Array1dViewMut<'a, T>) {
+    axis_reduce(
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        axis,
+        y.buf, &[y.dim], &[y.stride],
+        Some(T::zero()),
+        T::omp_reduce_axis_sum,
+    );
+  }
+
+  /// Like `axis_mean`, but threaded.
+  pub fn parallel_axis_mean(&'a self, axis: usize, y: Array1dViewMut<'a, T>) {
+    axis_reduce(
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        axis,
+        y.buf, &[y.dim], &[y.stride],
+        Some(T::from_f64(f64::NAN)),
+        T::omp_reduce_axis_mean,
+    );
+  }
+
+  /// Like `axis_max`, but threaded. Panics if the axis is empty.
+  pub fn parallel_axis_max(&'a self, axis: usize, y: Array1dViewMut<'a, T>) {
+    axis_reduce(
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        axis,
+        y.buf, &[y.dim], &[y.stride],
+        None,
+        T::omp_reduce_axis_max,
+    );
+  }
+
+  /// Like `axis_min`, but threaded. Panics if the axis is empty.
+  pub fn parallel_axis_min(&'a self, axis: usize, y: Array1dViewMut<'a, T>) {
+    axis_reduce(
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        axis,
+        y.buf, &[y.dim], &[y.stride],
+        None,
+        T::omp_reduce_axis_min,
+    );
+  }
+
+  /// Like `axis_argmax`, but threaded. Panics if the axis is empty.
+  pub fn parallel_axis_argmax(&'a self, axis: usize, y: Array1dViewMut<'a, u32>) {
+    axis_reduce(
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        axis,
+        y.buf, &[y.dim], &[y.stride],
+        None,
+        T::omp_reduce_axis_argmax,
+    );
+  }
+
+  /// Like `axis_argmin`, but threaded. Panics if the axis is empty.
+  pub fn parallel_axis_argmin(&'a self, axis: usize, y: Array1dViewMut<'a, u32>) {
+    axis_reduce(
+        self.buf, &[self.dim.0, self.dim.1], &[self.stride.0, self.stride.1],
+        axis,
+        y.buf, &[y.dim], &[y.stride],
+        None,
+        T::omp_reduce_axis_argmin,
+    );
+  }
+}
+
+impl<'a, T> Array3dView<'a, T> where T: 'a + KernelScalar {
+  /// Like `axis_sum`, but threaded.
+  pub fn parallel_axis_sum(&'a self, axis: usize, y: Array2dViewMut<'a, T>) {
+    axis_reduce(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        axis,
+        y.buf, &[y.dim.0, y.dim.1], &[y.stride.0, y.stride.1],
+        Some(T::zero()),
+        T::omp_reduce_axis_sum,
+    );
+  }
+
+  /// Like `axis_mean`, but threaded.
+  pub fn parallel_axis_mean(&'a self, axis: usize, y: Array2dViewMut<'a, T>) {
+    axis_reduce(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        axis,
+        y.buf, &[y.dim.0, y.dim.1], &[y.stride.0, y.stride.1],
+        Some(T::from_f64(f64::NAN)),
+        T::omp_reduce_axis_mean,
+    );
+  }
+
+  /// Like `axis_max`, but threaded. Panics if the axis is empty.
+  pub fn parallel_axis_max(&'a self, axis: usize, y: Array2dViewMut<'a, T>) {
+    axis_reduce(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        axis,
+        y.buf, &[y.dim.0, y.dim.1], &[y.stride.0, y.stride.1],
+        None,
+        T::omp_reduce_axis_max,
+    );
+  }
+
+  /// Like `axis_min`, but threaded. Panics if the axis is empty.
+  pub fn parallel_axis_min(&'a self, axis: usize, y: Array2dViewMut<'a, T>) {
+    axis_reduce(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        axis,
+        y.buf, &[y.dim.0, y.dim.1], &[y.stride.0, y.stride.1],
+        None,
+        T::omp_reduce_axis_min,
+    );
+  }
+
+  /// Like `axis_argmax`, but threaded. Panics if the axis is empty.
+  pub fn parallel_axis_argmax(&'a self, axis: usize, y: Array2dViewMut<'a, u32>) {
+    axis_reduce(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        axis,
+        y.buf, &[y.dim.0, y.dim.1], &[y.stride.0, y.stride.1],
+        None,
+        T::omp_reduce_axis_argmax,
+    );
+  }
+
+  /// Like `axis_argmin`, but threaded. Panics if the axis is empty.
+  pub fn parallel_axis_argmin(&'a self, axis: usize, y: Array2dViewMut<'a, u32>) {
+    axis_reduce(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2], &[self.stride.0, self.stride.1, self.stride.2],
+        axis,
+        y.buf, &[y.dim.0, y.dim.1], &[y.stride.0, y.stride.1],
+        None,
+        T::omp_reduce_axis_argmin,
+    );
+  }
+}
+
+impl<'a, T> Array4dView<'a, T> where T: 'a + KernelScalar {
+  /// Like `axis_sum`, but threaded.
+  pub fn parallel_axis_sum(&'a self, axis: usize, y: Array3dViewMut<'a, T>) {
+    axis_reduce(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        axis,
+        y.buf, &[y.dim.0, y.dim.1, y.dim.2], &[y.stride.0, y.stride.1, y.stride.2],
+        Some(T::zero()),
+        T::omp_reduce_axis_sum,
+    );
+  }
+
+  /// Like `axis_mean`, but threaded.
+  pub fn parallel_axis_mean(&'a self, axis: usize, y: Array3dViewMut<'a, T>) {
+    axis_reduce(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        axis,
+        y.buf, &[y.dim.0, y.dim.1, y.dim.2], &[y.stride.0, y.stride.1, y.stride.2],
+        Some(T::from_f64(f64::NAN)),
+        T::omp_reduce_axis_mean,
+    );
+  }
+
+  /// Like `axis_max`, but threaded. Panics if the axis is empty.
+  pub fn parallel_axis_max(&'a self, axis: usize, y: Array3dViewMut<'a, T>) {
+    axis_reduce(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        axis,
+        y.buf, &[y.dim.0, y.dim.1, y.dim.2], &[y.stride.0, y.stride.1, y.stride.2],
+        None,
+        T::omp_reduce_axis_max,
+    );
+  }
+
+  /// Like `axis_min`, but threaded. Panics if the axis is empty.
+  pub fn parallel_axis_min(&'a self, axis: usize, y: Array3dViewMut<'a, T>) {
+    axis_reduce(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        axis,
+        y.buf, &[y.dim.0, y.dim.1, y.dim.2], &[y.stride.0, y.stride.1, y.stride.2],
+        None,
+        T::omp_reduce_axis_min,
+    );
+  }
+
+  /// Like `axis_argmax`, but threaded. Panics if the axis is empty.
+  pub fn parallel_axis_argmax(&'a self, axis: usize, y: Array3dViewMut<'a, u32>) {
+    axis_reduce(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        axis,
+        y.buf, &[y.dim.0, y.dim.1, y.dim.2], &[y.stride.0, y.stride.1, y.stride.2],
+        None,
+        T::omp_reduce_axis_argmax,
+    );
+  }
+
+  /// Like `axis_argmin`, but threaded. Panics if the axis is empty.
+  pub fn parallel_axis_argmin(&'a self, axis: usize, y: Array3dViewMut<'a, u32>) {
+    axis_reduce(
+        self.buf, &[self.dim.0, self.dim.1, self.dim.2, self.dim.3], &[self.stride.0, self.stride.1, self.stride.2, self.stride.3],
+        axis,
+        y.buf, &[y.dim.0, y.dim.1, y.dim.2], &[y.stride.0, y.stride.1, y.stride.2],
+        None,
+        T::omp_reduce_axis_argmin,
+    );
+  }
+}
+
+impl<'a, T> Array2dViewMut<'a, T> where T: 'a + BlasScalar {
+  pub fn parallel_matrix_add(&'a mut self, alpha: T, x: Array2dView<'a, T>) {
+    self.parallel_matrix_add_trans(alpha, x, Transpose::N);
+  }
+
+  pub fn parallel_matrix_add_trans(&'a mut self, alpha: T, x: Array2dView<'a, T>, x_trans: Transpose) {
+    let (x_m, x_n) = x.dim();
+    let (y_m, y_n) = self.dim();
+    let (xt_m, xt_n) = match x_trans {
+      Transpose::N => (x_m, x_n),
+      Transpose::T => (x_n, x_m),
+    };
+    assert_eq!(xt_m, y_m);
+    assert_eq!(xt_n, y_n);
+    let (incx, ldx) = x.stride();
+    let (incy, ldy) = self.stride();
+    // Strides of `op(X)` laid out in the shape of `Y`.
+    let (xt_inc, xt_ld) = match x_trans {
+      Transpose::N => (incx, ldx),
+      Transpose::T => (ldx, incx),
+    };
+    if y_m == 0 || y_n == 0 {
+      return;
+    }
+    if y_n == 1 {
+      unsafe { T::parallel_axpy(
+          y_m,
+          alpha,
+          x.buf.as_ptr(),
+          xt_inc,
           self.buf.as_mut_ptr(),
-          self.dim(),
-          x.as_ptr(),
+          incy,
+      ) };
+    } else if y_m == 1 {
+      unsafe { T::parallel_axpy(
+          y_n,
+          alpha,
+          x.buf.as_ptr(),
+          xt_ld,
+          self.buf.as_mut_ptr(),
+          ldy,
+      ) };
+    } else if xt_inc == 1 && xt_ld == y_m && incy == 1 && ldy == y_m {
+      unsafe { T::parallel_axpy(
+          y_m * y_n,
+          alpha,
+          x.buf.as_ptr(),
+          1,
+          self.buf.as_mut_ptr(),
+          1,
       ) };
     } else {
-      unimplemented!();
+      for j in 0 .. y_n {
+        unsafe { T::parallel_axpy(
+            y_m,
+            alpha,
+            x.buf[j * xt_ld .. ].as_ptr(),
+            xt_inc,
+            self.buf[j * ldy .. ].as_mut_ptr(),
+            incy,
+        ) };
+      }
     }
   }
-}
 
-impl<'a> Array2dViewMut<'a, f32> {
-  pub fn parallel_matrix_prod(&'a mut self, alpha: f32, a: Array2dView<'a, f32>, a_trans: Transpose, b: Array2dView<'a, f32>, b_trans: Transpose, beta: f32) {
+  pub fn parallel_matrix_prod(&'a mut self, alpha: T, a: Array2dView<'a, T>, a_trans: Transpose, b: Array2dView<'a, T>, b_trans: Transpose, beta: T) {
     let (a_m, a_n) = a.dim();
     let (b_m, b_n) = b.dim();
     let (c_m, c_n) = self.dim();
@@ -245,42 +1221,15 @@ impl<'a> Array2dViewMut<'a, f32> {
     assert_eq!(1, a_inc);
     assert_eq!(1, b_inc);
     assert_eq!(1, c_inc);
-    if cfg!(not(feature = "mkl_parallel")) {
-      unsafe { openblas_parallel_cblas_sgemm(
-          CblasOrder::ColMajor,
-          match a_trans {
-            Transpose::N => CblasTranspose::NoTrans,
-            Transpose::T => CblasTranspose::Trans,
-          },
-          match b_trans {
-            Transpose::N => CblasTranspose::NoTrans,
-            Transpose::T => CblasTranspose::Trans,
-          },
-          c_m as _, c_n as _, k as _,
-          alpha,
-          a.buf.as_ptr(), lda as _,
-          b.buf.as_ptr(), ldb as _,
-          beta,
-          self.buf.as_mut_ptr(), ldc as _,
-      ) };
-    } else {
-      unsafe { cblas_sgemm(
-          CblasOrder::ColMajor,
-          match a_trans {
-            Transpose::N => CblasTranspose::NoTrans,
-            Transpose::T => CblasTranspose::Trans,
-          },
-          match b_trans {
-            Transpose::N => CblasTranspose::NoTrans,
-            Transpose::T => CblasTranspose::Trans,
-          },
-          c_m as _, c_n as _, k as _,
-          alpha,
-          a.buf.as_ptr(), lda as _,
-          b.buf.as_ptr(), ldb as _,
-          beta,
-          self.buf.as_mut_ptr(), ldc as _,
-      ) };
-    }
+    unsafe { T::parallel_gemm(
+        a_trans,
+        b_trans,
+        c_m, c_n, k,
+        alpha,
+        a.buf.as_ptr(), lda,
+        b.buf.as_ptr(), ldb,
+        beta,
+        self.buf.as_mut_ptr(), ldc,
+    ) };
   }
 }
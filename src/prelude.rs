@@ -10,9 +10,12 @@ pub use super::{
   SetConstant, ParallelSetConstant,
   Array1d, Array1dView, Array1dViewMut,
   Array2d, Array2dView, Array2dViewMut,
-  Array3d, //Array3dView, Array3dViewMut,
+  Array3d, Array3dView, Array3dViewMut,
   Array4d, Array4dView, Array4dViewMut,
   Batch, BatchArray1d, BatchArray3d,
 };
+pub use blas::{BlasScalar};
+pub use iterative::*;
+pub use kernels::{KernelScalar};
 pub use linalg::*;
 pub use serial::{NdArrayDtype, NdArrayDeserialize, NdArraySerialize};
@@ -0,0 +1,75 @@
+//! Checks the cast and clamp view methods on packed and strided views.
+
+extern crate densearray;
+
+use densearray::prelude::*;
+
+#[test]
+fn test_round_clamp_1d() {
+  let x = Array1d::from_storage(6, vec![-3.0f32, 0.5, 1.5, 127.2, 254.6, 1000.0]);
+  let mut y = Array1d::from_storage(6, vec![0u8; 6]);
+  y.as_view_mut().round_clamp_from_f32(x.as_view());
+  assert_eq!(y.as_slice(), &[0, 0, 2, 127, 255, 255]);
+  let mut z = Array1d::from_storage(6, vec![0i8; 6]);
+  z.as_view_mut().round_clamp_from_f32(x.as_view());
+  assert_eq!(z.as_slice(), &[-3, 0, 2, 127, 127, 127]);
+}
+
+#[test]
+#[should_panic]
+fn test_cast_dim_mismatch() {
+  let x = Array1d::from_storage(4, vec![0u8; 4]);
+  let mut y = Array1d::from_storage(5, vec![0.0f32; 5]);
+  y.as_view_mut().cast_from_u8(x.as_view());
+}
+
+#[test]
+fn test_cast_strided_2d() {
+  let x: Vec<u8> = (0 .. 20).map(|i| i as u8).collect();
+  let x = Array2d::from_storage((5, 4), x);
+  let mut y = Array2d::from_storage((4, 6), vec![-1.0f32; 24]);
+  // A 2x3 block of `x` into a 2x3 block of `y`; both are strided.
+  y.as_view_mut().view_mut((1, 2), (3, 5)).cast_from_u8(x.as_view().view((2, 1), (4, 4)));
+  for j in 0 .. 6 {
+    for i in 0 .. 4 {
+      let expected = if i >= 1 && i < 3 && j >= 2 && j < 5 {
+        ((i + 1) + (j - 1) * 5) as f32
+      } else {
+        -1.0
+      };
+      assert_eq!(expected, y.as_slice()[i + j * 4], "at ({}, {})", i, j);
+    }
+  }
+}
+
+#[test]
+fn test_clamp_inplace_3d() {
+  let x: Vec<f32> = (0 .. 24).map(|i| i as f32 - 12.0).collect();
+  let mut x = Array3d::from_storage((2, 3, 4), x);
+  x.as_view_mut().clamp(-2.5, 3.0);
+  for (i, &x_i) in x.as_slice().iter().enumerate() {
+    let v = i as f32 - 12.0;
+    assert_eq!(v.max(-2.5).min(3.0), x_i);
+  }
+}
+
+#[test]
+fn test_clamp_from_strided_2d() {
+  let x: Vec<f32> = (0 .. 20).map(|i| i as f32).collect();
+  let x = Array2d::from_storage((4, 5), x);
+  let mut y = Array2d::from_storage((2, 5), vec![0.0f32; 10]);
+  // The first two rows of `x`, i.e. a strided 2x5 view.
+  y.as_view_mut().clamp_from(x.as_view().view((0, 0), (2, 5)), 3.0, 13.0);
+  for j in 0 .. 5 {
+    for i in 0 .. 2 {
+      assert_eq!(((i + j * 4) as f32).max(3.0).min(13.0), y.as_slice()[i + j * 2]);
+    }
+  }
+}
+
+#[test]
+#[should_panic]
+fn test_clamp_bad_bounds() {
+  let mut x = Array1d::from_storage(3, vec![0.0f32; 3]);
+  x.as_view_mut().clamp(1.0, -1.0);
+}
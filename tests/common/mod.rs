@@ -0,0 +1,7 @@
+//! Helpers shared by the integration tests.
+
+/// `n` deterministic pseudorandom values in `[-0.5, 0.5)`, which differ with
+/// `seed`.
+pub fn inputs(n: usize, seed: usize) -> Vec<f64> {
+  (0 .. n).map(|i| ((i * 7919 + seed) % 1009) as f64 / 1009.0 - 0.5).collect()
+}
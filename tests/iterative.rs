@@ -0,0 +1,284 @@
+//! Checks the Lanczos iteration against dense eigendecompositions, and the
+//! Krylov linear solvers against dense residuals.
+
+extern crate densearray;
+
+mod common;
+
+use common::{inputs};
+use densearray::prelude::*;
+
+/// A diagonal spectrum with well separated values at both ends.
+fn spectrum(n: usize) -> Vec<f64> {
+  (0 .. n).map(|i| {
+    let x = i as f64 / n as f64;
+    if i < 3 {
+      -10.0 + i as f64
+    } else if i + 3 >= n {
+      10.0 + (i + 3 - n) as f64
+    } else {
+      x
+    }
+  }).collect()
+}
+
+#[test]
+fn test_lanczos_diagonal() {
+  let n = 400;
+  let d = spectrum(n);
+  let mut a = Array2d::zeros((n, n));
+  for i in 0 .. n {
+    a.as_mut_slice()[i + i * n] = d[i];
+  }
+  let x0 = Array1d::from_storage(n, inputs(n, 1));
+  for &reorth in &[Reorthogonalization::Full, Reorthogonalization::Selective] {
+    for &(which, expected) in &[(SpectrumEnd::Largest, [12.0, 11.0, 10.0]), (SpectrumEnd::Smallest, [-10.0, -9.0, -8.0])] {
+      let mut op = FnOperator::new(n, |x: Array1dView<f64>, y: Array1dViewMut<f64>| {
+        // Moving `y` shortens its borrow to the closure body.
+        let mut y = y;
+        y.matrix_vector_prod(1.0, a.as_view(), Transpose::N, x, 0.0);
+      });
+      let mut config = LanczosConfig::new(3, which);
+      config.reorth = reorth;
+      config.vectors = true;
+      config.tol = 1.0e-10;
+      let RitzPairs{values, vectors, num_converged, iters, ..} = lanczos(&mut op, x0.as_view(), &config).unwrap();
+      assert_eq!(3, num_converged);
+      assert!(iters < n / 2, "{} iterations", iters);
+      let vectors = vectors.unwrap();
+      for c in 0 .. 3 {
+        assert!((expected[c] - values[c]).abs() < 1.0e-8, "{:?} vs {:?}", expected, values);
+        // The Ritz vector is the matching unit vector, up to sign.
+        let i = d.iter().position(|&d_i| d_i == expected[c]).unwrap();
+        let y = &vectors.as_slice()[c * n .. (c + 1) * n];
+        assert!((y[i].abs() - 1.0).abs() < 1.0e-8);
+        let resid: f64 = (0 .. n).map(|l| (d[l] * y[l] - values[c] * y[l]).powi(2)).sum::<f64>().sqrt();
+        assert!(resid <= 1.0e-8);
+      }
+    }
+  }
+}
+
+#[test]
+fn test_lanczos_dense() {
+  let n = 30;
+  let m = inputs(n * n, 2);
+  let mut a = vec![0.0; n * n];
+  for j in 0 .. n {
+    for i in 0 .. n {
+      a[i + j * n] = m[i + j * n] + m[j + i * n];
+    }
+  }
+  let a = Array2d::from_storage((n, n), a);
+  let expected = symmetric_eigenvalues(a.as_view(), Uplo::Lower, EigenRange::All).unwrap();
+  let x0 = Array1d::from_storage(n, vec![1.0; n]);
+  let mut config = LanczosConfig::new(4, SpectrumEnd::Smallest);
+  config.tol = 1.0e-12;
+  let ritz = lanczos(&mut a.as_view(), x0.as_view(), &config).unwrap();
+  assert!(ritz.iters <= n);
+  for c in 0 .. 4 {
+    assert!((expected[c] - ritz.values[c]).abs() < 1.0e-10);
+  }
+  assert!(ritz.vectors.is_none());
+}
+
+#[test]
+fn test_lanczos_dense_selective() {
+  // Enough steps that the Lanczos vectors would lose orthogonality without
+  // the purges, and ghost copies of the converged values would appear.
+  let n = 200;
+  let m = inputs(n * n, 4);
+  let mut a = vec![0.0; n * n];
+  for j in 0 .. n {
+    for i in 0 .. n {
+      a[i + j * n] = m[i + j * n] + m[j + i * n];
+    }
+  }
+  let a = Array2d::from_storage((n, n), a);
+  let expected = symmetric_eigenvalues(a.as_view(), Uplo::Lower, EigenRange::All).unwrap();
+  let x0 = Array1d::from_storage(n, inputs(n, 5));
+  let k = 5;
+  let mut config = LanczosConfig::new(k, SpectrumEnd::Largest);
+  config.reorth = Reorthogonalization::Selective;
+  config.vectors = true;
+  config.tol = 1.0e-10;
+  let ritz = lanczos(&mut a.as_view(), x0.as_view(), &config).unwrap();
+  assert_eq!(k, ritz.num_converged);
+  let y = ritz.vectors.unwrap();
+  let y = y.as_slice();
+  for c in 0 .. k {
+    assert!((expected[n - 1 - c] - ritz.values[c]).abs() < 1.0e-8, "{:?}", ritz.values);
+    for l in 0 .. k {
+      let dot: f64 = (0 .. n).map(|i| y[i + c * n] * y[i + l * n]).sum();
+      let expected_dot = if c == l { 1.0 } else { 0.0 };
+      assert!((expected_dot - dot).abs() < 1.0e-8, "vectors {} and {}: {}", c, l, dot);
+    }
+  }
+}
+
+#[test]
+fn test_lanczos_invariant_subspace() {
+  // The starting vector lies in a two dimensional invariant subspace.
+  let n = 10;
+  let mut a = Array2d::zeros((n, n));
+  for i in 0 .. n {
+    a.as_mut_slice()[i + i * n] = (i + 1) as f32;
+  }
+  let mut op = FnOperator::new(n, |x: Array1dView<f32>, y: Array1dViewMut<f32>| {
+    let mut y = y;
+    y.matrix_vector_prod(1.0, a.as_view(), Transpose::N, x, 0.0);
+  });
+  let mut x0 = vec![0.0f32; n];
+  x0[2] = 1.0;
+  x0[7] = 1.0;
+  let x0 = Array1d::from_storage(n, x0);
+  let ritz = lanczos(&mut op, x0.as_view(), &LanczosConfig::new(3, SpectrumEnd::Largest)).unwrap();
+  assert_eq!(2, ritz.iters);
+  assert_eq!(2, ritz.values.len());
+  assert!((ritz.values[0] - 8.0).abs() < 1.0e-5 && (ritz.values[1] - 3.0).abs() < 1.0e-5);
+}
+
+#[test]
+fn test_lanczos_too_many_values() {
+  let n = 6;
+  let a = shifted_matrix(n, 1.0);
+  let x0 = Array1d::from_storage(n, inputs(n, 6));
+  let config = LanczosConfig::new(n + 1, SpectrumEnd::Largest);
+  assert_eq!(Some(LinalgError::InvalidArgument(3)), lanczos(&mut a.as_view(), x0.as_view(), &config).err());
+  let mut config = LanczosConfig::new(4, SpectrumEnd::Largest);
+  config.max_iters = 3;
+  assert_eq!(Some(LinalgError::InvalidArgument(3)), lanczos(&mut a.as_view(), x0.as_view(), &config).err());
+}
+
+#[test]
+fn test_lanczos_zero_start() {
+  let n = 6;
+  let a = shifted_matrix(n, 1.0);
+  let x0 = Array1d::from_storage(n, vec![0.0; n]);
+  let config = LanczosConfig::new(2, SpectrumEnd::Largest);
+  assert_eq!(Some(LinalgError::InvalidArgument(2)), lanczos(&mut a.as_view(), x0.as_view(), &config).err());
+}
+
+/// A symmetric matrix `M + M^T + shift * I` with a badly scaled diagonal.
+fn shifted_matrix(n: usize, shift: f64) -> Array2d<f64> {
+  let m = inputs(n * n, 3);
+  let mut a = vec![0.0; n * n];
+  for j in 0 .. n {
+    for i in 0 .. n {
+      a[i + j * n] = (m[i + j * n] + m[j + i * n]) / n as f64;
+    }
+    a[j + j * n] += shift * (1 + j % 7) as f64;
+  }
+  Array2d::from_storage((n, n), a)
+}
+
+fn residual_norm(a: &Array2d<f64>, x: &Array1d<f64>, b: &Array1d<f64>) -> f64 {
+  let mut r = b.clone();
+  r.as_view_mut().matrix_vector_prod(-1.0, a.as_view(), Transpose::N, x.as_view(), 1.0);
+  r.as_view().l2_norm()
+}
+
+#[test]
+fn test_conjugate_gradient() {
+  let n = 50;
+  let a = shifted_matrix(n, 20.0);
+  let b = Array1d::from_storage(n, inputs(n, 4));
+  let config = SolverConfig::new(1.0e-10, 200);
+  let mut x = Array1d::zeros(n);
+  let report = conjugate_gradient(&mut a.as_view(), b.as_view(), x.as_view_mut(), &config).unwrap();
+  assert!(report.converged);
+  assert!(report.residual_norm <= 1.0e-10 * b.as_view().l2_norm());
+  assert!(residual_norm(&a, &x, &b) <= 1.0e-8);
+  let diag: Vec<f64> = (0 .. n).map(|i| a.as_slice()[i + i * n]).collect();
+  let mut precond = DiagonalPreconditioner::new(diag.reshape(n));
+  let mut px = Array1d::zeros(n);
+  let p_report = preconditioned_conjugate_gradient(&mut a.as_view(), &mut precond, b.as_view(), px.as_view_mut(), &config).unwrap();
+  assert!(p_report.converged);
+  assert!(p_report.iters < report.iters, "{} vs {} iterations", p_report.iters, report.iters);
+  assert!(residual_norm(&a, &px, &b) <= 1.0e-8);
+  // Restarting from the solution takes no iterations.
+  let restart = conjugate_gradient(&mut a.as_view(), b.as_view(), x.as_view_mut(), &config).unwrap();
+  assert_eq!(0, restart.iters);
+}
+
+#[test]
+fn test_conjugate_gradient_limits() {
+  let n = 50;
+  let a = shifted_matrix(n, 20.0);
+  let b = Array1d::from_storage(n, inputs(n, 5));
+  let mut x = Array1d::zeros(n);
+  let report = conjugate_gradient(&mut a.as_view(), b.as_view(), x.as_view_mut(), &SolverConfig::new(1.0e-14, 3)).unwrap();
+  assert!(!report.converged);
+  assert_eq!(3, report.iters);
+  let indefinite = shifted_matrix(n, 0.0);
+  let mut x = Array1d::zeros(n);
+  match conjugate_gradient(&mut indefinite.as_view(), b.as_view(), x.as_view_mut(), &SolverConfig::new(1.0e-10, 200)) {
+    Err(LinalgError::NotPositiveDefinite(_)) => {}
+    r => panic!("expected a definiteness failure: {:?}", r),
+  }
+}
+
+#[test]
+fn test_preconditioned_conjugate_gradient_badly_scaled() {
+  // `D * (I + E) * D` for a small symmetric `E` and a diagonal `D` spanning
+  // two orders of magnitude. The Jacobi preconditioner undoes most of the
+  // scaling, which otherwise slows down CG.
+  let n = 100;
+  let e = shifted_matrix(n, 0.0);
+  let d: Vec<f64> = (0 .. n).map(|i| 10.0f64.powf(2.0 * i as f64 / (n - 1) as f64)).collect();
+  let mut a = Array2d::zeros((n, n));
+  for j in 0 .. n {
+    for i in 0 .. n {
+      let eye = if i == j { 1.0 } else { 0.0 };
+      a.as_mut_slice()[i + j * n] = d[i] * (eye + e.as_slice()[i + j * n]) * d[j];
+    }
+  }
+  let b = Array1d::from_storage(n, inputs(n, 8));
+  let config = SolverConfig::new(1.0e-10, 1000);
+  let mut x = Array1d::zeros(n);
+  let report = conjugate_gradient(&mut a.as_view(), b.as_view(), x.as_view_mut(), &config).unwrap();
+  assert!(report.converged);
+  let diag: Vec<f64> = (0 .. n).map(|i| a.as_slice()[i + i * n]).collect();
+  let mut precond = DiagonalPreconditioner::new(diag.reshape(n));
+  let mut px = Array1d::zeros(n);
+  let p_report = preconditioned_conjugate_gradient(&mut a.as_view(), &mut precond, b.as_view(), px.as_view_mut(), &config).unwrap();
+  assert!(p_report.converged);
+  assert!(3 * p_report.iters < report.iters, "{} vs {} iterations", p_report.iters, report.iters);
+  assert!(residual_norm(&a, &px, &b) <= 1.0e-9 * b.as_view().l2_norm());
+}
+
+#[test]
+fn test_preconditioned_conjugate_gradient_not_positive_definite() {
+  // For a negative definite operator the first search direction already
+  // has negative curvature, and `x` keeps the initial guess.
+  let n = 50;
+  let mut a = shifted_matrix(n, 20.0);
+  for a_ij in a.as_mut_slice().iter_mut() {
+    *a_ij = -*a_ij;
+  }
+  let diag: Vec<f64> = (0 .. n).map(|i| -a.as_slice()[i + i * n]).collect();
+  let mut precond = DiagonalPreconditioner::new(diag.reshape(n));
+  let b = Array1d::from_storage(n, inputs(n, 9));
+  let x0 = inputs(n, 10);
+  let mut x = Array1d::from_storage(n, x0.clone());
+  match preconditioned_conjugate_gradient(&mut a.as_view(), &mut precond, b.as_view(), x.as_view_mut(), &SolverConfig::new(1.0e-10, 200)) {
+    Err(LinalgError::NotPositiveDefinite(1)) => {}
+    r => panic!("expected a definiteness failure: {:?}", r),
+  }
+  assert_eq!(&x0[..], x.as_slice());
+}
+
+#[test]
+fn test_minres_indefinite() {
+  let n = 40;
+  let mut a = shifted_matrix(n, 1.0);
+  for i in 0 .. n / 2 {
+    a.as_mut_slice()[i + i * n] -= 10.0;
+  }
+  let b = Array1d::from_storage(n, inputs(n, 6));
+  let mut x = Array1d::from_storage(n, inputs(n, 7));
+  let report = minres(&mut a.as_view(), b.as_view(), x.as_view_mut(), &SolverConfig::new(1.0e-10, 200));
+  assert!(report.converged);
+  assert!(report.iters <= n + 5);
+  assert!(residual_norm(&a, &x, &b) <= 1.0e-8, "{}", residual_norm(&a, &x, &b));
+}
@@ -0,0 +1,413 @@
+//! Checks that the pure-Rust kernels in `densearray::kernels::rust` agree
+//! bitwise with the C kernels on random inputs. This needs the C kernels, so
+//! it is skipped with the `rust_kernels` feature.
+
+#![cfg(not(feature = "rust_kernels"))]
+
+extern crate densearray;
+
+use densearray::isa::{self, IsaLevel};
+use densearray::kernels as c;
+use densearray::kernels::rust;
+
+use std::cmp::{min};
+use std::fmt::{Debug};
+
+const DIMS: &'static [usize] = &[0, 1, 3, 4, 5, 8, 17, 64, 1000, 1031];
+
+struct Xorshift {
+  state: u64,
+}
+
+impl Xorshift {
+  fn new(seed: u64) -> Xorshift {
+    Xorshift{state: seed | 1}
+  }
+
+  fn next_u64(&mut self) -> u64 {
+    let mut x = self.state;
+    x ^= x << 13;
+    x ^= x >> 7;
+    x ^= x << 17;
+    self.state = x;
+    x
+  }
+
+  fn next_unit(&mut self) -> f64 {
+    (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+  }
+}
+
+trait TestScalar: Copy + Debug {
+  fn random(rng: &mut Xorshift) -> Self;
+  fn random_positive(rng: &mut Xorshift) -> Self;
+  fn same(self, other: Self) -> bool;
+}
+
+impl TestScalar for f32 {
+  fn random(rng: &mut Xorshift) -> f32 { (20.0 * rng.next_unit() - 10.0) as f32 }
+  fn random_positive(rng: &mut Xorshift) -> f32 { (0.01 + 10.0 * rng.next_unit()) as f32 }
+  fn same(self, other: f32) -> bool { self.to_bits() == other.to_bits() || (self.is_nan() && other.is_nan()) }
+}
+
+impl TestScalar for f64 {
+  fn random(rng: &mut Xorshift) -> f64 { 20.0 * rng.next_unit() - 10.0 }
+  fn random_positive(rng: &mut Xorshift) -> f64 { 0.01 + 10.0 * rng.next_unit() }
+  fn same(self, other: f64) -> bool { self.to_bits() == other.to_bits() || (self.is_nan() && other.is_nan()) }
+}
+
+fn random_vec<T: TestScalar>(rng: &mut Xorshift, n: usize) -> Vec<T> {
+  (0 .. n).map(|_| T::random(rng)).collect()
+}
+
+fn random_positive_vec<T: TestScalar>(rng: &mut Xorshift, n: usize) -> Vec<T> {
+  (0 .. n).map(|_| T::random_positive(rng)).collect()
+}
+
+fn assert_same<T: TestScalar>(name: &str, expected: &[T], actual: &[T]) {
+  assert_eq!(expected.len(), actual.len());
+  for (idx, (&e, &a)) in expected.iter().zip(actual.iter()).enumerate() {
+    assert!(e.same(a), "{}: mismatch at {}: C {:?} vs Rust {:?}", name, idx, e, a);
+  }
+}
+
+type UnaryKernel<T> = unsafe extern "C" fn(*mut T, usize);
+type ScalarKernel<T> = unsafe extern "C" fn(*mut T, usize, T);
+type BinaryKernel<T> = unsafe extern "C" fn(*mut T, usize, *const T);
+type AxpyKernel<T> = unsafe extern "C" fn(*mut T, usize, *const T, T);
+type CastKernel<T> = unsafe extern "C" fn(*mut T, usize, *const u8);
+type ChunkKernel<T> = unsafe extern "C" fn(*const T, usize, usize, usize, *mut T);
+type ChunkInnerProdKernel<T> = unsafe extern "C" fn(*const T, usize, usize, *const T, usize, usize, *mut T);
+type AxisKernel<T> = unsafe extern "C" fn(usize, usize, usize, *const T, usize, usize, usize, *mut T, usize, usize);
+type AxisArgKernel<T> = unsafe extern "C" fn(usize, usize, usize, *const T, usize, usize, usize, *mut u32, usize, usize);
+
+fn check_unary<T: TestScalar>(name: &str, c_fn: UnaryKernel<T>, rust_fn: UnaryKernel<T>, positive: bool) {
+  let mut rng = Xorshift::new(0x1234);
+  for &n in DIMS {
+    let x: Vec<T> = if positive { random_positive_vec(&mut rng, n) } else { random_vec(&mut rng, n) };
+    let mut y_c = x.clone();
+    let mut y_rust = x.clone();
+    unsafe { c_fn(y_c.as_mut_ptr(), n) };
+    unsafe { rust_fn(y_rust.as_mut_ptr(), n) };
+    assert_same(name, &y_c, &y_rust);
+  }
+}
+
+fn check_scalar<T: TestScalar>(name: &str, c_fn: ScalarKernel<T>, rust_fn: ScalarKernel<T>) {
+  let mut rng = Xorshift::new(0x2345);
+  for &n in DIMS {
+    let x: Vec<T> = random_vec(&mut rng, n);
+    let alpha = T::random_positive(&mut rng);
+    let mut y_c = x.clone();
+    let mut y_rust = x.clone();
+    unsafe { c_fn(y_c.as_mut_ptr(), n, alpha) };
+    unsafe { rust_fn(y_rust.as_mut_ptr(), n, alpha) };
+    assert_same(name, &y_c, &y_rust);
+  }
+}
+
+fn check_binary<T: TestScalar>(name: &str, c_fn: BinaryKernel<T>, rust_fn: BinaryKernel<T>) {
+  let mut rng = Xorshift::new(0x3456);
+  for &n in DIMS {
+    let x: Vec<T> = random_positive_vec(&mut rng, n);
+    let y: Vec<T> = random_vec(&mut rng, n);
+    let mut y_c = y.clone();
+    let mut y_rust = y.clone();
+    unsafe { c_fn(y_c.as_mut_ptr(), n, x.as_ptr()) };
+    unsafe { rust_fn(y_rust.as_mut_ptr(), n, x.as_ptr()) };
+    assert_same(name, &y_c, &y_rust);
+  }
+}
+
+fn check_axpy<T: TestScalar>(name: &str, c_fn: AxpyKernel<T>, rust_fn: AxpyKernel<T>) {
+  let mut rng = Xorshift::new(0x4567);
+  for &n in DIMS {
+    let x: Vec<T> = random_vec(&mut rng, n);
+    let y: Vec<T> = random_vec(&mut rng, n);
+    let alpha = T::random(&mut rng);
+    let mut y_c = y.clone();
+    let mut y_rust = y.clone();
+    unsafe { c_fn(y_c.as_mut_ptr(), n, x.as_ptr(), alpha) };
+    unsafe { rust_fn(y_rust.as_mut_ptr(), n, x.as_ptr(), alpha) };
+    assert_same(name, &y_c, &y_rust);
+  }
+}
+
+fn check_cast<T: TestScalar>(name: &str, c_fn: CastKernel<T>, rust_fn: CastKernel<T>) {
+  let mut rng = Xorshift::new(0x5678);
+  for &n in DIMS {
+    let x: Vec<u8> = (0 .. n).map(|_| rng.next_u64() as u8).collect();
+    let mut y_c: Vec<T> = random_vec(&mut rng, n);
+    let mut y_rust = y_c.clone();
+    unsafe { c_fn(y_c.as_mut_ptr(), n, x.as_ptr()) };
+    unsafe { rust_fn(y_rust.as_mut_ptr(), n, x.as_ptr()) };
+    assert_same(name, &y_c, &y_rust);
+  }
+}
+
+fn check_chunk<T: TestScalar>(name: &str, c_fn: ChunkKernel<T>, rust_fn: ChunkKernel<T>) {
+  let mut rng = Xorshift::new(0x6789);
+  for &n in DIMS {
+    for &inc in &[1, 3] {
+      for &chunk_sz in &[1, 7, 64] {
+        let x: Vec<T> = random_vec(&mut rng, n * inc);
+        let num_chunks = (n + chunk_sz - 1) / chunk_sz;
+        let mut p_c: Vec<T> = random_vec(&mut rng, num_chunks);
+        let mut p_rust = p_c.clone();
+        unsafe { c_fn(x.as_ptr(), n, inc, chunk_sz, p_c.as_mut_ptr()) };
+        unsafe { rust_fn(x.as_ptr(), n, inc, chunk_sz, p_rust.as_mut_ptr()) };
+        assert_same(name, &p_c, &p_rust);
+      }
+    }
+  }
+}
+
+fn check_chunk_inner_prod<T: TestScalar>(name: &str, c_fn: ChunkInnerProdKernel<T>, rust_fn: ChunkInnerProdKernel<T>) {
+  let mut rng = Xorshift::new(0x789a);
+  for &n in DIMS {
+    for &(incx, incy) in &[(1, 1), (2, 3)] {
+      let chunk_sz = 16;
+      let x: Vec<T> = random_vec(&mut rng, n * incx);
+      let y: Vec<T> = random_vec(&mut rng, n * incy);
+      let num_chunks = (n + chunk_sz - 1) / chunk_sz;
+      let mut p_c: Vec<T> = random_vec(&mut rng, num_chunks);
+      let mut p_rust = p_c.clone();
+      unsafe { c_fn(x.as_ptr(), n, incx, y.as_ptr(), incy, chunk_sz, p_c.as_mut_ptr()) };
+      unsafe { rust_fn(x.as_ptr(), n, incx, y.as_ptr(), incy, chunk_sz, p_rust.as_mut_ptr()) };
+      assert_same(name, &p_c, &p_rust);
+    }
+  }
+}
+
+// Packed (inner, red, outer) layouts: reducing the first axis exercises the
+// contiguous reduced axis path, the middle axis the blocked path, and a
+// padded inner stride the general path.
+const AXIS_SHAPES: &'static [(usize, usize, usize)] = &[(1, 1, 1), (1, 9, 4), (5, 1, 3), (7, 13, 2), (130, 5, 3), (3, 70, 1)];
+
+fn check_axis<T: TestScalar>(name: &str, c_fn: AxisKernel<T>, rust_fn: AxisKernel<T>) {
+  let mut rng = Xorshift::new(0x89ab);
+  for &(inner, red, outer) in AXIS_SHAPES {
+    for &pad in &[1, 2] {
+      let x: Vec<T> = random_vec(&mut rng, pad * inner * red * outer);
+      let (x_inc, x_red_inc, x_outer_inc) = (pad, pad * inner, pad * inner * red);
+      let mut y_c: Vec<T> = random_vec(&mut rng, inner * outer);
+      let mut y_rust = y_c.clone();
+      unsafe { c_fn(inner, red, outer, x.as_ptr(), x_inc, x_red_inc, x_outer_inc, y_c.as_mut_ptr(), 1, inner) };
+      unsafe { rust_fn(inner, red, outer, x.as_ptr(), x_inc, x_red_inc, x_outer_inc, y_rust.as_mut_ptr(), 1, inner) };
+      assert_same(name, &y_c, &y_rust);
+      // Reduced axis innermost.
+      let (x_inc, x_red_inc, x_outer_inc) = (red, 1, red * inner);
+      let x: Vec<T> = random_vec(&mut rng, inner * red * outer);
+      unsafe { c_fn(inner, red, outer, x.as_ptr(), x_inc, x_red_inc, x_outer_inc, y_c.as_mut_ptr(), 1, inner) };
+      unsafe { rust_fn(inner, red, outer, x.as_ptr(), x_inc, x_red_inc, x_outer_inc, y_rust.as_mut_ptr(), 1, inner) };
+      assert_same(name, &y_c, &y_rust);
+    }
+  }
+}
+
+fn check_axis_arg<T: TestScalar>(name: &str, c_fn: AxisArgKernel<T>, rust_fn: AxisArgKernel<T>) {
+  let mut rng = Xorshift::new(0x9abc);
+  for &(inner, red, outer) in AXIS_SHAPES {
+    let x: Vec<T> = random_vec(&mut rng, inner * red * outer);
+    let mut y_c: Vec<u32> = vec![0; inner * outer];
+    let mut y_rust: Vec<u32> = vec![0; inner * outer];
+    unsafe { c_fn(inner, red, outer, x.as_ptr(), 1, inner, inner * red, y_c.as_mut_ptr(), 1, inner) };
+    unsafe { rust_fn(inner, red, outer, x.as_ptr(), 1, inner, inner * red, y_rust.as_mut_ptr(), 1, inner) };
+    assert_eq!(y_c, y_rust, "{}", name);
+  }
+}
+
+#[test]
+fn test_vector_kernels_f32() {
+  check_unary::<f32>("square_f32", c::densearray_square_f32, rust::densearray_square_f32, false);
+  check_unary::<f32>("cube_f32", c::densearray_cube_f32, rust::densearray_cube_f32, false);
+  check_unary::<f32>("sqrt_f32", c::densearray_sqrt_f32, rust::densearray_sqrt_f32, true);
+  check_unary::<f32>("reciprocal_f32", c::densearray_reciprocal_f32, rust::densearray_reciprocal_f32, true);
+  check_scalar::<f32>("set_scalar_f32", c::densearray_set_scalar_f32, rust::densearray_set_scalar_f32);
+  check_scalar::<f32>("add_scalar_f32", c::densearray_add_scalar_f32, rust::densearray_add_scalar_f32);
+  check_scalar::<f32>("scale_f32", c::densearray_scale_f32, rust::densearray_scale_f32);
+  check_scalar::<f32>("div_scalar_f32", c::densearray_div_scalar_f32, rust::densearray_div_scalar_f32);
+  check_binary::<f32>("copy_f32", c::densearray_copy_f32, rust::densearray_copy_f32);
+  check_binary::<f32>("elem_mult_f32", c::densearray_elem_mult_f32, rust::densearray_elem_mult_f32);
+  check_binary::<f32>("elem_div_f32", c::densearray_elem_div_f32, rust::densearray_elem_div_f32);
+  check_binary::<f32>("elem_ldiv_f32", c::densearray_elem_ldiv_f32, rust::densearray_elem_ldiv_f32);
+  check_axpy::<f32>("vector_add_f32", c::densearray_vector_add_f32, rust::densearray_vector_add_f32);
+  check_axpy::<f32>("vector_average_f32", c::densearray_vector_average_f32, rust::densearray_vector_average_f32);
+  check_cast::<f32>("cast_u8_to_f32", c::densearray_cast_u8_to_f32, rust::densearray_cast_u8_to_f32);
+  check_unary::<f32>("omp_square_f32", c::densearray_omp_square_f32, rust::densearray_omp_square_f32, false);
+  check_axpy::<f32>("omp_vector_add_f32", c::densearray_omp_vector_add_f32, rust::densearray_omp_vector_add_f32);
+}
+
+#[test]
+fn test_vector_kernels_f64() {
+  check_unary::<f64>("square_f64", c::densearray_square_f64, rust::densearray_square_f64, false);
+  check_unary::<f64>("cube_f64", c::densearray_cube_f64, rust::densearray_cube_f64, false);
+  check_unary::<f64>("sqrt_f64", c::densearray_sqrt_f64, rust::densearray_sqrt_f64, true);
+  check_unary::<f64>("reciprocal_f64", c::densearray_reciprocal_f64, rust::densearray_reciprocal_f64, true);
+  check_scalar::<f64>("set_scalar_f64", c::densearray_set_scalar_f64, rust::densearray_set_scalar_f64);
+  check_scalar::<f64>("add_scalar_f64", c::densearray_add_scalar_f64, rust::densearray_add_scalar_f64);
+  check_scalar::<f64>("scale_f64", c::densearray_scale_f64, rust::densearray_scale_f64);
+  check_scalar::<f64>("div_scalar_f64", c::densearray_div_scalar_f64, rust::densearray_div_scalar_f64);
+  check_binary::<f64>("copy_f64", c::densearray_copy_f64, rust::densearray_copy_f64);
+  check_binary::<f64>("elem_mult_f64", c::densearray_elem_mult_f64, rust::densearray_elem_mult_f64);
+  check_binary::<f64>("elem_div_f64", c::densearray_elem_div_f64, rust::densearray_elem_div_f64);
+  check_binary::<f64>("elem_ldiv_f64", c::densearray_elem_ldiv_f64, rust::densearray_elem_ldiv_f64);
+  check_axpy::<f64>("vector_add_f64", c::densearray_vector_add_f64, rust::densearray_vector_add_f64);
+  check_axpy::<f64>("vector_average_f64", c::densearray_vector_average_f64, rust::densearray_vector_average_f64);
+  check_cast::<f64>("cast_u8_to_f64", c::densearray_cast_u8_to_f64, rust::densearray_cast_u8_to_f64);
+  check_unary::<f64>("omp_sqrt_f64", c::densearray_omp_sqrt_f64, rust::densearray_omp_sqrt_f64, true);
+  check_axpy::<f64>("omp_vector_average_f64", c::densearray_omp_vector_average_f64, rust::densearray_omp_vector_average_f64);
+}
+
+#[test]
+fn test_set_scalar_i32() {
+  let mut y_c = vec![0i32; 37];
+  let mut y_rust = vec![0i32; 37];
+  unsafe { c::densearray_set_scalar_i32(y_c.as_mut_ptr(), 37, -5) };
+  unsafe { rust::densearray_set_scalar_i32(y_rust.as_mut_ptr(), 37, -5) };
+  assert_eq!(y_c, y_rust);
+}
+
+#[test]
+fn test_chunk_kernels() {
+  check_chunk::<f32>("chunk_sum_f32", c::densearray_chunk_sum_f32, rust::densearray_chunk_sum_f32);
+  check_chunk::<f32>("chunk_abs_sum_f32", c::densearray_chunk_abs_sum_f32, rust::densearray_chunk_abs_sum_f32);
+  check_chunk::<f32>("chunk_square_sum_f32", c::densearray_chunk_square_sum_f32, rust::densearray_chunk_square_sum_f32);
+  check_chunk_inner_prod::<f32>("chunk_inner_prod_f32", c::densearray_chunk_inner_prod_f32, rust::densearray_chunk_inner_prod_f32);
+  check_chunk::<f64>("chunk_sum_f64", c::densearray_chunk_sum_f64, rust::densearray_chunk_sum_f64);
+  check_chunk::<f64>("chunk_abs_sum_f64", c::densearray_chunk_abs_sum_f64, rust::densearray_chunk_abs_sum_f64);
+  check_chunk::<f64>("chunk_square_sum_f64", c::densearray_chunk_square_sum_f64, rust::densearray_chunk_square_sum_f64);
+  check_chunk_inner_prod::<f64>("chunk_inner_prod_f64", c::densearray_chunk_inner_prod_f64, rust::densearray_chunk_inner_prod_f64);
+  check_chunk::<f32>("omp_chunk_sum_f32", c::densearray_omp_chunk_sum_f32, rust::densearray_omp_chunk_sum_f32);
+  check_chunk_inner_prod::<f64>("omp_chunk_inner_prod_f64", c::densearray_omp_chunk_inner_prod_f64, rust::densearray_omp_chunk_inner_prod_f64);
+}
+
+#[test]
+fn test_reduce_axis_kernels() {
+  check_axis::<f32>("reduce_axis_sum_f32", c::densearray_reduce_axis_sum_f32, rust::densearray_reduce_axis_sum_f32);
+  check_axis::<f32>("reduce_axis_mean_f32", c::densearray_reduce_axis_mean_f32, rust::densearray_reduce_axis_mean_f32);
+  check_axis::<f32>("reduce_axis_max_f32", c::densearray_reduce_axis_max_f32, rust::densearray_reduce_axis_max_f32);
+  check_axis::<f32>("reduce_axis_min_f32", c::densearray_reduce_axis_min_f32, rust::densearray_reduce_axis_min_f32);
+  check_axis_arg::<f32>("reduce_axis_argmax_f32", c::densearray_reduce_axis_argmax_f32, rust::densearray_reduce_axis_argmax_f32);
+  check_axis_arg::<f32>("reduce_axis_argmin_f32", c::densearray_reduce_axis_argmin_f32, rust::densearray_reduce_axis_argmin_f32);
+  check_axis::<f64>("reduce_axis_sum_f64", c::densearray_reduce_axis_sum_f64, rust::densearray_reduce_axis_sum_f64);
+  check_axis::<f64>("reduce_axis_mean_f64", c::densearray_reduce_axis_mean_f64, rust::densearray_reduce_axis_mean_f64);
+  check_axis::<f64>("reduce_axis_max_f64", c::densearray_reduce_axis_max_f64, rust::densearray_reduce_axis_max_f64);
+  check_axis::<f64>("reduce_axis_min_f64", c::densearray_reduce_axis_min_f64, rust::densearray_reduce_axis_min_f64);
+  check_axis_arg::<f64>("reduce_axis_argmax_f64", c::densearray_reduce_axis_argmax_f64, rust::densearray_reduce_axis_argmax_f64);
+  check_axis_arg::<f64>("reduce_axis_argmin_f64", c::densearray_reduce_axis_argmin_f64, rust::densearray_reduce_axis_argmin_f64);
+  check_axis::<f32>("omp_reduce_axis_mean_f32", c::densearray_omp_reduce_axis_mean_f32, rust::densearray_omp_reduce_axis_mean_f32);
+  check_axis_arg::<f64>("omp_reduce_axis_argmax_f64", c::densearray_omp_reduce_axis_argmax_f64, rust::densearray_omp_reduce_axis_argmax_f64);
+}
+
+type ConvertKernel<S, U> = unsafe extern "C" fn(usize, *const S, *mut U);
+
+fn check_convert<S: Copy, U: Copy + Default + Debug>(name: &str, c_fn: ConvertKernel<S, U>, rust_fn: ConvertKernel<S, U>, x: &[S], same: fn(U, U) -> bool) {
+  // Every prefix length in `DIMS` exercises a different packed/tail split.
+  for &n in DIMS.iter().chain(Some(x.len()).iter()) {
+    let n = min(n, x.len());
+    let mut y_c = vec![U::default(); n];
+    let mut y_rust = vec![U::default(); n];
+    unsafe { c_fn(n, x.as_ptr(), y_c.as_mut_ptr()) };
+    unsafe { rust_fn(n, x.as_ptr(), y_rust.as_mut_ptr()) };
+    for idx in 0 .. n {
+      assert!(same(y_c[idx], y_rust[idx]), "{}: mismatch at {} (n = {}): C {:?} vs Rust {:?}", name, idx, n, y_c[idx], y_rust[idx]);
+    }
+  }
+}
+
+fn same_int<U: PartialEq>(x: U, y: U) -> bool {
+  x == y
+}
+
+fn round_clamp_inputs(rng: &mut Xorshift, lower: f64, upper: f64) -> Vec<f32> {
+  let width = upper - lower;
+  let mut x: Vec<f32> = (0 .. 1031).map(|_| (lower - 0.25 * width + 1.5 * width * rng.next_unit()) as f32).collect();
+  // Ties, edge values and NaNs for the rounding kernels, also placed at the
+  // start so that they go through the packed loops.
+  let edges = [0.5, 1.5, 2.5, -0.5, -1.5, 0.0, lower as f32, upper as f32, (lower - 0.5) as f32, (upper - 0.5) as f32, (upper + 0.5) as f32, ::std::f32::NAN, ::std::f32::INFINITY, ::std::f32::NEG_INFINITY, 1.0e10, -1.0e10];
+  let mut y = edges.to_vec();
+  y.append(&mut x);
+  y.extend_from_slice(&edges);
+  y
+}
+
+#[test]
+fn test_cast_kernels() {
+  let mut rng = Xorshift::new(0xabcd);
+  let x = round_clamp_inputs(&mut rng, 0.0, 255.0);
+  check_convert::<f32, u8>("round_clamp_1d_f32_to_u8_sse2", c::densearray_kernel_round_clamp_1d_f32_to_u8_sse2, rust::densearray_kernel_round_clamp_1d_f32_to_u8_sse2, &x, same_int);
+  check_convert::<f32, u8>("round_clamp_1d_f32_to_u8_sse4", c::densearray_kernel_round_clamp_1d_f32_to_u8_sse4, rust::densearray_kernel_round_clamp_1d_f32_to_u8_sse4, &x, same_int);
+  check_convert::<f32, u8>("round_clamp_1d_f32_to_u8", c::densearray_kernel_round_clamp_1d_f32_to_u8, rust::densearray_kernel_round_clamp_1d_f32_to_u8, &x, same_int);
+  let x_i8 = round_clamp_inputs(&mut rng, -128.0, 127.0);
+  check_convert::<f32, i8>("round_clamp_1d_f32_to_i8", c::densearray_kernel_round_clamp_1d_f32_to_i8, rust::densearray_kernel_round_clamp_1d_f32_to_i8, &x_i8, same_int);
+  let x_u16 = round_clamp_inputs(&mut rng, 0.0, 65535.0);
+  check_convert::<f32, u16>("round_clamp_1d_f32_to_u16", c::densearray_kernel_round_clamp_1d_f32_to_u16, rust::densearray_kernel_round_clamp_1d_f32_to_u16, &x_u16, same_int);
+  let x_i32 = round_clamp_inputs(&mut rng, -2147483648.0, 2147483520.0);
+  check_convert::<f32, i32>("round_clamp_1d_f32_to_i32", c::densearray_kernel_round_clamp_1d_f32_to_i32, rust::densearray_kernel_round_clamp_1d_f32_to_i32, &x_i32, same_int);
+  check_convert::<f32, i32>("round_clamp_1d_f32_to_i32", c::densearray_kernel_round_clamp_1d_f32_to_i32, rust::densearray_kernel_round_clamp_1d_f32_to_i32, &x, same_int);
+  let mut z_c = vec![0.0f32; x.len()];
+  let mut z_rust = vec![0.0f32; x.len()];
+  for &n in DIMS.iter() {
+    unsafe { c::densearray_kernel_clamp_1d_f32(n, x.as_ptr(), z_c.as_mut_ptr(), -1.0, 100.0) };
+    unsafe { rust::densearray_kernel_clamp_1d_f32(n, x.as_ptr(), z_rust.as_mut_ptr(), -1.0, 100.0) };
+    assert_same("clamp_1d_f32", &z_c[ .. n], &z_rust[ .. n]);
+  }
+  unsafe { c::densearray_kernel_clamp_1d_f32_sse2(x.len(), x.as_ptr(), z_c.as_mut_ptr(), -1.0, 100.0) };
+  unsafe { rust::densearray_kernel_clamp_1d_f32_sse2(x.len(), x.as_ptr(), z_rust.as_mut_ptr(), -1.0, 100.0) };
+  assert_same("clamp_1d_f32_sse2", &z_c, &z_rust);
+  let b: Vec<u8> = (0 .. 1031).map(|i| i as u8).collect();
+  check_convert::<u8, f32>("cast_1d_u8_to_f32", c::densearray_kernel_cast_1d_u8_to_f32, rust::densearray_kernel_cast_1d_u8_to_f32, &b, TestScalar::same);
+  let h: Vec<u16> = (0 .. 1031).map(|i| (i * 67) as u16).chain(vec![0, 1, 32767, 32768, 65535]).collect();
+  check_convert::<u16, f32>("cast_1d_u16_to_f32", c::densearray_kernel_cast_1d_u16_to_f32, rust::densearray_kernel_cast_1d_u16_to_f32, &h, TestScalar::same);
+  check_convert::<f32, f64>("cast_1d_f32_to_f64", c::densearray_kernel_cast_1d_f32_to_f64, rust::densearray_kernel_cast_1d_f32_to_f64, &x, TestScalar::same);
+  let mut d: Vec<f64> = random_vec(&mut rng, 1031);
+  d.extend_from_slice(&[1.0e300, -1.0e300, 1.0e-300, ::std::f64::NAN, ::std::f64::INFINITY]);
+  check_convert::<f64, f32>("cast_1d_f64_to_f32", c::densearray_kernel_cast_1d_f64_to_f32, rust::densearray_kernel_cast_1d_f64_to_f32, &d, TestScalar::same);
+}
+
+#[test]
+fn test_forced_isa_levels() {
+  let detected = isa::detected_isa_level();
+  let mut rng = Xorshift::new(0xbcde);
+  let x = round_clamp_inputs(&mut rng, 0.0, 255.0);
+  let mut y_sse4 = vec![0u8; x.len()];
+  unsafe { c::densearray_kernel_round_clamp_1d_f32_to_u8_sse4(x.len(), x.as_ptr(), y_sse4.as_mut_ptr()) };
+  let mut z_sse2 = vec![0.0f32; x.len()];
+  unsafe { c::densearray_kernel_clamp_1d_f32_sse2(x.len(), x.as_ptr(), z_sse2.as_mut_ptr(), -1.0, 100.0) };
+  for &level in &[IsaLevel::Sse2, IsaLevel::Sse41, IsaLevel::Avx2, IsaLevel::Avx512] {
+    isa::force_isa_level(Some(level));
+    assert_eq!(min(level, detected), isa::isa_level());
+    check_unary::<f32>("sqrt_f32", c::densearray_sqrt_f32, rust::densearray_sqrt_f32, true);
+    check_axpy::<f32>("vector_average_f32", c::densearray_vector_average_f32, rust::densearray_vector_average_f32);
+    check_binary::<f64>("elem_div_f64", c::densearray_elem_div_f64, rust::densearray_elem_div_f64);
+    check_chunk::<f64>("chunk_square_sum_f64", c::densearray_chunk_square_sum_f64, rust::densearray_chunk_square_sum_f64);
+    check_axis::<f32>("reduce_axis_sum_f32", c::densearray_reduce_axis_sum_f32, rust::densearray_reduce_axis_sum_f32);
+    check_axis::<f64>("omp_reduce_axis_mean_f64", c::densearray_omp_reduce_axis_mean_f64, rust::densearray_omp_reduce_axis_mean_f64);
+    let mut y = vec![0u8; x.len()];
+    unsafe { c::densearray_kernel_round_clamp_1d_f32_to_u8(x.len(), x.as_ptr(), y.as_mut_ptr()) };
+    assert_eq!(y_sse4, y);
+    let mut z = vec![0.0f32; x.len()];
+    unsafe { c::densearray_kernel_clamp_1d_f32(x.len(), x.as_ptr(), z.as_mut_ptr(), -1.0, 100.0) };
+    assert_same("clamp_1d_f32", &z_sse2, &z);
+    if level <= detected {
+      match level {
+        IsaLevel::Avx2 => {
+          unsafe { c::densearray_kernel_round_clamp_1d_f32_to_u8_avx2(x.len(), x.as_ptr(), y.as_mut_ptr()) };
+          assert_eq!(y_sse4, y);
+          unsafe { c::densearray_kernel_clamp_1d_f32_avx2(x.len(), x.as_ptr(), z.as_mut_ptr(), -1.0, 100.0) };
+          assert_same("clamp_1d_f32_avx2", &z_sse2, &z);
+        }
+        IsaLevel::Avx512 => {
+          unsafe { c::densearray_kernel_round_clamp_1d_f32_to_u8_avx512(x.len(), x.as_ptr(), y.as_mut_ptr()) };
+          assert_eq!(y_sse4, y);
+          unsafe { c::densearray_kernel_clamp_1d_f32_avx512(x.len(), x.as_ptr(), z.as_mut_ptr(), -1.0, 100.0) };
+          assert_same("clamp_1d_f32_avx512", &z_sse2, &z);
+        }
+        _ => {}
+      }
+    }
+  }
+  isa::force_isa_level(None);
+  assert_eq!(detected, isa::isa_level());
+}
@@ -0,0 +1,659 @@
+//! Checks the dense factorizations, solvers and rank updates against
+//! products computed elementwise.
+
+extern crate densearray;
+
+mod common;
+
+use common::{inputs};
+use densearray::backend::{Backend, Reference};
+use densearray::prelude::*;
+
+/// `M^T * M + n * I` for a pseudorandom `n x n` matrix `M`.
+fn spd_matrix(n: usize, seed: usize) -> Array2d<f64> {
+  let m = inputs(n * n, seed);
+  let mut a = vec![0.0; n * n];
+  for j in 0 .. n {
+    for i in 0 .. n {
+      let mut s = if i == j { n as f64 } else { 0.0 };
+      for k in 0 .. n {
+        s += m[k + i * n] * m[k + j * n];
+      }
+      a[i + j * n] = s;
+    }
+  }
+  Array2d::from_storage((n, n), a)
+}
+
+/// `A * X` for column-major `A` (`m x k`) and `X` (`k x n`).
+fn matmul(a: &[f64], x: &[f64], m: usize, k: usize, n: usize) -> Vec<f64> {
+  let mut y = vec![0.0; m * n];
+  for j in 0 .. n {
+    for l in 0 .. k {
+      for i in 0 .. m {
+        y[i + j * m] += a[i + l * m] * x[l + j * k];
+      }
+    }
+  }
+  y
+}
+
+fn assert_close(expected: &[f64], actual: &[f64], tol: f64) {
+  assert_eq!(expected.len(), actual.len());
+  for (&e, &a) in expected.iter().zip(actual.iter()) {
+    assert!((e - a).abs() <= tol * e.abs().max(1.0), "{} vs {}", e, a);
+  }
+}
+
+#[test]
+fn test_cholesky_in_place() {
+  let n = 7;
+  let a = spd_matrix(n, 1);
+  for &uplo in &[Uplo::Lower, Uplo::Upper] {
+    let mut f = Array2d::from_storage((n, n), a.as_slice().to_vec());
+    f.as_view_mut().cholesky_in_place(uplo).unwrap();
+    // Rebuild `A` from the factor triangle alone.
+    let mut l = vec![0.0; n * n];
+    for j in 0 .. n {
+      for i in j .. n {
+        l[i + j * n] = match uplo {
+          Uplo::Lower => f.as_slice()[i + j * n],
+          Uplo::Upper => f.as_slice()[j + i * n],
+        };
+      }
+    }
+    let mut lt = vec![0.0; n * n];
+    for j in 0 .. n {
+      for i in 0 .. n {
+        lt[i + j * n] = l[j + i * n];
+      }
+    }
+    assert_close(a.as_slice(), &matmul(&l, &lt, n, n, n), 1.0e-12);
+  }
+}
+
+#[test]
+fn test_cholesky_not_positive_definite() {
+  let mut a = Array2d::from_storage((3, 3), vec![
+      4.0, 2.0, 0.0,
+      2.0, 1.0, 0.0,
+      0.0, 0.0, 1.0f64,
+  ]);
+  assert_eq!(Err(LinalgError::NotPositiveDefinite(2)), a.as_view_mut().cholesky_in_place(Uplo::Lower));
+  assert!(CholeskyFactor::new(a.as_view(), Uplo::Upper).is_err());
+}
+
+#[test]
+fn test_cholesky_factor_solve() {
+  let (n, nrhs) = (9, 4);
+  let a = spd_matrix(n, 2);
+  let x = inputs(n * nrhs, 3);
+  let b = matmul(a.as_slice(), &x, n, n, nrhs);
+  for &uplo in &[Uplo::Lower, Uplo::Upper] {
+    let factor = CholeskyFactor::new(a.as_view(), uplo).unwrap();
+    let mut y = Array2d::from_storage((n, nrhs), b.clone());
+    factor.solve_matrix(y.as_view_mut());
+    assert_close(&x, y.as_slice(), 1.0e-10);
+    let mut y = Array2d::from_storage((n, nrhs), b.clone());
+    y.as_view_mut().cholesky_solve(factor.factor(), uplo).unwrap();
+    assert_close(&x, y.as_slice(), 1.0e-10);
+    let mut y = Array1d::from_storage(n, b[ .. n].to_vec());
+    factor.solve(y.as_view_mut());
+    assert_close(&x[ .. n], y.as_slice(), 1.0e-10);
+  }
+}
+
+#[test]
+fn test_symm_linear_solve_keeps_input() {
+  let n = 6;
+  let a = spd_matrix(n, 4);
+  let a0 = a.as_slice().to_vec();
+  let x = inputs(n, 5);
+  let b = Array1d::from_storage(n, matmul(&a0, &x, n, n, 1));
+  let mut y = Array1d::zeros(n);
+  y.as_view_mut().symm_linear_solve(a.as_view(), b.as_view()).unwrap();
+  assert_close(&x, y.as_slice(), 1.0e-10);
+  assert_eq!(&a0[..], a.as_slice());
+}
+
+#[test]
+fn test_triangular_solve() {
+  let (n, nrhs) = (5, 3);
+  let mut t = inputs(n * n, 6);
+  for i in 0 .. n {
+    t[i + i * n] += 2.0;
+  }
+  let x = inputs(n * nrhs, 7);
+  for &uplo in &[Uplo::Lower, Uplo::Upper] {
+    for &trans in &[false, true] {
+      // The explicit `op(A)`, with the unused triangle zeroed.
+      let mut op_a = vec![0.0; n * n];
+      for j in 0 .. n {
+        for i in 0 .. n {
+          let in_tri = match uplo {
+            Uplo::Lower => i >= j,
+            Uplo::Upper => i <= j,
+          };
+          if in_tri {
+            if trans {
+              op_a[j + i * n] = t[i + j * n];
+            } else {
+              op_a[i + j * n] = t[i + j * n];
+            }
+          }
+        }
+      }
+      let a_trans = if trans { Transpose::T } else { Transpose::N };
+      let a = Array2d::from_storage((n, n), t.clone());
+      let b = matmul(&op_a, &x, n, n, nrhs);
+      let mut y = Array2d::from_storage((n, nrhs), b.iter().map(|&b_i| 2.0 * b_i).collect::<Vec<_>>());
+      y.as_view_mut().triangular_solve(0.5, a.as_view(), uplo, a_trans);
+      assert_close(&x, y.as_slice(), 1.0e-10);
+      let mut y = Array1d::from_storage(n, b[ .. n].to_vec());
+      y.as_view_mut().triangular_solve(a.as_view(), uplo, a_trans);
+      assert_close(&x[ .. n], y.as_slice(), 1.0e-10);
+    }
+  }
+}
+
+#[test]
+fn test_lu_factor() {
+  let (n, nrhs) = (8, 3);
+  let a = Array2d::from_storage((n, n), inputs(n * n, 8));
+  let x = inputs(n * nrhs, 9);
+  let b = matmul(a.as_slice(), &x, n, n, nrhs);
+  let lu = LuFactor::new(a.as_view()).unwrap();
+  let mut y = Array2d::from_storage((n, nrhs), b.clone());
+  lu.solve_matrix(y.as_view_mut());
+  assert_close(&x, y.as_slice(), 1.0e-10);
+  let mut y = Array1d::from_storage(n, b[ .. n].to_vec());
+  lu.solve(y.as_view_mut());
+  assert_close(&x[ .. n], y.as_slice(), 1.0e-10);
+  let inv = lu.inverse();
+  let mut eye = vec![0.0; n * n];
+  for i in 0 .. n {
+    eye[i + i * n] = 1.0;
+  }
+  assert_close(&eye, &matmul(a.as_slice(), inv.as_slice(), n, n, n), 1.0e-10);
+}
+
+#[test]
+fn test_lu_determinant() {
+  // The first pivot needs a row interchange, and the determinant is
+  // (1 * 1 - 3 * 2) * 4 = -20.
+  let a = Array2d::from_storage((3, 3), vec![
+      1.0, 2.0, 0.0,
+      3.0, 1.0, 0.0,
+      0.0, 0.0, 4.0f64,
+  ]);
+  let lu = LuFactor::new(a.as_view()).unwrap();
+  assert!((lu.determinant() - -20.0).abs() < 1.0e-12);
+  assert_eq!(-1.0, lu.determinant_sign());
+  assert!((lu.log_abs_determinant() - 20.0f64.ln()).abs() < 1.0e-12);
+  let a = Array2d::from_storage((2, 2), vec![1.0, 2.0, 2.0, 4.0f32]);
+  assert_eq!(Some(LinalgError::Singular(2)), LuFactor::new(a.as_view()).err());
+}
+
+#[test]
+fn test_qr() {
+  for &(m, n) in &[(7, 4), (4, 6), (5, 5)] {
+    let a = Array2d::from_storage((m, n), inputs(m * n, 10));
+    let k = m.min(n);
+    for &mode in &[QrMode::Full, QrMode::Thin] {
+      let Qr{q, r} = qr(a.as_view(), mode);
+      let q_n = if mode == QrMode::Full { m } else { k };
+      assert_eq!((m, q_n), q.dim());
+      assert_eq!((q_n, n), r.dim());
+      assert_close(a.as_slice(), &matmul(q.as_slice(), r.as_slice(), m, q_n, n), 1.0e-12);
+      for j in 0 .. n {
+        for i in j + 1 .. q_n {
+          assert_eq!(0.0, r.as_slice()[i + j * q_n]);
+        }
+      }
+      let mut qt = vec![0.0; q_n * m];
+      for j in 0 .. q_n {
+        for i in 0 .. m {
+          qt[j + i * q_n] = q.as_slice()[i + j * m];
+        }
+      }
+      let mut eye = vec![0.0; q_n * q_n];
+      for i in 0 .. q_n {
+        eye[i + i * q_n] = 1.0;
+      }
+      assert_close(&eye, &matmul(&qt, q.as_slice(), q_n, m, q_n), 1.0e-12);
+    }
+  }
+}
+
+#[test]
+fn test_least_squares() {
+  let (m, n, nrhs) = (9, 3, 2);
+  let a = Array2d::from_storage((m, n), inputs(m * n, 11));
+  let x = inputs(n * nrhs, 12);
+  let mut b = matmul(a.as_slice(), &x, m, n, nrhs);
+  // Perturb the first right-hand side by a vector orthogonal to the columns
+  // of `A`, which changes the residual but not the solution.
+  let Qr{q, ..} = qr(a.as_view(), QrMode::Full);
+  for i in 0 .. m {
+    b[i] += 0.5 * q.as_slice()[i + (m - 1) * m];
+  }
+  let b = Array2d::from_storage((m, nrhs), b);
+  let lsq = least_squares(a.as_view(), b.as_view()).unwrap();
+  assert_close(&x, lsq.solution.as_slice(), 1.0e-10);
+  assert_close(&[0.5, 0.0], &lsq.residual_norms, 1.0e-10);
+  let mut a = a.as_slice().to_vec();
+  for i in 0 .. m {
+    a[i + 2 * m] = a[i] - 2.0 * a[i + m];
+  }
+  let a = Array2d::from_storage((m, n), a);
+  assert_eq!(Some(LinalgError::RankDeficient(3)), least_squares(a.as_view(), b.as_view()).err());
+}
+
+#[test]
+fn test_reference_gels_underdetermined() {
+  // `A = [I | C]` has the null space spanned by the columns of `[-C; I]`,
+  // to which the minimum norm solution is orthogonal.
+  let (m, n, nrhs) = (3, 5, 2);
+  let c = inputs(m * (n - m), 13);
+  let mut a = eye(m);
+  a.extend_from_slice(&c);
+  let b = inputs(m * nrhs, 14);
+  let mut x = vec![0.0; n * nrhs];
+  for k in 0 .. nrhs {
+    x[k * n .. k * n + m].copy_from_slice(&b[k * m .. (k + 1) * m]);
+  }
+  let mut a_work = a.clone();
+  let status = unsafe { <Reference as Backend<f64>>::gels(
+      m, n, nrhs,
+      a_work.as_mut_ptr(), m,
+      x.as_mut_ptr(), n,
+  ) };
+  assert_eq!(0, status);
+  assert_close(&b, &matmul(&a, &x, m, n, nrhs), 1.0e-10);
+  for k in 0 .. nrhs {
+    for l in 0 .. n - m {
+      let mut dot = x[k * n + m + l];
+      for i in 0 .. m {
+        dot -= c[i + l * m] * x[k * n + i];
+      }
+      assert!(dot.abs() <= 1.0e-10, "{}", dot);
+    }
+  }
+  let status = unsafe { <Reference as Backend<f64>>::gels(
+      m, n, nrhs,
+      a_work.as_mut_ptr(), m,
+      x.as_mut_ptr(), m,
+  ) };
+  assert_eq!(-8, status);
+}
+
+/// `Q^T * Q` for column-major `Q` (`m x n`).
+fn gram(q: &[f64], m: usize, n: usize) -> Vec<f64> {
+  let mut qt = vec![0.0; n * m];
+  for j in 0 .. n {
+    for i in 0 .. m {
+      qt[j + i * n] = q[i + j * m];
+    }
+  }
+  matmul(&qt, q, n, m, n)
+}
+
+fn eye(n: usize) -> Vec<f64> {
+  let mut eye = vec![0.0; n * n];
+  for i in 0 .. n {
+    eye[i + i * n] = 1.0;
+  }
+  eye
+}
+
+#[test]
+fn test_svd() {
+  for &(m, n) in &[(7, 4), (4, 6), (5, 5)] {
+    let a = Array2d::from_storage((m, n), inputs(m * n, 13));
+    let k = m.min(n);
+    let values = svd(a.as_view(), SvdMode::ValuesOnly).unwrap();
+    assert!(values.u.is_none() && values.vt.is_none());
+    for &mode in &[SvdMode::Full, SvdMode::Thin] {
+      let Svd{u, s, vt} = svd(a.as_view(), mode).unwrap();
+      let (u, vt) = (u.unwrap(), vt.unwrap());
+      let (u_n, vt_m) = if mode == SvdMode::Full { (m, n) } else { (k, k) };
+      assert_eq!((m, u_n), u.dim());
+      assert_eq!((vt_m, n), vt.dim());
+      assert_close(&values.s, &s, 1.0e-12);
+      for i in 1 .. k {
+        assert!(s[i - 1] >= s[i]);
+      }
+      // `U[:, .. k] * diag(s) * Vt[.. k, :]`.
+      let mut us = vec![0.0; m * k];
+      for j in 0 .. k {
+        for i in 0 .. m {
+          us[i + j * m] = u.as_slice()[i + j * m] * s[j];
+        }
+      }
+      let mut vt_k = vec![0.0; k * n];
+      for j in 0 .. n {
+        for i in 0 .. k {
+          vt_k[i + j * k] = vt.as_slice()[i + j * vt_m];
+        }
+      }
+      assert_close(a.as_slice(), &matmul(&us, &vt_k, m, k, n), 1.0e-12);
+      assert_close(&eye(u_n), &gram(u.as_slice(), m, u_n), 1.0e-12);
+      let mut v = vec![0.0; n * vt_m];
+      for j in 0 .. n {
+        for i in 0 .. vt_m {
+          v[j + i * n] = vt.as_slice()[i + j * vt_m];
+        }
+      }
+      assert_close(&eye(vt_m), &gram(&v, n, vt_m), 1.0e-12);
+    }
+  }
+}
+
+#[test]
+fn test_svd_rank_deficient() {
+  // The third column is a combination of the first two.
+  let (m, n) = (6, 3);
+  let mut a = inputs(m * n, 14);
+  for i in 0 .. m {
+    a[i + 2 * m] = a[i] - 2.0 * a[i + m];
+  }
+  let a = Array2d::from_storage((m, n), a);
+  assert_eq!(2, matrix_rank(a.as_view()).unwrap());
+  let Svd{u, s, ..} = svd(a.as_view(), SvdMode::Full).unwrap();
+  assert!(s[2] <= 1.0e-12 * s[0]);
+  assert_close(&eye(m), &gram(u.unwrap().as_slice(), m, m), 1.0e-12);
+  // `A * A^+ * A = A` and `A^+ * A * A^+ = A^+`.
+  let pinv = pseudo_inverse(a.as_view()).unwrap();
+  assert_eq!((n, m), pinv.dim());
+  let a_pinv = matmul(a.as_slice(), pinv.as_slice(), m, n, m);
+  assert_close(a.as_slice(), &matmul(&a_pinv, a.as_slice(), m, m, n), 1.0e-10);
+  let pinv_a = matmul(pinv.as_slice(), a.as_slice(), n, m, n);
+  assert_close(pinv.as_slice(), &matmul(&pinv_a, pinv.as_slice(), n, n, m), 1.0e-10);
+}
+
+#[test]
+fn test_svd_norms() {
+  // A diagonal matrix with a permuted and negated diagonal.
+  let a = Array2d::from_storage((3, 4), vec![
+      0.0, 0.0, -1.0,
+      3.0, 0.0, 0.0,
+      0.0, 2.0, 0.0,
+      0.0, 0.0, 0.0f64,
+  ]);
+  assert_close(&[3.0, 2.0, 1.0], &singular_values(a.as_view()).unwrap(), 1.0e-12);
+  assert!((spectral_norm(a.as_view()).unwrap() - 3.0).abs() < 1.0e-12);
+  assert!((nuclear_norm(a.as_view()).unwrap() - 6.0).abs() < 1.0e-12);
+  assert_eq!(3, matrix_rank(a.as_view()).unwrap());
+  let empty = Array2d::<f32>::zeros((0, 3));
+  assert_eq!(0.0, spectral_norm(empty.as_view()).unwrap());
+  let Svd{vt, ..} = svd(empty.as_view(), SvdMode::Full).unwrap();
+  assert_eq!((3, 3), vt.unwrap().dim());
+}
+
+/// A symmetric matrix with eigenvalues of both signs.
+fn symmetric_matrix(n: usize, seed: usize) -> Vec<f64> {
+  let m = inputs(n * n, seed);
+  let mut a = vec![0.0; n * n];
+  for j in 0 .. n {
+    for i in 0 .. n {
+      a[i + j * n] = m[i + j * n] + m[j + i * n];
+    }
+  }
+  a
+}
+
+fn assert_eigenpairs(a: &[f64], n: usize, values: &[f64], vectors: &[f64]) {
+  let m = values.len();
+  let mut v_lambda = vectors.to_vec();
+  for j in 0 .. m {
+    for i in 0 .. n {
+      v_lambda[i + j * n] *= values[j];
+    }
+  }
+  assert_close(&v_lambda, &matmul(a, vectors, n, n, m), 1.0e-12);
+  assert_close(&eye(m), &gram(vectors, n, m), 1.0e-12);
+}
+
+#[test]
+fn test_symmetric_eigen() {
+  let n = 8;
+  let a = symmetric_matrix(n, 15);
+  // Only the `uplo` triangle is read.
+  let mut a_tri = a.clone();
+  for j in 0 .. n {
+    for i in j + 1 .. n {
+      a_tri[i + j * n] = 100.0;
+    }
+  }
+  let a_tri = Array2d::from_storage((n, n), a_tri);
+  let SymmetricEigen{values, vectors} = symmetric_eigen(a_tri.as_view(), Uplo::Upper, EigenRange::All).unwrap();
+  assert_eq!(n, values.len());
+  assert!(values[0] < 0.0 && values[n - 1] > 0.0);
+  for i in 1 .. n {
+    assert!(values[i - 1] <= values[i]);
+  }
+  assert_eigenpairs(&a, n, &values, vectors.as_slice());
+  let a = Array2d::from_storage((n, n), a);
+  let subset = symmetric_eigen(a.as_view(), Uplo::Lower, EigenRange::Indices(2, 5)).unwrap();
+  assert_eq!((n, 3), subset.vectors.dim());
+  assert_close(&values[2 .. 5], &subset.values, 1.0e-12);
+  assert_eigenpairs(a.as_slice(), n, &subset.values, subset.vectors.as_slice());
+  let (lo, hi) = (0.5 * (values[3] + values[4]), 0.5 * (values[6] + values[7]));
+  let subset = symmetric_eigenvalues(a.as_view(), Uplo::Lower, EigenRange::Values(lo, hi)).unwrap();
+  assert_close(&values[4 .. 7], &subset, 1.0e-12);
+  assert!(symmetric_eigenvalues(a.as_view(), Uplo::Lower, EigenRange::Indices(3, 3)).unwrap().is_empty());
+}
+
+#[test]
+fn test_symmetric_tridiagonal_eigenvectors() {
+  // The second matrix splits into blocks with a shared eigenvalue, and the
+  // third at a zero off-diagonal entry into blocks whose eigenvalues
+  // interleave.
+  let cases: Vec<(Vec<f64>, Vec<f64>)> = vec![
+    (inputs(9, 16), inputs(8, 17)),
+    (vec![1.0, 2.0, 1.0, 3.0], vec![0.0, 0.0, 0.5]),
+    (vec![1.0, 3.0, 2.0, 4.0, 0.5], vec![0.5, 0.0, 0.3, 0.2]),
+  ];
+  for (diag, offdiag) in cases {
+    let n = diag.len();
+    let mut t = vec![0.0; n * n];
+    for i in 0 .. n {
+      t[i + i * n] = diag[i];
+      if i + 1 < n {
+        t[i + 1 + i * n] = offdiag[i];
+        t[i + (i + 1) * n] = offdiag[i];
+      }
+    }
+    let mut values = vec![0.0; n];
+    let mut workspace = SymmetricTridiagonalEigenWorkspace::new(n);
+    assert_eq!(n, solve_symmetric_tridiagonal_eigenvalues(&diag, &offdiag, EigenRange::All, &mut values, &mut workspace, 0.0).unwrap());
+    let mut vectors = Array2d::zeros((n, n));
+    solve_symmetric_tridiagonal_eigenvectors(&diag, &offdiag, &values, &workspace, vectors.as_view_mut()).unwrap();
+    for i in 1 .. n {
+      values[i] = values[i].max(values[i - 1]);
+    }
+    assert_eigenpairs(&t, n, &values, vectors.as_slice());
+    // The eigenvectors of a subset of the spectrum.
+    let mut subset = vec![0.0; n];
+    assert_eq!(n - 2, solve_symmetric_tridiagonal_eigenvalues(&diag, &offdiag, EigenRange::Indices(1, n - 1), &mut subset, &mut workspace, 0.0).unwrap());
+    let mut vectors = Array2d::zeros((n, n - 2));
+    solve_symmetric_tridiagonal_eigenvectors(&diag, &offdiag, &subset[ .. n - 2], &workspace, vectors.as_view_mut()).unwrap();
+    assert_eigenpairs(&t, n, &subset[ .. n - 2], vectors.as_slice());
+  }
+}
+
+#[test]
+fn test_symmetric_tridiagonal_eigenvalues() {
+  // One workspace across growing dimensions, as in a Lanczos loop.
+  let mut workspace = SymmetricTridiagonalEigenWorkspace::new(0);
+  let mut values = vec![0.0; 12];
+  assert_eq!(Ok(0), solve_symmetric_tridiagonal_eigenvalues(&[], &[], EigenRange::All, &mut values, &mut workspace, 0.0));
+  for n in 1 .. 13 {
+    let (diag, offdiag) = (inputs(n, 18), inputs(n - 1, 19));
+    let m = solve_symmetric_tridiagonal_eigenvalues(&diag, &offdiag, EigenRange::All, &mut values, &mut workspace, 0.0).unwrap();
+    assert_eq!(n, m);
+    let mut t = vec![0.0; n * n];
+    for i in 0 .. n {
+      t[i + i * n] = diag[i];
+      if i + 1 < n {
+        t[i + 1 + i * n] = offdiag[i];
+        t[i + (i + 1) * n] = offdiag[i];
+      }
+    }
+    let t = Array2d::from_storage((n, n), t);
+    let expected = symmetric_eigenvalues(t.as_view(), Uplo::Lower, EigenRange::All).unwrap();
+    assert_close(&expected, &values[ .. n], 1.0e-12);
+    assert!(values[n ..].iter().all(|&x| x == 0.0));
+    if n >= 4 {
+      let mut subset = vec![0.0; n];
+      let m = solve_symmetric_tridiagonal_eigenvalues(&diag, &offdiag, EigenRange::Indices(n - 3, n), &mut subset, &mut workspace, 0.0).unwrap();
+      assert_eq!(3, m);
+      assert_close(&expected[n - 3 ..], &subset[ .. 3], 1.0e-12);
+      let (lo, hi) = (0.5 * (expected[0] + expected[1]), 0.5 * (expected[2] + expected[3]));
+      let m = solve_symmetric_tridiagonal_eigenvalues(&diag, &offdiag, EigenRange::Values(lo, hi), &mut subset, &mut workspace, 0.0).unwrap();
+      assert_eq!(2, m);
+      assert_close(&expected[1 .. 3], &subset[ .. 2], 1.0e-12);
+    }
+  }
+  assert_eq!(12, workspace.max_dim());
+  let mut values = vec![0.0f32; 3];
+  let m = solve_symmetric_tridiagonal_eigenvalues(&[2.0, 2.0, 2.0], &[1.0, 1.0], EigenRange::All, &mut values, &mut workspace, 0.0).unwrap();
+  assert_eq!(3, m);
+  let expected = [2.0 - 2.0f32.sqrt(), 2.0, 2.0 + 2.0f32.sqrt()];
+  for i in 0 .. 3 {
+    assert!((expected[i] - values[i]).abs() < 1.0e-5);
+  }
+}
+
+#[test]
+fn test_rank1_update() {
+  let (m, n) = (5, 4);
+  let x = Array1d::from_storage(m, inputs(m, 10));
+  let y = Array1d::from_storage(n, inputs(n, 11));
+  let expected: Vec<f64> = (0 .. m * n).map(|idx| 2.0 * x.as_slice()[idx % m] * y.as_slice()[idx / m]).collect();
+  let mut a = Array2d::from_storage((m, n), vec![1.0; m * n]);
+  a.as_view_mut().outer_prod(2.0, x.as_view(), y.as_view());
+  assert_close(&expected, a.as_slice(), 1.0e-12);
+  a.as_view_mut().rank1_update(-1.0, x.as_view(), y.as_view());
+  let half: Vec<f64> = expected.iter().map(|&e| 0.5 * e).collect();
+  assert_close(&half, a.as_slice(), 1.0e-12);
+}
+
+#[test]
+fn test_symmetric_rank_updates() {
+  let (n, k) = (6, 9);
+  // The covariance `X^T * X` of `k` samples in the rows of `X`.
+  let x = Array2d::from_storage((k, n), inputs(k * n, 12));
+  let mut xt = vec![0.0; n * k];
+  for j in 0 .. n {
+    for i in 0 .. k {
+      xt[j + i * n] = x.as_slice()[i + j * k];
+    }
+  }
+  let cov = matmul(&xt, x.as_slice(), n, k, n);
+  for &uplo in &[Uplo::Lower, Uplo::Upper] {
+    let mut c = Array2d::from_storage((n, n), vec![1.0; n * n]);
+    c.as_view_mut().symmetric_rank_k_update(1.0, x.as_view(), Transpose::T, 0.0, uplo, true);
+    assert_close(&cov, c.as_slice(), 1.0e-12);
+    // Without mirroring the other triangle is left alone.
+    let v = Array1d::from_storage(n, inputs(n, 13));
+    c.as_view_mut().symmetric_rank1_update(0.5, v.as_view(), uplo, false);
+    for j in 0 .. n {
+      for i in 0 .. n {
+        let idx = i + j * n;
+        let updated = i == j || (i > j) == (uplo == Uplo::Lower);
+        let expected = if updated { cov[idx] + 0.5 * v.as_slice()[i] * v.as_slice()[j] } else { cov[idx] };
+        assert!((expected - c.as_slice()[idx]).abs() <= 1.0e-12);
+      }
+    }
+  }
+  // `A * A^T` with `beta` accumulating into the existing matrix.
+  let mut c = Array2d::from_storage((k, k), vec![1.0; k * k]);
+  c.as_view_mut().symmetric_rank_k_update(1.0, x.as_view(), Transpose::N, 2.0, Uplo::Upper, true);
+  let expected: Vec<f64> = matmul(x.as_slice(), &xt, k, n, k).iter().map(|&e| e + 2.0).collect();
+  assert_close(&expected, c.as_slice(), 1.0e-12);
+}
+
+#[test]
+fn test_matrix_add_trans() {
+  // Also the single row and column shapes, which go through `axpy`.
+  for &(m, n) in &[(4, 7), (4, 1), (1, 7)] {
+    for &x_trans in &[Transpose::N, Transpose::T] {
+      for &parallel in &[false, true] {
+        // `X` is a sub-view, at an offset and with a padded column stride.
+        let (x_m, x_n) = match x_trans {
+          Transpose::N => (m, n),
+          Transpose::T => (n, m),
+        };
+        let ldx = x_m + 3;
+        let x = Array2d::from_storage((ldx, x_n + 2), inputs(ldx * (x_n + 2), 14));
+        let y0 = inputs(m * n, 15);
+        let mut expected = y0.clone();
+        for j in 0 .. n {
+          for i in 0 .. m {
+            let (r, c) = match x_trans {
+              Transpose::N => (i, j),
+              Transpose::T => (j, i),
+            };
+            expected[i + j * m] += 2.0 * x.as_slice()[1 + r + ldx * (1 + c)];
+          }
+        }
+        let mut y = Array2d::from_storage((m, n), y0);
+        let x = x.as_view().view((1, 1), (1 + x_m, 1 + x_n));
+        if parallel {
+          y.as_view_mut().parallel_matrix_add_trans(2.0, x, x_trans);
+        } else {
+          y.as_view_mut().matrix_add_trans(2.0, x, x_trans);
+        }
+        assert_close(&expected, y.as_slice(), 1.0e-12);
+      }
+    }
+  }
+}
+
+/// The column-major transpose of the `m x n` matrix `a`.
+fn transpose(a: &[f64], m: usize, n: usize) -> Vec<f64> {
+  let mut at = vec![0.0; m * n];
+  for j in 0 .. n {
+    for i in 0 .. m {
+      at[j + i * n] = a[i + j * m];
+    }
+  }
+  at
+}
+
+#[test]
+fn test_batched_matrix_prod() {
+  // Small enough chunks that the batch is split across threads.
+  densearray::parallel::with_min_chunk_len(16, || {
+    let (m, k, n, batch_sz) = (4, 5, 3, 7);
+    // `A` is stored transposed; `B` is a sub-view of a larger array.
+    let a = Array3d::from_storage((k, m, batch_sz), inputs(k * m * batch_sz, 20));
+    let b = Array3d::from_storage((k + 2, n, batch_sz), inputs((k + 2) * n * batch_sz, 21));
+    let c0 = inputs(m * n * batch_sz, 22);
+    let mut c = Array3d::from_storage((m, n, batch_sz), c0.clone());
+    c.as_view_mut().batched_matrix_prod(2.0, a.as_view(), Transpose::T, b.as_view().view((1, 0, 0), (k + 1, n, batch_sz)), Transpose::N, 0.5);
+    for l in 0 .. batch_sz {
+      let a_l = transpose(&a.as_slice()[l * k * m .. (l + 1) * k * m], k, m);
+      let b_l: Vec<f64> = (0 .. k * n).map(|idx| b.as_slice()[1 + idx % k + (k + 2) * (idx / k + n * l)]).collect();
+      let expected: Vec<f64> = matmul(&a_l, &b_l, m, k, n).iter().zip(&c0[l * m * n .. (l + 1) * m * n])
+        .map(|(&p, &c_i)| 2.0 * p + 0.5 * c_i)
+        .collect();
+      assert_close(&expected, &c.as_slice()[l * m * n .. (l + 1) * m * n], 1.0e-12);
+    }
+  });
+}
+
+#[test]
+fn test_batched_matrix_prod_4d() {
+  let (m, k, n, heads, batch_sz) = (3, 4, 2, 2, 3);
+  let a = Array4d::from_storage((m, k, heads, batch_sz), inputs(m * k * heads * batch_sz, 23));
+  let b = Array4d::from_storage((n, k, heads, batch_sz), inputs(n * k * heads * batch_sz, 24));
+  let mut c = Array4d::zeros((m, n, heads, batch_sz));
+  c.as_view_mut().batched_matrix_prod(1.0, a.as_view(), Transpose::N, b.as_view(), Transpose::T, 0.0);
+  for l in 0 .. heads * batch_sz {
+    let a_l = &a.as_slice()[l * m * k .. (l + 1) * m * k];
+    let b_l = transpose(&b.as_slice()[l * n * k .. (l + 1) * n * k], n, k);
+    assert_close(&matmul(a_l, &b_l, m, k, n), &c.as_slice()[l * m * n .. (l + 1) * m * n], 1.0e-12);
+  }
+}
@@ -0,0 +1,290 @@
+//! Checks the elementwise ops and reductions on strided 2d-4d views against
+//! elementwise references, in both serial and parallel flavours.
+
+extern crate densearray;
+#[cfg(feature = "rayon")]
+extern crate rayon;
+
+mod common;
+
+use densearray::prelude::*;
+use densearray::parallel;
+
+use std::panic::{self, AssertUnwindSafe};
+
+/// `common::inputs` as `f32`, shifted to `[-0.25, 0.75)`.
+fn inputs(n: usize, seed: usize) -> Vec<f32> {
+  common::inputs(n, seed).into_iter().map(|x| (x + 0.25) as f32).collect()
+}
+
+/// The flat indices of the elements in the `(1 .. 30, 2 .. 9, 0 .. 3, 1 .. 4)`
+/// sub-view of a packed `(32, 11, 3, 4)` array.
+fn sub_view_indices() -> Vec<usize> {
+  let mut idxs = vec![];
+  for l in 1 .. 4 {
+    for k in 0 .. 3 {
+      for j in 2 .. 9 {
+        for i in 1 .. 30 {
+          idxs.push(i + 32 * (j + 11 * (k + 3 * l)));
+        }
+      }
+    }
+  }
+  idxs
+}
+
+const DIM: (usize, usize, usize, usize) = (32, 11, 3, 4);
+const LEN: usize = 32 * 11 * 3 * 4;
+const LO: (usize, usize, usize, usize) = (1, 2, 0, 1);
+const HI: (usize, usize, usize, usize) = (30, 9, 3, 4);
+
+#[test]
+fn test_elementwise_strided_4d() {
+  // Small enough chunks that the parallel ops are split across lanes.
+  parallel::with_min_chunk_len(64, || {
+    let x = Array4d::from_storage(DIM, inputs(LEN, 1));
+    let y0 = inputs(LEN, 2);
+    for &parallel in &[false, true] {
+      let mut y = Array4d::from_storage(DIM, y0.clone());
+      if parallel {
+        y.as_view_mut().view_mut(LO, HI).parallel_add(2.0, x.as_view().view(LO, HI));
+        y.as_view_mut().view_mut(LO, HI).parallel_elem_mult(x.as_view().view(LO, HI));
+        y.as_view_mut().view_mut(LO, HI).parallel_square();
+        y.as_view_mut().view_mut(LO, HI).parallel_add_scalar(-1.0);
+      } else {
+        y.as_view_mut().view_mut(LO, HI).add(2.0, x.as_view().view(LO, HI));
+        y.as_view_mut().view_mut(LO, HI).elem_mult(x.as_view().view(LO, HI));
+        y.as_view_mut().view_mut(LO, HI).square();
+        y.as_view_mut().view_mut(LO, HI).add_scalar(-1.0);
+      }
+      let mut expected = y0.clone();
+      for idx in sub_view_indices() {
+        let t = (expected[idx] + 2.0 * x.as_slice()[idx]) * x.as_slice()[idx];
+        expected[idx] = t * t - 1.0;
+      }
+      assert_eq!(&expected[..], y.as_slice(), "parallel: {}", parallel);
+    }
+  });
+}
+
+#[test]
+fn test_set_constant_strided() {
+  let mut x = Array3d::from_storage((6, 5, 4), vec![0.0f32; 120]);
+  x.as_view_mut().view_mut((1, 0, 1), (5, 5, 3)).set_constant(1.0);
+  x.as_view_mut().view_mut((0, 2, 0), (6, 3, 4)).parallel_set_constant(2.0);
+  for k in 0 .. 4 {
+    for j in 0 .. 5 {
+      for i in 0 .. 6 {
+        let expected = if j == 2 {
+          2.0
+        } else if i >= 1 && i < 5 && k >= 1 && k < 3 {
+          1.0
+        } else {
+          0.0
+        };
+        assert_eq!(expected, x.as_slice()[i + 6 * (j + 5 * k)]);
+      }
+    }
+  }
+}
+
+/// Row `i` of `x`, which is the only way to get a strided 1d view.
+#[cfg(feature = "rayon")]
+fn strided_row<'a, T>(x: &'a mut Array2d<T>, i: usize) -> Array1dViewMut<'a, T> where T: Copy + Send {
+  use rayon::prelude::*;
+
+  let n = x.dim().1;
+  x.as_view_mut().view_mut((i, 0), (i + 1, n)).par_axis_iter_mut(0).collect::<Vec<_>>().pop().unwrap()
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_set_constant_strided_1d() {
+  parallel::with_min_chunk_len(1, || {
+    let mut x = Array2d::from_storage((6, 5), vec![0.0f32; 30]);
+    let mut y = Array2d::from_storage((6, 5), vec![0.0f64; 30]);
+    {
+      let mut row = strided_row(&mut x, 1);
+      assert_eq!(6, row.stride());
+      row.set_constant(1.0);
+    }
+    {
+      let mut row = strided_row(&mut x, 4);
+      row.parallel_set_constant(2.0);
+    }
+    {
+      let mut row = strided_row(&mut y, 3);
+      row.parallel_set_constant(3.0);
+    }
+    for j in 0 .. 5 {
+      for i in 0 .. 6 {
+        let expected = match i {
+          1 => 1.0,
+          4 => 2.0,
+          _ => 0.0,
+        };
+        assert_eq!(expected, x.as_slice()[i + 6 * j]);
+        assert_eq!(if i == 3 { 3.0 } else { 0.0 }, y.as_slice()[i + 6 * j]);
+      }
+    }
+  });
+}
+
+#[test]
+fn test_reductions_strided_4d() {
+  parallel::with_min_chunk_len(64, || {
+    let x = Array4d::from_storage(DIM, inputs(LEN, 3));
+    let y = Array4d::from_storage(DIM, inputs(LEN, 4));
+    let idxs = sub_view_indices();
+    let sum: f64 = idxs.iter().map(|&i| x.as_slice()[i] as f64).sum();
+    let abs_sum: f64 = idxs.iter().map(|&i| x.as_slice()[i].abs() as f64).sum();
+    let sq_sum: f64 = idxs.iter().map(|&i| (x.as_slice()[i] as f64).powi(2)).sum();
+    let dot: f64 = idxs.iter().map(|&i| x.as_slice()[i] as f64 * y.as_slice()[i] as f64).sum();
+    let close = |expected: f64, actual: f32| {
+      assert!((expected - actual as f64).abs() <= 1.0e-4 * expected.abs().max(1.0), "{} vs {}", expected, actual);
+    };
+    let x = x.as_view();
+    let y = y.as_view();
+    close(sum, x.view(LO, HI).elem_sum());
+    close(sum, x.view(LO, HI).elem_sum_with(Summation::Pairwise));
+    close(sum, x.view(LO, HI).parallel_elem_sum());
+    close(abs_sum, x.view(LO, HI).l1_norm());
+    close(abs_sum, x.view(LO, HI).parallel_l1_norm());
+    close(sq_sum.sqrt(), x.view(LO, HI).l2_norm());
+    close(sq_sum.sqrt(), x.view(LO, HI).parallel_l2_norm());
+    close(0.5 * dot, x.view(LO, HI).inner_prod(0.5, y.view(LO, HI)));
+    close(0.5 * dot, x.view(LO, HI).parallel_inner_prod(0.5, y.view(LO, HI)));
+  });
+}
+
+#[test]
+fn test_reductions_packed_2d() {
+  let x = Array2d::from_storage((40, 30), inputs(1200, 5));
+  let sum: f64 = x.as_slice().iter().map(|&x_i| x_i as f64).sum();
+  let sq_sum: f64 = x.as_slice().iter().map(|&x_i| (x_i as f64).powi(2)).sum();
+  assert!((sum - x.as_view().elem_sum() as f64).abs() < 1.0e-3);
+  assert!((sum - x.as_view().parallel_elem_sum() as f64).abs() < 1.0e-3);
+  assert!((sq_sum.sqrt() - x.as_view().parallel_l2_norm() as f64).abs() < 1.0e-3);
+}
+
+/// Reduces each fiber along `axis` of the `(lo, hi)` sub-view of a packed
+/// `dim` array with `f`; the results are packed in the remaining axes.
+fn axis_reference<F>(x: &[f32], dim: (usize, usize, usize), lo: (usize, usize, usize), hi: (usize, usize, usize), axis: usize, f: F) -> Vec<f64> where F: Fn(&[f32]) -> f64 {
+  let lo = [lo.0, lo.1, lo.2];
+  let hi = [hi.0, hi.1, hi.2];
+  let rest: Vec<usize> = (0 .. 3).filter(|&d| d != axis).collect();
+  let mut y = vec![];
+  for j in lo[rest[1]] .. hi[rest[1]] {
+    for i in lo[rest[0]] .. hi[rest[0]] {
+      let fiber: Vec<f32> = (lo[axis] .. hi[axis]).map(|r| {
+        let mut idx = [0; 3];
+        idx[rest[0]] = i;
+        idx[rest[1]] = j;
+        idx[axis] = r;
+        x[idx[0] + dim.0 * (idx[1] + dim.1 * idx[2])]
+      }).collect();
+      y.push(f(&fiber));
+    }
+  }
+  y
+}
+
+fn arg_by(fiber: &[f32], better: fn(f32, f32) -> bool) -> f64 {
+  let mut k = 0;
+  for r in 1 .. fiber.len() {
+    if better(fiber[r], fiber[k]) {
+      k = r;
+    }
+  }
+  k as f64
+}
+
+#[test]
+fn test_axis_reductions_3d() {
+  let dim = (7, 6, 5);
+  let x = Array3d::from_storage(dim, inputs(7 * 6 * 5, 6));
+  // The packed array, then a strided sub-view of it.
+  for &(lo, hi) in &[((0, 0, 0), dim), ((1, 1, 1), (6, 5, 4))] {
+    let sub_dim = [hi.0 - lo.0, hi.1 - lo.1, hi.2 - lo.2];
+    for axis in 0 .. 3 {
+      let rest: Vec<usize> = (0 .. 3).filter(|&d| d != axis).map(|d| sub_dim[d]).collect();
+      let y_dim = (rest[0], rest[1]);
+      let y_len = rest[0] * rest[1];
+      for &parallel in &[false, true] {
+        let mut y_sum = Array2d::from_storage(y_dim, vec![0.0f32; y_len]);
+        let mut y_mean = Array2d::from_storage(y_dim, vec![0.0f32; y_len]);
+        let mut y_max = Array2d::from_storage(y_dim, vec![0.0f32; y_len]);
+        let mut y_min = Array2d::from_storage(y_dim, vec![0.0f32; y_len]);
+        let mut y_argmax = Array2d::from_storage(y_dim, vec![0u32; y_len]);
+        let mut y_argmin = Array2d::from_storage(y_dim, vec![0u32; y_len]);
+        let x_sub = x.as_view().view(lo, hi);
+        if parallel {
+          x_sub.parallel_axis_sum(axis, y_sum.as_view_mut());
+          x_sub.parallel_axis_mean(axis, y_mean.as_view_mut());
+          x_sub.parallel_axis_max(axis, y_max.as_view_mut());
+          x_sub.parallel_axis_min(axis, y_min.as_view_mut());
+          x_sub.parallel_axis_argmax(axis, y_argmax.as_view_mut());
+          x_sub.parallel_axis_argmin(axis, y_argmin.as_view_mut());
+        } else {
+          x_sub.axis_sum(axis, y_sum.as_view_mut());
+          x_sub.axis_mean(axis, y_mean.as_view_mut());
+          x_sub.axis_max(axis, y_max.as_view_mut());
+          x_sub.axis_min(axis, y_min.as_view_mut());
+          x_sub.axis_argmax(axis, y_argmax.as_view_mut());
+          x_sub.axis_argmin(axis, y_argmin.as_view_mut());
+        }
+        let reference = |f: &Fn(&[f32]) -> f64| axis_reference(x.as_slice(), dim, lo, hi, axis, f);
+        let sum = reference(&|v| v.iter().map(|&v_i| v_i as f64).sum());
+        let n = sub_dim[axis] as f64;
+        let ctx = format!("lo: {:?}, axis: {}, parallel: {}", lo, axis, parallel);
+        for k in 0 .. y_len {
+          assert!((sum[k] - y_sum.as_slice()[k] as f64).abs() < 1.0e-5, "{}", ctx);
+          assert!((sum[k] / n - y_mean.as_slice()[k] as f64).abs() < 1.0e-5, "{}", ctx);
+        }
+        let max = reference(&|v| v.iter().fold(-1.0e30f32, |m, &v_i| m.max(v_i)) as f64);
+        let min = reference(&|v| v.iter().fold(1.0e30f32, |m, &v_i| m.min(v_i)) as f64);
+        let argmax = reference(&|v| arg_by(v, |a, b| a > b));
+        let argmin = reference(&|v| arg_by(v, |a, b| a < b));
+        let as_f64 = |y: &[f32]| y.iter().map(|&y_i| y_i as f64).collect::<Vec<_>>();
+        let idx_f64 = |y: &[u32]| y.iter().map(|&y_i| y_i as f64).collect::<Vec<_>>();
+        assert_eq!(max, as_f64(y_max.as_slice()), "{}", ctx);
+        assert_eq!(min, as_f64(y_min.as_slice()), "{}", ctx);
+        assert_eq!(argmax, idx_f64(y_argmax.as_slice()), "{}", ctx);
+        assert_eq!(argmin, idx_f64(y_argmin.as_slice()), "{}", ctx);
+      }
+    }
+  }
+}
+
+#[test]
+fn test_axis_reductions_empty_axis() {
+  let x = Array3d::from_storage((7, 6, 5), inputs(7 * 6 * 5, 7));
+  // A strided sub-view whose middle axis is empty.
+  let (lo, hi) = ((1, 2, 1), (6, 2, 4));
+  for &parallel in &[false, true] {
+    let mut y_sum = Array2d::from_storage((5, 3), vec![1.0f32; 15]);
+    let mut y_mean = Array2d::from_storage((5, 3), vec![1.0f32; 15]);
+    if parallel {
+      x.as_view().view(lo, hi).parallel_axis_sum(1, y_sum.as_view_mut());
+      x.as_view().view(lo, hi).parallel_axis_mean(1, y_mean.as_view_mut());
+    } else {
+      x.as_view().view(lo, hi).axis_sum(1, y_sum.as_view_mut());
+      x.as_view().view(lo, hi).axis_mean(1, y_mean.as_view_mut());
+    }
+    assert!(y_sum.as_slice().iter().all(|&y_i| y_i == 0.0));
+    assert!(y_mean.as_slice().iter().all(|&y_i| y_i.is_nan()));
+  }
+  // The max and min reductions have no value for an empty axis.
+  let x = x.as_view().view(lo, hi);
+  let panics = |f: &Fn()| panic::catch_unwind(AssertUnwindSafe(f)).is_err();
+  let y = || Array2d::from_storage((5, 3), vec![0.0f32; 15]);
+  let y_idx = || Array2d::from_storage((5, 3), vec![0u32; 15]);
+  assert!(panics(&|| x.axis_max(1, y().as_view_mut())));
+  assert!(panics(&|| x.axis_min(1, y().as_view_mut())));
+  assert!(panics(&|| x.axis_argmax(1, y_idx().as_view_mut())));
+  assert!(panics(&|| x.axis_argmin(1, y_idx().as_view_mut())));
+  assert!(panics(&|| x.parallel_axis_max(1, y().as_view_mut())));
+  assert!(panics(&|| x.parallel_axis_min(1, y().as_view_mut())));
+  assert!(panics(&|| x.parallel_axis_argmax(1, y_idx().as_view_mut())));
+  assert!(panics(&|| x.parallel_axis_argmin(1, y_idx().as_view_mut())));
+}
@@ -0,0 +1,54 @@
+//! Checks the rayon parallel iterators over views and batches.
+
+#![cfg(feature = "rayon")]
+
+extern crate densearray;
+extern crate rayon;
+
+use densearray::prelude::*;
+use rayon::prelude::*;
+
+#[test]
+fn test_par_axis_iter_mut_batch() {
+  let mut buf = vec![0.0f32; 24 * 10];
+  {
+    let mut x = BatchArray3d::from_storage((4, 3, 2), 10, &mut buf[..]);
+    x.set_batch_size(7);
+    x.as_view_mut().par_axis_iter_mut(3).enumerate().for_each(|(idx, mut sample)| {
+      sample.set_constant(idx as f32 + 1.0);
+    });
+  }
+  for (i, &x_i) in buf.iter().enumerate() {
+    let idx = i / 24;
+    assert_eq!(if idx < 7 { idx as f32 + 1.0 } else { 0.0 }, x_i);
+  }
+}
+
+#[test]
+fn test_par_axis_iter_mut_columns() {
+  let mut x = Array2d::from_storage((5, 6), vec![0.0f32; 30]);
+  x.as_view_mut().view_mut((1, 0), (4, 6)).par_axis_iter_mut(1).enumerate().for_each(|(j, mut col)| {
+    col.add_scalar(j as f32);
+  });
+  for j in 0 .. 6 {
+    for i in 0 .. 5 {
+      let expected = if i >= 1 && i < 4 { j as f32 } else { 0.0 };
+      assert_eq!(expected, x.as_slice()[i + 5 * j]);
+    }
+  }
+}
+
+#[test]
+#[should_panic]
+fn test_par_axis_iter_mut_overlapping_rows() {
+  let mut x = Array2d::from_storage((5, 6), vec![0.0f32; 30]);
+  let _ = x.as_view_mut().par_axis_iter_mut(0);
+}
+
+#[test]
+fn test_batch_par_iter_mut() {
+  let mut batch = Batch::new();
+  batch.set_batch_size(5, 0usize);
+  batch.par_iter_mut().enumerate().for_each(|(i, b_i)| *b_i = i * i);
+  assert_eq!(&[0, 1, 4, 9, 16], &batch[..]);
+}
@@ -0,0 +1,122 @@
+//! Checks the Rust-side parallel execution layer and the ops built on it.
+
+extern crate densearray;
+
+use densearray::prelude::*;
+use densearray::parallel;
+use densearray::parallel_linalg;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[test]
+fn test_parallel_for_covers_range() {
+  for &len in &[0, 1, 17, 1000, 100003] {
+    let counts: Vec<AtomicUsize> = (0 .. len).map(|_| AtomicUsize::new(0)).collect();
+    parallel::parallel_for(len, |range| {
+      for i in range {
+        counts[i].fetch_add(1, Ordering::Relaxed);
+      }
+    });
+    assert!(counts.iter().all(|c| c.load(Ordering::Relaxed) == 1));
+  }
+}
+
+#[test]
+#[should_panic]
+fn test_parallel_chunks_propagates_panic() {
+  parallel::parallel_chunks(64, |chunk| {
+    if chunk == 37 {
+      panic!();
+    }
+  });
+}
+
+#[test]
+fn test_parallel_exp() {
+  let n = 100003;
+  let x: Vec<f32> = (0 .. n).map(|i| (i % 97) as f32 * 0.1 - 5.0).collect();
+  let mut y = Array1d::from_storage(n, x.clone());
+  y.as_view_mut().parallel_exp();
+  for (&x_i, &y_i) in x.iter().zip(y.as_slice().iter()) {
+    assert_eq!(x_i.exp(), y_i);
+  }
+  let x: Vec<f64> = (0 .. n).map(|i| (i % 89) as f64 * 0.1 - 4.0).collect();
+  let mut y = Array1d::from_storage(n, x.clone());
+  y.as_view_mut().parallel_exp();
+  for (&x_i, &y_i) in x.iter().zip(y.as_slice().iter()) {
+    assert_eq!(x_i.exp(), y_i);
+  }
+}
+
+#[test]
+fn test_with_num_threads() {
+  let before = parallel::thread_settings();
+  let inner = parallel::with_num_threads(3, || {
+    let x: Vec<f32> = (0 .. 100003).map(|i| (i % 7) as f32).collect();
+    let mut y = Array1d::from_storage(x.len(), x.clone());
+    y.as_view_mut().parallel_add_scalar(1.0);
+    y.as_view_mut().parallel_exp();
+    for (&x_i, &y_i) in x.iter().zip(y.as_slice().iter()) {
+      assert_eq!((x_i + 1.0).exp(), y_i);
+    }
+    parallel::thread_settings()
+  });
+  assert_eq!(3, inner.num_threads);
+  assert!(cfg!(feature = "rust_kernels") || inner.omp_num_threads == 3);
+  assert_eq!(before, parallel::thread_settings());
+}
+
+#[test]
+fn test_deterministic_reductions() {
+  let n = 100003;
+  let x: Vec<f32> = (0 .. n).map(|i| ((i * 7919) % 1013) as f32 * 1.0e-3 - 0.5).collect();
+  let y: Vec<f32> = (0 .. n).map(|i| ((i * 104729) % 997) as f32 * 1.0e-3 - 0.25).collect();
+  // The rows of a matrix are strided vectors, and a sub-view of it is split
+  // into several lanes.
+  let a = Array2d::from_storage((3, n / 3), x[.. 3 * (n / 3)].to_vec());
+  let x = Array1d::from_storage(n, x);
+  let y = Array1d::from_storage(n, y);
+  let reductions = || {
+    let x = x.as_view();
+    let y = y.as_view();
+    let row = a.as_view().view((1, 0), (2, n / 3));
+    let sub = a.as_view().view((0, 1), (3, n / 3 - 1));
+    vec![
+      x.parallel_elem_sum(),
+      x.parallel_l1_norm(),
+      x.parallel_l2_norm(),
+      x.parallel_inner_prod(0.5, y),
+      row.parallel_elem_sum(),
+      row.parallel_l1_norm(),
+      row.parallel_l2_norm(),
+      row.parallel_inner_prod(0.5, row),
+      sub.parallel_elem_sum(),
+      sub.parallel_l1_norm(),
+      sub.parallel_l2_norm(),
+      sub.parallel_inner_prod(0.5, sub),
+    ].into_iter().map(|r| r.to_bits()).collect::<Vec<_>>()
+  };
+  parallel_linalg::with_deterministic_reductions(true, || {
+    let expected = parallel::with_num_threads(1, &reductions);
+    for &num_threads in &[2, 7, 16] {
+      assert_eq!(expected, parallel::with_num_threads(num_threads, &reductions), "num_threads: {}", num_threads);
+    }
+  });
+}
+
+#[test]
+fn test_parallel_l2_norm_no_overflow() {
+  // The squares overflow f32, but the norm does not.
+  let n = 100003;
+  let x = Array1d::from_storage(n, vec![1.0e30f32; n]);
+  let a = Array2d::from_storage((3, n / 3), vec![1.0e30f32; 3 * (n / 3)]);
+  let close = |expected: f64, actual: f32| {
+    assert!((expected - actual as f64).abs() <= 1.0e-5 * expected, "{} vs {}", expected, actual);
+  };
+  for &flag in &[false, true] {
+    parallel_linalg::with_deterministic_reductions(flag, || {
+      close(1.0e30 * (n as f64).sqrt(), x.as_view().parallel_l2_norm());
+      close(1.0e30 * (3.0 * (n / 3 - 2) as f64).sqrt(), a.as_view().view((0, 1), (3, n / 3 - 1)).parallel_l2_norm());
+    });
+  }
+}
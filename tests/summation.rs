@@ -0,0 +1,100 @@
+//! Checks the `Summation` modes against the usual error bounds on inputs
+//! which defeat naive summation.
+
+extern crate densearray;
+
+use densearray::prelude::*;
+
+/// The unit roundoff of `f32`.
+const U: f64 = 5.960464477539063e-8;
+
+struct Bounds {
+  pairwise:     f64,
+  compensated:  f64,
+}
+
+/// The error bounds of pairwise summation, which is naive over blocks of
+/// 128, and of compensated summation, for the exact sum `sum` of terms
+/// whose magnitudes sum to `abs_sum`.
+fn bounds(n: usize, sum: f64, abs_sum: f64) -> Bounds {
+  let depth = (n as f64 / 128.0).log2().ceil().max(0.0);
+  Bounds{
+    pairwise:     (128.0 + depth) * U * abs_sum,
+    compensated:  2.0 * U * sum.abs() + 4.0 * n as f64 * U * U * abs_sum,
+  }
+}
+
+fn check_modes(x: &[f32], naive_exceeds_pairwise: bool) {
+  let n = x.len();
+  let sum: f64 = x.iter().map(|&x_i| x_i as f64).sum();
+  let abs_sum: f64 = x.iter().map(|&x_i| (x_i as f64).abs()).sum();
+  let bounds = bounds(n, sum, abs_sum);
+  let x = Array1d::from_storage(n, x.to_vec());
+  let err = |mode: Summation| (x.as_view().elem_sum_with(mode) as f64 - sum).abs();
+  assert!(err(Summation::Pairwise) <= bounds.pairwise, "pairwise: {} > {}", err(Summation::Pairwise), bounds.pairwise);
+  assert!(err(Summation::Compensated) <= bounds.compensated, "compensated: {} > {}", err(Summation::Compensated), bounds.compensated);
+  assert!(err(Summation::F64Accum) <= bounds.compensated, "f64 accum: {} > {}", err(Summation::F64Accum), bounds.compensated);
+  assert!(err(Summation::Naive) > bounds.compensated, "naive: {} <= {}", err(Summation::Naive), bounds.compensated);
+  if naive_exceeds_pairwise {
+    assert!(err(Summation::Naive) > bounds.pairwise, "naive: {} <= {}", err(Summation::Naive), bounds.pairwise);
+  }
+}
+
+#[test]
+fn test_summation_large_then_small() {
+  // Each small term is below half an ulp of the running sum, so naive
+  // summation drops all of them.
+  let n = 10_000_000;
+  let mut x = vec![1.0e-8f32; n];
+  x[0] = 1.0;
+  check_modes(&x, true);
+}
+
+#[test]
+fn test_summation_cancellation() {
+  // Small terms between large ones which cancel; the exact sum is 1e4.
+  let mut x = vec![];
+  for _ in 0 .. 100_000 {
+    x.push(1.0e4f32);
+    x.push(0.1);
+    x.push(-1.0e4);
+  }
+  check_modes(&x, false);
+}
+
+#[test]
+fn test_axis_sum_with() {
+  // Each column is a cancellation sequence, stored with a row stride.
+  let (m, n) = (3000, 3);
+  let mut x = vec![0.0f32; (m + 1) * n];
+  for j in 0 .. n {
+    for i in 0 .. m {
+      x[i + (m + 1) * j] = match i % 3 {
+        0 => 1.0e4,
+        1 => 0.1 * (j + 1) as f32,
+        _ => -1.0e4,
+      };
+    }
+  }
+  let x = Array2d::from_storage((m + 1, n), x);
+  let x = x.as_view().view((0, 0), (m, n));
+  for &mean in &[false, true] {
+    let mut y = Array1d::from_storage(n, vec![0.0f32; n]);
+    if mean {
+      x.axis_mean_with(0, y.as_view_mut(), Summation::Compensated);
+    } else {
+      x.axis_sum_with(0, y.as_view_mut(), Summation::Compensated);
+    }
+    for j in 0 .. n {
+      let sum = 1000.0 * (0.1 * (j + 1) as f32) as f64;
+      let expected = if mean { sum / m as f64 } else { sum };
+      assert!((y.as_slice()[j] as f64 - expected).abs() <= 1.0e-6 * expected, "mean: {}, column {}", mean, j);
+    }
+  }
+  // An empty axis gives zeros and NaNs like the kernel reductions.
+  let mut y = Array1d::from_storage(n, vec![1.0f32; n]);
+  x.view((0, 0), (0, n)).axis_sum_with(0, y.as_view_mut(), Summation::Pairwise);
+  assert!(y.as_slice().iter().all(|&y_i| y_i == 0.0));
+  x.view((0, 0), (0, n)).axis_mean_with(0, y.as_view_mut(), Summation::Pairwise);
+  assert!(y.as_slice().iter().all(|&y_i| y_i.is_nan()));
+}